@@ -0,0 +1,151 @@
+// Client-side hot paths in isolation (`cargo bench`)
+//
+// Micro-benchmarks of the work a worker does for every transaction, with no
+// node involved, so changes to it can be measured and regressions caught:
+// tracking created objects and evicting past the tracker cap, building the
+// update and create transactions the load phase sends, signing them, and
+// writing and reading a saved state file of the size a long run produces.
+// Everything runs the library's own code (WorkerState, the ops.rs builders
+// through workload::build_transaction, SavedBenchmarkState) against synthetic
+// object refs.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use fdp_sui_bench::eviction::EvictionPolicy;
+use fdp_sui_bench::workload::{self, BuildContext, GasBudgets, Workload};
+use fdp_sui_bench::{abi, ops, SavedBenchmarkState, SavedWorkerObjects, TrackedObject, WorkerState};
+use sui_sdk::types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
+use sui_sdk::types::crypto::{get_key_pair, AccountKeyPair, EncodeDecodeBase64, SuiKeyPair};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+const RGP: u64 = 1_000;
+const TRACKER_CAP: usize = 10_000;
+
+fn object_ref() -> ObjectRef {
+    (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random())
+}
+
+fn tracked_object(updates: u32) -> TrackedObject {
+    TrackedObject {
+        id: ObjectID::random(),
+        version: 7,
+        digest: ObjectDigest::random(),
+        size: 4096,
+        origin: None,
+        updates,
+    }
+}
+
+/// A worker with a fresh key, a synthetic gas coin and `objects` tracked objects
+fn worker(objects: usize) -> WorkerState {
+    let (address, keypair): (_, AccountKeyPair) = get_key_pair();
+    let objects = (0..objects).map(|i| tracked_object(i as u32 % 8)).collect();
+    WorkerState::new(0, address, SuiKeyPair::Ed25519(keypair), object_ref(), objects)
+}
+
+/// What the worker loop builds with by default, for `batch` objects
+fn context(batch: usize) -> BuildContext {
+    BuildContext {
+        package_id: ObjectID::random(),
+        batch_size: batch,
+        use_blobs: false,
+        tags: None,
+        contract: abi::LATEST,
+        cold_leak: false,
+        expiration_epoch: None,
+    }
+}
+
+fn gas() -> GasBudgets {
+    GasBudgets { default: 500_000_000, create: None, update: None, blob: None }
+}
+
+const POLICIES: [(&str, EvictionPolicy); 3] = [
+    ("drop_newest", EvictionPolicy::DropNewest),
+    ("drop_coldest", EvictionPolicy::DropColdest),
+    ("drop_random", EvictionPolicy::DropRandom),
+];
+
+fn tracker(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tracker");
+    for (name, policy) in POLICIES {
+        // A created object arriving at a full tracker
+        let mut full = worker(TRACKER_CAP);
+        full.limit_tracked(policy, TRACKER_CAP);
+        group.bench_function(BenchmarkId::new("track_created_full", name), |b| {
+            b.iter(|| full.track_created(black_box(tracked_object(0))))
+        });
+        // A loaded population twice the cap cut down to it
+        group.bench_function(BenchmarkId::new("limit_2x_cap", name), |b| {
+            b.iter_batched(
+                || worker(2 * TRACKER_CAP),
+                |mut state| state.limit_tracked(policy, TRACKER_CAP),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    // Picking the next update batch from the tracked objects
+    let mut state = worker(TRACKER_CAP);
+    group.throughput(Throughput::Elements(50));
+    group.bench_function("select_50", |b| {
+        b.iter(|| {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            ops::for_update_batch(&mut state, black_box(50), 1, &mut builder, |_, _| Ok(())).unwrap();
+        })
+    });
+    group.finish();
+}
+
+fn ptb_construction(c: &mut Criterion) {
+    let gas = gas();
+    let mut state = worker(TRACKER_CAP);
+    let mut group = c.benchmark_group("ptb_build");
+    for batch in [1usize, 10, 50] {
+        let ctx = context(batch);
+        group.throughput(Throughput::Elements(batch as u64));
+        let builders: [(&str, &dyn Workload); 2] = [("update", &ops::UpdateCounters), ("create", &ops::CreateCounters)];
+        for (name, op) in builders {
+            group.bench_function(BenchmarkId::new(name, batch), |b| {
+                b.iter(|| workload::build_transaction(&mut state, op, black_box(&ctx), &gas, RGP).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn signing(c: &mut Criterion) {
+    let mut state = worker(TRACKER_CAP);
+    let (tx_data, _) = workload::build_transaction(&mut state, &ops::UpdateCounters, &context(50), &gas(), RGP).unwrap();
+    c.bench_function("sign/update_50", |b| b.iter(|| workload::sign(&state, black_box(tx_data.clone()))));
+}
+
+/// A saved state of `workers` x `per_worker` objects, as --save-objects writes it
+fn saved_state(workers: usize, per_worker: usize) -> SavedBenchmarkState {
+    let workers: Vec<_> = (0..workers)
+        .map(|worker_id| {
+            let (address, keypair): (_, AccountKeyPair) = get_key_pair();
+            SavedWorkerObjects {
+                worker_id,
+                address,
+                keypair_base64: SuiKeyPair::Ed25519(keypair).encode_base64(),
+                objects: (0..per_worker).map(|_| tracked_object(3)).collect(),
+            }
+        })
+        .collect();
+    SavedBenchmarkState { total_objects: workers.len() * per_worker, workers }
+}
+
+fn saved_state_serde(c: &mut Criterion) {
+    let state = saved_state(16, 10_000);
+    let json = serde_json::to_string_pretty(&state).unwrap();
+    let mut group = c.benchmark_group("saved_state");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(json.len() as u64));
+    group.bench_function("write_160k_objects", |b| b.iter(|| serde_json::to_string_pretty(black_box(&state)).unwrap()));
+    group.bench_function("read_160k_objects", |b| {
+        b.iter(|| serde_json::from_str::<SavedBenchmarkState>(black_box(&json)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tracker, ptb_construction, signing, saved_state_serde);
+criterion_main!(benches);
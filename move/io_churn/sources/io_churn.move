@@ -116,7 +116,35 @@ module io_churn::io_churn {
             i = i + 1;
         };
     }
-    
+
+    /// Create multiple blobs of `size` bytes each - for mixed-size populations
+    public entry fun create_blob_batch_sized(count: u64, size: u64, ctx: &mut TxContext) {
+        let sender = tx_context::sender(ctx);
+        let seed = tx_context::epoch(ctx);
+        let mut i = 0;
+        while (i < count) {
+            let blob = LargeBlob {
+                id: object::new(ctx),
+                owner: sender,
+                version: 0,
+                data: generate_blob_data(seed + i, size),
+                checksum: compute_checksum(0, seed + i),
+            };
+            transfer::transfer(blob, sender);
+            i = i + 1;
+        };
+    }
+
+    /// Rewrite all of the blob's data, keeping its current size
+    /// (update_blob would resize non-4KB blobs back to BLOB_SIZE)
+    public entry fun update_blob_same_size(blob: &mut LargeBlob, ctx: &TxContext) {
+        blob.version = blob.version + 1;
+        let seed = tx_context::epoch(ctx) + blob.version;
+        let size = vector::length(&blob.data);
+        blob.data = generate_blob_data(seed, size);
+        blob.checksum = compute_checksum(blob.version, seed);
+    }
+
     /// Update blob with new 4KB of pseudo-random data
     /// This is the KEY function - writes 4KB per call
     public entry fun update_blob(blob: &mut LargeBlob, ctx: &TxContext) {
@@ -134,6 +162,95 @@ module io_churn::io_churn {
         blob.checksum = compute_checksum(blob.version, seed);
     }
     
+    /// Partial update: rewrite only `len` bytes starting at `offset`
+    /// Used to compare small-overwrite vs full-overwrite amplification
+    public entry fun update_blob_partial(blob: &mut LargeBlob, offset: u64, len: u64, ctx: &TxContext) {
+        blob.version = blob.version + 1;
+        let size = vector::length(&blob.data);
+        let start = if (offset < size) { offset } else { 0 };
+        let end = if (start + len < size) { start + len } else { size };
+        let mut state = tx_context::epoch(ctx) + blob.version;
+        let mut i = start;
+        while (i < end) {
+            state = ((state * 1103515245 + 12345) % 2147483648);
+            *vector::borrow_mut(&mut blob.data, i) = ((state % 256) as u8);
+            i = i + 1;
+        };
+        blob.checksum = compute_checksum(blob.version, state);
+    }
+    
+    /// Append `len` bytes of pseudo-random data (growing objects, event-log-like state)
+    /// Once the blob would exceed `max_size` it starts over with just the new bytes
+    public entry fun append_blob(blob: &mut LargeBlob, len: u64, max_size: u64, ctx: &TxContext) {
+        blob.version = blob.version + 1;
+        let seed = tx_context::epoch(ctx) + blob.version;
+        let tail = generate_blob_data(seed, len);
+        if (vector::length(&blob.data) + len > max_size) {
+            blob.data = tail;
+        } else {
+            vector::append(&mut blob.data, tail);
+        };
+        blob.checksum = compute_checksum(blob.version, seed);
+    }
+    
+    // ═══════════════════════════════════════════════════════════════════
+    // STREAM TAGS - Attribute physical blocks to logical streams (--payload-tag)
+    // ═══════════════════════════════════════════════════════════════════
+
+    /// Overwrite the first 8 bytes of the data with `tag` (little-endian)
+    fun write_tag(data: &mut vector<u8>, tag: u64) {
+        let len = vector::length(data);
+        let mut t = tag;
+        let mut i = 0;
+        while (i < 8 && i < len) {
+            *vector::borrow_mut(data, i) = ((t & 0xff) as u8);
+            t = t >> 8;
+            i = i + 1;
+        };
+    }
+
+    /// Stamp a stream tag into the blob's data; called after the update in the same PTB
+    public entry fun tag_blob(blob: &mut LargeBlob, tag: u64) {
+        write_tag(&mut blob.data, tag);
+    }
+
+    /// Stamp a stream tag into the counter (in place of last_update)
+    public entry fun tag_counter(counter: &mut MicroCounter, tag: u64) {
+        counter.last_update = tag;
+    }
+
+    /// create_batch with every counter tagged
+    public entry fun create_batch_tagged(count: u64, tag: u64, ctx: &mut TxContext) {
+        let sender = tx_context::sender(ctx);
+        let mut i = 0;
+        while (i < count) {
+            let mut counter = create_counter(ctx);
+            counter.last_update = tag;
+            transfer::transfer(counter, sender);
+            i = i + 1;
+        };
+    }
+
+    /// create_blob_batch_sized with every blob tagged
+    public entry fun create_blob_batch_tagged(count: u64, size: u64, tag: u64, ctx: &mut TxContext) {
+        let sender = tx_context::sender(ctx);
+        let seed = tx_context::epoch(ctx);
+        let mut i = 0;
+        while (i < count) {
+            let mut data = generate_blob_data(seed + i, size);
+            write_tag(&mut data, tag);
+            let blob = LargeBlob {
+                id: object::new(ctx),
+                owner: sender,
+                version: 0,
+                data,
+                checksum: compute_checksum(0, seed + i),
+            };
+            transfer::transfer(blob, sender);
+            i = i + 1;
+        };
+    }
+
     /// Read blob and return checksum - forces disk read if not cached
     /// Academic basis: Read operations force block cache misses → disk I/O
     /// This is a "view" operation that touches all 4KB of data
@@ -204,6 +321,25 @@ module io_churn::io_churn {
         });
     }
     
+    // ═══════════════════════════════════════════════════════════════════
+    // FREEZING - Immutable objects are never rewritten (explicitly cold data)
+    // ═══════════════════════════════════════════════════════════════════
+
+    /// Make a counter immutable
+    public entry fun freeze_counter(counter: MicroCounter) {
+        transfer::freeze_object(counter);
+    }
+
+    /// Make a blob immutable
+    public entry fun freeze_blob(blob: LargeBlob) {
+        transfer::freeze_object(blob);
+    }
+
+    /// Read a counter (owned or immutable) - forces the object to be loaded
+    public entry fun read_counter(counter: &MicroCounter) {
+        let _ = counter.checksum ^ (counter.value as u256);
+    }
+
     // ═══════════════════════════════════════════════════════════════════
     // OBJECT UPDATES - The key to high WAF
     // ═══════════════════════════════════════════════════════════════════
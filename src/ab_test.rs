@@ -0,0 +1,371 @@
+// A/B comparison harness
+//
+// Runs the same benchmark configuration twice - against two RPC URLs, or
+// twice against the same node with an operator pause in between (e.g. to
+// remount the device with FDP enabled) - and writes one comparative report.
+// With --simultaneous the two sides run at the same time against their own
+// nodes, paced from one shared clock (lockstep.rs), so environmental noise
+// hits both measurements alike.
+//
+// WAF is not visible to the client, so it is sampled through an external
+// command (--waf-cmd, e.g. the FEMU stats script used by bench.sh) before and
+// after each run. Output lines are parsed the same way bench.sh does:
+//   "Host written ... <N>", "GC copied ... <N>", "WAF: <x.y>"
+// and the per-run WAF is derived from the before/after counter deltas.
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args as ClapArgs;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::BufRead;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::lockstep::Lockstep;
+use crate::results::BenchResult;
+use crate::{clock, run_benchmark, Args};
+
+/// Arguments for the `ab-test` subcommand
+#[derive(ClapArgs, Debug, Clone)]
+pub struct AbTestArgs {
+    /// RPC URL for run B (default: same as --rpc-url, with an operator pause)
+    #[clap(long)]
+    rpc_url_b: Option<String>,
+
+    /// Package ID for run B if the contract is deployed separately there
+    #[clap(long)]
+    package_id_b: Option<String>,
+
+    /// Faucet URL for run B when it is a separate network
+    #[clap(long)]
+    faucet_url_b: Option<String>,
+
+    /// Run A and B at the same time (needs --rpc-url-b and --target-tps),
+    /// pacing both from one clock after both finished setup
+    #[clap(long, default_value = "false")]
+    simultaneous: bool,
+
+    /// Label for run A in the report
+    #[clap(long, default_value = "nofdp")]
+    label_a: String,
+
+    /// Label for run B in the report
+    #[clap(long, default_value = "fdp")]
+    label_b: String,
+
+    /// Wait for Enter between runs (for device reconfiguration); implied when
+    /// no --rpc-url-b is given
+    #[clap(long, default_value = "false")]
+    pause: bool,
+
+    /// Shell command printing device write counters (Host written / GC copied / WAF)
+    #[clap(long)]
+    waf_cmd: Option<String>,
+
+    /// Device counter command for run B's device (default: --waf-cmd)
+    #[clap(long)]
+    waf_cmd_b: Option<String>,
+
+    /// Cooldown between the two runs in seconds
+    #[clap(long, default_value = "60")]
+    cooldown_secs: u64,
+
+    /// Directory for both run results and the comparative report
+    #[clap(long, default_value = "ab_results")]
+    output_dir: String,
+}
+
+/// Device write counters as reported by the --waf-cmd output
+#[derive(Debug, Clone, Default, Serialize)]
+struct WafSample {
+    host_written: Option<u64>,
+    gc_copied: Option<u64>,
+    waf: Option<f64>,
+}
+
+/// One side of the comparison
+#[derive(Debug, Serialize)]
+struct AbRun {
+    label: String,
+    rpc_url: String,
+    waf_before: Option<WafSample>,
+    waf_after: Option<WafSample>,
+    /// WAF over this run only, from counter deltas (falls back to reported WAF)
+    waf: Option<f64>,
+    result: BenchResult,
+}
+
+/// Metrics compared side by side in the report
+const COMPARED_METRICS: &[&str] = &[
+    "tps",
+    "tx_success",
+    "tx_failed",
+    "objects_created",
+    "objects_updated",
+    "duration_secs",
+];
+
+/// Run A, then B, and write ab_report.{json,txt}
+pub async fn run_ab_test(base: Args, ab: AbTestArgs) -> Result<()> {
+    let output_dir = PathBuf::from(&ab.output_dir);
+    std::fs::create_dir_all(&output_dir)
+        .context(format!("Failed to create A/B directory: {}", ab.output_dir))?;
+
+    let mut args_a = base.clone();
+    args_a.command = None;
+    args_a.output = Some(output_dir.join(format!("{}.json", ab.label_a)).to_string_lossy().into_owned());
+
+    let mut args_b = args_a.clone();
+    if let Some(url) = &ab.rpc_url_b {
+        args_b.rpc_url = url.clone();
+    }
+    if let Some(pkg) = &ab.package_id_b {
+        args_b.package_id = Some(pkg.clone());
+    }
+    if let Some(url) = &ab.faucet_url_b {
+        args_b.faucet_url = url.clone();
+    }
+    args_b.output = Some(output_dir.join(format!("{}.json", ab.label_b)).to_string_lossy().into_owned());
+    let waf_cmd_b = ab.waf_cmd_b.as_deref().or(ab.waf_cmd.as_deref());
+    own_files(&mut args_a, &ab.label_a);
+    own_files(&mut args_b, &ab.label_b);
+
+    info!("A/B test: A = {} ({}), B = {} ({})", ab.label_a, args_a.rpc_url, ab.label_b, args_b.rpc_url);
+    let (run_a, run_b) = if ab.simultaneous {
+        validate_simultaneous(&base, &ab)?;
+        let clock = Lockstep::new(&[&ab.label_a, &ab.label_b]);
+        args_a.lockstep = Some(clock.side(0));
+        args_b.lockstep = Some(clock.side(1));
+        info!("A/B test: running {} and {} simultaneously", ab.label_a, ab.label_b);
+        // The first side to fail takes the other down with it (it would wait at the start forever)
+        tokio::try_join!(
+            run_side(&ab.label_a, &args_a, ab.waf_cmd.as_deref()),
+            run_side(&ab.label_b, &args_b, waf_cmd_b),
+        )?
+    } else {
+        let run_a = run_side(&ab.label_a, &args_a, ab.waf_cmd.as_deref()).await?;
+
+        if ab.cooldown_secs > 0 {
+            info!("A/B test: cooling down for {}s...", ab.cooldown_secs);
+            sleep(Duration::from_secs(ab.cooldown_secs)).await;
+        }
+
+        if ab.pause || ab.rpc_url_b.is_none() {
+            wait_for_operator(&ab.label_b).await?;
+        }
+
+        let run_b = run_side(&ab.label_b, &args_b, waf_cmd_b).await?;
+        (run_a, run_b)
+    };
+
+    let report = build_report(&run_a, &run_b);
+    std::fs::write(output_dir.join("ab_report.json"), serde_json::to_string_pretty(&report)?)?;
+
+    let table = format_report(&run_a, &run_b);
+    std::fs::write(output_dir.join("ab_report.txt"), &table)?;
+    for line in table.lines() {
+        info!("{}", line);
+    }
+    info!("A/B report written to {}", output_dir.join("ab_report.txt").display());
+    Ok(())
+}
+
+/// Both sides at once need two nodes and a paced, fresh run
+fn validate_simultaneous(base: &Args, ab: &AbTestArgs) -> Result<()> {
+    if ab.rpc_url_b.is_none() || ab.rpc_url_b.as_deref() == Some(base.rpc_url.as_str()) {
+        return Err(anyhow!("--simultaneous runs both sides at once; it needs a different node in --rpc-url-b"));
+    }
+    if ab.pause {
+        return Err(anyhow!("--pause waits for the operator between the runs; it can't be used with --simultaneous"));
+    }
+    if base.target_tps == 0 {
+        return Err(anyhow!("--simultaneous paces both sides from one clock; it needs --target-tps"));
+    }
+    if base.resume {
+        return Err(anyhow!("--simultaneous starts both sides together; it can't --resume"));
+    }
+    if base.manage_node.is_some() || base.node_metrics_url.is_some() {
+        return Err(anyhow!("--manage-node and --node-metrics-url describe one node; they can't be used with --simultaneous"));
+    }
+    Ok(())
+}
+
+/// Give every file a side writes during its run a name of its own: `<path>.<label>`
+pub(crate) fn own_files(args: &mut Args, label: &str) {
+    for path in args.per_run_files().into_iter().flatten() {
+        *path = format!("{}.{}", path, label);
+    }
+}
+
+/// Run one side, sampling device counters around it
+async fn run_side(label: &str, args: &Args, waf_cmd: Option<&str>) -> Result<AbRun> {
+    let waf_before = match waf_cmd {
+        Some(cmd) => sample_waf(cmd).await,
+        None => None,
+    };
+
+    info!("A/B test: starting run {}", label);
+    let result = run_benchmark(args, clock::system()).await
+        .context(format!("A/B run {} failed", label))?;
+
+    let waf_after = match waf_cmd {
+        Some(cmd) => sample_waf(cmd).await,
+        None => None,
+    };
+    let waf = waf_delta(waf_before.as_ref(), waf_after.as_ref());
+
+    Ok(AbRun {
+        label: label.to_string(),
+        rpc_url: args.rpc_url.clone(),
+        waf_before,
+        waf_after,
+        waf,
+        result,
+    })
+}
+
+/// Block until the operator presses Enter
+async fn wait_for_operator(next_label: &str) -> Result<()> {
+    warn!("A/B test: reconfigure the device/node for run {} and press Enter to continue...", next_label);
+    tokio::task::spawn_blocking(|| {
+        let mut line = String::new();
+        std::io::stdin().lock().read_line(&mut line).map(|_| ())
+    })
+    .await?
+    .context("Failed to read operator confirmation from stdin")
+}
+
+/// Run the WAF command and parse its counters (None if it fails)
+async fn sample_waf(cmd: &str) -> Option<WafSample> {
+    let output = match tokio::process::Command::new("sh").arg("-c").arg(cmd).output().await {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("WAF command failed to start: {}", e);
+            return None;
+        }
+    };
+    if !output.status.success() {
+        warn!("WAF command exited with {}", output.status);
+    }
+    Some(parse_waf_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Extract the last "Host written", "GC copied" and "WAF:" values
+fn parse_waf_output(text: &str) -> WafSample {
+    let last_int = |line: &str| {
+        line.split(|c: char| !c.is_ascii_digit())
+            .rev()
+            .find(|s| !s.is_empty())
+            .and_then(|s| s.parse::<u64>().ok())
+    };
+
+    let mut sample = WafSample::default();
+    for line in text.lines() {
+        if line.contains("Host written") {
+            sample.host_written = last_int(line).or(sample.host_written);
+        } else if line.contains("GC copied") {
+            sample.gc_copied = last_int(line).or(sample.gc_copied);
+        } else if let Some(rest) = line.split("WAF:").nth(1) {
+            sample.waf = rest.split_whitespace().next()
+                .and_then(|s| s.parse::<f64>().ok())
+                .or(sample.waf);
+        }
+    }
+    sample
+}
+
+/// WAF over a run: (Δhost + Δgc) / Δhost, or the reported WAF after the run
+fn waf_delta(before: Option<&WafSample>, after: Option<&WafSample>) -> Option<f64> {
+    let after = after?;
+    if let (Some(b), Some(ah), Some(ag)) = (before, after.host_written, after.gc_copied) {
+        if let (Some(bh), Some(bg)) = (b.host_written, b.gc_copied) {
+            let host = ah.saturating_sub(bh);
+            let gc = ag.saturating_sub(bg);
+            if host > 0 {
+                return Some((host + gc) as f64 / host as f64);
+            }
+        }
+    }
+    after.waf
+}
+
+fn build_report(a: &AbRun, b: &AbRun) -> Value {
+    let mut comparison = serde_json::Map::new();
+    for key in COMPARED_METRICS {
+        let va = a.result.metric(key);
+        let vb = b.result.metric(key);
+        comparison.insert(key.to_string(), delta_json(va, vb));
+    }
+    comparison.insert("waf".to_string(), delta_json(a.waf, b.waf));
+
+    serde_json::json!({
+        "a": a,
+        "b": b,
+        "comparison": comparison,
+    })
+}
+
+fn delta_json(a: Option<f64>, b: Option<f64>) -> Value {
+    let delta = a.zip(b).map(|(a, b)| b - a);
+    let delta_pct = a.zip(b).and_then(|(a, b)| if a != 0.0 { Some((b - a) / a * 100.0) } else { None });
+    serde_json::json!({ "a": a, "b": b, "delta": delta, "delta_pct": delta_pct })
+}
+
+fn format_report(a: &AbRun, b: &AbRun) -> String {
+    let fmt = |v: Option<f64>| v.map(|v| format!("{:.3}", v)).unwrap_or_else(|| "-".to_string());
+
+    let mut out = String::new();
+    out.push_str("═══════════════════════════════════════════════════════════════\n");
+    out.push_str(&format!("  A/B REPORT: {} (A) vs {} (B)\n", a.label, b.label));
+    out.push_str("═══════════════════════════════════════════════════════════════\n");
+    out.push_str(&format!("{:<18} {:>14} {:>14} {:>14} {:>9}\n", "metric", a.label, b.label, "delta", "delta%"));
+
+    let mut rows: Vec<(&str, Option<f64>, Option<f64>)> = COMPARED_METRICS.iter()
+        .map(|key| (*key, a.result.metric(key), b.result.metric(key)))
+        .collect();
+    rows.push(("waf", a.waf, b.waf));
+
+    for (key, va, vb) in rows {
+        let delta = va.zip(vb).map(|(x, y)| y - x);
+        let pct = va.zip(vb).and_then(|(x, y)| if x != 0.0 { Some((y - x) / x * 100.0) } else { None });
+        out.push_str(&format!(
+            "{:<18} {:>14} {:>14} {:>14} {:>9}\n",
+            key, fmt(va), fmt(vb), fmt(delta),
+            pct.map(|p| format!("{:+.1}%", p)).unwrap_or_else(|| "-".to_string()),
+        ));
+    }
+
+    if a.waf.is_none() && b.waf.is_none() {
+        out.push_str("(no WAF data: pass --waf-cmd to sample device counters)\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn each_side_writes_its_own_files() {
+        let mut args = Args::try_parse_from([
+            "fdp-sui-bench", "--autosave", "state.json", "--hdr-log", "lat.hlog",
+            "--interval-log", "intervals.csv", "--tx-digests", "digests.log",
+        ]).unwrap();
+        let mut b = args.clone();
+        own_files(&mut args, "nofdp");
+        own_files(&mut b, "fdp");
+
+        assert_eq!(args.interval_log.as_deref(), Some("intervals.csv.nofdp"));
+        assert_eq!(b.interval_log.as_deref(), Some("intervals.csv.fdp"));
+        assert_eq!(args.autosave.as_deref(), Some("state.json.nofdp"));
+        // Files that are not set stay unset
+        assert_eq!(args.save_objects, None);
+        let a_files: Vec<_> = args.per_run_files().into_iter().flatten().map(|p| p.clone()).collect();
+        let b_files: Vec<_> = b.per_run_files().into_iter().flatten().map(|p| p.clone()).collect();
+        assert_eq!(a_files.len(), 4);
+        assert!(a_files.iter().all(|f| !b_files.contains(f)));
+    }
+}
@@ -0,0 +1,114 @@
+// io_churn contract versions (--contract-version)
+//
+// Each version of the contract is the previous one plus the functions the
+// benchmark's workloads came to need; a deployed package stays at the version
+// it was published from. This table is the adapter between the two: which
+// functions (and parameter counts) a version has, how to recognize a version
+// from a package's entry points, and which calls an older version can stand in
+// for. Every Move call of the built-in operations goes through `resolve`, so a
+// call a version lacks is either mapped to its stand-in or, if there is none,
+// the feature using it is switched off up front (capabilities.rs).
+// --contract-version pins the version, e.g. when the node doesn't serve
+// normalized modules or to reproduce an older package's behavior under
+// --backend mock; otherwise it is detected from the package.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractVersion {
+    /// Counters and 4KB blobs
+    V1,
+    /// + update_blob_partial (--update-bytes)
+    V2,
+    /// + sized blobs (--size-distribution)
+    V3,
+    /// + freezing and reads (--freeze-pct)
+    V4,
+    /// + append_blob (--append-bytes)
+    V5,
+    /// + stream tags (--payload-tag)
+    V6,
+}
+
+pub const LATEST: ContractVersion = ContractVersion::V6;
+
+/// Every function the benchmark calls: (name, parameters besides the TxContext, version it appeared in)
+const FUNCTIONS: &[(&str, usize, ContractVersion)] = &[
+    ("create_batch", 1, ContractVersion::V1),
+    ("increment_simple", 1, ContractVersion::V1),
+    ("increment", 2, ContractVersion::V1),
+    ("create_shared", 0, ContractVersion::V1),
+    ("create_blob_batch", 1, ContractVersion::V1),
+    ("update_blob", 1, ContractVersion::V1),
+    ("delete_blob", 1, ContractVersion::V1),
+    ("update_blob_partial", 3, ContractVersion::V2),
+    ("create_blob_batch_sized", 2, ContractVersion::V3),
+    ("update_blob_same_size", 1, ContractVersion::V3),
+    ("freeze_counter", 1, ContractVersion::V4),
+    ("freeze_blob", 1, ContractVersion::V4),
+    ("read_counter", 1, ContractVersion::V4),
+    ("read_blob", 1, ContractVersion::V4),
+    ("append_blob", 3, ContractVersion::V5),
+    ("create_batch_tagged", 2, ContractVersion::V6),
+    ("create_blob_batch_tagged", 3, ContractVersion::V6),
+    ("tag_counter", 2, ContractVersion::V6),
+    ("tag_blob", 2, ContractVersion::V6),
+];
+
+/// Calls an older version makes with another function: (function, first version with it, stand-in)
+const SHIMS: &[(&str, ContractVersion, &str)] = &[
+    // Before sized blobs every blob is 4KB, which update_blob keeps
+    ("update_blob_same_size", ContractVersion::V3, "update_blob"),
+];
+
+impl ContractVersion {
+    /// Functions of this version with their parameter counts
+    pub fn functions(self) -> BTreeMap<String, usize> {
+        FUNCTIONS.iter()
+            .filter(|(_, _, since)| *since <= self)
+            .map(|(name, params, _)| (name.to_string(), *params))
+            .collect()
+    }
+
+    /// Newest version whose functions a package has all of
+    pub fn detect(functions: &BTreeMap<String, usize>) -> Option<Self> {
+        use clap::ValueEnum;
+        Self::value_variants().iter().rev().copied()
+            .find(|version| version.functions().iter().all(|(name, params)| functions.get(name) == Some(params)))
+    }
+}
+
+/// The function a call to `function` uses on `version`
+pub fn resolve(version: ContractVersion, function: &'static str) -> &'static str {
+    SHIMS.iter()
+        .find(|(name, since, _)| *name == function && version < *since)
+        .map_or(function, |(_, _, stand_in)| stand_in)
+}
+
+/// Stand-ins `version` needs, as (function, stand-in)
+pub fn shims(version: ContractVersion) -> Vec<(&'static str, &'static str)> {
+    SHIMS.iter()
+        .filter(|(_, since, _)| version < *since)
+        .map(|(name, _, stand_in)| (*name, *stand_in))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_detected_from_their_functions() {
+        assert_eq!(ContractVersion::detect(&LATEST.functions()), Some(LATEST));
+        let mut v4 = ContractVersion::V4.functions();
+        assert_eq!(ContractVersion::detect(&v4), Some(ContractVersion::V4));
+        // A changed signature doesn't count as the function
+        v4.insert("update_blob_partial".to_string(), 2);
+        assert_eq!(ContractVersion::detect(&v4), Some(ContractVersion::V1));
+        assert_eq!(ContractVersion::detect(&BTreeMap::new()), None);
+        assert_eq!(resolve(ContractVersion::V2, "update_blob_same_size"), "update_blob");
+        assert_eq!(resolve(ContractVersion::V3, "update_blob_same_size"), "update_blob_same_size");
+    }
+}
@@ -0,0 +1,212 @@
+// Adaptive in-flight limit (--adaptive-inflight)
+//
+// Instead of a fixed --max-inflight, the number of semaphore permits is steered
+// by p95 latency: every ADAPT_INTERVAL the controller looks at the latency of
+// the transactions that finished since the last step. While p95 stays within
+// the target it adds 10% more permits (at least one); once p95 overshoots it
+// takes a quarter away (AIMD). The target is --adaptive-p95-ms, or by default
+// twice the lowest p95 seen so far, i.e. "as much concurrency as the node takes
+// before queueing doubles latency". --max-inflight stays the ceiling. Every
+// step is kept, so the result shows where the limit settled and how it got there.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{debug, info};
+
+use crate::latency::Histogram;
+use crate::timeline::Timestamp;
+use crate::BenchStats;
+
+/// How often the limit is reconsidered
+const ADAPT_INTERVAL: Duration = Duration::from_secs(2);
+/// Fewer completions than this in a step say too little about p95; the limit holds
+const MIN_SAMPLES: u64 = 20;
+/// Default target: this many times the best p95 seen
+const BASELINE_FACTOR: f64 = 2.0;
+
+/// Latency of transactions completed since the controller's last step
+#[derive(Default)]
+pub struct LatencyWindow {
+    window: Mutex<Histogram>,
+}
+
+impl LatencyWindow {
+    pub fn record(&self, latency: Duration) {
+        self.window.lock().unwrap().record(latency);
+    }
+
+    pub fn take(&self) -> Histogram {
+        std::mem::take(&mut *self.window.lock().unwrap())
+    }
+}
+
+/// Additive-increase / multiplicative-decrease on p95
+pub struct Aimd {
+    limit: usize,
+    max: usize,
+    target_p95_us: Option<u64>,
+    best_p95_us: Option<u64>,
+}
+
+impl Aimd {
+    pub fn new(start: usize, max: usize, target_p95_ms: Option<f64>) -> Self {
+        Self {
+            limit: start.clamp(1, max.max(1)),
+            max: max.max(1),
+            target_p95_us: target_p95_ms.map(|ms| (ms * 1000.0) as u64),
+            best_p95_us: None,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// p95 the limit is held under
+    pub fn target_us(&self) -> Option<u64> {
+        self.target_p95_us.or_else(|| self.best_p95_us.map(|best| (best as f64 * BASELINE_FACTOR) as u64))
+    }
+
+    /// One step given the window's p95; returns the new limit
+    pub fn step(&mut self, p95_us: u64) -> usize {
+        self.best_p95_us = Some(self.best_p95_us.map_or(p95_us, |best| best.min(p95_us)));
+        let over = self.target_us().is_some_and(|target| p95_us > target);
+        self.limit = if over {
+            (self.limit * 3 / 4).max(1)
+        } else {
+            (self.limit + (self.limit / 10).max(1)).min(self.max)
+        };
+        self.limit
+    }
+}
+
+/// Moves a semaphore between permit counts
+///
+/// Growing adds permits right away. Shrinking can only forget permits that are
+/// free; the rest is owed and taken back as in-flight transactions return them.
+#[derive(Default)]
+pub struct PermitResizer {
+    owed: usize,
+}
+
+impl PermitResizer {
+    /// Take back owed permits that have been returned since the last call
+    pub fn settle(&mut self, semaphore: &Semaphore) {
+        self.owed -= semaphore.forget_permits(self.owed);
+    }
+
+    pub fn resize(&mut self, semaphore: &Semaphore, before: usize, after: usize) {
+        if after > before {
+            let grow = after - before;
+            let repaid = grow.min(self.owed);
+            self.owed -= repaid;
+            semaphore.add_permits(grow - repaid);
+        } else if after < before {
+            let shrink = before - after;
+            self.owed += shrink - semaphore.forget_permits(shrink);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitStep {
+    pub at: Timestamp,
+    pub limit: usize,
+    pub p95_ms: f64,
+    pub samples: u64,
+}
+
+/// `adaptive_inflight` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct AdaptiveReport {
+    /// --adaptive-p95-ms, or null when derived from the best p95
+    pub target_p95_ms: Option<f64>,
+    pub best_p95_ms: Option<f64>,
+    pub max_inflight: usize,
+    pub start_limit: usize,
+    pub final_limit: usize,
+    pub min_limit: usize,
+    pub peak_limit: usize,
+    /// Mean limit over the steps taken
+    pub mean_limit: f64,
+    pub steps: Vec<LimitStep>,
+}
+
+/// Resize `semaphore` to follow the controller until the run stops
+pub async fn run(
+    semaphore: Arc<Semaphore>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    mut aimd: Aimd,
+    max_inflight: usize,
+    target_p95_ms: Option<f64>,
+) -> AdaptiveReport {
+    let start_limit = aimd.limit();
+    let mut resizer = PermitResizer::default();
+    let mut steps = Vec::new();
+    stats.adaptive.take();
+    while running.load(Ordering::Relaxed) {
+        stats.clock.sleep(ADAPT_INTERVAL).await;
+        resizer.settle(&semaphore);
+
+        let window = stats.adaptive.take();
+        if window.count() < MIN_SAMPLES {
+            continue;
+        }
+        let p95_us = window.quantile_us(0.95);
+        let before = aimd.limit();
+        let after = aimd.step(p95_us);
+        resizer.resize(&semaphore, before, after);
+        if after != before {
+            debug!("Adaptive in-flight: p95 {:.1}ms over {} txs -> limit {} -> {}",
+                p95_us as f64 / 1000.0, window.count(), before, after);
+        }
+        steps.push(LimitStep { at: Timestamp::now(&stats), limit: after, p95_ms: p95_us as f64 / 1000.0, samples: window.count() });
+    }
+
+    let limits = || steps.iter().map(|s| s.limit);
+    let report = AdaptiveReport {
+        target_p95_ms,
+        best_p95_ms: aimd.best_p95_us.map(|us| us as f64 / 1000.0),
+        max_inflight,
+        start_limit,
+        final_limit: aimd.limit(),
+        min_limit: limits().min().unwrap_or(start_limit),
+        peak_limit: limits().max().unwrap_or(start_limit),
+        mean_limit: if steps.is_empty() { start_limit as f64 } else { limits().sum::<usize>() as f64 / steps.len() as f64 },
+        steps,
+    };
+    info!("Adaptive in-flight: settled at {} (range {}-{}, mean {:.1}, ceiling {}), best p95 {}",
+        report.final_limit, report.min_limit, report.peak_limit, report.mean_limit, max_inflight,
+        report.best_p95_ms.map_or("-".to_string(), |ms| format!("{:.1}ms", ms)));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_until_latency_doubles_then_backs_off() {
+        let mut aimd = Aimd::new(10, 64, None);
+        assert_eq!(aimd.step(10_000), 11);
+        assert_eq!(aimd.step(15_000), 12);
+        // Twice the best p95 is still within target
+        assert_eq!(aimd.step(20_000), 13);
+        assert_eq!(aimd.step(25_000), 9);
+        assert_eq!(aimd.target_us(), Some(20_000));
+    }
+
+    #[test]
+    fn explicit_target_and_ceiling() {
+        let mut aimd = Aimd::new(60, 64, Some(50.0));
+        assert_eq!(aimd.step(40_000), 64);
+        assert_eq!(aimd.step(40_000), 64);
+        assert_eq!(aimd.step(60_000), 48);
+        let mut floor = Aimd::new(1, 64, Some(1.0));
+        assert_eq!(floor.step(5_000), 1);
+    }
+}
@@ -0,0 +1,166 @@
+// Address-count scaling stages (--address-stages)
+//
+// Every worker signs with its own address, so the number of active workers is
+// the number of distinct senders. This mode splits the run into equal stages
+// and activates only the first N workers in each one (e.g. 1, 2, 4, 8) while
+// --target-tps stays the same in total: the active workers share the rate. The
+// transaction mix and volume are constant across stages, so any change in
+// latency or write amplification between stages comes from owner-index
+// fan-out (more owners, more index entries and gas coins) alone.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::info;
+
+use crate::counters::CounterSnapshot;
+use crate::latency::{Histogram, LatencySummary};
+use crate::timeline::Timestamp;
+use crate::BenchStats;
+
+/// Active sender count and per-stage latency
+pub struct StageStats {
+    active: AtomicUsize,
+    stage: AtomicUsize,
+    latency: Mutex<BTreeMap<usize, Histogram>>,
+}
+
+impl StageStats {
+    pub fn new(workers: usize) -> Self {
+        Self { active: AtomicUsize::new(workers), stage: AtomicUsize::new(0), latency: Mutex::default() }
+    }
+
+    /// Number of workers (addresses) allowed to submit right now
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub fn record_latency(&self, latency: Duration) {
+        let stage = self.stage.load(Ordering::Relaxed);
+        self.latency.lock().unwrap().entry(stage).or_default().record(latency);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageReport {
+    pub stage: usize,
+    pub addresses: usize,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    #[serde(flatten)]
+    pub counters: CounterSnapshot,
+    pub tps: f64,
+    pub latency: LatencySummary,
+}
+
+/// Check the stage list against the worker count and rate target
+pub fn validate(stages: &[usize], workers: usize, target_tps: u64) -> Result<()> {
+    if stages.is_empty() {
+        return Ok(());
+    }
+    if stages.iter().any(|&n| n == 0 || n > workers) {
+        return Err(anyhow!("--address-stages values must be between 1 and --workers ({})", workers));
+    }
+    if target_tps == 0 {
+        return Err(anyhow!("--address-stages needs --target-tps so the total rate stays fixed across stages"));
+    }
+    Ok(())
+}
+
+/// Step through `stages` (addresses per stage), splitting `run_duration` equally
+///
+/// Stage boundaries are relative to the (possibly backdated) run start, so a
+/// resumed run skips the stages that already ran.
+pub async fn run_stages(
+    stages: Vec<usize>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    run_duration: Duration,
+) -> Vec<StageReport> {
+    let stage_duration = run_duration / stages.len().max(1) as u32;
+    let mut reports = Vec::with_capacity(stages.len());
+    for (stage, &addresses) in stages.iter().enumerate() {
+        let stage_end = stage_duration * (stage as u32 + 1);
+        if stats.elapsed() >= stage_end {
+            continue;
+        }
+        stats.stages.stage.store(stage, Ordering::Relaxed);
+        stats.stages.active.store(addresses, Ordering::Relaxed);
+        info!("Address stage {}/{}: {} sending addresses", stage + 1, stages.len(), addresses);
+        let start = Timestamp::now(&stats);
+        let before = CounterSnapshot::read(&stats);
+        while running.load(Ordering::Relaxed) && stats.elapsed() < stage_end {
+            stats.clock.sleep(Duration::from_millis(250).min(stage_end.saturating_sub(stats.elapsed()))).await;
+        }
+        let end = Timestamp::now(&stats);
+        let counters = CounterSnapshot::read(&stats).since(&before);
+        let secs = end.elapsed_secs - start.elapsed_secs;
+        let latency = stats.stages.latency.lock().unwrap().remove(&stage).unwrap_or_default();
+        reports.push(StageReport {
+            stage,
+            addresses,
+            start,
+            end,
+            counters,
+            tps: if secs > 0.0 { counters.tx_success as f64 / secs } else { 0.0 },
+            latency: latency.summary(),
+        });
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn stages_must_fit_the_workers_and_a_fixed_rate() {
+        assert!(validate(&[], 4, 0).is_ok());
+        assert!(validate(&[1, 2, 4], 4, 1000).is_ok());
+        assert!(validate(&[1, 8], 4, 1000).is_err());
+        assert!(validate(&[0, 2], 4, 1000).is_err());
+        assert!(validate(&[1, 2], 4, 0).is_err());
+    }
+
+    #[test]
+    fn each_stage_gets_its_share_of_the_run() {
+        let clock = VirtualClock::new();
+        let stats = Arc::new(BenchStats::new(4, clock.clone()));
+        let running = Arc::new(AtomicBool::new(true));
+        {
+            // One transaction and one latency sample per 250ms nap
+            let stats = stats.clone();
+            clock.on_sleep(move |_| {
+                stats.tx_success.add(0, 1);
+                stats.stages.record_latency(Duration::from_millis(10 * stats.stages.active() as u64));
+            });
+        }
+
+        let reports = futures::executor::block_on(run_stages(vec![1, 2, 4], stats.clone(), running, Duration::from_secs(30)));
+
+        assert_eq!(reports.iter().map(|r| r.addresses).collect::<Vec<_>>(), vec![1, 2, 4]);
+        for (report, end) in reports.iter().zip([10.0, 20.0, 30.0]) {
+            assert_eq!((report.end.elapsed_secs, report.counters.tx_success, report.tps), (end, 40, 4.0));
+        }
+        assert_eq!(reports[2].latency.max_ms, 40.0);
+        assert_eq!(stats.stages.active(), 4);
+    }
+
+    #[test]
+    fn a_resumed_run_skips_the_stages_already_run() {
+        let clock = VirtualClock::new();
+        let stats = Arc::new(BenchStats::new(4, clock.clone()));
+        clock.advance(Duration::from_secs(15));
+
+        let reports = futures::executor::block_on(run_stages(vec![1, 2, 4], stats, Arc::new(AtomicBool::new(true)), Duration::from_secs(30)));
+
+        assert_eq!(reports.iter().map(|r| (r.stage, r.start.elapsed_secs)).collect::<Vec<_>>(), vec![(1, 15.0), (2, 20.0)]);
+    }
+}
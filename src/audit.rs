@@ -0,0 +1,259 @@
+// Post-run effects audit
+//
+// Re-fetches effects for every digest recorded with --tx-digests and builds
+// an authoritative cost report from chain data alone: success/failure status,
+// checkpoint inclusion, and computation/storage/rebate totals. Comparing it
+// with the live-run results (--results) catches client-side accounting bugs.
+
+use anyhow::{Context, Result};
+use clap::Args as ClapArgs;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use sui_sdk::rpc_types::{
+    SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::types::base_types::TransactionDigest;
+use sui_sdk::types::gas_coin::MIST_PER_SUI;
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use tracing::{info, warn};
+
+use crate::tx_log::{read_tx_log, TxRecord};
+use crate::Args;
+
+/// Digests per multi-get request
+const AUDIT_BATCH_SIZE: usize = 50;
+
+/// Arguments for the `audit` subcommand
+#[derive(ClapArgs, Debug, Clone)]
+pub struct AuditArgs {
+    /// Digest log written by --tx-digests (JSONL or one digest per line)
+    #[clap(long)]
+    tx_digests: String,
+
+    /// Results JSON of the audited run, to cross-check live-run accounting
+    #[clap(long)]
+    results: Option<String>,
+
+    /// Write the audit report (JSON) to this file
+    #[clap(long)]
+    report: Option<String>,
+}
+
+/// Gas totals in MIST
+#[derive(Debug, Clone, Default, Serialize)]
+struct GasTotals {
+    computation_cost: u64,
+    storage_cost: u64,
+    storage_rebate: u64,
+    non_refundable_storage_fee: u64,
+}
+
+impl GasTotals {
+    fn add(&mut self, summary: &sui_sdk::types::gas::GasCostSummary) {
+        self.computation_cost += summary.computation_cost;
+        self.storage_cost += summary.storage_cost;
+        self.storage_rebate += summary.storage_rebate;
+        self.non_refundable_storage_fee += summary.non_refundable_storage_fee;
+    }
+
+    /// Net MIST spent: computation + storage - rebate
+    fn net(&self) -> i64 {
+        (self.computation_cost + self.storage_cost) as i64 - self.storage_rebate as i64
+    }
+}
+
+/// Per-op-type breakdown
+#[derive(Debug, Clone, Default, Serialize)]
+struct OpAudit {
+    transactions: u64,
+    success: u64,
+    gas: GasTotals,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct AuditReport {
+    recorded: u64,
+    found: u64,
+    missing: u64,
+    success: u64,
+    failed: u64,
+    in_checkpoint: u64,
+    not_in_checkpoint: u64,
+    gas: GasTotals,
+    net_gas_mist: i64,
+    net_gas_sui: f64,
+    by_op: BTreeMap<String, OpAudit>,
+    /// Live-run tx_success from --results, for comparison
+    live_tx_success: Option<u64>,
+    /// audited success - live tx_success (non-zero means an accounting discrepancy)
+    success_discrepancy: Option<i64>,
+    missing_digests: Vec<String>,
+    failed_digests: Vec<String>,
+}
+
+/// Re-query all recorded digests and print/write the cost report
+pub async fn run_audit(base: Args, audit: AuditArgs) -> Result<()> {
+    let records = read_tx_log(&audit.tx_digests)?;
+    info!("Audit: {} recorded transactions in {}", records.len(), audit.tx_digests);
+
+    let client = SuiClientBuilder::default()
+        .build(&base.rpc_url)
+        .await
+        .context("Failed to connect to SUI node")?;
+
+    let mut report = AuditReport {
+        recorded: records.len() as u64,
+        ..Default::default()
+    };
+
+    for (i, chunk) in records.chunks(AUDIT_BATCH_SIZE).enumerate() {
+        let responses = fetch_batch(&client, chunk).await;
+        for (record, response) in chunk.iter().zip(responses) {
+            apply_response(&mut report, record, response);
+        }
+        if (i + 1) % 20 == 0 {
+            info!("Audit: {}/{} checked", ((i + 1) * AUDIT_BATCH_SIZE).min(records.len()), records.len());
+        }
+    }
+
+    report.net_gas_mist = report.gas.net();
+    report.net_gas_sui = report.net_gas_mist as f64 / MIST_PER_SUI as f64;
+
+    if let Some(results_path) = &audit.results {
+        report.live_tx_success = Some(crate::results::load(results_path)?.tx_success);
+        report.success_discrepancy = report.live_tx_success
+            .map(|live| report.success as i64 - live as i64);
+    }
+
+    info!("═══════════════════════════════════════════════════════════════");
+    info!("  AUDIT REPORT");
+    info!("═══════════════════════════════════════════════════════════════");
+    info!("Recorded: {} | Found: {} | Missing: {}", report.recorded, report.found, report.missing);
+    info!("Success: {} | Failed: {} | In checkpoint: {} | Not in checkpoint: {}",
+        report.success, report.failed, report.in_checkpoint, report.not_in_checkpoint);
+    info!("Gas (MIST): computation {} | storage {} | rebate {} | non-refundable {}",
+        report.gas.computation_cost, report.gas.storage_cost,
+        report.gas.storage_rebate, report.gas.non_refundable_storage_fee);
+    info!("Net gas: {} MIST ({:.4} SUI)", report.net_gas_mist, report.net_gas_sui);
+    for (op, o) in &report.by_op {
+        info!("  {:<12} {} txs, {} success, net {} MIST", op, o.transactions, o.success, o.gas.net());
+    }
+    if let Some(discrepancy) = report.success_discrepancy {
+        if discrepancy != 0 {
+            warn!("Accounting discrepancy: audited success differs from live tx_success by {}", discrepancy);
+        } else {
+            info!("Live-run tx_success matches audited success");
+        }
+    }
+
+    if let Some(path) = &audit.report {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        info!("Audit report written to {}", path);
+    }
+
+    Ok(())
+}
+
+/// Fetch effects for a chunk; falls back to single gets if the batch call fails
+async fn fetch_batch(client: &SuiClient, chunk: &[TxRecord]) -> Vec<Option<SuiTransactionBlockResponse>> {
+    let options = || SuiTransactionBlockResponseOptions::new().with_effects();
+    let digests: Vec<Option<TransactionDigest>> = chunk.iter()
+        .map(|r| TransactionDigest::from_str(&r.digest).ok())
+        .collect();
+
+    if digests.iter().all(Option::is_some) {
+        let valid: Vec<TransactionDigest> = digests.iter().flatten().copied().collect();
+        if let Ok(responses) = client.read_api()
+            .multi_get_transactions_with_options(valid, options())
+            .await
+        {
+            if responses.len() == chunk.len() {
+                return responses.into_iter().map(Some).collect();
+            }
+        }
+    }
+
+    // Slow path: one request per digest so a single missing tx doesn't hide the rest
+    let mut responses = Vec::with_capacity(chunk.len());
+    for digest in digests {
+        let response = match digest {
+            Some(d) => client.read_api().get_transaction_with_options(d, options()).await.ok(),
+            None => None,
+        };
+        responses.push(response);
+    }
+    responses
+}
+
+fn apply_response(report: &mut AuditReport, record: &TxRecord, response: Option<SuiTransactionBlockResponse>) {
+    let op = report.by_op.entry(record.op.clone()).or_default();
+    op.transactions += 1;
+
+    let Some(response) = response else {
+        report.missing += 1;
+        report.missing_digests.push(record.digest.clone());
+        return;
+    };
+    let Some(effects) = &response.effects else {
+        report.missing += 1;
+        report.missing_digests.push(record.digest.clone());
+        return;
+    };
+
+    report.found += 1;
+    if response.checkpoint.is_some() {
+        report.in_checkpoint += 1;
+    } else {
+        report.not_in_checkpoint += 1;
+    }
+
+    let gas = effects.gas_cost_summary();
+    report.gas.add(gas);
+    op.gas.add(gas);
+
+    if effects.status().is_ok() {
+        report.success += 1;
+        op.success += 1;
+    } else {
+        report.failed += 1;
+        report.failed_digests.push(record.digest.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::gas::GasCostSummary;
+
+    #[test]
+    fn net_gas_subtracts_the_rebate() {
+        let mut totals = GasTotals::default();
+        totals.add(&GasCostSummary::new(1_000, 5_000, 4_000, 40));
+        totals.add(&GasCostSummary::new(1_000, 0, 3_000, 30));
+
+        assert_eq!((totals.computation_cost, totals.storage_cost, totals.storage_rebate), (2_000, 5_000, 7_000));
+        assert_eq!(totals.non_refundable_storage_fee, 70);
+        assert_eq!(totals.net(), 0);
+    }
+
+    #[test]
+    fn a_digest_without_a_response_is_missing() {
+        let mut report = AuditReport::default();
+        let record = TxRecord {
+            digest: "not-a-digest".to_string(),
+            worker: 1,
+            op: "update".to_string(),
+            elapsed_ms: 0,
+            unix_ms: 0,
+        };
+
+        apply_response(&mut report, &record, None);
+
+        assert_eq!((report.found, report.missing), (0, 1));
+        assert_eq!(report.missing_digests, vec!["not-a-digest".to_string()]);
+        assert_eq!(report.by_op["update"].transactions, 1);
+    }
+}
@@ -0,0 +1,181 @@
+// Worker state snapshots (--save-objects, --autosave)
+//
+// Both the end-of-run save and the periodic autosave write the same
+// SavedBenchmarkState format, so an autosave file can also be passed to
+// --load-objects. Writes go to a temp file and are renamed into place so a
+// crash mid-write never leaves a truncated state file behind.
+//
+// During the load phase a run journal (<autosave>.journal.jsonl) gets one line
+// of cumulative counters per autosave, so --resume can restore stats and the
+// remaining duration along with the worker state.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Arc;
+use sui_sdk::types::crypto::EncodeDecodeBase64;
+use tokio::sync::RwLock;
+
+use crate::{BenchStats, SavedBenchmarkState, SavedWorkerObjects, WorkerState};
+
+/// Capture keypairs (unless `keys` is false) and tracked objects of all workers
+pub async fn snapshot_state(workers: &[Arc<RwLock<WorkerState>>], keys: bool) -> SavedBenchmarkState {
+    let mut saved_workers = Vec::with_capacity(workers.len());
+    let mut total_objects = 0usize;
+
+    for worker in workers {
+        let state = worker.read().await;
+        // Quarantined objects are still owned; the next phase re-reads them anyway
+        let objects: Vec<_> = state.objects.iter().cloned()
+            .chain(state.quarantine.iter().map(|q| q.object.clone()))
+            .chain(state.cold.iter().cloned())
+            .collect();
+        total_objects += objects.len();
+        saved_workers.push(SavedWorkerObjects {
+            worker_id: state.id,
+            address: state.address,
+            // Encode keypair to base64 for portability (left out with --save-without-keys)
+            keypair_base64: if keys { state.keypair.encode_base64() } else { String::new() },
+            objects,
+        });
+    }
+
+    SavedBenchmarkState {
+        total_objects,
+        workers: saved_workers,
+    }
+}
+
+/// Atomically write a state file (temp file + rename)
+pub fn write_state(path: &str, state: &SavedBenchmarkState) -> Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    let json = serde_json::to_string_pretty(state)?;
+    std::fs::write(&tmp_path, json)
+        .context(format!("Failed to write state file: {}", tmp_path))?;
+    std::fs::rename(&tmp_path, path)
+        .context(format!("Failed to move state file into place: {}", path))?;
+    Ok(())
+}
+
+/// Read a state file written by write_state (or --save-objects)
+pub fn read_state(path: &str) -> Result<SavedBenchmarkState> {
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read state file: {}", path))?;
+    serde_json::from_str(&content).context(format!("Failed to parse state file: {}", path))
+}
+
+/// Snapshot and write in one step
+pub async fn save_workers(path: &str, workers: &[Arc<RwLock<WorkerState>>], keys: bool) -> Result<SavedBenchmarkState> {
+    let state = snapshot_state(workers, keys).await;
+    write_state(path, &state)?;
+    Ok(state)
+}
+
+/// Cumulative run counters at one autosave point (one journal line)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Seconds of load phase completed, including earlier resumed segments
+    pub elapsed_secs: f64,
+    pub tx_submitted: u64,
+    pub tx_success: u64,
+    pub tx_failed: u64,
+    pub objects_created: u64,
+    pub objects_updated: u64,
+    pub gas_spent: u64,
+    #[serde(default)]
+    pub gas_charged: u64,
+    #[serde(default)]
+    pub gas_rebated: u64,
+    /// Set by the final entry of a run that reached its deadline or its gas cap
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl JournalEntry {
+    pub fn from_stats(stats: &BenchStats, completed: bool) -> Self {
+        Self {
+            elapsed_secs: stats.elapsed().as_secs_f64(),
+            tx_submitted: stats.tx_submitted.get(),
+            tx_success: stats.tx_success.get(),
+            tx_failed: stats.tx_failed.get(),
+            objects_created: stats.objects_created.get(),
+            objects_updated: stats.objects_updated.get(),
+            gas_spent: stats.gas_spent.get(),
+            gas_charged: stats.gas_charged.get(),
+            gas_rebated: stats.gas_rebated.get(),
+            completed,
+        }
+    }
+}
+
+/// Journal file that accompanies an autosave state file
+pub fn journal_path(autosave_path: &str) -> String {
+    format!("{}.journal.jsonl", autosave_path)
+}
+
+/// Append one journal line (fsynced: the point is surviving power loss)
+pub fn append_journal(autosave_path: &str, entry: &JournalEntry) -> Result<()> {
+    let path = journal_path(autosave_path);
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!("Failed to open run journal: {}", path))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// Latest parseable journal entry (a torn last line is skipped)
+pub fn last_journal_entry(autosave_path: &str) -> Result<Option<JournalEntry>> {
+    let path = journal_path(autosave_path);
+    if !std::path::Path::new(&path).exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .context(format!("Failed to read run journal: {}", path))?;
+    Ok(content.lines().rev().find_map(|line| serde_json::from_str(line).ok()))
+}
+
+/// State + journal checkpoint of a running benchmark
+pub async fn checkpoint_run(path: &str, workers: &[Arc<RwLock<WorkerState>>], stats: &BenchStats, completed: bool, keys: bool) -> Result<()> {
+    save_workers(path, workers, keys).await?;
+    append_journal(path, &JournalEntry::from_stats(stats, completed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_files_round_trip_without_leaving_a_temp_file() {
+        let dir = std::env::temp_dir().join(format!("fdp-autosave-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json").to_string_lossy().into_owned();
+        let state = SavedBenchmarkState { total_objects: 0, workers: Vec::new() };
+
+        write_state(&path, &state).unwrap();
+
+        assert_eq!(read_state(&path).unwrap().total_objects, 0);
+        assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn the_last_whole_journal_line_wins_over_a_torn_one() {
+        let dir = std::env::temp_dir().join(format!("fdp-autosave-journal-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json").to_string_lossy().into_owned();
+        assert!(last_journal_entry(&path).unwrap().is_none());
+
+        for (elapsed_secs, completed) in [(60.0, false), (120.0, true)] {
+            append_journal(&path, &JournalEntry { elapsed_secs, tx_success: elapsed_secs as u64, completed, ..Default::default() }).unwrap();
+        }
+        let mut journal = std::fs::OpenOptions::new().append(true).open(journal_path(&path)).unwrap();
+        write!(journal, "{{\"elapsed_secs\":180.0,\"tx_sub").unwrap();
+
+        let last = last_journal_entry(&path).unwrap().unwrap();
+        assert_eq!((last.elapsed_secs, last.tx_success, last.completed), (120.0, 120, true));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+// Batch-size backoff after repeated failures (--batch-backoff-after, --batch-regrow-after)
+//
+// Some failures grow with the batch. A full batch runs out of gas, or its
+// effects exceed a size limit, or it contains one object that aborts
+// deterministically. Resending the same batch size keeps failing, and the run
+// spends its whole duration on failed transactions. With
+// --batch-backoff-after N, a worker whose transactions fail N times in a row
+// with the same class of failure halves its batch size. The class is insufficient
+// gas, a size limit, or a Move abort at a given command. Halving repeats, down
+// to single objects. After --batch-regrow-after successes in a row, the batch
+// doubles again, up to the configured --batch-size. Failures that don't depend on
+// the batch (timeouts, lock conflicts, stale refs) are ignored. Only built-in
+// and --workload transactions use the reduced size. The `batch_backoff` section
+// counts shrinks per class, regrowths, and the smallest batch any worker
+// dropped to.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::command_failures::CommandFailure;
+
+/// Failure reasons that a smaller batch can avoid
+const BATCH_REASONS: &[&str] = &[
+    "InsufficientGas",
+    "SizeLimitExceeded",
+    "EffectsTooLarge",
+    "WrittenObjectsTooLarge",
+    "MoveAbort",
+];
+
+/// The batch-dependent class of a failure, e.g. "MoveAbort(update_blob, 3) in command 4"
+pub fn class_of(err: &anyhow::Error) -> Option<String> {
+    let failure = CommandFailure::parse(&format!("{:#}", err))?;
+    if !BATCH_REASONS.iter().any(|r| failure.reason.starts_with(r)) {
+        return None;
+    }
+    Some(match failure.command {
+        Some(command) if failure.reason.starts_with("MoveAbort") => format!("{} in command {}", failure.reason, command),
+        _ => failure.reason,
+    })
+}
+
+/// One worker's reduction of its batch size
+#[derive(Debug)]
+pub struct BatchBackoff {
+    after: u32,
+    regrow_after: u32,
+    /// Halvings currently in effect
+    shift: u32,
+    class: Option<String>,
+    repeats: u32,
+    successes: u32,
+}
+
+impl BatchBackoff {
+    pub fn new(after: u32, regrow_after: u32) -> Self {
+        Self { after, regrow_after, shift: 0, class: None, repeats: 0, successes: 0 }
+    }
+
+    /// The batch size to use instead of `configured`
+    pub fn batch_size(&self, configured: usize) -> usize {
+        configured.checked_shr(self.shift).unwrap_or(0).max(1)
+    }
+
+    /// Count a failure; returns its class if the batch was halved because of it
+    pub fn failure(&mut self, class: Option<String>, configured: usize) -> Option<String> {
+        let class = class?;
+        self.successes = 0;
+        if self.class.as_ref() == Some(&class) {
+            self.repeats += 1;
+        } else {
+            self.class = Some(class.clone());
+            self.repeats = 1;
+        }
+        if self.repeats < self.after || self.batch_size(configured) <= 1 {
+            return None;
+        }
+        self.shift += 1;
+        self.repeats = 0;
+        Some(class)
+    }
+
+    /// Count a success; true if the batch was doubled back
+    pub fn success(&mut self) -> bool {
+        self.class = None;
+        self.repeats = 0;
+        if self.shift == 0 {
+            return false;
+        }
+        self.successes += 1;
+        if self.successes < self.regrow_after {
+            return false;
+        }
+        self.shift -= 1;
+        self.successes = 0;
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct BackoffStats {
+    shrinks: Mutex<BTreeMap<String, u64>>,
+    regrows: AtomicU64,
+    smallest_batch: AtomicUsize,
+}
+
+impl Default for BackoffStats {
+    fn default() -> Self {
+        Self { shrinks: Mutex::default(), regrows: AtomicU64::new(0), smallest_batch: AtomicUsize::new(usize::MAX) }
+    }
+}
+
+impl BackoffStats {
+    /// Count a worker's failed transaction against its backoff (if enabled)
+    pub fn failure(&self, worker_id: usize, backoff: Option<&mut BatchBackoff>, err: &anyhow::Error, configured: usize) {
+        let Some(backoff) = backoff else {
+            return;
+        };
+        let before = backoff.batch_size(configured);
+        if let Some(class) = backoff.failure(class_of(err), configured) {
+            let after = backoff.batch_size(configured);
+            warn!("Worker {}: {} failures in a row of {}; batch size {} -> {}", worker_id, backoff.after, class, before, after);
+            *self.shrinks.lock().unwrap().entry(class).or_default() += 1;
+            self.smallest_batch.fetch_min(after, Ordering::Relaxed);
+        }
+    }
+
+    /// Count a worker's successful transaction against its backoff (if enabled)
+    pub fn success(&self, worker_id: usize, backoff: Option<&mut BatchBackoff>, configured: usize) {
+        let Some(backoff) = backoff else {
+            return;
+        };
+        if backoff.success() {
+            info!("Worker {}: batch size back up to {}", worker_id, backoff.batch_size(configured));
+            self.regrows.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn report(&self, after: u32, regrow_after: u32) -> BackoffReport {
+        let shrinks_by_class = self.shrinks.lock().unwrap().clone();
+        let smallest = self.smallest_batch.load(Ordering::Relaxed);
+        BackoffReport {
+            after,
+            regrow_after,
+            shrinks: shrinks_by_class.values().sum(),
+            shrinks_by_class,
+            regrows: self.regrows.load(Ordering::Relaxed),
+            smallest_batch: (smallest != usize::MAX).then_some(smallest),
+        }
+    }
+}
+
+/// `batch_backoff` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct BackoffReport {
+    pub after: u32,
+    pub regrow_after: u32,
+    /// Batch halvings, in total and per failure class
+    pub shrinks: u64,
+    pub shrinks_by_class: BTreeMap<String, u64>,
+    pub regrows: u64,
+    /// Smallest batch any worker backed off to (None if none did)
+    pub smallest_batch: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_class_halves_and_successes_regrow() {
+        let abort = || class_of(&anyhow::anyhow!("MoveAbort in command 2, abort code 1 (mock backend)"));
+        assert_eq!(abort().as_deref(), Some("MoveAbort in command 2"));
+        assert_eq!(class_of(&anyhow::anyhow!("request timed out")), None);
+
+        let mut backoff = BatchBackoff::new(3, 2);
+        assert_eq!(backoff.failure(abort(), 50), None);
+        assert_eq!(backoff.failure(abort(), 50), None);
+        // A different class starts the count over
+        assert_eq!(backoff.failure(class_of(&anyhow::anyhow!("InsufficientGas")), 50), None);
+        for _ in 0..2 {
+            assert_eq!(backoff.failure(abort(), 50), None);
+        }
+        assert!(backoff.failure(abort(), 50).is_some());
+        assert_eq!(backoff.batch_size(50), 25);
+
+        assert!(!backoff.success());
+        assert!(backoff.success());
+        assert_eq!(backoff.batch_size(50), 50);
+    }
+}
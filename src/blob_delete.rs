@@ -0,0 +1,113 @@
+// Blob deletion workload (--workload delete_blob=<pct>, with --use-blobs)
+//
+// Deletes a batch of the worker's tracked blobs with io_churn::delete_blob.
+// Deleted objects leave tombstones in the node's object store until
+// compaction drops them, a write pattern the create/update mix never
+// produces. Creates keep refilling the pool; a worker deletes only while it
+// has more than two batches of blobs, so updates always find objects.
+
+use anyhow::{anyhow, Result};
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::ObjectArg;
+use sui_sdk::types::Identifier;
+
+use crate::validate::Percent;
+use crate::workload::{BuildContext, Effects, Planned, Workload};
+use crate::{Args, TrackedObject, TxOutcome, WorkerState, MAX_BLOB_BATCH};
+
+pub struct DeleteBlobs {
+    pct: Percent,
+}
+
+impl DeleteBlobs {
+    pub fn create(args: &Args, pct: Percent) -> Result<Arc<dyn Workload>> {
+        if !args.use_blobs {
+            return Err(anyhow!("--workload delete_blob needs --use-blobs (counters can't be deleted)"));
+        }
+        Ok(Arc::new(Self { pct }))
+    }
+
+    fn batch(state: &WorkerState, ctx: &BuildContext) -> usize {
+        let batch = ctx.batch_size.min(MAX_BLOB_BATCH);
+        state.objects.len().saturating_sub(2 * batch).min(batch)
+    }
+}
+
+impl Workload for DeleteBlobs {
+    fn name(&self) -> &'static str {
+        "delete_blob"
+    }
+
+    fn weight(&self) -> Percent {
+        self.pct
+    }
+
+    fn ready(&self, state: &WorkerState, ctx: &BuildContext) -> bool {
+        ctx.use_blobs && Self::batch(state, ctx) > 0
+    }
+
+    fn build_ptb(&self, state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        let count = Self::batch(state, ctx);
+        if count == 0 {
+            return Err(anyhow!("Not enough objects to delete"));
+        }
+        let start = rand::rngs::StdRng::from_entropy().gen_range(0..state.objects.len());
+        let mut batch = Vec::with_capacity(count);
+        for i in 0..count {
+            let obj = &state.objects[(start + i) % state.objects.len()];
+            let obj_arg = builder.obj(ObjectArg::ImmOrOwnedObject((obj.id, obj.version.into(), obj.digest)))?;
+            builder.programmable_move_call(
+                ctx.package_id,
+                Identifier::new("io_churn").unwrap(),
+                Identifier::new("delete_blob").unwrap(),
+                vec![],
+                vec![obj_arg],
+            );
+            batch.push(obj.id);
+        }
+        state.last_batch = batch;
+        state.batch_stride = 1;
+        Ok(Planned { objects: count, ..Planned::default() })
+    }
+
+    fn apply_effects(&self, objects: &mut Vec<TrackedObject>, effects: &Effects, _planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        let before = objects.len();
+        objects.retain(|o| !effects.deleted.contains(&o.id));
+        outcome.deleted = (before - objects.len()) as u64;
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID, TransactionDigest};
+
+    #[test]
+    fn deleting_needs_blobs() {
+        let pct = Percent::try_from(10).unwrap();
+        assert!(DeleteBlobs::create(&Args::try_parse_from(["fdp-sui-bench"]).unwrap(), pct).is_err());
+        let workload = DeleteBlobs::create(&Args::try_parse_from(["fdp-sui-bench", "--use-blobs"]).unwrap(), pct).unwrap();
+        assert_eq!((workload.name(), workload.weight()), ("delete_blob", pct));
+    }
+
+    #[test]
+    fn deleted_objects_leave_the_tracker() {
+        let mut objects: Vec<TrackedObject> = (0..4)
+            .map(|n| TrackedObject { id: ObjectID::from_single_byte(n), version: 1, digest: ObjectDigest::random(), size: 4096, origin: None, updates: 0 })
+            .collect();
+        let digest = TransactionDigest::new([1; 32]);
+        let mut effects = Effects::empty(digest);
+        effects.deleted = vec![ObjectID::from_single_byte(1), ObjectID::from_single_byte(3), ObjectID::from_single_byte(9)];
+        let mut outcome = TxOutcome::empty(digest);
+
+        let evicted = DeleteBlobs { pct: Percent::default() }.apply_effects(&mut objects, &effects, Planned::default(), &mut outcome);
+
+        assert!(evicted.is_empty());
+        assert_eq!(outcome.deleted, 2);
+        assert_eq!(objects.iter().map(|o| o.id).collect::<Vec<_>>(), vec![ObjectID::from_single_byte(0), ObjectID::from_single_byte(2)]);
+    }
+}
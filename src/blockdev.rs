@@ -0,0 +1,154 @@
+// Block device sampler (--sample-block-devs)
+//
+// Reads /sys/block/<dev>/stat at every stats interval for the configured
+// devices, iostat-style, and embeds per-device timelines in the results so
+// physical I/O can be lined up with the workload without merging files from a
+// separately run iostat.
+//
+// /sys/block/<dev>/stat fields (Documentation/block/stat.rst):
+//   read_ios read_merges read_sectors read_ticks
+//   write_ios write_merges write_sectors write_ticks
+//   in_flight io_ticks time_in_queue [discard... flush...]
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::timeline::{Ticker, Timestamp};
+use crate::BenchStats;
+
+/// Sectors in /sys/block stat are always 512 bytes, regardless of device block size
+const SECTOR_BYTES: u64 = 512;
+
+/// Cumulative counters of one device at one point in time
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct DevStat {
+    pub read_ios: u64,
+    pub read_sectors: u64,
+    pub read_ticks_ms: u64,
+    pub write_ios: u64,
+    pub write_sectors: u64,
+    pub write_ticks_ms: u64,
+    pub in_flight: u64,
+    pub io_ticks_ms: u64,
+    pub time_in_queue_ms: u64,
+}
+
+impl DevStat {
+    fn parse(line: &str) -> Result<Self> {
+        let f: Vec<u64> = line.split_whitespace()
+            .map(|v| v.parse::<u64>())
+            .collect::<Result<_, _>>()
+            .context("Malformed block stat line")?;
+        if f.len() < 11 {
+            return Err(anyhow!("Block stat line has {} fields, expected >= 11", f.len()));
+        }
+        Ok(Self {
+            read_ios: f[0],
+            read_sectors: f[2],
+            read_ticks_ms: f[3],
+            write_ios: f[4],
+            write_sectors: f[6],
+            write_ticks_ms: f[7],
+            in_flight: f[8],
+            io_ticks_ms: f[9],
+            time_in_queue_ms: f[10],
+        })
+    }
+
+    fn read(dev: &str) -> Result<Self> {
+        let path = format!("/sys/block/{}/stat", dev);
+        let line = std::fs::read_to_string(&path).context(format!("Failed to read {}", path))?;
+        Self::parse(&line)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DevSample {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    #[serde(flatten)]
+    pub stat: DevStat,
+}
+
+/// Per-device timeline plus start-to-end deltas
+#[derive(Debug, Clone, Serialize)]
+pub struct DevReport {
+    pub samples: Vec<DevSample>,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_ios: u64,
+    pub write_ios: u64,
+    pub time_in_queue_ms: u64,
+}
+
+/// Check the devices exist before the run starts
+pub fn validate(devs: &[String]) -> Result<()> {
+    for dev in devs {
+        DevStat::read(dev)?;
+    }
+    Ok(())
+}
+
+/// Sample all devices every stats interval until `running` is cleared (one final sample at the end)
+pub async fn run_block_sampler(
+    devs: Vec<String>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    ticker: Ticker,
+) -> BTreeMap<String, DevReport> {
+    let mut timelines: BTreeMap<String, Vec<DevSample>> = devs.iter().map(|d| (d.clone(), Vec::new())).collect();
+    let sample_all = |timelines: &mut BTreeMap<String, Vec<DevSample>>| {
+        for (dev, samples) in timelines.iter_mut() {
+            match DevStat::read(dev) {
+                Ok(stat) => samples.push(DevSample { at: Timestamp::now(&stats), stat }),
+                Err(e) => warn!("Block sampler: {}", e),
+            }
+        }
+    };
+
+    sample_all(&mut timelines);
+    while running.load(Ordering::Relaxed) {
+        ticker.tick(stats.clock.as_ref()).await;
+        sample_all(&mut timelines);
+    }
+
+    timelines.into_iter()
+        .map(|(dev, samples)| {
+            let (first, last) = match (samples.first(), samples.last()) {
+                (Some(f), Some(l)) => (f.stat, l.stat),
+                _ => (DevStat::default(), DevStat::default()),
+            };
+            let report = DevReport {
+                read_bytes: last.read_sectors.saturating_sub(first.read_sectors) * SECTOR_BYTES,
+                write_bytes: last.write_sectors.saturating_sub(first.write_sectors) * SECTOR_BYTES,
+                read_ios: last.read_ios.saturating_sub(first.read_ios),
+                write_ios: last.write_ios.saturating_sub(first.write_ios),
+                time_in_queue_ms: last.time_in_queue_ms.saturating_sub(first.time_in_queue_ms),
+                samples,
+            };
+            (dev, report)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_lines_parse_with_or_without_discard_fields() {
+        let line = "  4512  120  98304  2210  88110  3021  7340032  91544  3  60120  93754  0  0  0  0\n";
+        let stat = DevStat::parse(line).unwrap();
+        assert_eq!((stat.read_ios, stat.read_sectors, stat.read_ticks_ms), (4512, 98304, 2210));
+        assert_eq!((stat.write_ios, stat.write_sectors, stat.write_ticks_ms), (88110, 7340032, 91544));
+        assert_eq!((stat.in_flight, stat.io_ticks_ms, stat.time_in_queue_ms), (3, 60120, 93754));
+
+        assert!(DevStat::parse("4512 120 98304 2210 88110 3021 7340032 91544 3 60120 93754").is_ok());
+        assert!(DevStat::parse("4512 120 98304").is_err());
+        assert!(DevStat::parse("4512 120 x 2210 88110 3021 7340032 91544 3 60120 93754").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+// Local transaction building benchmark (--offline-build-bench)
+//
+// Measures how many PTBs per second this machine can construct and sign with
+// no node involved: one OS thread per --workers builds the same create/update
+// transactions the load phase does (against synthetic object refs) and signs
+// them. If this rate isn't well above the TPS seen in a real run, the load
+// generator is the bottleneck, not the node or the disk.
+
+use anyhow::Result;
+use rand::{Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_sdk::types::{
+    base_types::{ObjectDigest, ObjectID, SequenceNumber},
+    crypto::{get_key_pair, AccountKeyPair, SuiKeyPair},
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{ObjectArg, Transaction, TransactionData},
+    Identifier,
+};
+use tracing::info;
+
+use crate::Args;
+
+#[derive(Default)]
+struct ThreadResult {
+    built: u64,
+    build_time: Duration,
+    sign_time: Duration,
+}
+
+fn build_loop(args: &Args, package_id: ObjectID, stop: &AtomicBool) -> Result<ThreadResult> {
+    let mut rng = rand::rngs::StdRng::from_entropy();
+    let (address, keypair): (_, AccountKeyPair) = get_key_pair();
+    let keypair = SuiKeyPair::Ed25519(keypair);
+    let gas = (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+    let objects: Vec<_> = (0..args.batch_size.max(1))
+        .map(|_| (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random()))
+        .collect();
+
+    let mut result = ThreadResult::default();
+    while !stop.load(Ordering::Relaxed) {
+        let t0 = Instant::now();
+        let mut builder = ProgrammableTransactionBuilder::new();
+        if rng.gen_range(0..100) < args.create_pct.get() as u32 {
+            let count_arg = builder.pure(args.batch_size as u64)?;
+            builder.programmable_move_call(
+                package_id,
+                Identifier::new("io_churn")?,
+                Identifier::new("create_batch")?,
+                vec![],
+                vec![count_arg],
+            );
+        } else {
+            for obj in &objects {
+                let obj_arg = builder.obj(ObjectArg::ImmOrOwnedObject(*obj))?;
+                builder.programmable_move_call(
+                    package_id,
+                    Identifier::new("io_churn")?,
+                    Identifier::new("increment_simple")?,
+                    vec![],
+                    vec![obj_arg],
+                );
+            }
+        }
+        let tx_data = TransactionData::new_programmable(address, vec![gas], builder.finish(), args.gas_budget, 1000);
+        let t1 = Instant::now();
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&keypair]);
+        std::hint::black_box(&tx);
+        let t2 = Instant::now();
+
+        result.built += 1;
+        result.build_time += t1 - t0;
+        result.sign_time += t2 - t1;
+    }
+    Ok(result)
+}
+
+/// Run the offline build benchmark for --offline-build-secs and return the result JSON
+pub fn run_build_bench(args: &Args) -> Result<serde_json::Value> {
+    let package_id = match &args.package_id {
+        Some(id) => ObjectID::from_hex_literal(id)?,
+        None => ObjectID::random(),
+    };
+    let threads = args.workers.max(1);
+    let duration = Duration::from_secs(args.offline_build_secs);
+    info!("Offline build benchmark: {} threads, {}s, batch size {}, {}% creates",
+        threads, duration.as_secs(), args.batch_size, args.create_pct);
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let started = Instant::now();
+    let results = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                let stop = &stop;
+                scope.spawn(move || build_loop(args, package_id, stop))
+            })
+            .collect();
+        std::thread::sleep(duration);
+        stop.store(true, Ordering::Relaxed);
+        handles.into_iter()
+            .map(|h| h.join().map_err(|p| anyhow::anyhow!("Build thread panicked: {}", crate::panic_message(p)))?)
+            .collect::<Result<Vec<_>>>()
+    })?;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let built: u64 = results.iter().map(|r| r.built).sum();
+    let build_secs: f64 = results.iter().map(|r| r.build_time.as_secs_f64()).sum();
+    let sign_secs: f64 = results.iter().map(|r| r.sign_time.as_secs_f64()).sum();
+    let per_tx_us = |secs: f64| if built > 0 { secs * 1e6 / built as f64 } else { 0.0 };
+    let tps = built as f64 / elapsed;
+    info!("Built and signed {} PTBs in {:.1}s: {:.0} tx/s ({:.1} us build + {:.1} us sign per tx per thread)",
+        built, elapsed, tps, per_tx_us(build_secs), per_tx_us(sign_secs));
+
+    Ok(serde_json::json!({
+        "mode": "offline_build_bench",
+        "duration_secs": elapsed,
+        "threads": threads,
+        "batch_size": args.batch_size,
+        "create_pct": args.create_pct.get(),
+        "transactions_built": built,
+        "build_sign_tps": tps,
+        "build_us_per_tx": per_tx_us(build_secs),
+        "sign_us_per_tx": per_tx_us(sign_secs),
+        "per_thread_tps": tps / threads as f64,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn every_thread_builds_and_signs_transactions() {
+        let args = Args::try_parse_from(["fdp-sui-bench", "--workers", "2", "--batch-size", "4", "--create-pct", "50", "--offline-build-secs", "1"]).unwrap();
+
+        let result = run_build_bench(&args).unwrap();
+
+        assert_eq!((result["threads"].as_u64(), result["batch_size"].as_u64()), (Some(2), Some(4)));
+        assert!(result["transactions_built"].as_u64().unwrap() > 0);
+        assert!(result["build_us_per_tx"].as_f64().unwrap() > 0.0);
+        assert!(result["sign_us_per_tx"].as_f64().unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,247 @@
+// io_churn entry-point discovery
+//
+// The Move contract grows with the benchmark (partial and append updates,
+// sized blobs, freezing, tags, ...), but a package published from an older
+// checkout keeps the functions it was published with. Before the run the
+// package's normalized modules are read from the node and every optional
+// feature the run asks for is checked against them: a feature whose functions
+// are missing, or have a different number of parameters, is switched off with
+// a warning naming the function, instead of failing every transaction that
+// uses it. The core create/update functions must be there. With tenants, a
+// feature stays on only if every package has it. The functions found also name
+// the contract version (abi.rs): a call the version has a stand-in for doesn't
+// count as missing. --contract-version skips the probe and takes the version's
+// functions as given, which also applies them to mock runs; otherwise mock runs
+// skip the check. The `capabilities` section lists the version, what was found
+// and what was disabled.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use sui_sdk::rpc_types::{SuiMoveNormalizedFunction, SuiMoveNormalizedType};
+use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::SuiClient;
+use tracing::{info, warn};
+
+use crate::abi::{self, ContractVersion};
+use crate::validate::Percent;
+use crate::Args;
+
+const MODULE: &str = "io_churn";
+
+/// Functions every run calls: (name, parameters besides the TxContext)
+const CORE: &[(&str, usize)] = &[("create_batch", 1), ("increment_simple", 1)];
+const CORE_BLOBS: &[(&str, usize)] = &[("create_blob_batch", 1), ("update_blob", 1)];
+
+/// A feature that needs functions beyond the core ones
+struct Feature {
+    /// How the run asks for it
+    flag: &'static str,
+    functions: &'static [(&'static str, usize)],
+    requested: fn(&Args) -> bool,
+    disable: fn(&mut Args),
+}
+
+const FEATURES: &[Feature] = &[
+    Feature {
+        flag: "--update-bytes",
+        functions: &[("update_blob_partial", 3)],
+        requested: |a| a.use_blobs && a.update_bytes.is_some(),
+        disable: |a| a.update_bytes = None,
+    },
+    Feature {
+        flag: "--append-bytes",
+        functions: &[("append_blob", 3)],
+        requested: |a| a.use_blobs && a.append_bytes.is_some(),
+        disable: |a| a.append_bytes = None,
+    },
+    Feature {
+        flag: "--size-distribution",
+        functions: &[("create_blob_batch_sized", 2), ("update_blob_same_size", 1)],
+        requested: |a| a.use_blobs && a.size_distribution.is_some(),
+        disable: |a| a.size_distribution = None,
+    },
+    Feature {
+        flag: "--freeze-pct",
+        functions: &[("freeze_counter", 1), ("freeze_blob", 1), ("read_counter", 1), ("read_blob", 1)],
+        requested: |a| a.freeze_pct.get() > 0,
+        disable: |a| {
+            a.freeze_pct = Percent::default();
+            a.frozen_read_pct = Percent::default();
+        },
+    },
+    Feature {
+        flag: "--hot-objects",
+        functions: &[("create_shared", 0)],
+        requested: |a| a.hot_objects > 0,
+        disable: |a| a.hot_objects = 0,
+    },
+    Feature {
+        flag: "--payload-tag",
+        functions: &[("create_batch_tagged", 2), ("create_blob_batch_tagged", 3), ("tag_counter", 2), ("tag_blob", 2)],
+        requested: |a| a.payload_tag.is_some(),
+        disable: |a| a.payload_tag = None,
+    },
+    Feature {
+        flag: "--workload delete_blob",
+        functions: &[("delete_blob", 1)],
+        requested: |a| a.workload.iter().any(|w| w.name == "delete_blob"),
+        disable: |a| a.workload.retain(|w| w.name != "delete_blob"),
+    },
+    Feature {
+        flag: "--workload clock_update",
+        functions: &[("increment", 2)],
+        requested: |a| a.workload.iter().any(|w| w.name == "clock_update"),
+        disable: |a| a.workload.retain(|w| w.name != "clock_update"),
+    },
+];
+
+/// `capabilities` section of the results
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CapabilityReport {
+    pub packages: Vec<ObjectID>,
+    /// None if the functions match no version exactly
+    pub contract_version: Option<ContractVersion>,
+    /// Set with --contract-version rather than detected
+    pub pinned: bool,
+    /// Calls made with a stand-in on this version
+    pub shims: BTreeMap<&'static str, &'static str>,
+    /// Entry points found in every package, with their parameter count
+    pub functions: BTreeMap<String, usize>,
+    /// Requested features switched off, and the function each is missing
+    pub disabled: BTreeMap<&'static str, String>,
+}
+
+/// io_churn functions of the run's packages
+pub struct Capabilities {
+    report: CapabilityReport,
+}
+
+/// Parameters the caller passes: everything but the trailing TxContext
+fn arity(function: &SuiMoveNormalizedFunction) -> usize {
+    // Matched by name: the normalized type's shape differs between SDK versions
+    let is_context = |ty: &SuiMoveNormalizedType| format!("{:?}", ty).contains("TxContext");
+    function.parameters.iter().filter(|ty| !is_context(ty)).count()
+}
+
+impl Capabilities {
+    /// Read the io_churn module of every package
+    pub async fn probe(client: &SuiClient, packages: &[ObjectID]) -> Result<Self> {
+        let mut common: Option<BTreeMap<String, usize>> = None;
+        for package in packages.iter().collect::<BTreeSet<_>>() {
+            let modules = client.read_api()
+                .get_normalized_move_modules_by_package(*package)
+                .await
+                .context(format!("Failed to read the modules of package {}", package))?;
+            let module = modules.get(MODULE)
+                .ok_or_else(|| anyhow!("Package {} has no {} module; is --package-id right?", package, MODULE))?;
+            let functions: BTreeMap<String, usize> = module.exposed_functions.iter()
+                .map(|(name, function)| (name.clone(), arity(function)))
+                .collect();
+            common = Some(match common {
+                None => functions,
+                Some(seen) => seen.into_iter().filter(|(name, n)| functions.get(name) == Some(n)).collect(),
+            });
+        }
+        let functions = common.unwrap_or_default();
+        let version = ContractVersion::detect(&functions);
+        match version {
+            Some(version) => info!("{}: {} entry points in {} package(s), contract {:?}",
+                MODULE, functions.len(), packages.iter().collect::<BTreeSet<_>>().len(), version),
+            None => warn!("{}: {} entry points in {} package(s) match no contract version; checking features one by one",
+                MODULE, functions.len(), packages.iter().collect::<BTreeSet<_>>().len()),
+        }
+        Ok(Self::new(packages, functions, version, false))
+    }
+
+    /// Take `version`'s functions as the packages' (--contract-version)
+    pub fn pinned(version: ContractVersion, packages: &[ObjectID]) -> Self {
+        info!("{}: contract pinned to {:?}", MODULE, version);
+        Self::new(packages, version.functions(), Some(version), true)
+    }
+
+    fn new(packages: &[ObjectID], functions: BTreeMap<String, usize>, version: Option<ContractVersion>, pinned: bool) -> Self {
+        let shims = version.map(abi::shims).unwrap_or_default().into_iter()
+            .filter(|(_, stand_in)| functions.contains_key(*stand_in))
+            .collect();
+        Self {
+            report: CapabilityReport {
+                packages: packages.to_vec(),
+                contract_version: version,
+                pinned,
+                shims,
+                functions,
+                disabled: BTreeMap::new(),
+            },
+        }
+    }
+
+    /// Version the run's calls resolve against; a package matching none gets no stand-ins
+    pub fn version(&self) -> ContractVersion {
+        self.report.contract_version.unwrap_or(abi::LATEST)
+    }
+
+    /// The first of `functions` the packages lack (or have with another signature)
+    fn missing(&self, functions: &[(&str, usize)]) -> Option<String> {
+        functions.iter()
+            .filter(|(name, _)| !self.report.shims.keys().any(|shim| shim == name))
+            .find(|(name, params)| self.report.functions.get(*name) != Some(params))
+            .map(|(name, params)| match self.report.functions.get(*name) {
+                Some(found) => format!("{}::{} takes {} parameters, expected {}", MODULE, name, found, params),
+                None => format!("{}::{}", MODULE, name),
+            })
+    }
+
+    /// Fail without the core functions; switch off requested features the packages can't serve
+    pub fn restrict(&mut self, args: &mut Args) -> Result<()> {
+        args.contract_version = Some(self.version());
+        let core = if args.use_blobs { [CORE, CORE_BLOBS].concat() } else { CORE.to_vec() };
+        if let Some(missing) = self.missing(&core) {
+            return Err(anyhow!("The package lacks {}, which every run needs; publish io_churn from this tree", missing));
+        }
+        for feature in FEATURES.iter().filter(|f| (f.requested)(args)) {
+            if let Some(missing) = self.missing(feature.functions) {
+                warn!("Disabling {}: the package lacks {}", feature.flag, missing);
+                (feature.disable)(args);
+                self.report.disabled.insert(feature.flag, missing);
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply what the base run disabled to a worker's own args (config profiles)
+    pub fn restrict_worker(&self, args: &mut Args) {
+        args.contract_version = Some(self.version());
+        for feature in FEATURES.iter().filter(|f| (f.requested)(args)) {
+            if self.missing(feature.functions).is_some() {
+                (feature.disable)(args);
+            }
+        }
+    }
+
+    pub fn report(&self) -> CapabilityReport {
+        self.report.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities(functions: &[(&str, usize)]) -> Capabilities {
+        let functions = functions.iter().map(|(n, p)| (n.to_string(), *p)).collect();
+        Capabilities { report: CapabilityReport { functions, ..CapabilityReport::default() } }
+    }
+
+    #[test]
+    fn missing_or_changed_functions_are_reported() {
+        let caps = capabilities(&[("create_batch", 1), ("increment_simple", 1), ("append_blob", 2)]);
+        assert_eq!(caps.missing(CORE), None);
+        assert_eq!(caps.missing(CORE_BLOBS), Some("io_churn::create_blob_batch".to_string()));
+        assert_eq!(caps.missing(&[("append_blob", 3)]), Some("io_churn::append_blob takes 2 parameters, expected 3".to_string()));
+        // Stand-ins cover what an older version lacks
+        let v2 = Capabilities::pinned(ContractVersion::V2, &[]);
+        assert_eq!(v2.missing(&[("update_blob_same_size", 1)]), None);
+        assert!(v2.missing(&[("create_blob_batch_sized", 2)]).is_some());
+    }
+}
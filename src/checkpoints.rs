@@ -0,0 +1,217 @@
+// Checkpoint-contents sampler
+//
+// Follows checkpoints produced during the run and records, per checkpoint,
+// the transaction count and (optionally) the total serialized effects size.
+// This gives a node-independent measure of logical write volume and shows
+// whether the node keeps checkpointing at the expected rate under load.
+//
+// Fetching raw effects costs one multi-get per 50 transactions, so it is
+// opt-in (--checkpoint-effects-bytes); transaction counts come for free with
+// the checkpoint summaries.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_sdk::rpc_types::SuiTransactionBlockResponseOptions;
+use sui_sdk::types::sui_serde::BigInt;
+use sui_sdk::SuiClient;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+/// Checkpoints requested per get_checkpoints page
+const CHECKPOINT_PAGE_SIZE: usize = 100;
+
+/// Transactions per multi-get when summing effects sizes
+const EFFECTS_FETCH_BATCH: usize = 50;
+
+/// Poll interval when the sampler has caught up with the chain tip
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One observed checkpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointSample {
+    pub sequence_number: u64,
+    pub timestamp_ms: u64,
+    pub tx_count: u64,
+    pub effects_bytes: Option<u64>,
+}
+
+/// Aggregated checkpoint statistics for the results JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointReport {
+    pub first_checkpoint: Option<u64>,
+    pub last_checkpoint: Option<u64>,
+    pub checkpoints: u64,
+    pub transactions: u64,
+    pub effects_bytes: Option<u64>,
+    pub checkpoints_per_sec: f64,
+    pub avg_tx_per_checkpoint: f64,
+    pub avg_interval_ms: f64,
+    pub max_interval_ms: u64,
+    pub per_checkpoint: Vec<CheckpointSample>,
+}
+
+/// Follow checkpoints until `running` is cleared, then summarize
+pub async fn run_checkpoint_sampler(
+    client: SuiClient,
+    running: Arc<AtomicBool>,
+    fetch_effects: bool,
+) -> Result<CheckpointReport> {
+    // Start from the current tip so only checkpoints produced during the run count
+    let start = client.read_api().get_latest_checkpoint_sequence_number().await?;
+    info!("Checkpoint sampler: following from checkpoint {}", start);
+
+    let mut cursor = Some(BigInt::from(start));
+    let mut samples: Vec<CheckpointSample> = Vec::new();
+
+    while running.load(Ordering::Relaxed) {
+        let page = match client.read_api()
+            .get_checkpoints(cursor, Some(CHECKPOINT_PAGE_SIZE), false)
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                warn!("Checkpoint sampler: get_checkpoints failed: {}", e);
+                sleep(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+
+        for checkpoint in &page.data {
+            let effects_bytes = if fetch_effects {
+                Some(sum_effects_bytes(&client, &checkpoint.transactions).await)
+            } else {
+                None
+            };
+            debug!("Checkpoint {}: {} transactions", checkpoint.sequence_number, checkpoint.transactions.len());
+            samples.push(CheckpointSample {
+                sequence_number: checkpoint.sequence_number,
+                timestamp_ms: checkpoint.timestamp_ms,
+                tx_count: checkpoint.transactions.len() as u64,
+                effects_bytes,
+            });
+            cursor = Some(BigInt::from(checkpoint.sequence_number));
+        }
+
+        if !page.has_next_page {
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    Ok(summarize(samples, fetch_effects))
+}
+
+/// Total raw effects bytes for a checkpoint's transactions (0 on RPC errors)
+async fn sum_effects_bytes(client: &SuiClient, digests: &[sui_sdk::types::base_types::TransactionDigest]) -> u64 {
+    let mut total = 0u64;
+    for chunk in digests.chunks(EFFECTS_FETCH_BATCH) {
+        match client.read_api()
+            .multi_get_transactions_with_options(
+                chunk.to_vec(),
+                SuiTransactionBlockResponseOptions::new().with_raw_effects(),
+            )
+            .await
+        {
+            Ok(responses) => {
+                total += responses.iter().map(|r| r.raw_effects.len() as u64).sum::<u64>();
+            }
+            Err(e) => debug!("Checkpoint sampler: effects fetch failed: {}", e),
+        }
+    }
+    total
+}
+
+fn summarize(samples: Vec<CheckpointSample>, fetch_effects: bool) -> CheckpointReport {
+    let checkpoints = samples.len() as u64;
+    let transactions: u64 = samples.iter().map(|s| s.tx_count).sum();
+    let effects_bytes = if fetch_effects {
+        Some(samples.iter().filter_map(|s| s.effects_bytes).sum())
+    } else {
+        None
+    };
+
+    let intervals: Vec<u64> = samples.windows(2)
+        .map(|w| w[1].timestamp_ms.saturating_sub(w[0].timestamp_ms))
+        .collect();
+    let avg_interval_ms = if intervals.is_empty() {
+        0.0
+    } else {
+        intervals.iter().sum::<u64>() as f64 / intervals.len() as f64
+    };
+    let max_interval_ms = intervals.iter().copied().max().unwrap_or(0);
+
+    let span_secs = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => last.timestamp_ms.saturating_sub(first.timestamp_ms) as f64 / 1000.0,
+        _ => 0.0,
+    };
+
+    CheckpointReport {
+        first_checkpoint: samples.first().map(|s| s.sequence_number),
+        last_checkpoint: samples.last().map(|s| s.sequence_number),
+        checkpoints,
+        transactions,
+        effects_bytes,
+        checkpoints_per_sec: if span_secs > 0.0 { (checkpoints.saturating_sub(1)) as f64 / span_secs } else { 0.0 },
+        avg_tx_per_checkpoint: if checkpoints > 0 { transactions as f64 / checkpoints as f64 } else { 0.0 },
+        avg_interval_ms,
+        max_interval_ms,
+        per_checkpoint: samples,
+    }
+}
+
+impl CheckpointReport {
+    /// One-line summary for the final log
+    pub fn summary_line(&self) -> String {
+        format!(
+            "Checkpoints: {} observed ({:.2}/s, avg {:.0}ms, max {}ms apart) | {} txs ({:.1}/checkpoint){}",
+            self.checkpoints,
+            self.checkpoints_per_sec,
+            self.avg_interval_ms,
+            self.max_interval_ms,
+            self.transactions,
+            self.avg_tx_per_checkpoint,
+            self.effects_bytes
+                .map(|b| format!(" | effects: {:.1} MiB", b as f64 / (1024.0 * 1024.0)))
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(sequence_number: u64, timestamp_ms: u64, tx_count: u64, effects_bytes: Option<u64>) -> CheckpointSample {
+        CheckpointSample { sequence_number, timestamp_ms, tx_count, effects_bytes }
+    }
+
+    #[test]
+    fn summary_covers_rate_intervals_and_volume() {
+        let samples = vec![
+            sample(10, 1_000, 4, Some(400)),
+            sample(11, 1_250, 6, Some(600)),
+            sample(12, 2_000, 2, None),
+        ];
+        let report = summarize(samples, true);
+
+        assert_eq!((report.first_checkpoint, report.last_checkpoint), (Some(10), Some(12)));
+        assert_eq!((report.checkpoints, report.transactions), (3, 12));
+        assert_eq!(report.effects_bytes, Some(1_000));
+        // Two intervals over one second
+        assert_eq!(report.checkpoints_per_sec, 2.0);
+        assert_eq!(report.avg_interval_ms, 500.0);
+        assert_eq!(report.max_interval_ms, 750);
+        assert_eq!(report.avg_tx_per_checkpoint, 4.0);
+    }
+
+    #[test]
+    fn an_empty_run_summarizes_to_zeroes() {
+        let report = summarize(Vec::new(), false);
+
+        assert_eq!((report.first_checkpoint, report.checkpoints, report.effects_bytes), (None, 0, None));
+        assert_eq!((report.checkpoints_per_sec, report.avg_interval_ms, report.avg_tx_per_checkpoint), (0.0, 0.0, 0.0));
+        assert!(report.summary_line().starts_with("Checkpoints: 0 observed"));
+    }
+}
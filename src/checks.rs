@@ -0,0 +1,178 @@
+// End-of-run invariant checks (--check)
+//
+// Regressions in worker logic rarely crash a run. More often the numbers come
+// out wrong: nothing succeeds, an object ends up tracked twice, or a saved state
+// doesn't load back as what was written. Such a run can look normal until lab
+// time has been spent on it. --check runs the listed assertions after the run
+// and fails it (non-zero exit, after the results file is written) if any fails:
+//
+//   nonzero-tps  the run completed transactions at a non-zero rate
+//   tracker      no object is tracked twice, within a worker or across workers,
+//                and the state's object count matches its object lists
+//   round-trip   the end-of-run state, written as --save-objects would write it
+//                and read back, is unchanged
+//
+// With --backend mock this makes a short smoke test that needs no node, e.g.
+// `--backend mock --duration 10 --check nonzero-tps,tracker,round-trip` in CI.
+// The `checks` section lists every check with its outcome. The same checks are
+// library functions, which the integration tests (tests/) call on the result
+// and saved state of runs they drive in-process. Those runs use the mock
+// backend, not an in-process localnet: starting a Sui network and publishing
+// io_churn from a test needs the node crates, which this crate doesn't build
+// against, so checking against a real node is left to a short localnet run.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::results::BenchResult;
+use crate::{autosave, SavedBenchmarkState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Check {
+    NonzeroTps,
+    Tracker,
+    RoundTrip,
+}
+
+/// One check's outcome
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckOutcome {
+    pub check: Check,
+    pub passed: bool,
+    /// What failed (empty if it passed)
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+/// `checks` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecksReport {
+    pub passed: bool,
+    pub checks: Vec<CheckOutcome>,
+}
+
+impl ChecksReport {
+    /// Err naming the failed checks, for the run's exit status
+    pub fn into_result(self) -> Result<()> {
+        if self.passed {
+            return Ok(());
+        }
+        let failed: Vec<String> = self.checks.iter()
+            .filter(|c| !c.passed)
+            .map(|c| format!("{:?}: {}", c.check, c.detail))
+            .collect();
+        Err(anyhow!("--check failed: {}", failed.join("; ")))
+    }
+}
+
+/// Run `checks` against a finished run and its end-of-run state
+pub fn run(checks: &[Check], result: &BenchResult, state: &SavedBenchmarkState) -> ChecksReport {
+    let checks: Vec<CheckOutcome> = checks.iter()
+        .map(|&check| {
+            let outcome = match check {
+                Check::NonzeroTps => nonzero_tps(result),
+                Check::Tracker => tracker(state),
+                Check::RoundTrip => round_trip(state),
+            };
+            CheckOutcome { check, passed: outcome.is_ok(), detail: outcome.err().unwrap_or_default() }
+        })
+        .collect();
+    ChecksReport { passed: checks.iter().all(|c| c.passed), checks }
+}
+
+/// The run completed transactions at a non-zero rate
+pub fn nonzero_tps(result: &BenchResult) -> Result<(), String> {
+    if result.tx_success == 0 || result.tps <= 0.0 {
+        return Err(format!("{} successful transactions, {:.2} TPS ({} failed)", result.tx_success, result.tps, result.tx_failed));
+    }
+    Ok(())
+}
+
+/// No object is tracked twice, and the object count matches the object lists
+pub fn tracker(state: &SavedBenchmarkState) -> Result<(), String> {
+    let listed: usize = state.workers.iter().map(|w| w.objects.len()).sum();
+    if listed != state.total_objects {
+        return Err(format!("total_objects is {} but the workers list {}", state.total_objects, listed));
+    }
+    let mut seen = HashSet::with_capacity(listed);
+    for worker in &state.workers {
+        if let Some(obj) = worker.objects.iter().find(|o| !seen.insert(o.id)) {
+            return Err(format!("object {} tracked twice (second time by worker {})", obj.id, worker.worker_id));
+        }
+    }
+    Ok(())
+}
+
+/// Numbers the round-trip files, so concurrent checks in one process don't share one
+static ROUND_TRIPS: AtomicU64 = AtomicU64::new(0);
+
+/// The state reads back unchanged from a file written as --save-objects writes it
+pub fn round_trip(state: &SavedBenchmarkState) -> Result<(), String> {
+    let path = std::env::temp_dir().join(format!("fdp-sui-bench-check-{}-{}.json",
+        std::process::id(), ROUND_TRIPS.fetch_add(1, Ordering::Relaxed)));
+    let path = path.to_string_lossy();
+    let compare = || -> Result<bool> {
+        autosave::write_state(&path, state)?;
+        let read = autosave::read_state(&path)?;
+        Ok(serde_json::to_value(state)? == serde_json::to_value(&read)?)
+    };
+    let outcome = compare();
+    // write_state leaves its temporary file behind if the rename fails
+    for file in [path.to_string(), format!("{}.tmp", path)] {
+        let _ = std::fs::remove_file(file);
+    }
+    match outcome {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("the state read back differs from the one written".to_string()),
+        Err(e) => Err(format!("{:#}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SavedWorkerObjects, TrackedObject};
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SuiAddress};
+
+    #[test]
+    fn double_tracked_object_and_empty_run_fail() {
+        let obj = TrackedObject { id: ObjectID::random(), version: 3, digest: ObjectDigest::random(), size: 0, origin: None, updates: 0 };
+        let worker = |worker_id, objects: Vec<TrackedObject>| SavedWorkerObjects {
+            worker_id, address: SuiAddress::ZERO, keypair_base64: String::new(), objects,
+        };
+        let mut state = SavedBenchmarkState { total_objects: 1, workers: vec![worker(0, vec![obj.clone()]), worker(1, Vec::new())] };
+        let result = BenchResult { tx_success: 10, tps: 2.0, ..BenchResult::default() };
+        let report = run(&[Check::NonzeroTps, Check::Tracker, Check::RoundTrip], &result, &state);
+        assert!(report.passed, "{:?}", report);
+
+        state.workers[1].objects.push(obj);
+        state.total_objects = 2;
+        let report = run(&[Check::NonzeroTps, Check::Tracker], &BenchResult::default(), &state);
+        assert_eq!(report.checks.iter().map(|c| c.passed).collect::<Vec<_>>(), [false, false]);
+        assert!(report.into_result().is_err());
+    }
+
+    #[test]
+    fn concurrent_round_trips_use_their_own_files() {
+        let states: Vec<_> = (1..=8)
+            .map(|objects| {
+                let objects: Vec<_> = (0..objects * 100)
+                    .map(|_| TrackedObject { id: ObjectID::random(), version: 1, digest: ObjectDigest::random(), size: 0, origin: None, updates: 0 })
+                    .collect();
+                SavedBenchmarkState {
+                    total_objects: objects.len(),
+                    workers: vec![SavedWorkerObjects { worker_id: 0, address: SuiAddress::ZERO, keypair_base64: String::new(), objects }],
+                }
+            })
+            .collect();
+        std::thread::scope(|scope| {
+            let checks: Vec<_> = states.iter().map(|state| scope.spawn(move || round_trip(state))).collect();
+            for check in checks {
+                check.join().unwrap().unwrap();
+            }
+        });
+    }
+}
@@ -0,0 +1,99 @@
+// Per-object update counts (churn heatmap)
+//
+// Which objects got rewritten how often is the data temperature the device
+// actually saw; the selection policy (round-robin batches, waves, hot/cold
+// split, eviction) only predicts it. Every tracked object counts the updates
+// this run applied to it, and at the end the counts become the `update_counts`
+// section: a power-of-two histogram (objects updated 0, 1, 2-3, 4-7, ... times)
+// and how concentrated the updates were. --update-counts-csv also writes one
+// row per object, for heatmaps keyed by object ID.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+
+use crate::TrackedObject;
+
+/// Objects whose counts went into a report: (worker, list the object is on, object)
+pub type ChurnRow<'a> = (usize, &'static str, &'a TrackedObject);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChurnBucket {
+    pub min_updates: u32,
+    pub max_updates: u32,
+    pub objects: u64,
+}
+
+/// `update_counts` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct ChurnReport {
+    pub objects: u64,
+    pub total_updates: u64,
+    pub never_updated: u64,
+    pub max_updates: u32,
+    pub mean_updates: f64,
+    /// Share of all updates that went to the most-updated 10% of objects
+    pub top_decile_share: f64,
+    pub buckets: Vec<ChurnBucket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub csv: Option<String>,
+}
+
+/// Histogram bucket of a count: 0, 1, 2-3, 4-7, ...
+fn bucket(updates: u32) -> usize {
+    (u32::BITS - updates.leading_zeros()) as usize
+}
+
+pub fn report(mut counts: Vec<u32>) -> ChurnReport {
+    let total_updates: u64 = counts.iter().map(|c| *c as u64).sum();
+    let mut buckets: Vec<ChurnBucket> = Vec::new();
+    for &count in &counts {
+        let idx = bucket(count);
+        while buckets.len() <= idx {
+            let i = buckets.len() as u32;
+            let (min_updates, max_updates) = if i == 0 { (0, 0) } else { (1 << (i - 1), ((1u64 << i) - 1) as u32) };
+            buckets.push(ChurnBucket { min_updates, max_updates, objects: 0 });
+        }
+        buckets[idx].objects += 1;
+    }
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    let top: u64 = counts.iter().take(counts.len().div_ceil(10)).map(|c| *c as u64).sum();
+    ChurnReport {
+        objects: counts.len() as u64,
+        total_updates,
+        never_updated: counts.iter().filter(|c| **c == 0).count() as u64,
+        max_updates: counts.first().copied().unwrap_or(0),
+        mean_updates: if counts.is_empty() { 0.0 } else { total_updates as f64 / counts.len() as f64 },
+        top_decile_share: if total_updates > 0 { top as f64 / total_updates as f64 } else { 0.0 },
+        buckets,
+        csv: None,
+    }
+}
+
+/// One row per object: object_id,worker,list,updates,size
+pub fn write_csv<'a>(path: &str, rows: impl Iterator<Item = ChurnRow<'a>>) -> Result<()> {
+    let file = std::fs::File::create(path).context(format!("Failed to create {}", path))?;
+    let mut out = std::io::BufWriter::new(file);
+    writeln!(out, "object_id,worker,list,updates,size")?;
+    for (worker, list, obj) in rows {
+        writeln!(out, "{},{},{},{},{}", obj.id, worker, list, obj.updates, obj.size)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_fall_into_power_of_two_buckets() {
+        let report = report(vec![0, 0, 1, 2, 3, 4, 9, 0, 0, 81]);
+        assert_eq!((report.objects, report.total_updates, report.never_updated, report.max_updates), (10, 100, 4, 81));
+        let objects: Vec<u64> = report.buckets.iter().map(|b| b.objects).collect();
+        // 0 | 1 | 2-3 | 4-7 | 8-15 | 16-31 | 32-63 | 64-127
+        assert_eq!(objects, vec![4, 1, 2, 1, 1, 0, 0, 1]);
+        assert_eq!((report.buckets[7].min_updates, report.buckets[7].max_updates), (64, 127));
+        assert_eq!(report.top_decile_share, 0.81);
+    }
+}
@@ -0,0 +1,109 @@
+// Time source of the pacing, throttling and deadline code
+//
+// Workers read the time to check the run deadline and to place lockstep
+// slots, and they sleep to hold --target-tps / --target-write-bps and to back
+// off under the failure throttle. All of that goes through `Clock`, which
+// run_benchmark takes and BenchStats carries. The run's start and deadline
+// and the load phase's own control loops read and sleep on it too: the stats
+// ticker, the memory monitor, the stage controllers, the adaptive in-flight
+// controller, the outlier threshold, the stall detector, soak maintenance and
+// the effects verification delay. What waits on the outside world stays on
+// real time: setup (faucet, seeding), the node watchers (epochs, checkpoints,
+// RPC probe), the mock backend's latency, the progress display and the
+// sweep/A-B cooldowns. Transaction latency is always measured in real time.
+//
+// Real runs use `TokioClock`. Tests use `VirtualClock`, where a sleep
+// advances the clock instead of waiting and returns immediately, after an
+// optional hook that lets the test change the world in the meantime. A
+// minute of paced load can then be checked in microseconds, deterministically,
+// and the test can time-travel with `advance` to put a worker behind its
+// schedule.
+
+use futures::future::BoxFuture;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub trait Clock: Send + Sync + Debug {
+    fn now(&self) -> Instant;
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    fn sleep_until(&self, at: Instant) -> BoxFuture<'static, ()> {
+        self.sleep(at.saturating_duration_since(self.now()))
+    }
+}
+
+/// Wall-clock time and tokio timers
+#[derive(Debug, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn sleep_until(&self, at: Instant) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep_until(at.into()))
+    }
+}
+
+pub fn system() -> Arc<dyn Clock> {
+    Arc::new(TokioClock)
+}
+
+/// Simulated time: sleeping moves the clock forward and returns at once
+#[cfg(test)]
+pub struct VirtualClock {
+    start: Instant,
+    elapsed: std::sync::Mutex<Duration>,
+    /// Called after every sleep with the new elapsed time
+    on_sleep: std::sync::Mutex<Option<Box<dyn FnMut(Duration) + Send>>>,
+}
+
+#[cfg(test)]
+impl VirtualClock {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { start: Instant::now(), elapsed: Default::default(), on_sleep: Default::default() })
+    }
+
+    /// Jump ahead, as if the caller had been busy for `by`
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+
+    /// Run `hook` whenever a sleep ends, to change the world while the sleeper "waits"
+    pub fn on_sleep(&self, hook: impl FnMut(Duration) + Send + 'static) {
+        *self.on_sleep.lock().unwrap() = Some(Box::new(hook));
+    }
+}
+
+#[cfg(test)]
+impl Debug for VirtualClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VirtualClock").field("elapsed", &self.elapsed()).finish()
+    }
+}
+
+#[cfg(test)]
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.advance(duration);
+        if let Some(hook) = self.on_sleep.lock().unwrap().as_mut() {
+            hook(self.elapsed());
+        }
+        Box::pin(std::future::ready(()))
+    }
+}
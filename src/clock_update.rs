@@ -0,0 +1,88 @@
+// Clock-stamped counter updates (--workload clock_update=<pct>)
+//
+// Increments a batch of tracked counters with io_churn::increment, which stamps
+// each one with the time read from the shared Clock object (0x6). The Clock is a
+// read-only shared input: nothing in the transaction contends for a write lock
+// on it, but it still makes the transaction go through consensus, and every
+// validator has to serve the same Clock version to all of them. This is the most
+// common shared-object pattern in deployed dapps and one the owned create/update
+// mix and the --hot-objects counters (mutable shared inputs) don't exercise.
+
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::ObjectArg;
+use sui_sdk::types::Identifier;
+use sui_sdk::types::{SUI_CLOCK_OBJECT_ID, SUI_CLOCK_OBJECT_SHARED_VERSION};
+
+use crate::ops::{for_update_batch, update_versions};
+use crate::validate::Percent;
+use crate::workload::{BuildContext, Effects, Planned, Workload};
+use crate::{Args, TrackedObject, TxOutcome, WorkerState, COUNTER_OBJECT_BYTES};
+
+pub struct ClockUpdate {
+    pct: Percent,
+}
+
+impl ClockUpdate {
+    pub fn create(args: &Args, pct: Percent) -> Result<Arc<dyn Workload>> {
+        if args.use_blobs {
+            return Err(anyhow!("--workload clock_update updates counters; it can't be used with --use-blobs"));
+        }
+        Ok(Arc::new(Self { pct }))
+    }
+}
+
+impl Workload for ClockUpdate {
+    fn name(&self) -> &'static str {
+        "clock_update"
+    }
+
+    fn weight(&self) -> Percent {
+        self.pct
+    }
+
+    fn ready(&self, state: &WorkerState, _ctx: &BuildContext) -> bool {
+        !state.objects.is_empty()
+    }
+
+    fn build_ptb(&self, state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        let clock = builder.obj(ObjectArg::SharedObject {
+            id: SUI_CLOCK_OBJECT_ID,
+            initial_shared_version: SUI_CLOCK_OBJECT_SHARED_VERSION,
+            mutable: false,
+        })?;
+        for_update_batch(state, ctx.batch_size, 1, builder, |builder, obj_arg| {
+            builder.programmable_move_call(
+                ctx.package_id,
+                Identifier::new("io_churn").unwrap(),
+                Identifier::new("increment").unwrap(),
+                vec![],
+                vec![obj_arg, clock],
+            );
+            Ok(())
+        })?;
+        Ok(Planned { object_bytes: COUNTER_OBJECT_BYTES, size: 0, objects: state.last_batch.len() })
+    }
+
+    fn apply_effects(&self, objects: &mut Vec<TrackedObject>, effects: &Effects, planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        // The Clock is only read, so it never shows up as mutated
+        outcome.updated = update_versions(objects, effects, |_| {});
+        outcome.bytes_written = outcome.updated * planned.object_bytes;
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn blobs_are_rejected() {
+        let blobs = Args::try_parse_from(["fdp-sui-bench", "--use-blobs"]).unwrap();
+        assert!(ClockUpdate::create(&blobs, Percent::default()).is_err());
+        let counters = Args::try_parse_from(["fdp-sui-bench"]).unwrap();
+        assert_eq!(ClockUpdate::create(&counters, Percent::default()).unwrap().name(), "clock_update");
+    }
+}
@@ -0,0 +1,185 @@
+// Per-command attribution of failed batches
+//
+// A programmable transaction succeeds or fails as a whole, so one stale or
+// aborting object out of fifty takes the other forty-nine down with it, and the
+// failure count alone can't say which call went wrong or why. The node does say.
+// A transaction that executed and failed carries a status such as
+// `MoveAbort(MoveLocation { .. function_name: Some("update_blob") }, 3) in
+// command 17`. One rejected before execution names the input object it couldn't
+// use. Both are parsed into a reason and a command index. That index maps back to
+// an object of the worker's last update batch, since each object gets
+// `batch_stride` consecutive commands. The `command_failures` section counts
+// failures per reason and per command position, and keeps the first few
+// attributed failures as examples.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use sui_sdk::types::base_types::ObjectID;
+
+use crate::failures;
+
+/// Attributed failures kept verbatim in the report
+const MAX_EXAMPLES: usize = 20;
+
+/// Failure kinds named in execution statuses and input-object errors
+const REASONS: &[&str] = &[
+    "InsufficientGas",
+    "InsufficientCoinBalance",
+    "MoveObjectTooBig",
+    "MovePrimitiveRuntimeError",
+    "ArithmeticError",
+    "CommandArgumentError",
+    "EffectsTooLarge",
+    "WrittenObjectsTooLarge",
+    "ExecutionCancelledDueToSharedObjectCongestion",
+    "ObjectVersionUnavailableForConsumption",
+    "ObjectNotFound",
+    "ObjectLockConflict",
+    "TransactionExpired",
+    "SizeLimitExceeded",
+    "MoveAbort",
+];
+
+/// What a failure message says about which command failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandFailure {
+    /// e.g. "MoveAbort(update_blob, 3)" or "InsufficientGas"
+    pub reason: String,
+    /// Index of the failing command, if the message gives one
+    pub command: Option<usize>,
+    /// Objects the message names
+    pub objects: Vec<ObjectID>,
+}
+
+impl CommandFailure {
+    /// Parse a failure message; None if it names no known reason
+    pub fn parse(msg: &str) -> Option<Self> {
+        let reason = move_abort(msg).or_else(|| {
+            let lower = msg.to_ascii_lowercase();
+            REASONS.iter().find(|r| lower.contains(&r.to_ascii_lowercase())).map(|r| r.to_string())
+        })?;
+        let command = msg.rfind("in command ")
+            .and_then(|pos| {
+                let digits: String = msg[pos + "in command ".len()..].chars().take_while(|c| c.is_ascii_digit()).collect();
+                digits.parse().ok()
+            });
+        Some(Self { reason, command, objects: failures::object_ids_in(msg) })
+    }
+
+    /// The batch object the failure belongs to, and its command
+    ///
+    /// A command index picks the object directly. Otherwise the first batch
+    /// object the message names is taken.
+    pub fn attribute(&self, batch: &[ObjectID], stride: usize) -> Option<(usize, ObjectID)> {
+        let stride = stride.max(1);
+        match self.command {
+            Some(command) => batch.get(command / stride).map(|id| (command, *id)),
+            None => batch.iter().position(|id| self.objects.contains(id)).map(|pos| (pos * stride, batch[pos])),
+        }
+    }
+}
+
+/// "MoveAbort(<function>, <code>)" from a MoveAbort status
+fn move_abort(msg: &str) -> Option<String> {
+    let rest = &msg[msg.find("MoveAbort(")?..];
+    let function = rest.find("function_name: Some(\"")
+        .map(|pos| &rest[pos + "function_name: Some(\"".len()..])
+        .and_then(|name| name.split('"').next())
+        .unwrap_or("?");
+    let code = rest.find(") in command").map_or(rest, |end| &rest[..end]);
+    let code = code.rsplit(", ").next().unwrap_or("?").trim_end_matches(')');
+    Some(format!("MoveAbort({}, {})", function, code))
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    failures: u64,
+    attributed: u64,
+    by_reason: BTreeMap<String, u64>,
+    by_command: BTreeMap<usize, u64>,
+    examples: Vec<AttributedFailure>,
+}
+
+#[derive(Debug, Default)]
+pub struct CommandFailureStats {
+    counts: Mutex<Counts>,
+}
+
+impl CommandFailureStats {
+    /// Count a failed transaction against the update batch it carried
+    pub fn record(&self, err: &anyhow::Error, batch: &[ObjectID], stride: usize) {
+        let Some(failure) = CommandFailure::parse(&format!("{:#}", err)) else {
+            return;
+        };
+        let mut counts = self.counts.lock().unwrap();
+        counts.failures += 1;
+        *counts.by_reason.entry(failure.reason.clone()).or_default() += 1;
+        let Some((command, object)) = failure.attribute(batch, stride) else {
+            return;
+        };
+        counts.attributed += 1;
+        *counts.by_command.entry(command).or_default() += 1;
+        if counts.examples.len() < MAX_EXAMPLES {
+            counts.examples.push(AttributedFailure { reason: failure.reason, command, batch: batch.len(), object });
+        }
+    }
+
+    /// None if no failure had a known reason
+    pub fn report(&self) -> Option<CommandFailuresReport> {
+        let counts = self.counts.lock().unwrap();
+        (counts.failures > 0).then(|| CommandFailuresReport {
+            failures: counts.failures,
+            attributed: counts.attributed,
+            by_reason: counts.by_reason.clone(),
+            by_command: counts.by_command.clone(),
+            examples: counts.examples.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttributedFailure {
+    pub reason: String,
+    pub command: usize,
+    /// Objects in the failed batch
+    pub batch: usize,
+    #[serde(with = "crate::object_id_serde")]
+    pub object: ObjectID,
+}
+
+/// `command_failures` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandFailuresReport {
+    /// Failed transactions with a known reason
+    pub failures: u64,
+    /// Of those, traced to one command and object of the batch
+    pub attributed: u64,
+    pub by_reason: BTreeMap<String, u64>,
+    /// Attributed failures per command index
+    pub by_command: BTreeMap<usize, u64>,
+    pub examples: Vec<AttributedFailure>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abort_is_traced_to_its_command_and_object() {
+        let status = "Transaction 9xK failed: MoveAbort(MoveLocation { module: ModuleId { address: 0x2, \
+            name: Identifier(\"io_churn\") }, function: 7, instruction: 12, function_name: Some(\"update_blob\") }, 3) in command 5";
+        let failure = CommandFailure::parse(status).unwrap();
+        assert_eq!((failure.reason.as_str(), failure.command), ("MoveAbort(update_blob, 3)", Some(5)));
+        let batch: Vec<ObjectID> = (0..4).map(|_| ObjectID::random()).collect();
+        // With tags every object has two commands: 5 is the tag call of the third
+        assert_eq!(failure.attribute(&batch, 2), Some((5, batch[2])));
+        assert_eq!(failure.attribute(&batch, 1), None);
+
+        let stale = format!("Error checking transaction input objects: ObjectVersionUnavailableForConsumption \
+            {{ provided_obj_ref: ({}, SequenceNumber(4), o#1), current_version: SequenceNumber(5) }}", batch[1]);
+        let failure = CommandFailure::parse(&stale).unwrap();
+        assert_eq!(failure.attribute(&batch, 1), Some((1, batch[1])));
+        assert!(CommandFailure::parse("error sending request").is_none());
+    }
+}
@@ -0,0 +1,89 @@
+// Node/SDK compatibility gate (--force)
+//
+// A node on a protocol version the linked SDK doesn't know doesn't fail at
+// connect time: it fails minutes into the run with a BCS error on some effects
+// or an execution error on a transaction the SDK built the old way. Before
+// anything else the node's protocol version is compared with the range this
+// build's SDK supports, and the RPC API version with the SDK's; on a mismatch
+// the run stops with both sides spelled out, unless --force turns it into a
+// warning. Either way the versions are recorded under `compatibility`.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use sui_protocol_config::ProtocolVersion;
+use sui_sdk::SuiClient;
+use tracing::{info, warn};
+
+/// `compatibility` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatReport {
+    pub node_protocol_version: u64,
+    /// Protocol versions the node's own binary supports
+    pub node_min_protocol_version: u64,
+    pub node_max_protocol_version: u64,
+    pub sdk_min_protocol_version: u64,
+    pub sdk_max_protocol_version: u64,
+    pub node_api_version: String,
+    pub problems: Vec<String>,
+    /// Started despite the problems (--force)
+    pub forced: bool,
+}
+
+/// What is wrong with running this SDK against a node at `node_version`
+fn problems(node_version: u64, sdk: (u64, u64), api_check: Result<(), String>) -> Vec<String> {
+    let mut problems = Vec::new();
+    if node_version > sdk.1 {
+        problems.push(format!(
+            "node is on protocol version {}, newer than the {} this build's SDK supports; rebuild against a newer Sui checkout",
+            node_version, sdk.1));
+    } else if node_version < sdk.0 {
+        problems.push(format!(
+            "node is on protocol version {}, older than the {} this build's SDK supports; rebuild against an older Sui checkout",
+            node_version, sdk.0));
+    }
+    if let Err(e) = api_check {
+        problems.push(format!("RPC API version mismatch: {}", e));
+    }
+    problems
+}
+
+/// Fail on an incompatible node unless `force` is set
+pub async fn check(client: &SuiClient, force: bool) -> Result<CompatReport> {
+    let config = client.read_api().get_protocol_config(None).await?;
+    let sdk = (ProtocolVersion::MIN.as_u64(), ProtocolVersion::MAX.as_u64());
+    let node_version = config.protocol_version.as_u64();
+    let problems = problems(node_version, sdk, client.check_api_version().map_err(|e| e.to_string()));
+    let report = CompatReport {
+        node_protocol_version: node_version,
+        node_min_protocol_version: config.min_supported_protocol_version.as_u64(),
+        node_max_protocol_version: config.max_supported_protocol_version.as_u64(),
+        sdk_min_protocol_version: sdk.0,
+        sdk_max_protocol_version: sdk.1,
+        node_api_version: client.api_version().to_string(),
+        forced: force && !problems.is_empty(),
+        problems,
+    };
+    if report.problems.is_empty() {
+        info!("Node protocol version {} (SDK supports {}-{})", node_version, sdk.0, sdk.1);
+    } else if force {
+        for problem in &report.problems {
+            warn!("Compatibility: {} (continuing because of --force)", problem);
+        }
+    } else {
+        return Err(anyhow!("Incompatible node: {}; pass --force to run anyway", report.problems.join("; ")));
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_outside_the_sdk_range_are_problems() {
+        assert!(problems(40, (1, 45), Ok(())).is_empty());
+        assert!(problems(46, (1, 45), Ok(()))[0].contains("newer"));
+        assert!(problems(1, (2, 45), Ok(()))[0].contains("older"));
+        assert_eq!(problems(40, (1, 45), Err("1.30 vs 1.28".to_string())).len(), 1);
+    }
+}
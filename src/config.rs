@@ -0,0 +1,329 @@
+// Benchmark config file (--config, JSON)
+//
+// Holds settings that don't fit on the command line. Currently the `workers`
+// section: per-worker profile overrides so one run can mix tenant behaviors,
+// e.g. create-heavy blob writers next to update-only counter churners:
+//
+//   {
+//     "workers": [
+//       { "name": "blob-writers", "workers": "0-3", "use_blobs": true, "create_pct": 90 },
+//       { "name": "counter-churn", "workers": "4-7", "use_blobs": false, "create_pct": 0 }
+//     ]
+//   }
+//
+// `workers` is a worker index, an inclusive range "a-b", or a list of either.
+// Fields left out fall back to the command-line value. Profiles are applied in
+// order, so a later profile wins where ranges overlap.
+//
+// The `tenants` section groups workers into tenants, each with its own package
+// (and therefore its own object types and pool) and a target rate for the
+// whole group. Results report TPS and latency per tenant, so one tenant's
+// churn can be checked against another's latency:
+//
+//   {
+//     "tenants": [
+//       { "name": "noisy", "workers": "0-5", "target_tps": 3000 },
+//       { "name": "victim", "workers": "6-7", "package_id": "0x...", "target_tps": 200 }
+//     ]
+//   }
+//
+// A worker belongs to at most one tenant; workers outside every tenant keep
+// --package-id and the global rate.
+//
+// Top-level `target_tps`, `create_pct`, `batch_size` and `memory_threshold` /
+// `memory_critical` / `memory_emergency` override the command line for all
+// workers (profiles and tenants still apply on top). These knobs, including
+// their per-profile and per-tenant forms, are re-read when the file changes
+// during a run (see reload.rs); everything else is fixed at startup.
+//
+// The `slo` section declares objectives each stats interval is checked
+// against, e.g. `"slo": { "p99_ms": 800, "max_error_pct": 1.0 }` (see slo.rs).
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::sizes::SizeDistribution;
+use crate::slo::SloSpec;
+use crate::validate::{Fraction, Percent};
+use crate::{Args, BLOB_OBJECT_BYTES};
+
+/// Top-level config file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchConfig {
+    /// Per-worker overrides
+    #[serde(default)]
+    pub workers: Vec<WorkerProfile>,
+    /// Worker groups with their own package and rate
+    #[serde(default)]
+    pub tenants: Vec<TenantSpec>,
+    /// Global overrides (hot-reloadable)
+    pub target_tps: Option<u64>,
+    pub create_pct: Option<Percent>,
+    pub batch_size: Option<usize>,
+    pub memory_threshold: Option<Fraction>,
+    pub memory_critical: Option<Fraction>,
+    pub memory_emergency: Option<Fraction>,
+    /// Per-interval objectives
+    pub slo: Option<SloSpec>,
+}
+
+/// Worker selector: single index, "a-b" range, or a list of those
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum WorkerSelector {
+    Index(usize),
+    Range(String),
+    List(Vec<WorkerSelector>),
+}
+
+impl WorkerSelector {
+    fn matches(&self, worker_id: usize) -> Result<bool> {
+        Ok(match self {
+            WorkerSelector::Index(i) => *i == worker_id,
+            WorkerSelector::Range(spec) => {
+                let (lo, hi) = match spec.split_once('-') {
+                    Some((lo, hi)) => (lo.trim().parse::<usize>(), hi.trim().parse::<usize>()),
+                    None => (spec.trim().parse::<usize>(), spec.trim().parse::<usize>()),
+                };
+                match (lo, hi) {
+                    (Ok(lo), Ok(hi)) => (lo..=hi).contains(&worker_id),
+                    _ => return Err(anyhow!("Invalid worker range '{}': expected N or A-B", spec)),
+                }
+            }
+            WorkerSelector::List(list) => {
+                for selector in list {
+                    if selector.matches(worker_id)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+        })
+    }
+}
+
+/// Overrides for a set of workers (None = keep the command-line value)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkerProfile {
+    /// Label used in logs and results
+    #[serde(default)]
+    pub name: Option<String>,
+    pub workers: WorkerSelector,
+    pub create_pct: Option<Percent>,
+    pub use_blobs: Option<bool>,
+    pub batch_size: Option<usize>,
+    /// This worker's own TPS (not a share of the global target)
+    pub target_tps: Option<u64>,
+    pub update_bytes: Option<u64>,
+    pub size_distribution: Option<String>,
+}
+
+/// A group of workers sharing a package and a rate target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantSpec {
+    pub name: String,
+    pub workers: WorkerSelector,
+    /// Published io_churn package for this tenant (default: --package-id)
+    pub package_id: Option<String>,
+    /// TPS for the whole tenant, split evenly across its workers
+    pub target_tps: Option<u64>,
+}
+
+impl BenchConfig {
+    /// Load and validate a config file
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read config file: {}", path))?;
+        let config: BenchConfig = serde_json::from_str(&content)
+            .context(format!("Failed to parse config file: {}", path))?;
+        for profile in &config.workers {
+            // Surface selector and distribution errors before any setup work
+            profile.workers.matches(0)?;
+            if let Some(spec) = &profile.size_distribution {
+                spec.parse::<SizeDistribution>().map_err(|e| anyhow!("Worker profile: {}", e))?;
+            }
+            if profile.update_bytes.is_some_and(|b| b == 0 || b > BLOB_OBJECT_BYTES) {
+                return Err(anyhow!("Worker profile update_bytes must be between 1 and {}", BLOB_OBJECT_BYTES));
+            }
+        }
+        if config.batch_size == Some(0) {
+            return Err(anyhow!("batch_size must be at least 1"));
+        }
+        if let Some(slo) = &config.slo {
+            slo.validate()?;
+        }
+        for (idx, tenant) in config.tenants.iter().enumerate() {
+            tenant.workers.matches(0)?;
+            if tenant.name.is_empty() {
+                return Err(anyhow!("Tenant {} needs a name", idx));
+            }
+            if config.tenants[..idx].iter().any(|t| t.name == tenant.name) {
+                return Err(anyhow!("Duplicate tenant name '{}'", tenant.name));
+            }
+        }
+        Ok(config)
+    }
+
+    /// The tenant `worker_id` belongs to, if any
+    pub fn tenant_of(&self, worker_id: usize) -> Result<Option<&TenantSpec>> {
+        let mut found: Option<&TenantSpec> = None;
+        for tenant in &self.tenants {
+            if !tenant.workers.matches(worker_id)? {
+                continue;
+            }
+            if let Some(other) = found {
+                return Err(anyhow!("Worker {} is in both tenant '{}' and '{}'", worker_id, other.name, tenant.name));
+            }
+            found = Some(tenant);
+        }
+        Ok(found)
+    }
+
+    /// Worker indices of `tenant` out of `workers` in total
+    pub fn tenant_workers(tenant: &TenantSpec, workers: usize) -> Result<Vec<usize>> {
+        let mut ids = Vec::new();
+        for worker_id in 0..workers {
+            if tenant.workers.matches(worker_id)? {
+                ids.push(worker_id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Command-line Args with the top-level overrides applied
+    pub fn global_args(&self, base: &Args) -> Args {
+        let mut args = base.clone();
+        if let Some(v) = self.target_tps {
+            args.target_tps = v;
+        }
+        if let Some(v) = self.create_pct {
+            args.create_pct = v;
+        }
+        if let Some(v) = self.batch_size {
+            args.batch_size = v;
+        }
+        if let Some(v) = self.memory_threshold {
+            args.memory_threshold = v;
+        }
+        if let Some(v) = self.memory_critical {
+            args.memory_critical = v;
+        }
+        if let Some(v) = self.memory_emergency {
+            args.memory_emergency = v;
+        }
+        args
+    }
+
+    /// Effective Args for one worker, plus the names of the profiles applied
+    pub fn worker_args(&self, base: &Args, worker_id: usize) -> Result<(Args, Vec<String>)> {
+        let mut args = self.global_args(base);
+        let mut applied = Vec::new();
+        for (idx, profile) in self.workers.iter().enumerate() {
+            if !profile.workers.matches(worker_id)? {
+                continue;
+            }
+            if let Some(v) = profile.create_pct {
+                args.create_pct = v;
+            }
+            if let Some(v) = profile.use_blobs {
+                args.use_blobs = v;
+            }
+            if let Some(v) = profile.batch_size {
+                args.batch_size = v;
+            }
+            if let Some(v) = profile.target_tps {
+                // Worker pacing divides the target across all workers
+                args.target_tps = v * args.workers as u64;
+            }
+            if let Some(v) = profile.update_bytes {
+                args.update_bytes = Some(v);
+            }
+            if let Some(spec) = &profile.size_distribution {
+                args.size_distribution = Some(spec.parse().map_err(|e: String| anyhow!(e))?);
+            }
+            applied.push(profile.name.clone().unwrap_or_else(|| format!("profile{}", idx)));
+        }
+        if let Some(tenant) = self.tenant_of(worker_id)? {
+            if let Some(v) = tenant.target_tps {
+                // The tenant's share per worker, in the same all-workers units as above
+                let members = Self::tenant_workers(tenant, base.workers)?.len().max(1) as u64;
+                args.target_tps = v * args.workers as u64 / members;
+            }
+            applied.push(format!("tenant:{}", tenant.name));
+        }
+        Ok((args, applied))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn config(json: &str) -> BenchConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn selectors_match_indices_ranges_and_lists() {
+        let selector: WorkerSelector = serde_json::from_str(r#"[1, "4-6", "9"]"#).unwrap();
+        let matched: Vec<usize> = (0..10).filter(|&w| selector.matches(w).unwrap()).collect();
+        assert_eq!(matched, vec![1, 4, 5, 6, 9]);
+        assert!(WorkerSelector::Range("4-x".to_string()).matches(0).is_err());
+    }
+
+    #[test]
+    fn later_profiles_and_tenants_apply_on_top_of_the_globals() {
+        let base = Args::try_parse_from(["fdp-sui-bench", "--workers", "8", "--target-tps", "800"]).unwrap();
+        let config = config(r#"{
+            "create_pct": 20,
+            "workers": [
+                { "name": "blobs", "workers": "0-3", "use_blobs": true, "create_pct": 90 },
+                { "workers": 3, "create_pct": 0, "target_tps": 50 }
+            ],
+            "tenants": [{ "name": "noisy", "workers": "4-7", "target_tps": 400 }]
+        }"#);
+
+        let (args, applied) = config.worker_args(&base, 3).unwrap();
+        assert_eq!((args.use_blobs, args.create_pct.get(), args.target_tps), (true, 0, 400));
+        assert_eq!(applied, vec!["blobs".to_string(), "profile1".to_string()]);
+
+        let (args, applied) = config.worker_args(&base, 5).unwrap();
+        assert_eq!((args.use_blobs, args.create_pct.get(), args.target_tps), (false, 20, 800));
+        assert_eq!(applied, vec!["tenant:noisy".to_string()]);
+    }
+
+    #[test]
+    fn a_worker_in_two_tenants_is_rejected() {
+        let config = config(r#"{ "tenants": [
+            { "name": "a", "workers": "0-3" },
+            { "name": "b", "workers": "3-7" }
+        ] }"#);
+        assert_eq!(config.tenant_of(2).unwrap().unwrap().name, "a");
+        assert!(config.tenant_of(3).is_err());
+        assert_eq!(BenchConfig::tenant_workers(&config.tenants[1], 6).unwrap(), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn load_rejects_invalid_files() {
+        let dir = std::env::temp_dir().join(format!("fdp-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bench.json").to_string_lossy().into_owned();
+        for bad in [
+            r#"{ "batch_size": 0 }"#,
+            r#"{ "create_pct": 101 }"#,
+            r#"{ "unknown": 1 }"#,
+            r#"{ "workers": [{ "workers": "0-1", "size_distribution": "fixed:0" }] }"#,
+            r#"{ "tenants": [{ "name": "a", "workers": 0 }, { "name": "a", "workers": 1 }] }"#,
+        ] {
+            std::fs::write(&path, bad).unwrap();
+            assert!(BenchConfig::load(&path).is_err(), "{}", bad);
+        }
+        std::fs::write(&path, r#"{ "workers": [{ "workers": "0-1", "size_distribution": "fixed:512" }] }"#).unwrap();
+        assert_eq!(BenchConfig::load(&path).unwrap().workers.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,96 @@
+// Stats split by execution path
+//
+// Sui runs a transaction that only touches owned and immutable objects on the
+// fast path (certified and executed without ordering), while one that touches a
+// shared object is sequenced through consensus first. The two pipelines write
+// very differently underneath - consensus adds its own DAG and commit stores on
+// top of the object writes - so a mixed run's overall latency hides which path
+// is slow. Each successful transaction is classified from its effects (any
+// shared object in the effects means consensus) and counted under `consensus_paths`.
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::latency::{Histogram, LatencySummary};
+use crate::TxOutcome;
+
+#[derive(Default)]
+pub struct PathStats {
+    fast_path: Mutex<PathTotals>,
+    consensus: Mutex<PathTotals>,
+}
+
+#[derive(Default)]
+struct PathTotals {
+    objects_written: u64,
+    latency: Histogram,
+}
+
+impl PathStats {
+    pub fn record(&self, outcome: &TxOutcome, latency: Duration) {
+        let path = if outcome.consensus { &self.consensus } else { &self.fast_path };
+        let mut totals = path.lock().unwrap();
+        totals.objects_written += outcome.created + outcome.updated;
+        totals.latency.record(latency);
+    }
+
+    pub fn report(&self) -> ConsensusPathReport {
+        let fast_path = self.fast_path.lock().unwrap().summary();
+        let consensus = self.consensus.lock().unwrap().summary();
+        let total = fast_path.transactions + consensus.transactions;
+        ConsensusPathReport {
+            consensus_share: if total > 0 { consensus.transactions as f64 / total as f64 } else { 0.0 },
+            fast_path,
+            consensus,
+        }
+    }
+}
+
+impl PathTotals {
+    fn summary(&self) -> PathSummary {
+        PathSummary { transactions: self.latency.count(), objects_written: self.objects_written, latency: self.latency.summary() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathSummary {
+    pub transactions: u64,
+    pub objects_written: u64,
+    pub latency: LatencySummary,
+}
+
+/// `consensus_paths` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsensusPathReport {
+    /// Owned and immutable objects only
+    pub fast_path: PathSummary,
+    /// Touched at least one shared object
+    pub consensus: PathSummary,
+    /// Fraction of successful transactions that went through consensus
+    pub consensus_share: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::TransactionDigest;
+
+    fn outcome(updated: u64, consensus: bool) -> TxOutcome {
+        TxOutcome { updated, consensus, ..TxOutcome::empty(TransactionDigest([3; 32])) }
+    }
+
+    #[test]
+    fn transactions_are_split_by_path() {
+        let stats = PathStats::default();
+        for _ in 0..3 {
+            stats.record(&outcome(10, false), Duration::from_millis(200));
+        }
+        stats.record(&outcome(5, true), Duration::from_millis(900));
+        let report = stats.report();
+        assert_eq!((report.fast_path.transactions, report.fast_path.objects_written), (3, 30));
+        assert_eq!((report.consensus.transactions, report.consensus.objects_written), (1, 5));
+        assert_eq!(report.consensus_share, 0.25);
+        assert!(report.consensus.latency.p50_ms > report.fast_path.latency.p99_ms);
+    }
+}
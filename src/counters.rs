@@ -0,0 +1,145 @@
+// Sharded counters for the hot-path statistics
+//
+// At tens of thousands of transactions per second every worker bumping the
+// same few AtomicU64s makes their cache lines bounce between cores. Each hot
+// counter is therefore split into one cache-line-padded shard per worker: a
+// worker only ever writes its own shard, and readers (stats reporter, progress
+// line, journal, results) aggregate on read. The shards double as cheap
+// per-worker totals.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::BenchStats;
+
+/// One shard on its own cache line
+#[repr(align(64))]
+#[derive(Default)]
+struct Shard(AtomicU64);
+
+pub struct ShardedCounter {
+    /// Carried over from earlier run segments (--resume); not attributed to a worker
+    base: AtomicU64,
+    shards: Box<[Shard]>,
+}
+
+impl ShardedCounter {
+    pub fn new(shards: usize) -> Self {
+        Self {
+            base: AtomicU64::new(0),
+            shards: (0..shards.max(1)).map(|_| Shard::default()).collect(),
+        }
+    }
+
+    /// Add to the shard owned by `worker`
+    pub fn add(&self, worker: usize, n: u64) {
+        self.shards[worker % self.shards.len()].0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// Aggregated total
+    pub fn get(&self) -> u64 {
+        self.base.load(Ordering::Relaxed)
+            + self.shards.iter().map(|s| s.0.load(Ordering::Relaxed)).sum::<u64>()
+    }
+
+    /// Start from `total` (resumed runs); per-worker shards stay at zero
+    pub fn set_base(&self, total: u64) {
+        self.base.store(total, Ordering::Relaxed);
+    }
+
+    /// This segment's count for one worker
+    pub fn shard(&self, worker: usize) -> u64 {
+        self.shards.get(worker).map_or(0, |s| s.0.load(Ordering::Relaxed))
+    }
+
+    pub fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+}
+
+/// Cumulative hot-path counters at one point in time (or the difference of two)
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CounterSnapshot {
+    pub tx_submitted: u64,
+    pub tx_success: u64,
+    pub tx_failed: u64,
+    pub objects_created: u64,
+    pub objects_updated: u64,
+    #[serde(rename = "gas_spent_mist")]
+    pub gas_spent: u64,
+}
+
+impl CounterSnapshot {
+    pub fn read(stats: &BenchStats) -> Self {
+        Self {
+            tx_submitted: stats.tx_submitted.get(),
+            tx_success: stats.tx_success.get(),
+            tx_failed: stats.tx_failed.get(),
+            objects_created: stats.objects_created.get(),
+            objects_updated: stats.objects_updated.get(),
+            gas_spent: stats.gas_spent.get(),
+        }
+    }
+
+    /// This segment's counts for a subset of workers (sum of their shards)
+    pub fn for_workers(stats: &BenchStats, workers: &[usize]) -> Self {
+        let sum = |counter: &ShardedCounter| workers.iter().map(|&w| counter.shard(w)).sum();
+        Self {
+            tx_submitted: sum(&stats.tx_submitted),
+            tx_success: sum(&stats.tx_success),
+            tx_failed: sum(&stats.tx_failed),
+            objects_created: sum(&stats.objects_created),
+            objects_updated: sum(&stats.objects_updated),
+            gas_spent: sum(&stats.gas_spent),
+        }
+    }
+
+    /// Counts accumulated since `earlier`
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            tx_submitted: self.tx_submitted - earlier.tx_submitted,
+            tx_success: self.tx_success - earlier.tx_success,
+            tx_failed: self.tx_failed - earlier.tx_failed,
+            objects_created: self.objects_created - earlier.objects_created,
+            objects_updated: self.objects_updated - earlier.objects_updated,
+            gas_spent: self.gas_spent - earlier.gas_spent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock;
+
+    #[test]
+    fn shards_add_up_on_top_of_the_resumed_base() {
+        let counter = ShardedCounter::new(3);
+        counter.set_base(100);
+        counter.add(0, 5);
+        counter.add(2, 7);
+        // Out-of-range workers wrap instead of panicking
+        counter.add(4, 1);
+
+        assert_eq!(counter.get(), 113);
+        assert_eq!((counter.shard(0), counter.shard(1), counter.shard(2), counter.shard(9)), (5, 1, 7, 0));
+        assert_eq!(ShardedCounter::new(0).num_shards(), 1);
+    }
+
+    #[test]
+    fn snapshots_cover_all_or_some_workers() {
+        let stats = BenchStats::new(3, clock::system());
+        stats.tx_success.set_base(50);
+        for worker in 0..3 {
+            stats.tx_success.add(worker, 10 * (worker as u64 + 1));
+            stats.gas_spent.add(worker, 1_000);
+        }
+        let before = CounterSnapshot::read(&stats);
+        stats.tx_success.add(1, 5);
+
+        assert_eq!(CounterSnapshot::read(&stats).since(&before).tx_success, 5);
+        assert_eq!(before.tx_success, 110);
+        let some = CounterSnapshot::for_workers(&stats, &[0, 2]);
+        assert_eq!((some.tx_success, some.gas_spent), (40, 2_000));
+    }
+}
@@ -0,0 +1,125 @@
+// Node DB fingerprinting and fresh-DB enforcement
+//
+// WAF and throughput depend heavily on how much data the node's RocksDB already
+// holds, so every run records the DB directory size (--node-db) and the latest
+// checkpoint height at start and end. --require-fresh-db refuses to start when
+// the DB is already larger than --fresh-db-max-mib or past
+// --fresh-db-max-checkpoint, so comparisons aren't made across unlike states.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::path::Path;
+use sui_sdk::SuiClient;
+use tracing::info;
+
+use crate::timeline::{unix_ms, utc_rfc3339};
+use crate::Args;
+
+/// Node DB size and chain height at one point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct DbFingerprint {
+    pub unix_ms: u64,
+    pub utc: String,
+    pub db_path: Option<String>,
+    pub db_bytes: Option<u64>,
+    pub db_files: Option<u64>,
+    pub checkpoint_height: Option<u64>,
+}
+
+/// Total size and file count under `path` (symlinks are not followed)
+fn dir_size(path: &Path) -> Result<(u64, u64)> {
+    let mut bytes = 0;
+    let mut files = 0;
+    let mut stack = vec![path.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir).context(format!("Failed to read {}", dir.display()))? {
+            let entry = entry?;
+            let meta = entry.metadata()?;
+            if meta.is_dir() {
+                stack.push(entry.path());
+            } else if meta.is_file() {
+                bytes += meta.len();
+                files += 1;
+            }
+        }
+    }
+    Ok((bytes, files))
+}
+
+/// Take a fingerprint of the node DB directory (if known) and the chain height
+pub async fn fingerprint(client: &SuiClient, db_path: Option<&str>) -> Result<DbFingerprint> {
+    let (db_bytes, db_files) = match db_path {
+        Some(path) => {
+            let (bytes, files) = dir_size(Path::new(path))?;
+            (Some(bytes), Some(files))
+        }
+        None => (None, None),
+    };
+    let checkpoint_height = client.read_api().get_latest_checkpoint_sequence_number().await.ok();
+    let ms = unix_ms();
+    Ok(DbFingerprint {
+        unix_ms: ms,
+        utc: utc_rfc3339(ms),
+        db_path: db_path.map(str::to_string),
+        db_bytes,
+        db_files,
+        checkpoint_height,
+    })
+}
+
+/// Pre-flight: fingerprint the DB and enforce --require-fresh-db
+pub async fn preflight(client: &SuiClient, args: &Args) -> Result<DbFingerprint> {
+    let fp = fingerprint(client, args.node_db.as_deref()).await?;
+    info!(
+        "Node DB: {} ({} files), checkpoint height {}",
+        fp.db_bytes.map_or("unknown size".to_string(), |b| format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0))),
+        fp.db_files.unwrap_or(0),
+        fp.checkpoint_height.map_or("unknown".to_string(), |h| h.to_string()),
+    );
+
+    if !args.require_fresh_db {
+        return Ok(fp);
+    }
+    let max_bytes = args.fresh_db_max_mib * 1024 * 1024;
+    match fp.db_bytes {
+        Some(bytes) if bytes > max_bytes => {
+            return Err(anyhow!(
+                "--require-fresh-db: node DB {} is {:.1} MiB, above the {} MiB limit",
+                fp.db_path.as_deref().unwrap_or(""), bytes as f64 / (1024.0 * 1024.0), args.fresh_db_max_mib
+            ));
+        }
+        Some(_) => {}
+        None if args.fresh_db_max_checkpoint.is_none() => {
+            return Err(anyhow!("--require-fresh-db needs --node-db <dir> or --fresh-db-max-checkpoint"));
+        }
+        None => {}
+    }
+    if let Some(max_height) = args.fresh_db_max_checkpoint {
+        let height = fp.checkpoint_height
+            .ok_or_else(|| anyhow!("--require-fresh-db: could not read the checkpoint height"))?;
+        if height > max_height {
+            return Err(anyhow!(
+                "--require-fresh-db: checkpoint height {} is above the limit of {}", height, max_height
+            ));
+        }
+    }
+    Ok(fp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dir_size_counts_files_in_nested_directories() {
+        let dir = std::env::temp_dir().join(format!("fdp-db-check-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("store/live")).unwrap();
+        std::fs::write(dir.join("CURRENT"), [0u8; 16]).unwrap();
+        std::fs::write(dir.join("store/live/000042.sst"), [0u8; 4096]).unwrap();
+        std::fs::write(dir.join("store/live/MANIFEST-000001"), [0u8; 100]).unwrap();
+
+        assert_eq!(dir_size(&dir).unwrap(), (4212, 3));
+        assert!(dir_size(&dir.join("missing")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
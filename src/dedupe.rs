@@ -0,0 +1,63 @@
+// Duplicate-submission protection
+//
+// A retried submission can come back with the digest of a transaction that was
+// already executed and accounted for. Each worker remembers its most recent
+// digests in a bounded LRU; a response whose digest is already known is
+// reported as a duplicate and neither stats nor the object tracker are touched
+// again, so accounting stays idempotent.
+
+use std::collections::{HashSet, VecDeque};
+use sui_sdk::types::base_types::TransactionDigest;
+
+/// Digests remembered per worker
+pub const SEEN_DIGESTS_PER_WORKER: usize = 4096;
+
+/// Bounded set of recently seen transaction digests (oldest evicted first)
+pub struct DigestLru {
+    set: HashSet<TransactionDigest>,
+    order: VecDeque<TransactionDigest>,
+    capacity: usize,
+}
+
+impl DigestLru {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            set: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Remember a digest; returns false if it was already known
+    pub fn insert(&mut self, digest: TransactionDigest) -> bool {
+        if !self.set.insert(digest) {
+            return false;
+        }
+        self.order.push_back(digest);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_digests_are_duplicates_until_evicted() {
+        let digest = |n: u8| TransactionDigest::new([n; 32]);
+        let mut lru = DigestLru::new(2);
+        assert!(lru.insert(digest(1)));
+        assert!(!lru.insert(digest(1)));
+        assert!(lru.insert(digest(2)));
+        // A third digest evicts the oldest
+        assert!(lru.insert(digest(3)));
+        assert!(!lru.insert(digest(2)));
+        assert!(lru.insert(digest(1)));
+        assert_eq!((lru.set.len(), lru.order.len()), (2, 2));
+    }
+}
@@ -0,0 +1,186 @@
+// In-flight depth sweep (--inflight-stages)
+//
+// Splits the run into equal stages and holds the in-flight limit at one depth
+// per stage, e.g. 4,8,16,32,64, so a single run (one population, one node
+// state) yields a throughput-vs-concurrency curve. The first
+// --inflight-warmup-secs of each stage let the pipeline fill or drain to the
+// new depth and are not measured; TPS and latency are taken over the rest of
+// the stage. Shrinking the limit waits for in-flight transactions to return
+// their permits, which the warm-up covers. The `inflight_sweep` section lists
+// one point per depth, plus the depth with the highest TPS and the knee: the
+// smallest depth within 5% of it, past which more concurrency only adds
+// latency.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::info;
+
+use crate::adaptive::PermitResizer;
+use crate::counters::CounterSnapshot;
+use crate::latency::LatencySummary;
+use crate::timeline::Timestamp;
+use crate::BenchStats;
+
+/// The knee is the smallest depth reaching this share of the best TPS
+const KNEE_SHARE: f64 = 0.95;
+const POLL: Duration = Duration::from_millis(250);
+
+/// One point of the curve
+#[derive(Debug, Clone, Serialize)]
+pub struct DepthPoint {
+    pub inflight: usize,
+    /// Measured window (after the warm-up)
+    pub start: Timestamp,
+    pub end: Timestamp,
+    #[serde(flatten)]
+    pub counters: CounterSnapshot,
+    pub tps: f64,
+    /// Mean transactions actually on the wire over the measured window
+    pub mean_inflight: f64,
+    pub latency: LatencySummary,
+}
+
+/// `inflight_sweep` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct DepthSweepReport {
+    pub warmup_secs: u64,
+    pub stage_secs: f64,
+    pub points: Vec<DepthPoint>,
+    pub peak_tps_inflight: Option<usize>,
+    pub knee_inflight: Option<usize>,
+}
+
+/// Check the depths against the run length
+pub fn validate(stages: &[usize], warmup_secs: u64, duration: u64) -> Result<()> {
+    if stages.is_empty() {
+        return Ok(());
+    }
+    if stages.contains(&0) {
+        return Err(anyhow!("--inflight-stages values must be at least 1"));
+    }
+    if warmup_secs * stages.len() as u64 >= duration {
+        return Err(anyhow!("--duration ({}s) leaves no measured time in {} stages of --inflight-warmup-secs ({}s) each",
+            duration, stages.len(), warmup_secs));
+    }
+    Ok(())
+}
+
+/// Depth with the highest TPS, and the smallest depth within KNEE_SHARE of it
+fn peak_and_knee(points: &[DepthPoint]) -> (Option<usize>, Option<usize>) {
+    let Some(peak) = points.iter().max_by(|a, b| a.tps.total_cmp(&b.tps)) else {
+        return (None, None);
+    };
+    let knee = points.iter()
+        .filter(|p| p.tps >= peak.tps * KNEE_SHARE)
+        .map(|p| p.inflight)
+        .min();
+    (Some(peak.inflight), knee)
+}
+
+/// Sleep until `until` (run-relative), taking back owed permits as they return
+async fn hold(stats: &BenchStats, running: &AtomicBool, semaphore: &Semaphore, resizer: &mut PermitResizer, until: Duration) -> (f64, u64) {
+    let (mut inflight_sum, mut polls) = (0.0, 0);
+    while running.load(Ordering::Relaxed) && stats.elapsed() < until {
+        stats.clock.sleep(POLL.min(until.saturating_sub(stats.elapsed()))).await;
+        resizer.settle(semaphore);
+        inflight_sum += stats.inflight.inflight() as f64;
+        polls += 1;
+    }
+    (inflight_sum, polls)
+}
+
+/// Step the in-flight limit through `stages`, splitting `run_duration` equally
+///
+/// `semaphore` must start with `stages[0]` permits. Stage boundaries are
+/// relative to the (possibly backdated) run start, so a resumed run skips the
+/// stages that already ran.
+pub async fn run(
+    stages: Vec<usize>,
+    semaphore: Arc<Semaphore>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    run_duration: Duration,
+    warmup: Duration,
+) -> DepthSweepReport {
+    let stage_duration = run_duration / stages.len().max(1) as u32;
+    let mut resizer = PermitResizer::default();
+    let mut limit = stages[0];
+    let mut points = Vec::with_capacity(stages.len());
+    for (stage, &inflight) in stages.iter().enumerate() {
+        let stage_start = stage_duration * stage as u32;
+        let stage_end = stage_start + stage_duration;
+        if stats.elapsed() >= stage_end {
+            continue;
+        }
+        resizer.resize(&semaphore, limit, inflight);
+        limit = inflight;
+        info!("In-flight stage {}/{}: depth {}", stage + 1, stages.len(), inflight);
+        let measure_from = (stage_start + warmup).max(stats.elapsed());
+        hold(&stats, &running, &semaphore, &mut resizer, measure_from).await;
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+
+        stats.adaptive.take();
+        let start = Timestamp::now(&stats);
+        let before = CounterSnapshot::read(&stats);
+        let (inflight_sum, polls) = hold(&stats, &running, &semaphore, &mut resizer, stage_end).await;
+        let end = Timestamp::now(&stats);
+        let counters = CounterSnapshot::read(&stats).since(&before);
+        let secs = end.elapsed_secs - start.elapsed_secs;
+        let point = DepthPoint {
+            inflight,
+            start,
+            end,
+            counters,
+            tps: if secs > 0.0 { counters.tx_success as f64 / secs } else { 0.0 },
+            mean_inflight: if polls > 0 { inflight_sum / polls as f64 } else { 0.0 },
+            latency: stats.adaptive.take().summary(),
+        };
+        info!("In-flight depth {}: {:.1} TPS, p50 {:.1}ms p99 {:.1}ms ({:.1} on the wire)",
+            inflight, point.tps, point.latency.p50_ms, point.latency.p99_ms, point.mean_inflight);
+        points.push(point);
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+    }
+    let (peak_tps_inflight, knee_inflight) = peak_and_knee(&points);
+    DepthSweepReport {
+        warmup_secs: warmup.as_secs(),
+        stage_secs: stage_duration.as_secs_f64(),
+        points,
+        peak_tps_inflight,
+        knee_inflight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(inflight: usize, tps: f64) -> DepthPoint {
+        DepthPoint {
+            inflight,
+            start: Timestamp::default(),
+            end: Timestamp::default(),
+            counters: CounterSnapshot::default(),
+            tps,
+            mean_inflight: inflight as f64,
+            latency: crate::latency::Histogram::new().summary(),
+        }
+    }
+
+    #[test]
+    fn knee_is_the_smallest_depth_near_peak() {
+        let points = [point(4, 400.0), point(8, 760.0), point(16, 980.0), point(32, 1000.0), point(64, 990.0)];
+        assert_eq!(peak_and_knee(&points), (Some(32), Some(16)));
+        assert_eq!(peak_and_knee(&[]), (None, None));
+        assert!(validate(&[4, 8], 30, 60).is_err());
+        assert!(validate(&[4, 0], 5, 60).is_err());
+        assert!(validate(&[4, 8], 10, 60).is_ok());
+    }
+}
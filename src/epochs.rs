@@ -0,0 +1,209 @@
+// Per-epoch result segmentation (--epoch-poll-secs)
+//
+// Storage behaves differently around epoch boundaries: reconfiguration,
+// pruning of the previous epoch's data and checkpoint compaction all kick in
+// right after the epoch changes, while mid-epoch is steady state. A background
+// watcher polls the current epoch and, whenever it changes, closes a segment
+// with the counter deltas since the previous boundary. Latency is recorded per
+// epoch as it happens (workers tag each sample with the epoch the watcher last
+// saw), so each segment carries its own percentiles.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sui_sdk::SuiClient;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::counters::CounterSnapshot;
+use crate::latency::{Histogram, LatencySummary};
+use crate::timeline::Timestamp;
+use crate::BenchStats;
+
+/// Epoch not known yet
+const UNKNOWN_EPOCH: u64 = u64::MAX;
+
+/// Current epoch as seen by the watcher, and latency histograms per epoch
+pub struct EpochStats {
+    current: AtomicU64,
+    latency: Mutex<BTreeMap<u64, BTreeMap<&'static str, Histogram>>>,
+    /// Reference gas price refreshed after an epoch change (--soak; 0 = use the one cached at startup)
+    rgp: AtomicU64,
+}
+
+impl Default for EpochStats {
+    fn default() -> Self {
+        Self { current: AtomicU64::new(UNKNOWN_EPOCH), latency: Mutex::default(), rgp: AtomicU64::new(0) }
+    }
+}
+
+impl EpochStats {
+    /// Epoch the watcher last saw
+    pub fn current(&self) -> Option<u64> {
+        Some(self.current.load(Ordering::Relaxed)).filter(|e| *e != UNKNOWN_EPOCH)
+    }
+
+    /// Reference gas price of the current epoch, if it was refreshed during the run
+    pub fn reference_gas_price(&self) -> Option<u64> {
+        Some(self.rgp.load(Ordering::Relaxed)).filter(|rgp| *rgp > 0)
+    }
+
+    /// Store a refreshed reference gas price; true if it changed
+    pub fn set_reference_gas_price(&self, rgp: u64) -> bool {
+        self.rgp.swap(rgp, Ordering::Relaxed) != rgp
+    }
+
+    /// Attribute a successful transaction's latency to the current epoch
+    pub fn record_latency(&self, op: &'static str, latency: Duration) {
+        let epoch = self.current.load(Ordering::Relaxed);
+        if epoch == UNKNOWN_EPOCH {
+            return;
+        }
+        self.latency.lock().unwrap()
+            .entry(epoch).or_default()
+            .entry(op).or_default()
+            .record(latency);
+    }
+
+    fn take_latency(&self, epoch: u64) -> BTreeMap<&'static str, Histogram> {
+        self.latency.lock().unwrap().remove(&epoch).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EpochSegment {
+    pub epoch: u64,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    /// False for the first/last segment of the run (cut by run start/end, not an epoch change)
+    pub started_at_boundary: bool,
+    pub ended_at_boundary: bool,
+    #[serde(flatten)]
+    pub counters: CounterSnapshot,
+    pub tps: f64,
+    pub latency: LatencySummary,
+    pub latency_by_op: BTreeMap<&'static str, LatencySummary>,
+}
+
+struct OpenSegment {
+    epoch: u64,
+    start: Timestamp,
+    at_boundary: bool,
+    counters: CounterSnapshot,
+}
+
+impl OpenSegment {
+    fn close(self, stats: &BenchStats, ended_at_boundary: bool) -> (EpochSegment, CounterSnapshot) {
+        let end = Timestamp::now(stats);
+        let now = CounterSnapshot::read(stats);
+        let by_op = stats.epochs.take_latency(self.epoch);
+        let mut all = Histogram::new();
+        for h in by_op.values() {
+            all.merge(h);
+        }
+        let secs = end.elapsed_secs - self.start.elapsed_secs;
+        let counters = now.since(&self.counters);
+        let segment = EpochSegment {
+            epoch: self.epoch,
+            start: self.start,
+            end,
+            started_at_boundary: self.at_boundary,
+            ended_at_boundary,
+            counters,
+            tps: if secs > 0.0 { counters.tx_success as f64 / secs } else { 0.0 },
+            latency: all.summary(),
+            latency_by_op: by_op.iter().map(|(op, h)| (*op, h.summary())).collect(),
+        };
+        (segment, now)
+    }
+}
+
+async fn latest_epoch(client: &SuiClient) -> Option<u64> {
+    match client.governance_api().get_latest_sui_system_state().await {
+        Ok(state) => Some(state.epoch),
+        Err(e) => {
+            warn!("Epoch watcher: {}", e);
+            None
+        }
+    }
+}
+
+/// Poll the epoch every `interval` until `running` is cleared; one segment per epoch seen
+pub async fn run_epoch_watcher(
+    client: SuiClient,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    interval: Duration,
+) -> Vec<EpochSegment> {
+    let mut segments = Vec::new();
+    let mut open: Option<OpenSegment> = None;
+    while running.load(Ordering::Relaxed) {
+        if let Some(epoch) = latest_epoch(&client).await {
+            match open.take() {
+                Some(segment) if segment.epoch != epoch => {
+                    info!("Epoch changed: {} -> {}", segment.epoch, epoch);
+                    stats.epochs.current.store(epoch, Ordering::Relaxed);
+                    let (closed, counters) = segment.close(&stats, true);
+                    segments.push(closed);
+                    open = Some(OpenSegment { epoch, start: Timestamp::now(&stats), at_boundary: true, counters });
+                }
+                Some(segment) => open = Some(segment),
+                None => {
+                    stats.epochs.current.store(epoch, Ordering::Relaxed);
+                    open = Some(OpenSegment {
+                        epoch,
+                        start: Timestamp::now(&stats),
+                        at_boundary: false,
+                        counters: CounterSnapshot::read(&stats),
+                    });
+                }
+            }
+        }
+        sleep(interval).await;
+    }
+    if let Some(segment) = open {
+        segments.push(segment.close(&stats, false).0);
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn reference_gas_price_changes_are_reported_once() {
+        let epochs = EpochStats::default();
+        assert_eq!((epochs.current(), epochs.reference_gas_price()), (None, None));
+        assert!(epochs.set_reference_gas_price(750));
+        assert!(!epochs.set_reference_gas_price(750));
+        assert_eq!(epochs.reference_gas_price(), Some(750));
+    }
+
+    #[test]
+    fn a_segment_carries_its_epochs_counters_and_latency() {
+        let clock = VirtualClock::new();
+        let stats = BenchStats::new(1, clock.clone());
+        // Latency before the first poll has no epoch to go to
+        stats.epochs.record_latency("update", Duration::from_millis(50));
+        stats.epochs.current.store(7, Ordering::Relaxed);
+        stats.tx_success.add(0, 40);
+        let open = OpenSegment { epoch: 7, start: Timestamp::now(&stats), at_boundary: true, counters: CounterSnapshot::read(&stats) };
+
+        clock.advance(Duration::from_secs(10));
+        stats.tx_success.add(0, 200);
+        stats.epochs.record_latency("update", Duration::from_millis(20));
+        stats.epochs.record_latency("create", Duration::from_millis(40));
+        let (segment, counters) = open.close(&stats, false);
+
+        assert_eq!((segment.epoch, segment.started_at_boundary, segment.ended_at_boundary), (7, true, false));
+        assert_eq!((segment.counters.tx_success, segment.tps), (200, 20.0));
+        assert_eq!(counters.tx_success, 240);
+        assert_eq!(segment.latency.count, 2);
+        assert_eq!(segment.latency_by_op.len(), 2);
+        assert!(stats.epochs.take_latency(7).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+// Tracked-object eviction policy (--eviction-policy)
+//
+// A worker stops tracking objects when its tracker is full and when memory
+// pressure makes it shed part of the population. Which objects it keeps is the
+// population every later update draws from, so the choice shapes the access
+// pattern of the rest of the run:
+//
+// - drop-newest (default): keep the earliest objects; new objects past the cap
+//   are not tracked and pressure drops cut the tail of the list.
+// - drop-coldest: drop the objects this run updated least (oldest first among
+//   equals), so the population drifts toward what is already hot; at the cap a
+//   new object replaces a never-updated old one.
+// - drop-random: drop uniformly at random, new objects included.
+
+use clap::ValueEnum;
+use rand::Rng;
+use serde::Serialize;
+
+use crate::{TrackedObject, WorkerState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EvictionPolicy {
+    /// Keep the earliest objects; drop the most recently added
+    #[default]
+    DropNewest,
+    /// Drop the least-updated objects first
+    DropColdest,
+    /// Drop objects at random
+    DropRandom,
+}
+
+impl EvictionPolicy {
+    /// Remove `count` objects from `objects` and return them
+    pub fn evict(self, objects: &mut Vec<TrackedObject>, count: usize, rng: &mut impl Rng) -> Vec<TrackedObject> {
+        let count = count.min(objects.len());
+        if count == 0 {
+            return Vec::new();
+        }
+        match self {
+            EvictionPolicy::DropNewest => objects.split_off(objects.len() - count),
+            EvictionPolicy::DropRandom => {
+                // Partial Fisher-Yates: move `count` random objects to the tail
+                let len = objects.len();
+                for i in 0..count {
+                    let pick = rng.gen_range(0..len - i);
+                    objects.swap(pick, len - 1 - i);
+                }
+                objects.split_off(len - count)
+            }
+            EvictionPolicy::DropColdest => {
+                let mut order: Vec<usize> = (0..objects.len()).collect();
+                order.sort_by_key(|&i| (objects[i].updates, i));
+                let mut evict = vec![false; objects.len()];
+                for &i in &order[..count] {
+                    evict[i] = true;
+                }
+                let mut evicted = Vec::with_capacity(count);
+                let mut kept = Vec::with_capacity(objects.len() - count);
+                for (obj, evict) in objects.drain(..).zip(evict) {
+                    if evict { evicted.push(obj) } else { kept.push(obj) }
+                }
+                *objects = kept;
+                evicted
+            }
+        }
+    }
+}
+
+impl WorkerState {
+    /// Drop `count` updatable objects according to the worker's eviction policy
+    pub fn evict(&mut self, count: usize) -> Vec<TrackedObject> {
+        let policy = self.eviction;
+        let evicted = policy.evict(&mut self.objects, count, &mut rand::thread_rng());
+        self.spill(&evicted);
+        evicted
+    }
+
+    /// Set the tracker cap and eviction policy, and drop what the population
+    /// already has over the cap (a loaded or resumed one may be larger); returns how many were over
+    pub fn limit_tracked(&mut self, policy: EvictionPolicy, cap: usize) -> usize {
+        self.eviction = policy;
+        self.max_tracked = cap;
+        let excess = (self.objects.len() + self.cold.len()).saturating_sub(cap);
+        if excess > 0 {
+            self.evict(excess);
+        }
+        excess
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID};
+
+    fn objects(updates: &[u32]) -> Vec<TrackedObject> {
+        updates.iter().enumerate()
+            .map(|(n, u)| TrackedObject { id: ObjectID::from_single_byte(n as u8), version: 1, digest: ObjectDigest::random(), size: 0, origin: None, updates: *u })
+            .collect()
+    }
+
+    fn ids(objects: &[TrackedObject]) -> Vec<ObjectID> {
+        objects.iter().map(|o| o.id).collect()
+    }
+
+    #[test]
+    fn drop_newest_cuts_the_tail() {
+        let mut objs = objects(&[0, 0, 0, 0]);
+        let all = ids(&objs);
+        let evicted = EvictionPolicy::DropNewest.evict(&mut objs, 3, &mut rand::thread_rng());
+        assert_eq!(ids(&objs), all[..1].to_vec());
+        assert_eq!(ids(&evicted), all[1..].to_vec());
+    }
+
+    #[test]
+    fn drop_coldest_keeps_the_most_updated_in_order() {
+        let mut objs = objects(&[5, 0, 9, 0, 1]);
+        let all = ids(&objs);
+        let evicted = EvictionPolicy::DropColdest.evict(&mut objs, 3, &mut rand::thread_rng());
+        assert_eq!(ids(&objs), vec![all[0], all[2]]);
+        assert_eq!(ids(&evicted), vec![all[1], all[3], all[4]]);
+    }
+
+    #[test]
+    fn drop_random_keeps_the_rest() {
+        let mut objs = objects(&[0; 10]);
+        let mut all = ids(&objs);
+        let evicted = EvictionPolicy::DropRandom.evict(&mut objs, 4, &mut rand::thread_rng());
+        assert_eq!((objs.len(), evicted.len()), (6, 4));
+        let mut seen = [ids(&objs), ids(&evicted)].concat();
+        seen.sort();
+        all.sort();
+        assert_eq!(seen, all);
+    }
+}
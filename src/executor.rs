@@ -0,0 +1,263 @@
+// Transaction submission and effects application
+//
+// `TxExecutor` is where a signed transaction leaves the process: the node's
+// quorum driver for real runs, or something else entirely. Either way the
+// answer comes back reduced to `Effects`. `apply` then updates the sending
+// worker: duplicate responses first, then the gas coin, then the workload's
+// created/mutated objects. That state sits behind `EffectsTarget`, so the tests
+// below replay synthetic responses through `MockExecutor` into a plain struct,
+// without a node or a keypair.
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+use sui_sdk::rpc_types::SuiTransactionBlockResponseOptions;
+use sui_sdk::types::base_types::{ObjectID, ObjectRef, TransactionDigest};
+use sui_sdk::types::transaction::{Transaction, TransactionData};
+use sui_sdk::types::transaction_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::SuiClient;
+
+use crate::workload::{Effects, Planned, Workload};
+use crate::{simulate_tx, TrackedObject, TxOutcome, WorkerState};
+
+/// Submits signed transactions and waits for their effects
+pub trait TxExecutor: Send + Sync {
+    fn execute(&self, tx: Transaction) -> BoxFuture<'_, Result<Effects>>;
+
+    /// Dry-run instead (--simulate): nothing is written, counts come from the simulated effects
+    fn simulate(&self, tx_data: TransactionData, gas_id: ObjectID, object_bytes: u64) -> BoxFuture<'_, Result<TxOutcome>>;
+}
+
+impl TxExecutor for SuiClient {
+    fn execute(&self, tx: Transaction) -> BoxFuture<'_, Result<Effects>> {
+        Box::pin(async move {
+            let response = self
+                .quorum_driver_api()
+                .execute_transaction_block(
+                    tx,
+                    SuiTransactionBlockResponseOptions::new()
+                        .with_effects()
+                        .with_object_changes(),
+                    Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+                )
+                .await?;
+            Ok(Effects::from_response(&response))
+        })
+    }
+
+    fn simulate(&self, tx_data: TransactionData, gas_id: ObjectID, object_bytes: u64) -> BoxFuture<'_, Result<TxOutcome>> {
+        Box::pin(simulate_tx(self, tx_data, gas_id, object_bytes))
+    }
+}
+
+/// The state a transaction's effects are applied to
+pub trait EffectsTarget {
+    /// Remember the digest; false if it was already accounted for
+    fn first_sighting(&mut self, digest: TransactionDigest) -> bool;
+
+    /// The gas coin after the transaction
+    fn gas_charged(&mut self, gas_coin: ObjectRef);
+
+    fn objects_mut(&mut self) -> &mut Vec<TrackedObject>;
+
+    /// Start tracking an object the transaction created
+    fn track_created(&mut self, obj: TrackedObject);
+}
+
+impl EffectsTarget for WorkerState {
+    fn first_sighting(&mut self, digest: TransactionDigest) -> bool {
+        self.seen_digests.insert(digest)
+    }
+
+    fn gas_charged(&mut self, gas_coin: ObjectRef) {
+        WorkerState::gas_charged(self, gas_coin)
+    }
+
+    fn objects_mut(&mut self) -> &mut Vec<TrackedObject> {
+        &mut self.objects
+    }
+
+    fn track_created(&mut self, obj: TrackedObject) {
+        WorkerState::track_created(self, obj)
+    }
+}
+
+/// Apply one transaction's effects to `target` (a repeated digest changes nothing)
+pub fn apply(target: &mut impl EffectsTarget, workload: &dyn Workload, effects: &Effects, planned: Planned) -> TxOutcome {
+    if !target.first_sighting(effects.digest) {
+        return TxOutcome::duplicate(effects.digest);
+    }
+    let mut outcome = TxOutcome { gas_used: effects.gas_used, charge: effects.charge, consensus: effects.shared, ..TxOutcome::empty(effects.digest) };
+    if let Some(gas_coin) = effects.gas_coin {
+        target.gas_charged(gas_coin);
+    }
+    for obj in workload.apply_effects(target.objects_mut(), effects, planned, &mut outcome) {
+        target.track_created(obj);
+    }
+    outcome
+}
+
+/// Answers every submission with the next scripted result
+#[cfg(test)]
+pub struct MockExecutor {
+    script: std::sync::Mutex<std::collections::VecDeque<Result<Effects>>>,
+    submitted: std::sync::atomic::AtomicU64,
+}
+
+#[cfg(test)]
+impl MockExecutor {
+    pub fn new(script: Vec<Result<Effects>>) -> Self {
+        Self { script: std::sync::Mutex::new(script.into()), submitted: Default::default() }
+    }
+
+    /// What the next submission gets (an error once the script runs out)
+    pub fn next(&self) -> Result<Effects> {
+        self.submitted.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.script.lock().unwrap().pop_front()
+            .unwrap_or_else(|| Err(anyhow::anyhow!("MockExecutor: no scripted response left")))
+    }
+
+    pub fn submitted(&self) -> u64 {
+        self.submitted.load(std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+impl TxExecutor for MockExecutor {
+    fn execute(&self, _tx: Transaction) -> BoxFuture<'_, Result<Effects>> {
+        let result = self.next();
+        Box::pin(async move { result })
+    }
+
+    fn simulate(&self, _tx_data: TransactionData, _gas_id: ObjectID, _object_bytes: u64) -> BoxFuture<'_, Result<TxOutcome>> {
+        let result = self.next().map(|effects| TxOutcome { gas_used: effects.gas_used, charge: effects.charge, ..TxOutcome::empty(effects.digest) });
+        Box::pin(async move { result })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::{CreateCounters, UpdateCounters};
+    use crate::COUNTER_OBJECT_BYTES;
+    use anyhow::anyhow;
+    use std::collections::HashSet;
+    use sui_sdk::rpc_types::SuiTransactionBlockResponse;
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SequenceNumber};
+
+    const GAS: u8 = 200;
+
+    /// A worker without a keypair
+    #[derive(Default)]
+    struct Worker {
+        gas_coin: Option<ObjectRef>,
+        objects: Vec<TrackedObject>,
+        seen: HashSet<TransactionDigest>,
+        created: usize,
+    }
+
+    impl EffectsTarget for Worker {
+        fn first_sighting(&mut self, digest: TransactionDigest) -> bool {
+            self.seen.insert(digest)
+        }
+
+        fn gas_charged(&mut self, gas_coin: ObjectRef) {
+            self.gas_coin = Some(gas_coin);
+        }
+
+        fn objects_mut(&mut self) -> &mut Vec<TrackedObject> {
+            &mut self.objects
+        }
+
+        fn track_created(&mut self, obj: TrackedObject) {
+            self.created += 1;
+            self.objects.push(obj);
+        }
+    }
+
+    fn obj_ref(n: u8, version: u64) -> ObjectRef {
+        (ObjectID([n; 32]), SequenceNumber(version), ObjectDigest([n.wrapping_add(version as u8); 32]))
+    }
+
+    /// Effects of transaction `tx`: the gas coin moves to version `tx + 1`
+    fn effects(tx: u8, created: &[u8], mutated: &[(u8, u64)]) -> Effects {
+        let mut effects = Effects::empty(TransactionDigest([tx; 32]));
+        effects.gas_used = 1_000 * tx as u64;
+        effects.gas_coin = Some(obj_ref(GAS, tx as u64 + 1));
+        effects.created = created.iter().map(|n| obj_ref(*n, 1)).collect();
+        effects.mutated = mutated.iter().map(|(n, v)| obj_ref(*n, *v)).collect();
+        effects
+    }
+
+    fn counters() -> Planned {
+        Planned { object_bytes: COUNTER_OBJECT_BYTES, size: 0, objects: 2 }
+    }
+
+    #[test]
+    fn created_then_updated_objects_follow_the_effects() {
+        let mock = MockExecutor::new(vec![
+            Ok(effects(1, &[1, 2], &[])),
+            Ok(effects(2, &[], &[(1, 2), (2, 2)])),
+        ]);
+        let mut worker = Worker::default();
+
+        let created = apply(&mut worker, &CreateCounters, &mock.next().unwrap(), counters());
+        assert_eq!((created.created, created.gas_used), (2, 1_000));
+        assert_eq!(worker.gas_coin, Some(obj_ref(GAS, 2)));
+        assert_eq!(worker.created, 2);
+
+        let updated = apply(&mut worker, &UpdateCounters, &mock.next().unwrap(), counters());
+        assert_eq!(updated.updated, 2);
+        assert_eq!(updated.bytes_written, 2 * COUNTER_OBJECT_BYTES);
+        assert_eq!(worker.gas_coin, Some(obj_ref(GAS, 3)));
+        assert!(worker.objects.iter().all(|o| o.version == 2));
+        assert_eq!(worker.objects[0].digest, obj_ref(1, 2).2);
+        assert_eq!(mock.submitted(), 2);
+    }
+
+    #[test]
+    fn repeated_digest_is_applied_once() {
+        let mut worker = Worker::default();
+        let first = apply(&mut worker, &CreateCounters, &effects(1, &[1], &[]), counters());
+        assert!(!first.duplicate);
+
+        // Same digest again, e.g. a retried submission answered twice
+        let again = apply(&mut worker, &CreateCounters, &effects(1, &[1], &[]), counters());
+        assert!(again.duplicate);
+        assert_eq!((again.created, again.gas_used), (0, 0));
+        assert_eq!(worker.objects.len(), 1);
+    }
+
+    #[test]
+    fn response_without_effects_leaves_worker_untouched() {
+        let response = SuiTransactionBlockResponse {
+            digest: TransactionDigest([3; 32]),
+            effects: None,
+            events: None,
+            object_changes: None,
+            balance_changes: None,
+            timestamp_ms: None,
+            confirmed_local_execution: None,
+            checkpoint: None,
+            errors: Vec::new(),
+            raw_effects: Vec::new(),
+        };
+        let mut worker = Worker { gas_coin: Some(obj_ref(GAS, 1)), ..Default::default() };
+        let outcome = apply(&mut worker, &UpdateCounters, &Effects::from_response(&response), counters());
+        assert_eq!(outcome.digest, response.digest);
+        assert_eq!((outcome.updated, outcome.gas_used), (0, 0));
+        assert_eq!(worker.gas_coin, Some(obj_ref(GAS, 1)));
+    }
+
+    #[test]
+    fn failed_submission_changes_nothing() {
+        let mock = MockExecutor::new(vec![Err(anyhow!("ObjectLocked"))]);
+        let mut worker = Worker::default();
+        for _ in 0..2 {
+            if let Ok(effects) = mock.next() {
+                apply(&mut worker, &CreateCounters, &effects, counters());
+            }
+        }
+        assert_eq!(mock.submitted(), 2);
+        assert!(worker.gas_coin.is_none() && worker.objects.is_empty());
+    }
+}
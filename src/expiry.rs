@@ -0,0 +1,109 @@
+// Transaction expiration and stale work (--tx-expiration-epochs, --stale-after-ms)
+//
+// When a node stalls, the load it couldn't take doesn't go away: workers keep
+// holding signed transactions and waiting for in-flight permits, and the
+// moment the node recovers they all land on it at once. That burst is part of
+// what a recovery measurement then sees, although no real client would still
+// send hour-old work. Two guards bound it. --tx-expiration-epochs N builds
+// every workload transaction with an epoch expiration N epochs past the
+// current one (from the epoch watcher), so the node itself refuses
+// transactions that outlived their epoch; such rejections are counted as
+// expired instead of as ordinary failures. --stale-after-ms drops a
+// transaction the worker decided to send but that waited longer than that for
+// an in-flight permit: the worker lets it go without submitting it and moves on
+// to fresh work. The `expiry` section counts both.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use sui_sdk::types::transaction::{TransactionData, TransactionExpiration};
+
+/// Substrings of the node's rejection of an expired transaction
+const EXPIRED_PATTERNS: &[&str] = &["transactionexpired", "transaction expired"];
+
+/// `tx_data` valid through `epoch` (unchanged without one)
+pub fn expire_at(tx_data: TransactionData, epoch: Option<u64>) -> TransactionData {
+    match (tx_data, epoch) {
+        (TransactionData::V1(mut data), Some(epoch)) => {
+            data.expiration = TransactionExpiration::Epoch(epoch);
+            TransactionData::V1(data)
+        }
+        (tx_data, _) => tx_data,
+    }
+}
+
+pub fn is_expired(err: &anyhow::Error) -> bool {
+    let msg = format!("{:#}", err).to_ascii_lowercase();
+    EXPIRED_PATTERNS.iter().any(|p| msg.contains(p))
+}
+
+#[derive(Debug, Default)]
+pub struct ExpiryStats {
+    expired: AtomicU64,
+    stale_dropped: AtomicU64,
+    /// Longest permit wait of a dropped transaction
+    stale_max_wait_ms: AtomicU64,
+}
+
+impl ExpiryStats {
+    pub fn record_expired(&self) {
+        self.expired.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Whether work that waited `waited` for its permit is too old to send; counts it if so
+    pub fn drop_stale(&self, waited: Duration, limit: Option<Duration>) -> bool {
+        if !limit.is_some_and(|limit| waited > limit) {
+            return false;
+        }
+        self.stale_dropped.fetch_add(1, Ordering::Relaxed);
+        self.stale_max_wait_ms.fetch_max(waited.as_millis() as u64, Ordering::Relaxed);
+        true
+    }
+
+    pub fn report(&self, expiration_epochs: Option<u64>, stale_after_ms: Option<u64>) -> ExpiryReport {
+        ExpiryReport {
+            expiration_epochs,
+            stale_after_ms,
+            expired: self.expired.load(Ordering::Relaxed),
+            stale_dropped: self.stale_dropped.load(Ordering::Relaxed),
+            stale_max_wait_ms: self.stale_max_wait_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// `expiry` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiryReport {
+    pub expiration_epochs: Option<u64>,
+    pub stale_after_ms: Option<u64>,
+    /// Transactions the node rejected as expired
+    pub expired: u64,
+    /// Transactions dropped unsent after waiting too long for a permit
+    pub stale_dropped: u64,
+    pub stale_max_wait_ms: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
+    use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_sdk::types::transaction::TransactionDataAPI;
+
+    #[test]
+    fn expiration_is_set_and_stale_work_dropped() {
+        let gas = (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+        let tx_data = TransactionData::new_programmable(SuiAddress::ZERO, vec![gas], ProgrammableTransactionBuilder::new().finish(), 1_000, 1_000);
+        assert_eq!(*expire_at(tx_data.clone(), Some(12)).expiration(), TransactionExpiration::Epoch(12));
+        assert_eq!(*expire_at(tx_data, None).expiration(), TransactionExpiration::None);
+        assert!(is_expired(&anyhow::anyhow!("Error checking transaction input objects: TransactionExpired")));
+
+        let stats = ExpiryStats::default();
+        let limit = Some(Duration::from_millis(500));
+        assert!(!stats.drop_stale(Duration::from_millis(400), limit));
+        assert!(stats.drop_stale(Duration::from_millis(900), limit));
+        assert!(!stats.drop_stale(Duration::from_secs(60), None));
+        let report = stats.report(None, Some(500));
+        assert_eq!((report.stale_dropped, report.stale_max_wait_ms), (1, 900));
+    }
+}
@@ -0,0 +1,193 @@
+// Sliding-window failure-rate throttle
+//
+// Workers slow down when too many recent transactions fail and pause when
+// nearly all of them do. "Recent" is the last --failure-window-secs: the rate
+// is taken from snapshots of the cumulative submitted/failed counters, at most
+// one per second, against the oldest snapshot still inside the window. A bad
+// first minute therefore stops throttling a minute after the failures stop,
+// instead of weighing on the cumulative rate for the rest of the run. The
+// thresholds and pauses are --failure-slow-pct/--failure-slow-ms and
+// --failure-pause-pct/--failure-pause-secs; throttling only starts once the
+// window holds --failure-min-tx transactions.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::clock::Clock;
+
+const SNAPSHOT_EVERY: Duration = Duration::from_secs(1);
+
+/// What the throttle asks a worker to do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Throttle {
+    None,
+    Slow(Duration),
+    Pause { rate: f64, pause: Duration },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ThrottleSettings {
+    pub window: Duration,
+    pub min_tx: u64,
+    pub slow_pct: u8,
+    pub slow: Duration,
+    pub pause_pct: u8,
+    pub pause: Duration,
+}
+
+impl ThrottleSettings {
+    pub fn from_args(args: &crate::Args) -> Self {
+        Self {
+            window: Duration::from_secs(args.failure_window_secs),
+            min_tx: args.failure_min_tx,
+            slow_pct: args.failure_slow_pct.get(),
+            slow: Duration::from_millis(args.failure_slow_ms),
+            pause_pct: args.failure_pause_pct.get(),
+            pause: Duration::from_secs(args.failure_pause_secs),
+        }
+    }
+}
+
+/// `failure_throttle` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct ThrottleReport {
+    pub window_secs: u64,
+    pub slowdowns: u64,
+    pub pauses: u64,
+    /// Highest failure rate seen over the window, in percent
+    pub peak_rate_pct: f64,
+}
+
+#[derive(Default)]
+pub struct FailureWindow {
+    /// (taken at, submitted, failed)
+    snapshots: Mutex<VecDeque<(Instant, u64, u64)>>,
+    slowdowns: AtomicU64,
+    pauses: AtomicU64,
+    peak_rate_ppm: AtomicU64,
+}
+
+impl FailureWindow {
+    /// Transactions (submitted, failed) within `window` of `now`, given the cumulative counters
+    fn observe(&self, now: Instant, submitted: u64, failed: u64, window: Duration) -> (u64, u64) {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        if !snapshots.back().is_some_and(|(at, _, _)| now.saturating_duration_since(*at) < SNAPSHOT_EVERY) {
+            snapshots.push_back((now, submitted, failed));
+        }
+        while snapshots.len() > 1 && snapshots.front().is_some_and(|(at, _, _)| now.saturating_duration_since(*at) > window) {
+            snapshots.pop_front();
+        }
+        let (_, oldest_submitted, oldest_failed) = snapshots[0];
+        (submitted.saturating_sub(oldest_submitted), failed.saturating_sub(oldest_failed))
+    }
+
+    pub fn check(&self, now: Instant, submitted: u64, failed: u64, settings: &ThrottleSettings) -> Throttle {
+        let (submitted, failed) = self.observe(now, submitted, failed, settings.window);
+        if submitted < settings.min_tx.max(1) {
+            return Throttle::None;
+        }
+        let rate = failed as f64 / submitted as f64;
+        self.peak_rate_ppm.fetch_max((rate * 1e6) as u64, Ordering::Relaxed);
+        if rate * 100.0 > settings.pause_pct as f64 {
+            self.pauses.fetch_add(1, Ordering::Relaxed);
+            Throttle::Pause { rate, pause: settings.pause }
+        } else if rate * 100.0 > settings.slow_pct as f64 {
+            self.slowdowns.fetch_add(1, Ordering::Relaxed);
+            Throttle::Slow(settings.slow)
+        } else {
+            Throttle::None
+        }
+    }
+
+    /// Check the window at the clock's time and sleep off a slowdown or pause
+    pub async fn throttle(&self, submitted: u64, failed: u64, settings: &ThrottleSettings, clock: &dyn Clock) {
+        match self.check(clock.now(), submitted, failed, settings) {
+            Throttle::Pause { rate, pause } => {
+                warn!("Critical failure rate ({:.1}% over the last {}s) - pausing {:?}", rate * 100.0, settings.window.as_secs(), pause);
+                clock.sleep(pause).await;
+            }
+            Throttle::Slow(delay) => clock.sleep(delay).await,
+            Throttle::None => {}
+        }
+    }
+
+    pub fn report(&self, settings: &ThrottleSettings) -> ThrottleReport {
+        ThrottleReport {
+            window_secs: settings.window.as_secs(),
+            slowdowns: self.slowdowns.load(Ordering::Relaxed),
+            pauses: self.pauses.load(Ordering::Relaxed),
+            peak_rate_pct: self.peak_rate_ppm.load(Ordering::Relaxed) as f64 / 1e4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn failures_age_out_of_the_window() {
+        let window = FailureWindow::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let window_len = Duration::from_secs(60);
+        // A bad first minute: 500 of 1000 failed
+        assert_eq!(window.observe(at(0), 0, 0, window_len), (0, 0));
+        assert_eq!(window.observe(at(30), 1000, 500, window_len), (1000, 500));
+        // Recovery: the next 1000 all succeed
+        assert_eq!(window.observe(at(61), 2000, 500, window_len), (1000, 0));
+        assert_eq!(window.observe(at(120), 3000, 500, window_len), (1000, 0));
+    }
+
+    #[test]
+    fn failing_worker_is_paused_then_released_in_simulated_time() {
+        let settings = ThrottleSettings {
+            window: Duration::from_secs(60),
+            min_tx: 10,
+            slow_pct: 10,
+            slow: Duration::from_millis(100),
+            pause_pct: 50,
+            pause: Duration::from_secs(5),
+        };
+        let window = FailureWindow::default();
+        let clock = VirtualClock::new();
+        let tx = Duration::from_millis(50);
+        let (mut submitted, mut failed) = (0, 0);
+        // (transactions sent, of them right after a throttle sleep) from `from` until `until` seconds
+        let mut run = |from: u64, until: u64, failing: bool| {
+            let (mut sent, mut throttled) = (0, 0);
+            futures::executor::block_on(async {
+                while clock.elapsed() < Duration::from_secs(until) {
+                    let before = clock.now();
+                    window.throttle(submitted, failed, &settings, clock.as_ref()).await;
+                    if clock.now() > before && clock.elapsed() >= Duration::from_secs(from) {
+                        throttled += 1;
+                    }
+                    clock.advance(tx);
+                    submitted += 1;
+                    failed += failing as u64;
+                    sent += 1;
+                }
+            });
+            (sent, throttled)
+        };
+
+        // Everything fails for a minute: past --failure-min-tx each transaction is followed by a 5s pause
+        let (sent, throttled) = run(0, 60, true);
+        assert!(sent < 30, "{} sent while failing", sent);
+        assert_eq!(throttled, sent - 10);
+        // Recovery: slowed while the failures are still in the window, then unthrottled
+        let (_, throttled) = run(60, 150, false);
+        assert!(throttled > 0);
+        let (sent, throttled) = run(150, 180, false);
+        assert_eq!((sent, throttled), (600, 0));
+        let report = window.report(&settings);
+        assert_eq!(report.peak_rate_pct, 100.0);
+        assert!(report.pauses > 0 && report.slowdowns > 0);
+    }
+}
@@ -0,0 +1,279 @@
+// Transaction failure classification and object quarantine
+//
+// Most failures are transient and retried by simply picking objects again. Lock
+// conflicts are different: once an owned object version is locked by another
+// (possibly equivocating) transaction, every later transaction using that ref
+// fails until the epoch ends. Retrying the stale refs wedges the worker, so they
+// are moved to a quarantine and only returned once a re-read shows a newer
+// version or the epoch has advanced.
+//
+// The worker's gas coin has the same problem on its own: if a transaction
+// mutated gas but failed, or its response was lost, the cached ref is stale and
+// every later transaction fails on it. Such errors trigger a re-read of the gas
+// coin from chain and the ref is repaired in place.
+
+use anyhow::Result;
+use std::time::{Duration, Instant};
+use sui_sdk::rpc_types::SuiObjectDataOptions;
+use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::{TrackedObject, WorkerState};
+
+/// How often a worker re-checks its quarantined objects
+pub const QUARANTINE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Substrings identifying owned-object lock conflicts / equivocation
+const LOCK_CONFLICT_PATTERNS: &[&str] = &[
+    "objectlockconflict",
+    "already locked",
+    "equivocat",
+    "conflicting transaction",
+    "objects are locked",
+];
+
+/// Substrings of errors caused by using an outdated object ref
+const STALE_REF_PATTERNS: &[&str] = &[
+    "objectversionunavailableforconsumption",
+    "not available for consumption",
+    "objectnotfound",
+    "object not found",
+    "could not find the referenced object",
+];
+
+/// Substrings of errors where the transaction may have executed without us seeing effects
+const LOST_RESPONSE_PATTERNS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "error sending request",
+    "connection",
+    "returned no effects",
+];
+
+/// A tracked object taken out of rotation after a lock conflict
+pub struct QuarantinedObject {
+    pub object: TrackedObject,
+    pub epoch: u64,
+}
+
+/// True if the error is an owned-object lock conflict / equivocation
+pub fn is_lock_conflict(err: &anyhow::Error) -> bool {
+    let msg = format!("{:#}", err).to_ascii_lowercase();
+    LOCK_CONFLICT_PATTERNS.iter().any(|p| msg.contains(p))
+}
+
+/// True if the error may have left the worker's cached gas ref stale
+///
+/// Either the error names the gas coin as outdated, or the response was lost
+/// and the transaction may have executed (mutating gas) anyway.
+pub fn may_have_stale_gas(err: &anyhow::Error, gas_id: &ObjectID) -> bool {
+    let raw = format!("{:#}", err);
+    let msg = raw.to_ascii_lowercase();
+    let stale_ref = STALE_REF_PATTERNS.iter().any(|p| msg.contains(p));
+    let names_gas = object_ids_in(&raw).contains(gas_id) || msg.contains("gas");
+    (stale_ref && names_gas) || LOST_RESPONSE_PATTERNS.iter().any(|p| msg.contains(p))
+}
+
+/// Re-read the worker's gas coin and repair the cached ref; returns true if it changed
+///
+/// If the coin no longer exists (e.g. merged away) the worker's largest coin
+/// takes its place.
+pub async fn repair_gas_coin(client: &SuiClient, worker: &RwLock<WorkerState>) -> Result<bool> {
+    let (id, address, cached) = {
+        let state = worker.read().await;
+        (state.id, state.address, state.gas_coin)
+    };
+    let response = client.read_api()
+        .get_object_with_options(cached.0, SuiObjectDataOptions::new())
+        .await?;
+    let fresh = match response.data {
+        Some(data) => (data.object_id, data.version, data.digest),
+        None => {
+            let coins = client.coin_read_api().get_coins(address, None, None, None).await?;
+            coins.data.iter().max_by_key(|c| c.balance)
+                .map(|c| c.object_ref())
+                .ok_or_else(|| anyhow::anyhow!("Worker {}: no gas coins left", id))?
+        }
+    };
+    if fresh == cached {
+        return Ok(false);
+    }
+    let mut state = worker.write().await;
+    // Another transaction of this worker may have advanced the ref meanwhile
+    if state.gas_coin != cached {
+        return Ok(false);
+    }
+    warn!("Worker {}: repaired stale gas ref {} v{} -> {} v{}",
+        id, cached.0, cached.1.value(), fresh.0, fresh.1.value());
+    state.gas_coin = fresh;
+    // An unseen execution also merged whatever fragments it paid with
+    state.smash_coins.clear();
+    Ok(true)
+}
+
+/// Object IDs mentioned in an error message (0x-prefixed 64-hex-digit strings)
+pub fn object_ids_in(msg: &str) -> Vec<ObjectID> {
+    let mut ids = Vec::new();
+    let mut rest = msg;
+    while let Some(pos) = rest.find("0x") {
+        let candidate = &rest[pos..];
+        let hex_len = candidate[2..].chars().take_while(|c| c.is_ascii_hexdigit()).count();
+        if hex_len == 64 {
+            if let Ok(id) = ObjectID::from_hex_literal(&candidate[..66]) {
+                ids.push(id);
+            }
+        }
+        rest = &candidate[2 + hex_len..];
+    }
+    ids
+}
+
+/// Move the objects implicated in a lock conflict into quarantine; returns how many
+///
+/// Objects named in the error are quarantined; if none are named, the whole
+/// batch that was last submitted is.
+pub fn quarantine_for_error(state: &mut WorkerState, err: &anyhow::Error, epoch: u64) -> usize {
+    let mut ids = object_ids_in(&format!("{:#}", err));
+    ids.retain(|id| state.objects.iter().any(|o| o.id == *id));
+    if ids.is_empty() {
+        ids = state.last_batch.clone();
+    }
+
+    let before = state.objects.len();
+    let mut kept = Vec::with_capacity(before);
+    for obj in state.objects.drain(..) {
+        if ids.contains(&obj.id) {
+            state.quarantine.push(QuarantinedObject { object: obj, epoch });
+        } else {
+            kept.push(obj);
+        }
+    }
+    state.objects = kept;
+    let moved = before - state.objects.len();
+    if moved > 0 {
+        debug!("Worker {}: quarantined {} locked objects", state.id, moved);
+    }
+    moved
+}
+
+/// Re-read quarantined objects and release those that are usable again
+///
+/// An object is released when the chain shows a newer version than ours (the
+/// ref was merely stale) or when the epoch has advanced past the conflict.
+pub async fn release_quarantine(client: &SuiClient, state: &mut WorkerState, current_epoch: u64) -> Result<usize> {
+    if state.quarantine.is_empty() {
+        return Ok(0);
+    }
+    let ids: Vec<ObjectID> = state.quarantine.iter().map(|q| q.object.id).collect();
+    let mut fresh = Vec::with_capacity(ids.len());
+    for chunk in ids.chunks(50) {
+        fresh.extend(client.read_api()
+            .multi_get_object_with_options(chunk.to_vec(), SuiObjectDataOptions::new())
+            .await?);
+    }
+
+    let mut still_quarantined = Vec::new();
+    let mut released = 0;
+    for (q, response) in std::mem::take(&mut state.quarantine).into_iter().zip(fresh) {
+        let Some(data) = response.data else {
+            // Gone (deleted/transferred) - drop it
+            continue;
+        };
+        let newer = data.version.value() > q.object.version;
+        if newer || current_epoch > q.epoch {
+            state.objects.push(TrackedObject {
+                id: data.object_id,
+                version: data.version.value(),
+                digest: data.digest,
+                size: q.object.size,
+                origin: q.object.origin.clone(),
+                updates: q.object.updates,
+            });
+            released += 1;
+        } else {
+            still_quarantined.push(q);
+        }
+    }
+    state.quarantine = still_quarantined;
+    if released > 0 {
+        info!("Worker {}: released {} quarantined objects ({} still locked)",
+            state.id, released, state.quarantine.len());
+    }
+    Ok(released)
+}
+
+/// Per-worker bookkeeping for when to next check the quarantine
+pub struct QuarantineTimer {
+    next_check: Instant,
+}
+
+impl QuarantineTimer {
+    pub fn new() -> Self {
+        Self { next_check: Instant::now() + QUARANTINE_CHECK_INTERVAL }
+    }
+
+    /// True (and re-armed) once per QUARANTINE_CHECK_INTERVAL
+    pub fn due(&mut self) -> bool {
+        if Instant::now() < self.next_check {
+            return false;
+        }
+        self.next_check = Instant::now() + QUARANTINE_CHECK_INTERVAL;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use sui_sdk::types::base_types::{ObjectDigest, SequenceNumber, SuiAddress};
+    use sui_sdk::types::crypto::{get_key_pair, AccountKeyPair, SuiKeyPair};
+
+    fn worker(objects: usize) -> WorkerState {
+        let (address, keypair): (SuiAddress, AccountKeyPair) = get_key_pair();
+        let gas_coin = (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+        let objects = (0..objects)
+            .map(|n| TrackedObject { id: ObjectID::from_single_byte(n as u8), version: 1, digest: ObjectDigest::random(), size: 0, origin: None, updates: 0 })
+            .collect();
+        WorkerState::new(0, address, SuiKeyPair::Ed25519(keypair), gas_coin, objects)
+    }
+
+    #[test]
+    fn object_ids_are_found_in_error_messages() {
+        let a = ObjectID::from_single_byte(1);
+        let b = ObjectID::from_single_byte(2);
+        let msg = format!("Objects {} and {}, not 0x1234 or 0x{}", a, b, "f".repeat(65));
+        assert_eq!(object_ids_in(&msg), vec![a, b]);
+        assert!(object_ids_in("no ids here").is_empty());
+    }
+
+    #[test]
+    fn errors_are_classified_by_their_message() {
+        let gas = ObjectID::from_single_byte(9);
+        assert!(is_lock_conflict(&anyhow!("ObjectLockConflict on owned object")));
+        assert!(!is_lock_conflict(&anyhow!("InsufficientGas")));
+
+        let stale_gas = anyhow!("ObjectVersionUnavailableForConsumption {}", gas);
+        assert!(may_have_stale_gas(&stale_gas, &gas));
+        let stale_other = anyhow!("ObjectVersionUnavailableForConsumption {}", ObjectID::from_single_byte(3));
+        assert!(!may_have_stale_gas(&stale_other, &gas));
+        assert!(may_have_stale_gas(&anyhow!("request timed out"), &gas));
+    }
+
+    #[test]
+    fn named_objects_are_quarantined_or_else_the_last_batch() {
+        let mut state = worker(4);
+        let named = anyhow!("ObjectLockConflict: {} is locked", ObjectID::from_single_byte(2));
+        assert_eq!(quarantine_for_error(&mut state, &named, 7), 1);
+        assert_eq!(state.quarantine[0].object.id, ObjectID::from_single_byte(2));
+        assert_eq!(state.quarantine[0].epoch, 7);
+
+        state.last_batch = vec![ObjectID::from_single_byte(0), ObjectID::from_single_byte(3)];
+        assert_eq!(quarantine_for_error(&mut state, &anyhow!("objects are locked"), 7), 2);
+        let left: Vec<ObjectID> = state.objects.iter().map(|o| o.id).collect();
+        assert_eq!(left, vec![ObjectID::from_single_byte(1)]);
+        assert_eq!(state.quarantine.len(), 3);
+    }
+}
@@ -0,0 +1,175 @@
+// Faucet client
+//
+// Rate-limited access to the local faucet. Prefers the batched v1 API
+// (POST /v1/gas returns a task id, GET /v1/status/<task> reports completion)
+// and falls back to the legacy synchronous POST /gas endpoint when the
+// faucet doesn't serve v1. Polling the task status replaces the old blind
+// sleep before probing for coins.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use sui_sdk::types::base_types::SuiAddress;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{debug, warn};
+
+/// How long to poll a batched faucet task before giving up on it
+const TASK_TIMEOUT: Duration = Duration::from_secs(60);
+const TASK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_ATTEMPTS: u32 = 3;
+
+pub struct FaucetClient {
+    base_url: String,
+    http: reqwest::Client,
+    /// Minimum spacing between requests (None = unlimited)
+    min_interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+    /// Cleared once the faucet answers 404 on the v1 batch endpoint
+    use_batch_api: AtomicBool,
+}
+
+impl FaucetClient {
+    /// `rate_limit` is requests per second (0 = unlimited)
+    pub fn new(base_url: &str, rate_limit: f64, use_batch_api: bool) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            min_interval: (rate_limit > 0.0).then(|| Duration::from_secs_f64(1.0 / rate_limit)),
+            next_slot: Mutex::new(Instant::now()),
+            use_batch_api: AtomicBool::new(use_batch_api),
+        }
+    }
+
+    /// Request gas for `address`; Ok once the faucet reports the transfer done
+    pub async fn request(&self, address: SuiAddress) -> Result<()> {
+        let mut last_err = anyhow!("no faucet attempt made");
+        for attempt in 1..=MAX_ATTEMPTS {
+            self.wait_for_slot().await;
+            let result = if self.use_batch_api.load(Ordering::Relaxed) {
+                self.request_batched(address).await
+            } else {
+                self.request_legacy(address).await
+            };
+            match result {
+                Ok(()) => {
+                    debug!("Faucet request succeeded for {} (attempt {})", address, attempt);
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!("Faucet request failed for {} (attempt {}): {}", address, attempt, e);
+                    last_err = e;
+                }
+            }
+            if attempt < MAX_ATTEMPTS {
+                sleep(Duration::from_millis(500 * attempt as u64)).await;
+            }
+        }
+        Err(last_err)
+    }
+
+    /// Token-bucket-of-one: each caller reserves the next free send slot
+    async fn wait_for_slot(&self) {
+        let Some(interval) = self.min_interval else { return };
+        let wait_until = {
+            let mut next = self.next_slot.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + interval;
+            slot
+        };
+        tokio::time::sleep_until(wait_until.into()).await;
+    }
+
+    fn request_body(address: SuiAddress) -> Value {
+        serde_json::json!({ "FixedAmountRequest": { "recipient": address.to_string() } })
+    }
+
+    async fn request_legacy(&self, address: SuiAddress) -> Result<()> {
+        let resp = self.http
+            .post(format!("{}/gas", self.base_url))
+            .json(&Self::request_body(address))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("faucet returned status {}", resp.status()));
+        }
+        let body: Value = resp.json().await.unwrap_or(Value::Null);
+        match body.get("error").and_then(Value::as_str) {
+            Some(err) => Err(anyhow!("faucet error: {}", err)),
+            None => Ok(()),
+        }
+    }
+
+    async fn request_batched(&self, address: SuiAddress) -> Result<()> {
+        let resp = self.http
+            .post(format!("{}/v1/gas", self.base_url))
+            .json(&Self::request_body(address))
+            .send()
+            .await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            warn!("Faucet has no batch endpoint - falling back to /gas");
+            self.use_batch_api.store(false, Ordering::Relaxed);
+            return self.request_legacy(address).await;
+        }
+        if !resp.status().is_success() {
+            return Err(anyhow!("faucet returned status {}", resp.status()));
+        }
+        let body: Value = resp.json().await?;
+        if let Some(err) = body.get("error").and_then(Value::as_str) {
+            return Err(anyhow!("faucet error: {}", err));
+        }
+        let task = body.get("task").and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("faucet batch response has no task id"))?
+            .to_string();
+        self.wait_for_task(&task).await
+    }
+
+    /// Poll /v1/status/<task> until the batch containing our request is sent
+    async fn wait_for_task(&self, task: &str) -> Result<()> {
+        let deadline = Instant::now() + TASK_TIMEOUT;
+        while Instant::now() < deadline {
+            sleep(TASK_POLL_INTERVAL).await;
+            let resp = self.http
+                .get(format!("{}/v1/status/{}", self.base_url, task))
+                .send()
+                .await;
+            let Ok(resp) = resp else { continue };
+            let body: Value = resp.json().await.unwrap_or(Value::Null);
+            let status = body.pointer("/status/status").and_then(Value::as_str).unwrap_or("");
+            match status {
+                "SUCCEEDED" => return Ok(()),
+                "DISCARDED" | "FAILED" => return Err(anyhow!("faucet task {} {}", task, status.to_lowercase())),
+                _ => debug!("Faucet task {}: {}", task, if status.is_empty() { "pending" } else { status }),
+            }
+        }
+        Err(anyhow!("faucet task {} not completed after {:?}", task, TASK_TIMEOUT))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_are_spaced_by_the_rate_limit() {
+        let faucet = FaucetClient::new("http://127.0.0.1:9123/", 100.0, true);
+        assert_eq!(faucet.base_url, "http://127.0.0.1:9123");
+        assert_eq!(faucet.min_interval, Some(Duration::from_millis(10)));
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            faucet.wait_for_slot().await;
+        }
+        // The first slot is free, the next two wait one interval each
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(FaucetClient::new("http://127.0.0.1:9123", 0.0, true).min_interval.is_none());
+    }
+
+    #[test]
+    fn requests_name_the_recipient() {
+        let address = SuiAddress::random_for_testing_only();
+        let body = FaucetClient::request_body(address);
+        assert_eq!(body["FixedAmountRequest"]["recipient"], address.to_string());
+    }
+}
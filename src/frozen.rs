@@ -0,0 +1,229 @@
+// Freeze / immutable-object workload (--freeze-pct, --frozen-read-pct)
+//
+// Every --freeze-interval seconds each worker freezes --freeze-pct percent of
+// its tracked objects (oldest first) and moves them to a separate frozen list.
+// Frozen objects are immutable: they are never rewritten again and their refs
+// never go stale, so they form an explicitly cold population. A share of
+// operations (--frozen-read-pct) reads them back, touching cold data without
+// invalidating it - the ideal cold stream for checking that FDP keeps it out
+// of the reclaim units the hot objects churn through.
+//
+// Freezing needs the freeze_counter / freeze_blob / read_counter entry points,
+// so packages published before they were added must be republished.
+
+use anyhow::{anyhow, Result};
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_sdk::rpc_types::{ObjectChange, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions};
+use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{ObjectArg, Transaction, TransactionData};
+use sui_sdk::types::transaction_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::types::Identifier;
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+
+use crate::{gas_spent, simulate_tx, GasCharge, TrackedObject, TxOutcome, WorkerState};
+
+/// Objects frozen per transaction
+const FREEZE_CHUNK: usize = 256;
+
+/// Fires once per --freeze-interval
+pub struct FreezeTimer {
+    interval: Duration,
+    next: Instant,
+}
+
+impl FreezeTimer {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, next: Instant::now() + interval }
+    }
+
+    /// True (and re-armed) once per interval
+    pub fn due(&mut self) -> bool {
+        if Instant::now() < self.next {
+            return false;
+        }
+        self.next = Instant::now() + self.interval;
+        true
+    }
+}
+
+/// Freeze `pct` percent of the worker's tracked objects (oldest first); `updated` is the number frozen
+///
+/// Returns None if there was nothing to freeze.
+pub async fn freeze_objects(
+    client: &SuiClient,
+    worker: &RwLock<WorkerState>,
+    package_id: ObjectID,
+    pct: u8,
+    use_blobs: bool,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<Option<TxOutcome>> {
+    let mut state = worker.write().await;
+    // Always leave something to update
+    let count = (state.objects.len() * pct as usize / 100)
+        .min(state.objects.len().saturating_sub(1))
+        .min(FREEZE_CHUNK)
+        .min(state.max_tracked.saturating_sub(state.frozen.len()));
+    if count == 0 {
+        return Ok(None);
+    }
+    let function = if use_blobs { "freeze_blob" } else { "freeze_counter" };
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for obj in &state.objects[..count] {
+        let obj_arg = builder.obj(ObjectArg::ImmOrOwnedObject((obj.id, obj.version.into(), obj.digest)))?;
+        builder.programmable_move_call(
+            package_id,
+            Identifier::new("io_churn").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            vec![obj_arg],
+        );
+    }
+    state.last_batch = state.objects[..count].iter().map(|o| o.id).collect();
+    state.batch_stride = 1;
+
+    let tx_data = TransactionData::new_programmable(
+        state.address,
+        vec![state.gas_coin],
+        builder.finish(),
+        gas_budget,
+        rgp,
+    );
+    let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new()
+                .with_effects()
+                .with_object_changes(),
+            Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+        )
+        .await?;
+
+    let effects = response.effects.as_ref()
+        .ok_or_else(|| anyhow!("{} response has no effects", function))?;
+    if effects.status().is_err() {
+        return Err(anyhow!("{} failed: {:?}", function, effects.status()));
+    }
+    let gas_used = gas_spent(effects.gas_cost_summary());
+    let charge = GasCharge::of(effects.gas_cost_summary());
+    let gas_obj = effects.gas_object();
+    state.gas_coin = (gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest);
+
+    // Frozen objects get one last version; they keep that ref for good
+    let mut frozen: Vec<TrackedObject> = state.objects.drain(..count).collect();
+    for change in response.object_changes.iter().flatten() {
+        if let ObjectChange::Mutated { object_id, version, digest, .. } = change {
+            if let Some(obj) = frozen.iter_mut().find(|o| o.id == *object_id) {
+                obj.version = version.value();
+                obj.digest = *digest;
+            }
+        }
+    }
+    state.frozen.append(&mut frozen);
+    Ok(Some(TxOutcome {
+        created: 0,
+        updated: count as u64,
+        gas_used,
+        charge,
+        digest: response.digest,
+        bytes_written: 0,
+        object_sizes: Vec::new(),
+        read: 0,
+        deleted: 0,
+        duplicate: false,
+        consensus: false,
+    }))
+}
+
+/// Read up to `count` frozen objects in one transaction (nothing but gas is written)
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_frozen_read(
+    client: &SuiClient,
+    worker: &Arc<RwLock<WorkerState>>,
+    package_id: ObjectID,
+    count: usize,
+    use_blobs: bool,
+    gas_budget: u64,
+    rgp: u64,
+    simulate: bool,
+) -> Result<TxOutcome> {
+    let mut state = worker.write().await;
+    if state.frozen.is_empty() {
+        return Err(anyhow!("No frozen objects to read"));
+    }
+    let function = if use_blobs { "read_blob" } else { "read_counter" };
+    let read_count = count.min(state.frozen.len());
+    let start_idx = rand::rngs::StdRng::from_entropy().gen_range(0..state.frozen.len());
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for i in 0..read_count {
+        let obj = &state.frozen[(start_idx + i) % state.frozen.len()];
+        let obj_arg = builder.obj(ObjectArg::ImmOrOwnedObject((obj.id, obj.version.into(), obj.digest)))?;
+        builder.programmable_move_call(
+            package_id,
+            Identifier::new("io_churn").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            vec![obj_arg],
+        );
+    }
+    // Immutable objects can't be lock-conflicted
+    state.last_batch.clear();
+
+    let tx_data = TransactionData::new_programmable(
+        state.address,
+        state.gas_payment(),
+        builder.finish(),
+        gas_budget,
+        rgp,
+    );
+
+    if simulate {
+        let outcome = simulate_tx(client, tx_data, state.gas_coin.0, 0).await?;
+        return Ok(TxOutcome { read: read_count as u64, ..outcome });
+    }
+
+    let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new().with_effects(),
+            Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+        )
+        .await?;
+
+    // A response for a transaction already accounted for (e.g. a retried submission)
+    if !state.seen_digests.insert(response.digest) {
+        return Ok(TxOutcome::duplicate(response.digest));
+    }
+
+    let (mut gas_used, mut charge) = (0u64, GasCharge::default());
+    if let Some(effects) = &response.effects {
+        gas_used = gas_spent(effects.gas_cost_summary());
+        charge = GasCharge::of(effects.gas_cost_summary());
+        let gas_obj = effects.gas_object();
+        state.gas_charged((gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest));
+    }
+
+    Ok(TxOutcome {
+        created: 0,
+        updated: 0,
+        gas_used,
+        charge,
+        digest: response.digest,
+        bytes_written: 0,
+        object_sizes: Vec::new(),
+        read: read_count as u64,
+        deleted: 0,
+        duplicate: false,
+        consensus: false,
+    })
+}
@@ -0,0 +1,101 @@
+// Gas-only transactions (--workload gas_only=<pct>)
+//
+// Sends the gas coin back to its owner and does nothing else, so the only
+// object written is the gas coin itself. Interleaved with the real mix, these
+// transactions measure the fixed cost every transaction pays - the effects,
+// the transaction record and one coin rewrite - in gas and in latency. The
+// `gas_baseline` section subtracts it from the rest of the run to give the
+// cost of the object payloads alone.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+use crate::latency::LatencySummary;
+use crate::validate::Percent;
+use crate::workload::{BuildContext, Effects, Planned, Workload, WorkloadReport};
+use crate::{Args, TrackedObject, TxOutcome, WorkerState};
+
+pub struct GasOnly {
+    pct: Percent,
+}
+
+impl GasOnly {
+    pub fn create(_args: &Args, pct: Percent) -> Result<Arc<dyn Workload>> {
+        Ok(Arc::new(Self { pct }))
+    }
+}
+
+impl Workload for GasOnly {
+    fn name(&self) -> &'static str {
+        "gas_only"
+    }
+
+    fn weight(&self) -> Percent {
+        self.pct
+    }
+
+    fn build_ptb(&self, state: &mut WorkerState, _ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        builder.pay_all_sui(state.address);
+        Ok(Planned::default())
+    }
+
+    fn apply_effects(&self, _objects: &mut Vec<TrackedObject>, _effects: &Effects, _planned: Planned, _outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        Vec::new()
+    }
+}
+
+/// `gas_baseline` section: the fixed per-transaction cost and what payloads add on top
+#[derive(Debug, Clone, Serialize)]
+pub struct GasBaseline {
+    pub gas_only_tx: u64,
+    /// Net MIST of one gas-only transaction
+    pub baseline_net_mist: f64,
+    pub baseline_latency: LatencySummary,
+    pub other_tx: u64,
+    pub other_net_mist: f64,
+    /// (other transactions' mean net - baseline) per object they wrote
+    pub payload_net_mist_per_object: Option<f64>,
+}
+
+/// Compare the gas_only report with the run totals (net gas, successes, objects written)
+pub fn baseline(report: &WorkloadReport, latency: LatencySummary, net_gas: i64, tx_success: u64, objects_written: u64) -> Option<GasBaseline> {
+    if report.tx_success == 0 {
+        return None;
+    }
+    let baseline_net_mist = report.net_mist as f64 / report.tx_success as f64;
+    let other_tx = tx_success.saturating_sub(report.tx_success);
+    let other_net_mist = if other_tx > 0 { (net_gas - report.net_mist) as f64 / other_tx as f64 } else { 0.0 };
+    let per_tx_objects = objects_written as f64 / other_tx.max(1) as f64;
+    Some(GasBaseline {
+        gas_only_tx: report.tx_success,
+        baseline_net_mist,
+        baseline_latency: latency,
+        other_tx,
+        other_net_mist,
+        payload_net_mist_per_object: (other_tx > 0 && per_tx_objects > 0.0)
+            .then(|| (other_net_mist - baseline_net_mist) / per_tx_objects),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::latency::Histogram;
+
+    #[test]
+    fn payload_cost_is_measured_above_the_baseline() {
+        let report = WorkloadReport { name: "gas_only", pct: 10, tx_success: 10, tx_failed: 0, objects: 0, net_mist: 10_000 };
+        // 90 other transactions writing 50 objects each at 1000 + 50 * 200 MIST
+        let other = 90 * (1_000 + 50 * 200);
+        let baseline = baseline(&report, Histogram::new().summary(), 10_000 + other, 100, 90 * 50).unwrap();
+        assert_eq!((baseline.gas_only_tx, baseline.other_tx), (10, 90));
+        assert_eq!(baseline.baseline_net_mist, 1_000.0);
+        assert_eq!(baseline.other_net_mist, 11_000.0);
+        assert_eq!(baseline.payload_net_mist_per_object, Some(200.0));
+
+        let idle = WorkloadReport { tx_success: 0, ..report };
+        assert!(super::baseline(&idle, Histogram::new().summary(), 0, 0, 0).is_none());
+    }
+}
@@ -0,0 +1,249 @@
+// Per-worker gas coin pools
+//
+// A worker normally owns a single gas coin, which serializes all of its
+// transactions (each one mutates the coin). With --gas-coins-per-worker N the
+// faucet coin is split into N coins at init: `gas_coin` stays the primary coin
+// and the rest go to `gas_pool`, so independent transactions (e.g. seed
+// batches) can be in flight concurrently on distinct coins. Before splitting,
+// any extra coins a reused address holds are merged into the primary coin.
+//
+// With --gas-smash N the extra coins are not merged up front. They become the
+// worker's `smash_coins` instead, and every workload transaction pays with the
+// primary coin plus up to N-1 of them, so the gas payment itself merges and
+// deletes coin objects as the run goes. --gas-smash-refill splits fresh
+// fragments off the primary coin whenever they run out, to keep that write
+// pattern going for the whole run.
+
+use anyhow::{anyhow, Context, Result};
+use std::sync::Arc;
+use sui_sdk::rpc_types::{
+    ObjectChange,
+    SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::types::{
+    base_types::ObjectRef,
+    gas::GasCostSummary,
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Transaction, TransactionData},
+    transaction_driver_types::ExecuteTransactionRequestType,
+};
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::WorkerState;
+
+/// Max coins merged per transaction (protocol limit is 256 gas payment objects)
+pub const MAX_MERGE_COINS: usize = 250;
+
+/// Balance of each refilled fragment (0.001 SUI)
+const FRAGMENT_MIST: u64 = 1_000_000;
+
+/// Merge all SUI coins the worker owns into its primary coin
+///
+/// Reused addresses accumulate many small faucet grants; picking only the
+/// largest coin leaves the rest idle and can exhaust the balance mid-run. The
+/// coins are gas-smashed (all passed as gas payment) and pay_all_sui sends the
+/// merged coin back to the owner, so the result is one coin with the total.
+pub async fn consolidate_gas(
+    client: &SuiClient,
+    worker: &Arc<RwLock<WorkerState>>,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<()> {
+    let mut state = worker.write().await;
+
+    let mut coins = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = client
+            .coin_read_api()
+            .get_coins(state.address, None, cursor, None)
+            .await
+            .context("Failed to get coins")?;
+        coins.extend(page.data);
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    if coins.len() <= 1 {
+        return Ok(());
+    }
+    // Largest first so it becomes the surviving coin
+    coins.sort_by_key(|c| std::cmp::Reverse(c.balance));
+    let total: u64 = coins.iter().map(|c| c.balance).sum();
+    let count = coins.len();
+
+    let mut primary = coins[0].object_ref();
+    let mut rest: Vec<_> = coins[1..].iter().map(|c| c.object_ref()).collect();
+    while !rest.is_empty() {
+        let take = rest.len().min(MAX_MERGE_COINS - 1);
+        let mut payment = vec![primary];
+        payment.extend(rest.drain(..take));
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.pay_all_sui(state.address);
+        let tx_data = TransactionData::new_programmable(
+            state.address,
+            payment,
+            builder.finish(),
+            gas_budget,
+            rgp,
+        );
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+
+        let response = client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await
+            .context("Failed to merge gas coins")?;
+        let effects = response.effects.as_ref()
+            .ok_or_else(|| anyhow!("Worker {}: gas merge returned no effects", state.id))?;
+        if effects.status().is_err() {
+            return Err(anyhow!("Worker {}: gas merge failed: {:?}", state.id, effects.status()));
+        }
+        let gas_obj = effects.gas_object();
+        primary = (gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest);
+    }
+
+    state.gas_coin = primary;
+    info!("Worker {}: merged {} gas coins ({} MIST total)", state.id, count, total);
+    Ok(())
+}
+
+/// Split the worker's primary gas coin into `coins` roughly equal coins
+pub async fn split_gas_coin(
+    client: &SuiClient,
+    worker: &Arc<RwLock<WorkerState>>,
+    coins: usize,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<()> {
+    if coins <= 1 {
+        return Ok(());
+    }
+    let mut state = worker.write().await;
+
+    let balance = client
+        .coin_read_api()
+        .get_coins(state.address, None, None, None)
+        .await
+        .context("Failed to get coins")?
+        .data
+        .into_iter()
+        .find(|c| c.coin_object_id == state.gas_coin.0)
+        .map(|c| c.balance)
+        .ok_or_else(|| anyhow!("Worker {}: primary gas coin not found", state.id))?;
+
+    // Keep a full budget on the primary coin for the split itself
+    let amount = balance.saturating_sub(gas_budget) / coins as u64;
+    if amount < gas_budget {
+        return Err(anyhow!(
+            "Worker {}: balance {} too small to split into {} coins of at least one gas budget ({})",
+            state.id, balance, coins, gas_budget
+        ));
+    }
+
+    let (created, _) = pay_self(client, &mut state, coins - 1, amount, gas_budget, rgp).await?;
+    state.gas_pool.extend(created);
+
+    info!("Worker {}: gas split into {} coins of {} MIST", state.id, state.gas_pool.len() + 1, amount);
+    Ok(())
+}
+
+/// Split `count` coins of `amount` off the primary coin; returns the new coins and the gas cost
+pub async fn pay_self(
+    client: &SuiClient,
+    state: &mut WorkerState,
+    count: usize,
+    amount: u64,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<(Vec<ObjectRef>, GasCostSummary)> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.pay_sui(vec![state.address; count], vec![amount; count])?;
+    let tx_data = TransactionData::new_programmable(
+        state.address,
+        vec![state.gas_coin],
+        builder.finish(),
+        gas_budget,
+        rgp,
+    );
+    let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new()
+                .with_effects()
+                .with_object_changes(),
+            Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+        )
+        .await
+        .context("Failed to split gas coin")?;
+
+    let effects = response.effects.as_ref()
+        .ok_or_else(|| anyhow!("Worker {}: gas split returned no effects", state.id))?;
+    if effects.status().is_err() {
+        return Err(anyhow!("Worker {}: gas split failed: {:?}", state.id, effects.status()));
+    }
+    let gas_obj = effects.gas_object();
+    state.gas_coin = (gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest);
+
+    let mut created = Vec::new();
+    for change in response.object_changes.iter().flatten() {
+        if let ObjectChange::Created { object_id, version, digest, .. } = change {
+            created.push((*object_id, *version, *digest));
+        }
+    }
+    Ok((created, effects.gas_cost_summary().clone()))
+}
+
+/// Keep every other coin the worker owns as a gas-smash fragment (--gas-smash)
+pub async fn collect_fragments(client: &SuiClient, worker: &Arc<RwLock<WorkerState>>) -> Result<()> {
+    let mut state = worker.write().await;
+    let mut cursor = None;
+    loop {
+        let page = client
+            .coin_read_api()
+            .get_coins(state.address, None, cursor, None)
+            .await
+            .context("Failed to get coins")?;
+        for coin in page.data {
+            let id = coin.coin_object_id;
+            if id != state.gas_coin.0 && !state.gas_pool.iter().any(|c| c.0 == id) {
+                state.smash_coins.push(coin.object_ref());
+            }
+        }
+        if !page.has_next_page {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    if !state.smash_coins.is_empty() {
+        info!("Worker {}: {} fragmented coins left for gas smashing", state.id, state.smash_coins.len());
+    }
+    Ok(())
+}
+
+/// Split `count` small fragments off the primary coin for later gas payments; returns the gas cost
+pub async fn refill_fragments(
+    client: &SuiClient,
+    worker: &Arc<RwLock<WorkerState>>,
+    count: usize,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<GasCostSummary> {
+    let mut state = worker.write().await;
+    let (created, gas) = pay_self(client, &mut state, count, FRAGMENT_MIST, gas_budget, rgp).await?;
+    state.smash_coins.extend(created);
+    state.smash_refills += 1;
+    Ok(gas)
+}
@@ -0,0 +1,176 @@
+// Per-stream gas coins (--gas-streams)
+//
+// Every transaction mutates the gas coin it pays with, so a run that measures
+// a cold stream (objects created once) against a hot stream (objects rewritten
+// over and over) also writes one gas coin version per transaction into both.
+// With --gas-streams each worker splits two more coins off its primary coin at
+// init, one per stream: built-in creates (cold) pay only with the create coin
+// and built-in updates (hot) only with the update coin, while setup and every
+// other workload keep the primary coin. Gas writes therefore land on a small,
+// known set of coin objects per stream instead of being spread over whatever
+// the worker happens to do. The `gas_streams` section lists the coins of each
+// stream, so device-side analysis can attribute or exclude their writes, and
+// the transactions each stream paid for.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::sync::Arc;
+use sui_sdk::rpc_types::SuiObjectDataOptions;
+use sui_sdk::types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::{failures, gas_pool, WorkerState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasStream {
+    Create,
+    Update,
+}
+
+impl GasStream {
+    const ALL: [GasStream; 2] = [GasStream::Create, GasStream::Update];
+
+    /// Stream of a workload's transactions, if it has one of its own
+    pub fn of(workload: &str) -> Option<Self> {
+        match workload {
+            "create" | "create_blob" => Some(GasStream::Create),
+            "update" | "update_blob" => Some(GasStream::Update),
+            _ => None,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A worker's stream coins and the transactions each paid for
+#[derive(Debug, Clone)]
+pub struct StreamCoins {
+    coins: [ObjectRef; 2],
+    paid: [u64; 2],
+}
+
+impl StreamCoins {
+    pub fn new(create: ObjectRef, update: ObjectRef) -> Self {
+        Self { coins: [create, update], paid: [0; 2] }
+    }
+
+    pub fn coin(&self, stream: GasStream) -> ObjectRef {
+        self.coins[stream.index()]
+    }
+
+    /// The stream's coin after a transaction it paid for
+    pub fn charged(&mut self, stream: GasStream, coin: ObjectRef) {
+        self.coins[stream.index()] = coin;
+        self.paid[stream.index()] += 1;
+    }
+}
+
+/// Split the two stream coins off the worker's primary coin, a third of its balance each
+pub async fn split(client: &SuiClient, worker: &Arc<RwLock<WorkerState>>, gas_budget: u64, rgp: u64) -> Result<()> {
+    let mut state = worker.write().await;
+    let balance = client
+        .coin_read_api()
+        .get_coins(state.address, None, None, None)
+        .await
+        .context("Failed to get coins")?
+        .data
+        .into_iter()
+        .find(|c| c.coin_object_id == state.gas_coin.0)
+        .map(|c| c.balance)
+        .ok_or_else(|| anyhow!("Worker {}: primary gas coin not found", state.id))?;
+    let amount = balance.saturating_sub(gas_budget) / 3;
+    if amount < gas_budget {
+        return Err(anyhow!("Worker {}: balance {} too small for two stream coins of at least one gas budget ({})",
+            state.id, balance, gas_budget));
+    }
+    let (created, _) = gas_pool::pay_self(client, &mut state, 2, amount, gas_budget, rgp).await?;
+    let [create, update] = created[..] else {
+        return Err(anyhow!("Worker {}: stream coin split created {} coins", state.id, created.len()));
+    };
+    info!("Worker {}: stream gas coins {} (create) and {} (update), {} MIST each", state.id, create.0, update.0, amount);
+    state.stream_gas = Some(StreamCoins::new(create, update));
+    Ok(())
+}
+
+/// Synthetic stream coins for the mock backend
+pub fn mock(state: &mut WorkerState) {
+    let coin = || (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+    state.stream_gas = Some(StreamCoins::new(coin(), coin()));
+}
+
+/// Re-read stream coins a failure may have left stale; returns true if one changed
+pub async fn repair(client: &SuiClient, worker: &RwLock<WorkerState>, err: &anyhow::Error) -> Result<bool> {
+    let Some(coins) = worker.read().await.stream_gas.clone() else {
+        return Ok(false);
+    };
+    let mut repaired = false;
+    for stream in GasStream::ALL {
+        let cached = coins.coin(stream);
+        if !failures::may_have_stale_gas(err, &cached.0) {
+            continue;
+        }
+        let data = client.read_api()
+            .get_object_with_options(cached.0, SuiObjectDataOptions::new())
+            .await?
+            .data
+            .ok_or_else(|| anyhow!("Stream gas coin {} no longer exists", cached.0))?;
+        let fresh = (data.object_id, data.version, data.digest);
+        let mut state = worker.write().await;
+        let Some(current) = state.stream_gas.as_mut() else { continue };
+        // Another transaction may have advanced the ref meanwhile
+        if fresh != cached && current.coins[stream.index()] == cached {
+            warn!("Worker {}: repaired stale {:?} stream gas ref {} v{} -> v{}",
+                state.id, stream, cached.0, cached.1.value(), fresh.1.value());
+            current.coins[stream.index()] = fresh;
+            repaired = true;
+        }
+    }
+    Ok(repaired)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamReport {
+    pub stream: GasStream,
+    /// Transactions that paid with the stream's coins
+    pub transactions: u64,
+    pub coins: Vec<ObjectID>,
+}
+
+/// `gas_streams` section of the results
+pub async fn report(workers: &[Arc<RwLock<WorkerState>>]) -> Vec<StreamReport> {
+    let mut reports: Vec<StreamReport> = GasStream::ALL.iter()
+        .map(|&stream| StreamReport { stream, transactions: 0, coins: Vec::new() })
+        .collect();
+    for worker in workers {
+        let Some(coins) = worker.read().await.stream_gas.clone() else { continue };
+        for report in &mut reports {
+            report.transactions += coins.paid[report.stream.index()];
+            report.coins.push(coins.coin(report.stream).0);
+        }
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_creates_and_updates_pay_from_their_own_coin() {
+        assert_eq!(GasStream::of("create_blob"), Some(GasStream::Create));
+        assert_eq!(GasStream::of("update"), Some(GasStream::Update));
+        assert_eq!(GasStream::of("delete_blob"), None);
+        let coin = |n| (ObjectID::from_single_byte(n), SequenceNumber::from_u64(1), ObjectDigest::random());
+        let mut coins = StreamCoins::new(coin(1), coin(2));
+        let next = (ObjectID::from_single_byte(2), SequenceNumber::from_u64(2), ObjectDigest::random());
+        coins.charged(GasStream::Update, next);
+        assert_eq!(coins.coin(GasStream::Update), next);
+        assert_eq!(coins.coin(GasStream::Create).0, ObjectID::from_single_byte(1));
+        assert_eq!(coins.paid, [0, 1]);
+    }
+}
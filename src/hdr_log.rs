@@ -0,0 +1,253 @@
+// HdrHistogram interval log output (--hdr-log)
+//
+// Writes one histogram per stats interval in the HdrHistogram log format
+// (v1.3), so hdr-plot, HistogramLogProcessor and latency-by-time tooling can
+// read the run directly and percentiles can be recomputed over any sub-window.
+// Each interval has an untagged line (all ops merged) plus one "Tag=<op>" line
+// per operation type. Values are microseconds. An existing log (a rerun, or
+// --resume) is appended to: the header is only written to an empty file, and
+// a run continuing one restates its StartTime, which the log readers take as
+// the base of the interval timestamps that follow.
+//
+// Histograms use the V2 compressed encoding: ZigZag-LEB128 counts wrapped in a
+// zlib stream, base64-encoded on the line.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::write::ZlibEncoder;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::latency::{Histogram, HIGHEST_TRACKABLE_US};
+use crate::rotation::{self, RotationPolicy, RotationReport, Segments};
+use crate::timeline::utc_rfc3339;
+
+const ENCODING_COOKIE_V2: i32 = 0x1c84_9303;
+const COMPRESSED_ENCODING_COOKIE_V2: i32 = 0x1c84_9304;
+const SIGNIFICANT_DIGITS: i32 = 1;
+/// Interval_Max column is written in milliseconds
+const MAX_VALUE_UNIT_RATIO: f64 = 1000.0;
+
+fn put_zigzag_leb128(out: &mut Vec<u8>, value: i64) {
+    let mut v = ((value << 1) ^ (value >> 63)) as u64;
+    loop {
+        let byte = (v & 0x7F) as u8;
+        v >>= 7;
+        if v == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// V2 encoding of the counts array (zero runs collapse into one negative entry)
+fn encode_counts(counts: &[u64]) -> Vec<u8> {
+    let used = counts.iter().rposition(|&c| c > 0).map_or(0, |i| i + 1);
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < used {
+        if counts[i] == 0 {
+            let run = counts[i..used].iter().take_while(|&&c| c == 0).count();
+            put_zigzag_leb128(&mut out, if run > 1 { -(run as i64) } else { 0 });
+            i += run;
+        } else {
+            put_zigzag_leb128(&mut out, counts[i] as i64);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Base64 of the compressed V2 encoding of a histogram
+pub fn encode_histogram(h: &Histogram) -> Result<String> {
+    let payload = encode_counts(h.counts());
+    let mut encoded = Vec::with_capacity(40 + payload.len());
+    encoded.extend(ENCODING_COOKIE_V2.to_be_bytes());
+    encoded.extend((payload.len() as i32).to_be_bytes());
+    encoded.extend(0i32.to_be_bytes()); // normalizing index offset
+    encoded.extend(SIGNIFICANT_DIGITS.to_be_bytes());
+    encoded.extend(1i64.to_be_bytes()); // lowest discernible value
+    encoded.extend((HIGHEST_TRACKABLE_US as i64).to_be_bytes());
+    encoded.extend(1.0f64.to_be_bytes()); // integer-to-double conversion ratio
+    encoded.extend(payload);
+
+    let mut zlib = ZlibEncoder::new(Vec::with_capacity(encoded.len()), flate2::Compression::default());
+    zlib.write_all(&encoded)?;
+    let compressed = zlib.finish()?;
+    let mut wrapped = Vec::with_capacity(8 + compressed.len());
+    wrapped.extend(COMPRESSED_ENCODING_COOKIE_V2.to_be_bytes());
+    wrapped.extend((compressed.len() as i32).to_be_bytes());
+    wrapped.extend(compressed);
+    Ok(BASE64.encode(wrapped))
+}
+
+/// Interval log file being written during the run
+pub struct HdrLogWriter {
+    out: BufWriter<File>,
+    start_unix_ms: u64,
+    last_offset_secs: f64,
+    /// Segments of the log (--rotate-mb / --rotate-secs)
+    segments: Option<Segments>,
+}
+
+impl HdrLogWriter {
+    /// Create the log and write its header; `start_unix_ms` is the log's StartTime
+    pub fn create(path: &str, start_unix_ms: u64, rotation: Option<RotationPolicy>) -> Result<Self> {
+        let out = Self::open(path, start_unix_ms)?;
+        let segments = rotation.map(|policy| Segments::new(path, policy));
+        Ok(Self { out, start_unix_ms, last_offset_secs: 0.0, segments })
+    }
+
+    /// The file at `path` for appending, with the log header if it is new (every
+    /// segment of a rotated log has one) or this run's StartTime if it is not
+    fn open(path: &str, start_unix_ms: u64) -> Result<BufWriter<File>> {
+        let (mut out, empty) = rotation::open_append(path).context("Failed to open HDR log")?;
+        if empty {
+            writeln!(out, "#[Logged with fdp-sui-bench (values in microseconds)]")?;
+            writeln!(out, "#[Histogram log format version 1.3]")?;
+        }
+        writeln!(out, "#[StartTime: {:.3} (seconds since epoch), {}]",
+            start_unix_ms as f64 / 1000.0, utc_rfc3339(start_unix_ms))?;
+        if empty {
+            writeln!(out, "\"StartTimestamp\",\"Interval_Length\",\"Interval_Max\",\"Interval_Compressed_Histogram\"")?;
+        }
+        out.flush()?;
+        Ok(out)
+    }
+
+    /// Append the interval ending at `now_unix_ms` (untagged total plus one tagged line per op)
+    pub fn write_interval(&mut self, now_unix_ms: u64, by_op: &BTreeMap<&'static str, Histogram>) -> Result<()> {
+        let end = now_unix_ms.saturating_sub(self.start_unix_ms) as f64 / 1000.0;
+        let (start, length) = (self.last_offset_secs, (end - self.last_offset_secs).max(0.0));
+        self.last_offset_secs = end;
+
+        let mut total = Histogram::new();
+        for h in by_op.values() {
+            total.merge(h);
+        }
+        let line = |h: &Histogram| -> Result<String> {
+            Ok(format!("{:.3},{:.3},{:.3},{}",
+                start, length, h.max_us() as f64 / MAX_VALUE_UNIT_RATIO, encode_histogram(h)?))
+        };
+        let mut lines = format!("{}\n", line(&total)?);
+        for (op, h) in by_op.iter().filter(|(_, h)| !h.is_empty()) {
+            lines.push_str(&format!("Tag={},{}\n", op, line(h)?));
+        }
+        self.out.write_all(lines.as_bytes())?;
+        self.out.flush()?;
+        if let Some(segments) = self.segments.as_mut() {
+            segments.wrote(lines.len());
+            if segments.due() {
+                segments.close()?;
+                self.out = Self::open(segments.path(), self.start_unix_ms)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the segment index (None without rotation)
+    pub fn finish_segments(&mut self) -> Result<Option<RotationReport>> {
+        self.out.flush()?;
+        self.segments.as_mut().map(Segments::finish).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(values_us: &[u64]) -> BTreeMap<&'static str, Histogram> {
+        let mut h = Histogram::new();
+        for &us in values_us {
+            h.record(std::time::Duration::from_micros(us));
+        }
+        BTreeMap::from([("update", h)])
+    }
+
+    /// The histogram counts back from a line's base64 column, checking the V2 header on the way
+    fn decode_histogram(column: &str) -> Vec<u64> {
+        use flate2::read::ZlibDecoder;
+        use std::io::Read;
+        let be_i32 = |b: &[u8]| i32::from_be_bytes(b[..4].try_into().unwrap());
+
+        let wrapped = BASE64.decode(column).unwrap();
+        assert_eq!(be_i32(&wrapped[0..]), COMPRESSED_ENCODING_COOKIE_V2);
+        assert_eq!(be_i32(&wrapped[4..]) as usize, wrapped.len() - 8);
+        let mut encoded = Vec::new();
+        ZlibDecoder::new(&wrapped[8..]).read_to_end(&mut encoded).unwrap();
+        assert_eq!(be_i32(&encoded[0..]), ENCODING_COOKIE_V2);
+        assert_eq!(be_i32(&encoded[4..]) as usize, encoded.len() - 40);
+        assert_eq!(be_i32(&encoded[12..]), SIGNIFICANT_DIGITS);
+        assert_eq!(i64::from_be_bytes(encoded[24..32].try_into().unwrap()), HIGHEST_TRACKABLE_US as i64);
+
+        let (mut counts, mut value, mut shift) = (Vec::new(), 0u64, 0);
+        for &byte in &encoded[40..] {
+            value |= ((byte & 0x7F) as u64) << shift;
+            shift += 7;
+            if byte & 0x80 != 0 {
+                continue;
+            }
+            let n = (value >> 1) as i64 ^ -((value & 1) as i64);
+            if n < 0 {
+                counts.resize(counts.len() + (-n) as usize, 0);
+            } else {
+                counts.push(n as u64);
+            }
+            (value, shift) = (0, 0);
+        }
+        counts
+    }
+
+    #[test]
+    fn written_intervals_decode_back_to_their_histograms() {
+        let dir = std::env::temp_dir().join(format!("fdp-hdr-log-decode-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("latency.hlog").to_string_lossy().into_owned();
+        // Values far apart leave long zero runs between the buckets
+        let by_op = interval(&[3, 3, 150, 4_000, 4_100, 2_500_000]);
+
+        let mut log = HdrLogWriter::create(&path, 1_000_000, None).unwrap();
+        log.write_interval(1_002_000, &by_op).unwrap();
+        drop(log);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let line = content.lines().find(|l| l.starts_with("Tag=update,")).unwrap();
+        let columns: Vec<_> = line.split(',').collect();
+        assert_eq!(columns[1..4], ["0.000", "2.000", "2500.000"]);
+        let h = &by_op["update"];
+        let used = h.counts().iter().rposition(|&c| c > 0).unwrap() + 1;
+        assert_eq!(decode_histogram(columns[4]), &h.counts()[..used]);
+        assert_eq!(decode_histogram(&encode_histogram(&Histogram::new()).unwrap()), Vec::<u64>::new());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_resumed_run_appends_to_the_earlier_log() {
+        let dir = std::env::temp_dir().join(format!("fdp-hdr-log-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("latency.hlog").to_string_lossy().into_owned();
+
+        let mut first = HdrLogWriter::create(&path, 1_000_000, None).unwrap();
+        first.write_interval(1_001_000, &interval(&[100, 200])).unwrap();
+        drop(first);
+        let mut resumed = HdrLogWriter::create(&path, 1_005_000, None).unwrap();
+        resumed.write_interval(1_006_000, &interval(&[300])).unwrap();
+        drop(resumed);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.iter().filter(|l| l.starts_with("#[Histogram log format")).count(), 1);
+        assert_eq!(lines.iter().filter(|l| l.starts_with("\"StartTimestamp\"")).count(), 1);
+        let starts: Vec<_> = lines.iter().filter(|l| l.starts_with("#[StartTime: ")).collect();
+        assert_eq!(starts.len(), 2);
+        assert!(starts[1].starts_with("#[StartTime: 1005.000 "), "{}", starts[1]);
+        // Both runs' intervals, each relative to its own StartTime
+        let intervals: Vec<_> = lines.iter().filter(|l| l.starts_with("0.000,1.000,")).collect();
+        assert_eq!(intervals.len(), 2);
+        assert_eq!(lines.iter().filter(|l| l.starts_with("Tag=update,")).count(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
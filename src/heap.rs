@@ -0,0 +1,110 @@
+// The benchmark's own heap footprint (--self-memory-limit-mb)
+//
+// The global allocator wraps the system one and keeps a running count of live
+// heap bytes. Host-wide usage (memory.rs) can't tell our tracked objects apart
+// from the node's caches on the same box; this count can, so the memory
+// throttle can shed tracked objects when the client itself grows too big while
+// the host still looks fine.
+
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator plus live-byte accounting
+pub struct Counting;
+
+impl Counting {
+    fn grew(size: usize) {
+        let now = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(now, Ordering::Relaxed);
+    }
+
+    fn shrank(size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call is forwarded to System unchanged; only counters are updated
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrank(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                Self::grew(new_size - layout.size());
+            } else {
+                Self::shrank(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Live heap bytes right now
+pub fn allocated() -> usize {
+    ALLOCATED.load(Ordering::Relaxed)
+}
+
+/// Highest live heap bytes seen so far
+pub fn peak() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/// Live heap as a fraction of `limit_mb`, the memory throttle's self reading
+pub fn usage(limit_mb: u64) -> f64 {
+    allocated() as f64 / (limit_mb * 1024 * 1024) as f64
+}
+
+/// `self_memory` section of the results
+#[derive(Debug, Serialize)]
+pub struct HeapSummary {
+    pub limit_mb: u64,
+    pub peak_mb: f64,
+    pub end_mb: f64,
+}
+
+impl HeapSummary {
+    pub fn now(limit_mb: u64) -> Self {
+        let mb = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+        Self { limit_mb, peak_mb: mb(peak()), end_mb: mb(allocated()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn live_allocations_are_counted() {
+        // Other tests allocate concurrently, so only check what this one holds
+        let block = vec![1u8; 8 << 20];
+        assert!(allocated() >= block.len());
+        assert!(peak() >= block.len());
+        assert!(usage(4) >= 2.0);
+        drop(block);
+        assert!(peak() >= 8 << 20);
+    }
+}
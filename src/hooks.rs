@@ -0,0 +1,139 @@
+// External collector hooks (--exec-before / --exec-interval / --exec-after)
+//
+// Runs user-provided shell commands at the run boundaries and every stats
+// interval (nvme-cli scripts, blktrace start/stop, vendor tools...) and keeps
+// their output in the results, so new telemetry doesn't need code here. The
+// commands get BENCH_PHASE and BENCH_ELAPSED_SECS in their environment.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::{info, warn};
+
+use crate::timeline::{Ticker, Timestamp};
+use crate::BenchStats;
+
+/// Captured output is truncated to this many bytes per stream
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// One hook invocation
+#[derive(Debug, Clone, Serialize)]
+pub struct HookRun {
+    pub command: String,
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub duration_secs: f64,
+    /// None if the command was killed by a signal or timed out
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub stderr: String,
+}
+
+fn capture(bytes: &[u8]) -> String {
+    let end = bytes.len().min(MAX_OUTPUT_BYTES);
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Run one command through `sh -c`, failures are recorded rather than returned
+pub async fn run_hook(command: &str, phase: &str, stats: &BenchStats, limit: Duration) -> HookRun {
+    let at = Timestamp::now(stats);
+    let started = Instant::now();
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("BENCH_PHASE", phase)
+        .env("BENCH_ELAPSED_SECS", format!("{:.3}", at.elapsed_secs))
+        .kill_on_drop(true)
+        .output();
+
+    let (exit_code, timed_out, stdout, stderr) = match timeout(limit, child).await {
+        Ok(Ok(out)) => (out.status.code(), false, capture(&out.stdout), capture(&out.stderr)),
+        Ok(Err(e)) => (None, false, String::new(), format!("failed to spawn: {}", e)),
+        Err(_) => (None, true, String::new(), String::new()),
+    };
+    if timed_out {
+        warn!("Hook '{}' ({}) timed out after {}s", command, phase, limit.as_secs());
+    } else if exit_code != Some(0) {
+        warn!("Hook '{}' ({}) exited with {:?}", command, phase, exit_code);
+    }
+    HookRun {
+        command: command.to_string(),
+        at,
+        duration_secs: started.elapsed().as_secs_f64(),
+        exit_code,
+        timed_out,
+        stdout,
+        stderr,
+    }
+}
+
+/// Run a list of commands in order
+pub async fn run_all(commands: &[String], phase: &str, stats: &BenchStats, limit: Duration) -> Vec<HookRun> {
+    let mut runs = Vec::with_capacity(commands.len());
+    for command in commands {
+        info!("Running {} hook: {}", phase, command);
+        runs.push(run_hook(command, phase, stats, limit).await);
+    }
+    runs
+}
+
+/// Run the interval hooks every stats interval until `running` is cleared
+pub async fn run_interval_hooks(
+    commands: Vec<String>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    ticker: Ticker,
+    limit: Duration,
+) -> Vec<HookRun> {
+    let mut runs = Vec::new();
+    while running.load(Ordering::Relaxed) {
+        ticker.tick(stats.clock.as_ref()).await;
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+        for command in &commands {
+            runs.push(run_hook(command, "interval", &stats, limit).await);
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock;
+
+    #[tokio::test]
+    async fn hooks_see_their_phase_and_report_exit_codes() {
+        let stats = BenchStats::new(1, clock::system());
+        let commands = ["echo \"$BENCH_PHASE\"; echo oops >&2; exit 3".to_string(), "true".to_string()];
+
+        let runs = run_all(&commands, "before", &stats, Duration::from_secs(10)).await;
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!((runs[0].stdout.as_str(), runs[0].stderr.as_str()), ("before\n", "oops\n"));
+        assert_eq!((runs[0].exit_code, runs[0].timed_out), (Some(3), false));
+        assert_eq!(runs[1].exit_code, Some(0));
+    }
+
+    #[tokio::test]
+    async fn a_hook_past_its_limit_is_killed() {
+        let stats = BenchStats::new(1, clock::system());
+
+        let run = run_hook("sleep 5", "after", &stats, Duration::from_millis(100)).await;
+
+        assert!(run.timed_out);
+        assert_eq!(run.exit_code, None);
+        assert!(run.duration_secs < 5.0);
+    }
+
+    #[test]
+    fn captured_output_is_truncated() {
+        assert_eq!(capture(&vec![b'x'; MAX_OUTPUT_BYTES + 10]).len(), MAX_OUTPUT_BYTES);
+    }
+}
@@ -0,0 +1,110 @@
+// Hot/cold split workload (--hot-fraction)
+//
+// The hypothesis FDP is meant to exploit in its purest form: a small hot set
+// that is rewritten over and over, and a large cold set that is written once
+// and never touched again. At the start of the load phase each worker keeps
+// --hot-fraction of its tracked objects as the hot set; the rest, and every
+// object created during the run, move to a cold list that updates never draw
+// from (it is still saved with --save-objects). Write volume is counted per
+// temperature, so the split that actually reached the device is on record
+// instead of assumed from the settings.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{TxOutcome, WorkerState};
+
+impl WorkerState {
+    /// Keep `fraction` of the tracked objects (at least one) hot and move the rest to the cold list;
+    /// from now on created objects go to the cold list too
+    pub fn split_hot_cold(&mut self, fraction: f64) {
+        if self.cold_split {
+            return;
+        }
+        self.cold_split = true;
+        let keep = ((self.objects.len() as f64 * fraction).ceil() as usize).clamp(1, self.objects.len().max(1));
+        let cold = self.objects.split_off(keep.min(self.objects.len()));
+        self.cold.extend(cold);
+    }
+}
+
+/// Objects and logical bytes written per temperature
+#[derive(Debug, Default)]
+pub struct TemperatureStats {
+    hot_writes: AtomicU64,
+    hot_bytes: AtomicU64,
+    cold_writes: AtomicU64,
+    cold_bytes: AtomicU64,
+}
+
+impl TemperatureStats {
+    /// Creates are cold data, updates rewrite the hot set
+    pub fn record(&self, outcome: &TxOutcome) {
+        if outcome.created > 0 {
+            self.cold_writes.fetch_add(outcome.created, Ordering::Relaxed);
+            self.cold_bytes.fetch_add(outcome.bytes_written, Ordering::Relaxed);
+        } else if outcome.updated > 0 {
+            self.hot_writes.fetch_add(outcome.updated, Ordering::Relaxed);
+            self.hot_bytes.fetch_add(outcome.bytes_written, Ordering::Relaxed);
+        }
+    }
+
+    pub fn report(&self, fraction: f64, hot_objects: usize, cold_objects: usize) -> HotColdReport {
+        let hot_bytes = self.hot_bytes.load(Ordering::Relaxed);
+        let cold_bytes = self.cold_bytes.load(Ordering::Relaxed);
+        let hot_writes = self.hot_writes.load(Ordering::Relaxed);
+        let total = hot_bytes + cold_bytes;
+        HotColdReport {
+            hot_fraction: fraction,
+            hot_objects,
+            cold_objects,
+            hot_writes,
+            hot_bytes,
+            cold_writes: self.cold_writes.load(Ordering::Relaxed),
+            cold_bytes,
+            hot_byte_share: if total > 0 { hot_bytes as f64 / total as f64 } else { 0.0 },
+            rewrites_per_hot_object: if hot_objects > 0 { hot_writes as f64 / hot_objects as f64 } else { 0.0 },
+        }
+    }
+}
+
+/// `hot_cold` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct HotColdReport {
+    pub hot_fraction: f64,
+    /// Hot and cold objects tracked at the end of the run
+    pub hot_objects: usize,
+    pub cold_objects: usize,
+    /// Object writes and logical bytes: updates of the hot set, creates of cold objects
+    pub hot_writes: u64,
+    pub hot_bytes: u64,
+    pub cold_writes: u64,
+    pub cold_bytes: u64,
+    pub hot_byte_share: f64,
+    pub rewrites_per_hot_object: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::TransactionDigest;
+
+    fn outcome(created: u64, updated: u64, bytes: u64) -> TxOutcome {
+        TxOutcome { created, updated, bytes_written: bytes, ..TxOutcome::empty(TransactionDigest([1; 32])) }
+    }
+
+    #[test]
+    fn writes_are_split_by_temperature() {
+        let stats = TemperatureStats::default();
+        stats.record(&outcome(10, 0, 1_000));
+        stats.record(&outcome(0, 50, 5_000));
+        stats.record(&outcome(0, 50, 4_000));
+        // Reads and gas-only transactions write no payload
+        stats.record(&outcome(0, 0, 0));
+        let report = stats.report(0.1, 20, 500);
+        assert_eq!((report.hot_writes, report.hot_bytes), (100, 9_000));
+        assert_eq!((report.cold_writes, report.cold_bytes), (10, 1_000));
+        assert_eq!(report.hot_byte_share, 0.9);
+        assert_eq!(report.rewrites_per_hot_object, 5.0);
+    }
+}
@@ -0,0 +1,347 @@
+// Shared-object hotspot workload (--hot-objects, --hot-skew, --hot-pct)
+//
+// Owned-object updates take Sui's fast path and never contend. Updates to
+// shared objects are sequenced by consensus, and transactions touching the same
+// shared object are serialized behind each other. This module creates a set of
+// shared MicroCounters before the load phase and routes a share of update
+// transactions to them, picking targets from a Zipf distribution: --hot-skew 0
+// spreads updates uniformly over all --hot-objects, larger values concentrate
+// them on the first few until a single counter absorbs nearly all contention.
+// Sweeping the two knobs moves the workload from single-hotspot serialization to
+// well-distributed shared load.
+
+use anyhow::{anyhow, Context, Result};
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_sdk::rpc_types::{ObjectChange, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions};
+use sui_sdk::types::base_types::{ObjectID, SequenceNumber};
+use sui_sdk::types::object::Owner;
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{ObjectArg, Transaction, TransactionData};
+use sui_sdk::types::transaction_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::types::Identifier;
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::{gas_spent, simulate_tx, GasCharge, TxOutcome, WorkerState, COUNTER_OBJECT_BYTES};
+
+/// create_shared calls per setup transaction
+const CREATE_CHUNK: usize = 256;
+
+/// The shared counters and how often each was updated
+pub struct HotSet {
+    objects: Vec<(ObjectID, SequenceNumber)>,
+    /// Cumulative Zipf probabilities by rank
+    cdf: Vec<f64>,
+    skew: f64,
+    updates: Vec<AtomicU64>,
+    /// Package the counters were created with (tenants may use other packages)
+    package_id: ObjectID,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HotObjectUpdates {
+    pub object_id: String,
+    pub initial_shared_version: u64,
+    pub updates: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HotspotReport {
+    pub hot_objects: usize,
+    pub skew: f64,
+    pub updates: u64,
+    /// Share of shared-object updates that hit the hottest counter
+    pub top_share: f64,
+    /// Number of equally-loaded counters that would give the same contention (inverse Simpson index)
+    pub effective_objects: f64,
+    pub by_object: Vec<HotObjectUpdates>,
+}
+
+impl HotSet {
+    fn new(objects: Vec<(ObjectID, SequenceNumber)>, skew: f64, package_id: ObjectID) -> Self {
+        let weights: Vec<f64> = (1..=objects.len()).map(|rank| (rank as f64).powf(-skew)).collect();
+        let total: f64 = weights.iter().sum();
+        let cdf = weights.iter()
+            .scan(0.0, |acc, w| {
+                *acc += w / total;
+                Some(*acc)
+            })
+            .collect();
+        let updates = objects.iter().map(|_| AtomicU64::new(0)).collect();
+        Self { objects, cdf, skew, updates, package_id }
+    }
+
+    pub fn package_id(&self) -> ObjectID {
+        self.package_id
+    }
+
+    /// Index of a counter drawn from the Zipf distribution
+    fn pick(&self, rng: &mut impl Rng) -> usize {
+        let r: f64 = rng.gen();
+        self.cdf.partition_point(|c| *c < r).min(self.objects.len() - 1)
+    }
+
+    /// Up to `count` distinct counters (fewer when the skew keeps hitting the same ones)
+    fn pick_batch(&self, count: usize, rng: &mut impl Rng) -> Vec<usize> {
+        let want = count.clamp(1, self.objects.len());
+        let mut picked = Vec::with_capacity(want);
+        for _ in 0..want * 8 {
+            let idx = self.pick(rng);
+            if !picked.contains(&idx) {
+                picked.push(idx);
+                if picked.len() == want {
+                    break;
+                }
+            }
+        }
+        picked
+    }
+
+    pub fn report(&self) -> HotspotReport {
+        let counts: Vec<u64> = self.updates.iter().map(|u| u.load(Ordering::Relaxed)).collect();
+        let total: u64 = counts.iter().sum();
+        let share = |c: u64| if total > 0 { c as f64 / total as f64 } else { 0.0 };
+        let simpson: f64 = counts.iter().map(|c| share(*c).powi(2)).sum();
+        HotspotReport {
+            hot_objects: self.objects.len(),
+            skew: self.skew,
+            updates: total,
+            top_share: counts.iter().copied().max().map_or(0.0, share),
+            effective_objects: if simpson > 0.0 { 1.0 / simpson } else { 0.0 },
+            by_object: self.objects.iter().zip(&counts)
+                .map(|((id, version), updates)| HotObjectUpdates {
+                    object_id: id.to_string(),
+                    initial_shared_version: version.value(),
+                    updates: *updates,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Create `count` shared counters, paid for by `worker`
+pub async fn create_hot_objects(
+    client: &SuiClient,
+    worker: &RwLock<WorkerState>,
+    package_id: ObjectID,
+    count: usize,
+    skew: f64,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<Arc<HotSet>> {
+    let mut state = worker.write().await;
+    let mut objects = Vec::with_capacity(count);
+    while objects.len() < count {
+        let chunk = (count - objects.len()).min(CREATE_CHUNK);
+        let mut builder = ProgrammableTransactionBuilder::new();
+        for _ in 0..chunk {
+            builder.programmable_move_call(
+                package_id,
+                Identifier::new("io_churn").unwrap(),
+                Identifier::new("create_shared").unwrap(),
+                vec![],
+                vec![],
+            );
+        }
+        let tx_data = TransactionData::new_programmable(
+            state.address,
+            vec![state.gas_coin],
+            builder.finish(),
+            gas_budget,
+            rgp,
+        );
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+        let response = client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new()
+                    .with_effects()
+                    .with_object_changes(),
+                Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await
+            .context("Failed to create shared hot objects")?;
+
+        let effects = response.effects.as_ref()
+            .ok_or_else(|| anyhow!("create_shared response has no effects"))?;
+        if effects.status().is_err() {
+            return Err(anyhow!("create_shared failed: {:?}", effects.status()));
+        }
+        let gas_obj = effects.gas_object();
+        state.gas_coin = (gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest);
+        for change in response.object_changes.iter().flatten() {
+            if let ObjectChange::Created { object_id, owner: Owner::Shared { initial_shared_version }, .. } = change {
+                objects.push((*object_id, *initial_shared_version));
+            }
+        }
+    }
+    info!("Created {} shared hot objects (skew {})", objects.len(), skew);
+    Ok(Arc::new(HotSet::new(objects, skew, package_id)))
+}
+
+/// Increment up to `count` hot counters in one transaction (goes through consensus)
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_hot_update(
+    client: &SuiClient,
+    worker: &Arc<RwLock<WorkerState>>,
+    package_id: ObjectID,
+    hot: &HotSet,
+    count: usize,
+    gas_budget: u64,
+    rgp: u64,
+    simulate: bool,
+) -> Result<TxOutcome> {
+    let mut state = worker.write().await;
+    let picked = hot.pick_batch(count, &mut rand::rngs::StdRng::from_entropy());
+
+    let mut builder = ProgrammableTransactionBuilder::new();
+    for &idx in &picked {
+        let (id, initial_shared_version) = hot.objects[idx];
+        let obj_arg = builder.obj(ObjectArg::SharedObject { id, initial_shared_version, mutable: true })?;
+        builder.programmable_move_call(
+            package_id,
+            Identifier::new("io_churn").unwrap(),
+            Identifier::new("increment_simple").unwrap(),
+            vec![],
+            vec![obj_arg],
+        );
+    }
+    // Shared objects can't be lock-conflicted; nothing owned to quarantine
+    state.last_batch.clear();
+
+    let tx_data = TransactionData::new_programmable(
+        state.address,
+        state.gas_payment(),
+        builder.finish(),
+        gas_budget,
+        rgp,
+    );
+
+    if simulate {
+        return simulate_tx(client, tx_data, state.gas_coin.0, COUNTER_OBJECT_BYTES).await;
+    }
+
+    let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new()
+                .with_effects()
+                .with_object_changes(),
+            Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+        )
+        .await?;
+
+    // A response for a transaction already accounted for (e.g. a retried submission)
+    if !state.seen_digests.insert(response.digest) {
+        return Ok(TxOutcome::duplicate(response.digest));
+    }
+
+    let mut updated_count = 0u64;
+    let (mut gas_used, mut charge) = (0u64, GasCharge::default());
+    let mut consensus = true;
+
+    if let Some(effects) = &response.effects {
+        gas_used = gas_spent(effects.gas_cost_summary());
+        charge = GasCharge::of(effects.gas_cost_summary());
+        consensus = !effects.shared_objects().is_empty();
+        let gas_obj = effects.gas_object();
+        state.gas_charged((gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest));
+
+        for change in response.object_changes.iter().flatten() {
+            if let ObjectChange::Mutated { object_id, .. } = change {
+                if let Some(idx) = picked.iter().copied().find(|&i| hot.objects[i].0 == *object_id) {
+                    hot.updates[idx].fetch_add(1, Ordering::Relaxed);
+                    updated_count += 1;
+                }
+            }
+        }
+    }
+
+    Ok(TxOutcome {
+        created: 0,
+        updated: updated_count,
+        gas_used,
+        charge,
+        digest: response.digest,
+        bytes_written: updated_count * COUNTER_OBJECT_BYTES,
+        object_sizes: Vec::new(),
+        read: 0,
+        deleted: 0,
+        duplicate: false,
+        consensus,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hot_set(objects: usize, skew: f64) -> HotSet {
+        let objects = (0..objects).map(|n| (ObjectID::from_single_byte(n as u8), SequenceNumber::from_u64(n as u64 + 1))).collect();
+        HotSet::new(objects, skew, ObjectID::random())
+    }
+
+    fn pick_shares(hot: &HotSet, picks: usize) -> Vec<f64> {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let mut counts = vec![0usize; hot.objects.len()];
+        for _ in 0..picks {
+            counts[hot.pick(&mut rng)] += 1;
+        }
+        counts.iter().map(|c| *c as f64 / picks as f64).collect()
+    }
+
+    #[test]
+    fn zero_skew_spreads_picks_evenly() {
+        let hot = hot_set(4, 0.0);
+        assert!((hot.cdf[3] - 1.0).abs() < 1e-12);
+        for share in pick_shares(&hot, 20_000) {
+            assert!((share - 0.25).abs() < 0.02, "{}", share);
+        }
+    }
+
+    #[test]
+    fn high_skew_concentrates_picks_on_the_first_counter() {
+        let hot = hot_set(8, 3.0);
+        let shares = pick_shares(&hot, 20_000);
+        // 1 / sum(rank^-3) for 8 ranks
+        assert!((shares[0] - 0.837).abs() < 0.02, "{}", shares[0]);
+        assert!(shares[0] > shares[1] && shares[1] > shares[2]);
+    }
+
+    #[test]
+    fn batches_hold_distinct_counters() {
+        let hot = hot_set(5, 0.0);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(2);
+        let batch = hot.pick_batch(10, &mut rng);
+        let mut distinct = batch.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert!(batch.len() <= 5);
+        assert_eq!(distinct.len(), batch.len());
+        assert_eq!(hot.pick_batch(0, &mut rng).len(), 1);
+    }
+
+    #[test]
+    fn the_report_gives_top_share_and_effective_objects() {
+        let hot = hot_set(4, 1.0);
+        for (idx, updates) in [6u64, 2, 2, 0].into_iter().enumerate() {
+            hot.updates[idx].store(updates, Ordering::Relaxed);
+        }
+
+        let report = hot.report();
+
+        assert_eq!((report.hot_objects, report.updates), (4, 10));
+        assert_eq!(report.top_share, 0.6);
+        // 1 / (0.36 + 0.04 + 0.04)
+        assert!((report.effective_objects - 1.0 / 0.44).abs() < 1e-9);
+        assert_eq!(report.by_object[1].initial_shared_version, 2);
+        assert_eq!(hot_set(2, 0.0).report().effective_objects, 0.0);
+    }
+}
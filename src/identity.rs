@@ -0,0 +1,146 @@
+// Worker identity cache (--identity-cache)
+//
+// Fresh mode normally generates brand-new addresses every run, each needing a
+// faucet grant. With an identity cache the generated keypairs are persisted and
+// reused next run: their existing gas coins are picked up (the faucet is only
+// hit when the balance ran low) and objects they still own from earlier runs
+// are re-discovered on chain and tracked again.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use sui_sdk::rpc_types::{SuiObjectDataOptions, SuiObjectResponseQuery};
+use sui_sdk::types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_sdk::SuiClient;
+
+use crate::meta_cache::{Holder, MetaCache, ObjectKind, ObjectMeta};
+use crate::{sui_address_serde, TrackedObject};
+
+/// One persisted worker identity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedIdentity {
+    pub worker_id: usize,
+    #[serde(with = "sui_address_serde")]
+    pub address: SuiAddress,
+    pub keypair_base64: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IdentityFile {
+    identities: Vec<CachedIdentity>,
+}
+
+/// Load cached identities (empty when the file doesn't exist yet)
+pub fn load(path: &str) -> Result<Vec<CachedIdentity>> {
+    if !Path::new(path).exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read identity cache: {}", path))?;
+    let file: IdentityFile = serde_json::from_str(&content)
+        .context(format!("Failed to parse identity cache: {}", path))?;
+    Ok(file.identities)
+}
+
+/// Persist identities, keeping cached entries for worker ids not in `current`
+pub fn save(path: &str, mut current: Vec<CachedIdentity>, previous: &[CachedIdentity]) -> Result<()> {
+    for old in previous {
+        if !current.iter().any(|c| c.worker_id == old.worker_id) {
+            current.push(old.clone());
+        }
+    }
+    current.sort_by_key(|c| c.worker_id);
+    let json = serde_json::to_string_pretty(&IdentityFile { identities: current })?;
+    std::fs::write(path, json).context(format!("Failed to write identity cache: {}", path))
+}
+
+/// Largest existing SUI coin if its balance covers at least `min_balance`
+pub async fn existing_gas(client: &SuiClient, address: SuiAddress, min_balance: u64) -> Result<Option<ObjectRef>> {
+    let coins = client
+        .coin_read_api()
+        .get_coins(address, None, None, None)
+        .await
+        .context("Failed to get coins")?;
+    Ok(coins.data.into_iter()
+        .filter(|c| c.balance >= min_balance)
+        .max_by_key(|c| c.balance)
+        .map(|c| c.object_ref()))
+}
+
+/// io_churn MicroCounter/LargeBlob objects the address still owns (at most `limit`)
+pub async fn owned_bench_objects(
+    client: &SuiClient,
+    address: SuiAddress,
+    package_id: ObjectID,
+    limit: usize,
+    meta_cache: Option<&MetaCache>,
+) -> Result<Vec<TrackedObject>> {
+    let prefix = format!("{}::io_churn::", package_id.to_hex_literal());
+    let mut objects = Vec::new();
+    let mut cursor = None;
+
+    loop {
+        let page = client
+            .read_api()
+            .get_owned_objects(
+                address,
+                Some(SuiObjectResponseQuery::new_with_options(SuiObjectDataOptions::new().with_type())),
+                cursor,
+                None,
+            )
+            .await
+            .context("Failed to list owned objects")?;
+
+        for data in page.data.into_iter().filter_map(|r| r.data) {
+            let is_bench_object = data.type_.as_ref()
+                .map(|t| t.to_string())
+                .is_some_and(|t| t == format!("{}MicroCounter", prefix) || t == format!("{}LargeBlob", prefix));
+            if is_bench_object && objects.len() < limit {
+                if let (Some(cache), Some(type_)) = (meta_cache, &data.type_) {
+                    cache.insert(data.object_id, ObjectMeta { owner: Holder::Address(address), kind: ObjectKind::of(&type_.to_string()) });
+                }
+                objects.push(TrackedObject {
+                    id: data.object_id,
+                    version: data.version.value(),
+                    digest: data.digest,
+                    size: 0,
+                    origin: None,
+                    updates: 0,
+                });
+            }
+        }
+
+        if !page.has_next_page || objects.len() >= limit {
+            break;
+        }
+        cursor = page.next_cursor;
+    }
+    Ok(objects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(worker_id: usize, keypair_base64: &str) -> CachedIdentity {
+        CachedIdentity { worker_id, address: SuiAddress::random_for_testing_only(), keypair_base64: keypair_base64.to_string() }
+    }
+
+    #[test]
+    fn saving_keeps_cached_workers_that_did_not_run() {
+        let dir = std::env::temp_dir().join(format!("fdp-identity-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("identities.json").to_string_lossy().into_owned();
+        assert!(load(&path).unwrap().is_empty());
+
+        let previous = vec![identity(0, "old0"), identity(1, "old1"), identity(3, "old3")];
+        let current = vec![identity(2, "new2"), identity(1, "new1")];
+        save(&path, current.clone(), &previous).unwrap();
+
+        let loaded = load(&path).unwrap();
+        let keys: Vec<(usize, &str)> = loaded.iter().map(|i| (i.worker_id, i.keypair_base64.as_str())).collect();
+        assert_eq!(keys, vec![(0, "old0"), (1, "new1"), (2, "new2"), (3, "old3")]);
+        assert_eq!(loaded[2].address, current[0].address);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+// In-flight queue depth and semaphore-wait instrumentation
+//
+// Workers take a permit from the --max-inflight semaphore before submitting. If
+// they spend a large share of their time waiting for permits while the node
+// still has headroom, max_inflight is the bottleneck; if they rarely wait and
+// latency still climbs, the node is. The instantaneous number of transactions
+// actually on the wire is tracked separately (permits are also held while a
+// worker paces itself).
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::counters::ShardedCounter;
+
+pub struct InflightStats {
+    inflight: AtomicU64,
+    peak_inflight: AtomicU64,
+    wait_us: ShardedCounter,
+    acquires: ShardedCounter,
+}
+
+/// Decrements the in-flight gauge when the submission finishes
+pub struct InflightGuard<'a> {
+    stats: &'a InflightStats,
+}
+
+impl Drop for InflightGuard<'_> {
+    fn drop(&mut self) {
+        self.stats.inflight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueSummary {
+    pub peak_inflight: u64,
+    pub semaphore_acquires: u64,
+    pub semaphore_wait_secs: f64,
+    pub mean_semaphore_wait_ms: f64,
+    /// Share of total worker time spent waiting for a permit
+    pub semaphore_wait_fraction: f64,
+}
+
+impl InflightStats {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            inflight: AtomicU64::new(0),
+            peak_inflight: AtomicU64::new(0),
+            wait_us: ShardedCounter::new(workers),
+            acquires: ShardedCounter::new(workers),
+        }
+    }
+
+    /// Acquire a permit, recording how long the worker waited for it
+    pub async fn acquire<'s>(&self, semaphore: &'s Semaphore, worker: usize) -> anyhow::Result<SemaphorePermit<'s>> {
+        let started = Instant::now();
+        let permit = semaphore.acquire().await?;
+        self.wait_us.add(worker, started.elapsed().as_micros() as u64);
+        self.acquires.add(worker, 1);
+        Ok(permit)
+    }
+
+    /// Mark one transaction as on the wire until the guard drops
+    pub fn start(&self) -> InflightGuard<'_> {
+        let now = self.inflight.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak_inflight.fetch_max(now, Ordering::Relaxed);
+        InflightGuard { stats: self }
+    }
+
+    pub fn inflight(&self) -> u64 {
+        self.inflight.load(Ordering::Relaxed)
+    }
+
+    /// Cumulative (wait_us, acquires)
+    pub fn wait_totals(&self) -> (u64, u64) {
+        (self.wait_us.get(), self.acquires.get())
+    }
+
+    pub fn summary(&self, workers: usize, elapsed: Duration) -> QueueSummary {
+        let (wait_us, acquires) = self.wait_totals();
+        let worker_time = workers as f64 * elapsed.as_secs_f64();
+        QueueSummary {
+            peak_inflight: self.peak_inflight.load(Ordering::Relaxed),
+            semaphore_acquires: acquires,
+            semaphore_wait_secs: wait_us as f64 / 1e6,
+            mean_semaphore_wait_ms: if acquires > 0 { wait_us as f64 / 1000.0 / acquires as f64 } else { 0.0 },
+            semaphore_wait_fraction: if worker_time > 0.0 { wait_us as f64 / 1e6 / worker_time } else { 0.0 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_gauge_follows_guards_and_keeps_its_peak() {
+        let stats = InflightStats::new(2);
+        {
+            let _a = stats.start();
+            let _b = stats.start();
+            assert_eq!(stats.inflight(), 2);
+        }
+        let _c = stats.start();
+        assert_eq!(stats.inflight(), 1);
+        assert_eq!(stats.summary(2, Duration::from_secs(1)).peak_inflight, 2);
+    }
+
+    #[test]
+    fn permit_waits_are_summed_per_acquire() {
+        let stats = InflightStats::new(2);
+        let semaphore = Semaphore::new(2);
+        futures::executor::block_on(async {
+            let _first = stats.acquire(&semaphore, 0).await.unwrap();
+            let _second = stats.acquire(&semaphore, 1).await.unwrap();
+        });
+
+        let summary = stats.summary(2, Duration::from_secs(10));
+        assert_eq!(summary.semaphore_acquires, 2);
+        assert!(summary.semaphore_wait_fraction < 0.01);
+        assert_eq!(InflightStats::new(1).summary(1, Duration::ZERO).semaphore_wait_fraction, 0.0);
+    }
+}
@@ -0,0 +1,138 @@
+// Interval timeline streamed to a file (--interval-log)
+//
+// The timeline in the results is only written at the end of the run, and a
+// --soak run keeps just its most recent samples in memory. --interval-log
+// appends each sample to a file as the stats reporter takes it: CSV with a
+// header line if the path ends in .csv, JSON lines otherwise. Fields filled
+// in after the run (pacing status, stall seconds, SLO verdicts) are not in the
+// file. An existing file (a rerun, or --resume) is appended to, and the header
+// is only written to an empty one. With a rotation limit the file is split
+// into segments like the other logs (see rotation.rs), and every CSV segment
+// starts with the header.
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::rotation::{self, RotationPolicy, RotationReport, Segments};
+use crate::timeline::IntervalSample;
+
+const CSV_HEADER: &str = "elapsed_secs,unix_ms,utc,tx_submitted,tx_success,tx_failed,objects_created,objects_updated,\
+                          interval_tps,requested_tps,inflight,semaphore_wait_ms";
+
+/// Interval log file being written during the run
+pub struct IntervalLog {
+    out: BufWriter<File>,
+    csv: bool,
+    /// Segments of the log (--rotate-mb / --rotate-secs)
+    segments: Option<Segments>,
+}
+
+impl IntervalLog {
+    pub fn create(path: &str, rotation: Option<RotationPolicy>) -> Result<Self> {
+        let csv = path.ends_with(".csv");
+        let out = Self::open(path, csv)?;
+        Ok(Self { out, csv, segments: rotation.map(|policy| Segments::new(path, policy)) })
+    }
+
+    /// The file at `path` for appending, with the header first if it is a new CSV file
+    fn open(path: &str, csv: bool) -> Result<BufWriter<File>> {
+        let (mut out, empty) = rotation::open_append(path).context("Failed to open interval log")?;
+        if csv && empty {
+            writeln!(out, "{}", CSV_HEADER)?;
+            out.flush()?;
+        }
+        Ok(out)
+    }
+
+    /// Append one sample
+    pub fn write(&mut self, sample: &IntervalSample) -> Result<()> {
+        let line = if self.csv { csv_line(sample) } else { serde_json::to_string(sample)? };
+        writeln!(self.out, "{}", line)?;
+        self.out.flush()?;
+        if let Some(segments) = self.segments.as_mut() {
+            segments.wrote(line.len() + 1);
+            if segments.due() {
+                segments.close()?;
+                self.out = Self::open(segments.path(), self.csv)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the segment index (None without rotation)
+    pub fn finish_segments(&mut self) -> Result<Option<RotationReport>> {
+        self.out.flush()?;
+        self.segments.as_mut().map(Segments::finish).transpose()
+    }
+}
+
+fn csv_line(s: &IntervalSample) -> String {
+    format!("{:.3},{},{},{},{},{},{},{},{:.3},{},{},{:.3}",
+        s.at.elapsed_secs, s.at.unix_ms, s.at.utc, s.tx_submitted, s.tx_success, s.tx_failed,
+        s.objects_created, s.objects_updated, s.interval_tps,
+        s.requested_tps.map_or(String::new(), |tps| format!("{:.3}", tps)), s.inflight, s.semaphore_wait_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rotation::Compression;
+
+    #[test]
+    fn every_csv_segment_starts_with_the_header() {
+        let dir = std::env::temp_dir().join(format!("fdp-interval-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("intervals.csv").to_string_lossy().into_owned();
+        let policy = RotationPolicy { max_bytes: Some(1), max_age: None, compress: Compression::None };
+        let mut log = IntervalLog::create(&path, Some(policy)).unwrap();
+
+        let mut sample = IntervalSample::default();
+        for second in 1..=2 {
+            sample.at.elapsed_secs = second as f64;
+            sample.tx_success = second * 100;
+            log.write(&sample).unwrap();
+        }
+        let report = log.finish_segments().unwrap().unwrap();
+
+        assert_eq!(report.segments, 3);
+        for (file, row) in [(format!("{}.00000", path), "1.000,"), (format!("{}.00001", path), "2.000,")] {
+            let content = std::fs::read_to_string(file).unwrap();
+            let lines: Vec<_> = content.lines().collect();
+            assert_eq!(lines[0], CSV_HEADER);
+            assert!(lines[1].starts_with(row), "{}", lines[1]);
+            assert_eq!(lines[1].split(',').count(), CSV_HEADER.split(',').count());
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), format!("{}\n", CSV_HEADER));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_resumed_run_appends_to_the_earlier_log() {
+        let dir = std::env::temp_dir().join(format!("fdp-interval-log-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("intervals.csv").to_string_lossy().into_owned();
+        let policy = RotationPolicy { max_bytes: Some(1 << 20), max_age: None, compress: Compression::None };
+
+        let mut sample = IntervalSample::default();
+        for second in 1..=2 {
+            let mut log = IntervalLog::create(&path, Some(policy)).unwrap();
+            sample.at.elapsed_secs = second as f64;
+            log.write(&sample).unwrap();
+            log.finish_segments().unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[1].starts_with("1.000,"), "{}", lines[1]);
+        assert!(lines[2].starts_with("2.000,"), "{}", lines[2]);
+        // The index lists the file once, with both runs' bytes
+        let index: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{}.index.json", path)).unwrap()).unwrap();
+        assert_eq!(index.as_array().unwrap().len(), 1);
+        assert_eq!(index[0]["bytes"], content.len() as u64);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+// Worker keys from a Sui keystore (--keystore, --save-without-keys)
+//
+// State files normally carry each worker's private key next to its address, so
+// a later phase can sign for the objects it loads. Where private keys may not
+// be written to disk outside the managed keystore, --save-without-keys leaves
+// them out of --save-objects and --autosave files, and loading resolves each
+// saved address to its key in --keystore (a `sui.keystore` file: a JSON list of
+// base64 flag||private-key strings, as written by `sui keytool`). Fresh-mode
+// workers then take their identities from the keystore as well, in file order,
+// so every address a saved state mentions has its key there. Like cached
+// identities, they reuse the gas and objects the addresses already own.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use sui_sdk::types::base_types::SuiAddress;
+use sui_sdk::types::crypto::{EncodeDecodeBase64, SuiKeyPair};
+
+use crate::SavedBenchmarkState;
+
+pub struct Keystore {
+    path: String,
+    keys: Vec<(SuiAddress, SuiKeyPair)>,
+}
+
+impl Keystore {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path).context(format!("Failed to read keystore: {}", path))?;
+        let encoded: Vec<String> = serde_json::from_str(&content).context(format!("Failed to parse keystore: {}", path))?;
+        let keys = encoded.iter().enumerate()
+            .map(|(i, key)| {
+                let keypair = SuiKeyPair::decode_base64(key)
+                    .map_err(|e| anyhow!("Keystore {}: key {} is not a valid key: {}", path, i, e))?;
+                Ok((SuiAddress::from(&keypair.public()), keypair))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { path: path.to_string(), keys })
+    }
+
+    /// The `index`-th identity in file order
+    pub fn identity(&self, index: usize) -> Option<(SuiAddress, SuiKeyPair)> {
+        self.keys.get(index).map(|(address, keypair)| (*address, keypair.copy()))
+    }
+
+    /// Fill in the keys a state file was saved without
+    pub fn fill(&self, state: &mut SavedBenchmarkState) -> Result<()> {
+        let by_address: HashMap<SuiAddress, &SuiKeyPair> = self.keys.iter().map(|(a, k)| (*a, k)).collect();
+        for worker in state.workers.iter_mut().filter(|w| w.keypair_base64.is_empty()) {
+            let keypair = by_address.get(&worker.address)
+                .ok_or_else(|| anyhow!("Worker {} ({}) was saved without its key, and {} has none for it", worker.worker_id, worker.address, self.path))?;
+            worker.keypair_base64 = keypair.encode_base64();
+        }
+        Ok(())
+    }
+}
+
+/// Fail on keyless workers when there is no keystore to resolve them
+pub fn require_keys(state: &SavedBenchmarkState, path: &str) -> Result<()> {
+    match state.workers.iter().find(|w| w.keypair_base64.is_empty()) {
+        Some(worker) => Err(anyhow!("{}: worker {} ({}) was saved without its key; pass --keystore", path, worker.worker_id, worker.address)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SavedWorkerObjects;
+    use sui_sdk::types::crypto::{get_key_pair, AccountKeyPair};
+
+    #[test]
+    fn saved_addresses_resolve_to_keystore_keys() {
+        let (address, keypair): (SuiAddress, AccountKeyPair) = get_key_pair();
+        let keypair = SuiKeyPair::Ed25519(keypair);
+        let keystore = Keystore { path: "test".to_string(), keys: vec![(address, keypair.copy())] };
+        let worker = |address| SavedWorkerObjects { worker_id: 0, address, keypair_base64: String::new(), objects: Vec::new() };
+        let mut state = SavedBenchmarkState { total_objects: 0, workers: vec![worker(address)] };
+        assert!(require_keys(&state, "state.json").is_err());
+        keystore.fill(&mut state).unwrap();
+        assert_eq!(state.workers[0].keypair_base64, keypair.encode_base64());
+        let mut unknown = SavedBenchmarkState { total_objects: 0, workers: vec![worker(SuiAddress::ZERO)] };
+        assert!(keystore.fill(&mut unknown).is_err());
+    }
+}
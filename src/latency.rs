@@ -0,0 +1,263 @@
+// Transaction latency by operation type and batch size
+//
+// Every successful transaction's submit-to-effects latency is recorded in a
+// histogram keyed by (operation, objects in the transaction), so percentiles
+// stay interpretable when the run mixes creates, updates and blob operations of
+// different sizes. Histograms are log-linear (16 linear sub-buckets per power of
+// two of microseconds, <= ~6% error), so memory is fixed regardless of run length.
+// The layout is exactly HdrHistogram's with 1 significant digit and a 1us unit,
+// which lets hdr_log write them out as standard interval logs.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SUB_BUCKETS: u64 = 16;
+const SUB_BUCKET_BITS: u32 = 4;
+/// Covers up to 2^37 us (~38 hours)
+const NUM_BUCKETS: usize = 34 * SUB_BUCKETS as usize;
+/// Largest value the bucket layout can hold (HdrHistogram highestTrackableValue)
+pub const HIGHEST_TRACKABLE_US: u64 = (1 << 37) - 1;
+
+/// Latency histogram in microseconds
+#[derive(Clone)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    sum_us: u64,
+    max_us: u64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self { counts: vec![0; NUM_BUCKETS], count: 0, sum_us: 0, max_us: 0 }
+    }
+
+    fn bucket(us: u64) -> usize {
+        if us < SUB_BUCKETS {
+            return us as usize;
+        }
+        let exp = 63 - us.leading_zeros();
+        let sub = (us >> (exp - SUB_BUCKET_BITS)) & (SUB_BUCKETS - 1);
+        (((exp - SUB_BUCKET_BITS + 1) as u64 * SUB_BUCKETS + sub) as usize).min(NUM_BUCKETS - 1)
+    }
+
+    /// Midpoint of a bucket's value range
+    fn bucket_value(idx: usize) -> u64 {
+        let idx = idx as u64;
+        if idx < SUB_BUCKETS {
+            return idx;
+        }
+        let shift = idx / SUB_BUCKETS - 1;
+        let low = (SUB_BUCKETS + idx % SUB_BUCKETS) << shift;
+        low + ((1u64 << shift) >> 1)
+    }
+
+    /// Raw bucket counts (HdrHistogram counts-array order)
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn max_us(&self) -> u64 {
+        self.max_us
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn record(&mut self, latency: Duration) {
+        let us = latency.as_micros().min(u64::MAX as u128) as u64;
+        self.counts[Self::bucket(us)] += 1;
+        self.count += 1;
+        self.sum_us = self.sum_us.saturating_add(us);
+        self.max_us = self.max_us.max(us);
+    }
+
+    pub fn merge(&mut self, other: &Histogram) {
+        for (a, b) in self.counts.iter_mut().zip(&other.counts) {
+            *a += b;
+        }
+        self.count += other.count;
+        self.sum_us = self.sum_us.saturating_add(other.sum_us);
+        self.max_us = self.max_us.max(other.max_us);
+    }
+
+    /// Value at quantile q (0.0..=1.0), in microseconds
+    pub fn quantile_us(&self, q: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((q * self.count as f64).ceil() as u64).clamp(1, self.count);
+        let mut seen = 0;
+        for (idx, &c) in self.counts.iter().enumerate() {
+            seen += c;
+            if seen >= rank {
+                return Self::bucket_value(idx).min(self.max_us);
+            }
+        }
+        self.max_us
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        let ms = |us: u64| us as f64 / 1000.0;
+        LatencySummary {
+            count: self.count,
+            mean_ms: if self.count > 0 { ms(self.sum_us) / self.count as f64 } else { 0.0 },
+            p50_ms: ms(self.quantile_us(0.50)),
+            p90_ms: ms(self.quantile_us(0.90)),
+            p99_ms: ms(self.quantile_us(0.99)),
+            p999_ms: ms(self.quantile_us(0.999)),
+            max_ms: ms(self.max_us),
+        }
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub p999_ms: f64,
+    pub max_ms: f64,
+}
+
+/// What a latency sample is tagged with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LatencyTag {
+    pub op: &'static str,
+    /// Objects created/updated by the transaction
+    pub batch_size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaggedSummary {
+    pub op: &'static str,
+    pub batch_size: u64,
+    #[serde(flatten)]
+    pub latency: LatencySummary,
+}
+
+#[derive(Default)]
+struct Histograms {
+    /// Whole run
+    by_tag: BTreeMap<LatencyTag, Histogram>,
+    /// Since the last take_interval(), per op
+    interval_by_op: BTreeMap<&'static str, Histogram>,
+}
+
+/// Per-tag histograms shared by all workers
+#[derive(Default)]
+pub struct LatencyStats {
+    inner: Mutex<Histograms>,
+}
+
+impl LatencyStats {
+    pub fn record(&self, op: &'static str, batch_size: u64, latency: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.by_tag
+            .entry(LatencyTag { op, batch_size })
+            .or_default()
+            .record(latency);
+        inner.interval_by_op.entry(op).or_default().record(latency);
+    }
+
+    /// Per-op histograms recorded since the previous call (resets them)
+    pub fn take_interval(&self) -> BTreeMap<&'static str, Histogram> {
+        std::mem::take(&mut self.inner.lock().unwrap().interval_by_op)
+    }
+
+    /// Histogram over all tags
+    pub fn overall(&self) -> Histogram {
+        let mut all = Histogram::new();
+        for h in self.inner.lock().unwrap().by_tag.values() {
+            all.merge(h);
+        }
+        all
+    }
+
+    /// Per-op histograms (all batch sizes merged)
+    pub fn by_op(&self) -> BTreeMap<&'static str, Histogram> {
+        let mut ops: BTreeMap<&'static str, Histogram> = BTreeMap::new();
+        for (tag, h) in self.inner.lock().unwrap().by_tag.iter() {
+            ops.entry(tag.op).or_default().merge(h);
+        }
+        ops
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let by_tag: Vec<TaggedSummary> = self.inner.lock().unwrap().by_tag.iter()
+            .map(|(tag, h)| TaggedSummary { op: tag.op, batch_size: tag.batch_size, latency: h.summary() })
+            .collect();
+        let by_op: BTreeMap<&'static str, LatencySummary> = self.by_op().iter()
+            .map(|(op, h)| (*op, h.summary()))
+            .collect();
+        serde_json::json!({
+            "all": self.overall().summary(),
+            "by_op": by_op,
+            "by_tag": by_tag,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantiles_stay_within_the_bucket_error() {
+        let mut h = Histogram::new();
+        for us in 1..=100_000u64 {
+            h.record(Duration::from_micros(us));
+        }
+        for (q, exact) in [(0.5, 50_000.0), (0.9, 90_000.0), (0.99, 99_000.0)] {
+            let error = (h.quantile_us(q) as f64 - exact).abs() / exact;
+            assert!(error <= 0.0625, "q{} = {}", q, h.quantile_us(q));
+        }
+        assert_eq!(h.quantile_us(1.0), 100_000);
+        assert_eq!(h.summary().max_ms, 100.0);
+        assert_eq!(Histogram::new().quantile_us(0.5), 0);
+    }
+
+    #[test]
+    fn buckets_are_exact_below_sixteen_and_ordered_above() {
+        for us in 0..SUB_BUCKETS {
+            assert_eq!(Histogram::bucket_value(Histogram::bucket(us)), us);
+        }
+        let mut last = 0;
+        for us in (16..1 << 20).step_by(97) {
+            let bucket = Histogram::bucket(us);
+            assert!(bucket >= last);
+            last = bucket;
+        }
+        assert_eq!(Histogram::bucket(u64::MAX), NUM_BUCKETS - 1);
+    }
+
+    #[test]
+    fn samples_are_kept_per_tag_and_per_interval() {
+        let stats = LatencyStats::default();
+        stats.record("create", 1, Duration::from_millis(10));
+        stats.record("create", 8, Duration::from_millis(30));
+        stats.record("update", 8, Duration::from_millis(20));
+
+        let interval = stats.take_interval();
+        assert_eq!((interval["create"].count(), interval["update"].count()), (2, 1));
+        assert!(stats.take_interval().is_empty());
+        assert_eq!(stats.overall().count(), 3);
+        assert_eq!(stats.by_op()["create"].max_us(), 30_000);
+        assert_eq!(stats.to_json()["by_tag"].as_array().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,3756 @@
+// FDP SUI Benchmark - SDK-based High-Throughput I/O Benchmark
+//
+// This benchmark bypasses the `sui client` CLI overhead by directly using
+// the SUI SDK to submit transactions. It's designed to maximize disk I/O
+// for measuring Write Amplification Factor (WAF) on FDP vs non-FDP storage.
+//
+// Key features:
+// - Direct SDK transaction submission (no CLI process spawning)
+// - Async connection pooling to the SUI node
+// - Batched PTB transactions (multiple operations per TX)
+// - Mixed CREATE/UPDATE workload for hot/cold data segregation testing
+// - Memory-efficient with configurable concurrency limits
+//
+// The benchmark is a library with a thin binary (main.rs) on top: `cli` is the
+// command line, `run_benchmark` one run from parsed Args. Integration tests
+// (tests/) and benches (benches/) drive runs in-process and assert on the
+// returned BenchResult and saved state with the `checks` functions.
+
+mod ab_test;
+pub mod abi;
+mod adaptive;
+mod address_scaling;
+mod audit;
+pub mod autosave;
+mod batch_backoff;
+mod blob_delete;
+mod blockdev;
+mod build_bench;
+mod capabilities;
+mod checkpoints;
+pub mod checks;
+mod churn;
+pub mod clock;
+mod clock_update;
+mod command_failures;
+mod compat;
+mod config;
+mod consensus_path;
+mod counters;
+mod dedupe;
+mod depth_sweep;
+mod epochs;
+pub mod eviction;
+mod executor;
+mod expiry;
+mod db_check;
+mod failure_rate;
+mod failures;
+mod faucet;
+mod frozen;
+mod gas_only;
+mod gas_pool;
+mod gas_streams;
+mod hdr_log;
+mod heap;
+mod hooks;
+mod hot_cold;
+mod hotspot;
+mod identity;
+mod inflight;
+mod interval_log;
+mod keystore;
+mod latency;
+mod limits;
+mod load;
+mod lockstep;
+mod lookup;
+mod memory;
+mod meta_cache;
+mod mix;
+mod mock;
+mod node;
+mod nvme;
+pub mod ops;
+mod outliers;
+mod pacing;
+mod partition;
+mod phases;
+mod presets;
+mod pressure;
+mod progress;
+mod pruning;
+mod reload;
+mod reshard;
+pub mod results;
+mod rocksdb_metrics;
+mod rotation;
+mod rpc_probe;
+mod sizes;
+mod slo;
+mod soak;
+mod spill;
+mod stalls;
+mod sweep;
+mod tagging;
+mod tenants;
+mod timeline;
+mod tx_log;
+mod validate;
+mod verify;
+mod wave;
+pub mod workload;
+
+use anyhow::{Context, Result, anyhow};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use futures::{StreamExt, TryStreamExt, stream::FuturesUnordered};
+use rand::Rng;
+use rand::SeedableRng;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use counters::ShardedCounter;
+use validate::{Fraction, Percent};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_sdk::rpc_types::{
+    SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_sdk::types::{
+    base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest},
+    gas::GasCostSummary,
+    crypto::{get_key_pair, SuiKeyPair, AccountKeyPair, EncodeDecodeBase64},
+    programmable_transaction_builder::ProgrammableTransactionBuilder,
+    transaction::{Transaction, TransactionData},
+    transaction_driver_types::ExecuteTransactionRequestType,
+    Identifier,
+};
+use tokio::sync::{Semaphore, RwLock};
+use tokio::time::sleep;
+use tracing::{info, warn, error, debug};
+
+#[global_allocator]
+static ALLOCATOR: heap::Counting = heap::Counting;
+
+/// Maximum objects tracked per worker to prevent memory bloat
+const DEFAULT_MAX_TRACKED_OBJECTS: usize = 5000;
+
+/// Approximate logical bytes written per object operation (used for byte-rate pacing)
+const COUNTER_OBJECT_BYTES: u64 = 100;
+const BLOB_OBJECT_BYTES: u64 = 4096;
+
+/// Blob operations are capped per TX since each blob is 4KB
+const MAX_BLOB_BATCH: usize = 20;
+
+/// Cached identities skip the faucet while their largest coin covers this many gas budgets
+const MIN_REUSE_BALANCE_BUDGETS: u64 = 20;
+
+/// Supervisor: restarts allowed per worker, and delay before each restart
+const MAX_WORKER_RESTARTS: u32 = 5;
+const WORKER_RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Seed phase progress/autosave interval
+const SEED_PROGRESS_INTERVAL_SECS: u64 = 5;
+
+/// Partial blob overwrite settings (--update-bytes)
+#[derive(Debug, Clone, Copy)]
+pub struct PartialUpdate {
+    /// Bytes rewritten per update
+    bytes: u64,
+    /// Random slice within the blob instead of always the prefix
+    random_offset: bool,
+}
+
+/// Growing-blob settings (--append-bytes)
+#[derive(Debug, Clone, Copy)]
+pub struct AppendUpdate {
+    /// Bytes appended per update
+    bytes: u64,
+    /// Size at which a blob starts over (--max-object-bytes)
+    max_size: u64,
+}
+
+impl AppendUpdate {
+    /// Blob size after one append (mirrors io_churn::append_blob)
+    fn grown(&self, size: u64) -> u64 {
+        if size + self.bytes > self.max_size { self.bytes } else { size + self.bytes }
+    }
+}
+
+/// Memory pressure levels for graduated throttling
+/// Level 0: Normal operation
+/// Level 1: Light throttle (75-85% memory) - small delay, keep 75% objects
+/// Level 2: Heavy throttle (85-92% memory) - longer delay, keep 50% objects  
+/// Level 3: Emergency throttle (>92% memory) - max delay, keep 25% objects, skip creates
+const MEM_PRESSURE_NORMAL: u8 = 0;
+const MEM_PRESSURE_LIGHT: u8 = 1;
+const MEM_PRESSURE_HEAVY: u8 = 2;
+const MEM_PRESSURE_EMERGENCY: u8 = 3;
+
+/// Pressure level for a usage fraction (host memory, or own heap / --self-memory-limit-mb)
+fn pressure_level(usage: f64, mem: reload::MemoryThresholds) -> u8 {
+    if usage >= mem.emergency {
+        MEM_PRESSURE_EMERGENCY  // >92%: max throttle (but NO abort!)
+    } else if usage >= mem.critical {
+        MEM_PRESSURE_HEAVY      // >85%: heavy throttle
+    } else if usage >= mem.threshold {
+        MEM_PRESSURE_LIGHT      // >75%: light throttle
+    } else {
+        MEM_PRESSURE_NORMAL     // <75%: normal operation
+    }
+}
+
+/// FDP SUI Benchmark - High-throughput I/O load generator
+#[derive(Parser, Debug, Clone)]
+#[clap(name = "fdp-sui-bench")]
+pub struct Args {
+    /// SUI RPC URL
+    #[clap(long, default_value = "http://127.0.0.1:9000")]
+    rpc_url: String,
+
+    /// Where transactions go: the node at --rpc-url, or mock to answer them locally
+    /// (synthetic latency, errors and effects; no node, no gas)
+    #[clap(long, value_enum, default_value = "node")]
+    backend: mock::Backend,
+
+    /// --backend mock latency in ms: fixed:20, uniform:5-50 or lognormal:20,0.5
+    #[clap(long, default_value = "lognormal:20,0.5")]
+    mock_latency: mock::LatencyDist,
+
+    /// --backend mock failures and their share of transactions, e.g. lock=1,timeout=0.5,abort=0.5
+    #[clap(long, value_delimiter = ',')]
+    mock_errors: Vec<mock::ErrorSpec>,
+
+    /// Package ID of the deployed io_churn contract (required for benchmark runs)
+    #[clap(long, env = "FDP_PACKAGE_ID")]
+    package_id: Option<String>,
+
+    /// Benchmark duration in seconds
+    #[clap(long, default_value = "300")]
+    duration: u64,
+
+    /// Number of concurrent workers (keep low for VM stability!)
+    #[clap(long, default_value = "8")]
+    workers: usize,
+
+    /// Objects per transaction batch (higher = more I/O per TX)
+    #[clap(long, default_value = "50")]
+    batch_size: usize,
+
+    /// Target transactions per second (0 = unlimited)
+    #[clap(long, default_value = "0")]
+    target_tps: u64,
+
+    /// Target logical write rate across all workers, e.g. 8MiB, 512K (overrides --target-tps)
+    #[clap(long, value_parser = parse_byte_size, conflicts_with = "target_tps")]
+    target_write_bps: Option<u64>,
+
+    /// Split the run into equal stages with this many sending addresses (workers) each, e.g. 1,2,4,8;
+    /// --target-tps is shared by the active workers so the total rate stays fixed
+    #[clap(long, value_delimiter = ',')]
+    address_stages: Vec<usize>,
+
+    /// Start the workers evenly spread over this many seconds instead of all at once (0 = all at once)
+    #[clap(long, default_value = "0")]
+    worker_ramp_secs: u64,
+
+    /// Maximum concurrent in-flight transactions (keep low for VM stability!)
+    #[clap(long, default_value = "100")]
+    max_inflight: usize,
+
+    /// Steer the in-flight limit by p95 latency (AIMD), starting at a quarter of --max-inflight, which stays the ceiling
+    #[clap(long)]
+    adaptive_inflight: bool,
+
+    /// p95 latency target in ms for --adaptive-inflight (default: twice the best p95 seen)
+    #[clap(long)]
+    adaptive_p95_ms: Option<f64>,
+
+    /// Split the run into equal stages holding the in-flight limit at each of these depths, e.g. 4,8,16,32,64
+    /// (replaces --max-inflight); the results get a throughput-vs-concurrency curve
+    #[clap(long, value_delimiter = ',', conflicts_with = "adaptive_inflight")]
+    inflight_stages: Vec<usize>,
+
+    /// Unmeasured seconds at the start of each --inflight-stages stage while the pipeline settles
+    #[clap(long, default_value = "10")]
+    inflight_warmup_secs: u64,
+
+    /// Percentage of CREATE operations (vs UPDATE) - keep low to reduce memory growth!
+    #[clap(long, default_value = "5")]
+    create_pct: Percent,
+
+    /// How creates are spread among updates: random (roll per transaction) or interleaved (exact share)
+    #[clap(long, value_enum, default_value = "random")]
+    mix_schedule: mix::MixSchedule,
+
+    /// Seed the worker RNGs (seed + worker id) so the random mix is reproducible
+    #[clap(long)]
+    mix_seed: Option<u64>,
+
+    /// Create this many shared counters and send updates to them through consensus (0 = owned objects only)
+    #[clap(long, default_value = "0")]
+    hot_objects: usize,
+
+    /// Keep this fraction of each worker's objects as a hot set that all updates go to; the rest,
+    /// and every object created during the run, are written once and never touched again
+    #[clap(long)]
+    hot_fraction: Option<Fraction>,
+
+    /// Assign every object a temperature as the worker takes it on: this percentage is hot and
+    /// takes all updates, the rest is cold (interleaved in creation order)
+    #[clap(long)]
+    create_hot_pct: Option<Percent>,
+
+    /// Percentage of update transactions that rewrite cold objects anyway (with --create-hot-pct)
+    #[clap(long, default_value = "0")]
+    cold_leak_pct: Percent,
+
+    /// Stamp a stream tag (worker, tenant or temperature) into every created and updated object's payload,
+    /// so device-side tooling can attribute blocks to streams; needs a package with the *_tagged functions
+    #[clap(long, value_enum)]
+    payload_tag: Option<tagging::PayloadTag>,
+
+    /// io_churn contract version of the package (v1..v6); detected from its entry points when omitted.
+    /// Pinning skips the probe and also applies the version's limits to mock runs
+    #[clap(long, value_enum)]
+    contract_version: Option<abi::ContractVersion>,
+
+    /// Zipf exponent for picking hot objects: 0 = uniform, larger = contention concentrated on fewer objects
+    #[clap(long, default_value = "0")]
+    hot_skew: f64,
+
+    /// Percentage of UPDATE operations that target the shared hot objects (requires --hot-objects)
+    #[clap(long, default_value = "100")]
+    hot_pct: Percent,
+
+    /// Every --freeze-interval, freeze this percentage of each worker's tracked objects (0 = off)
+    #[clap(long, default_value = "0")]
+    freeze_pct: Percent,
+
+    /// Seconds between freeze rounds
+    #[clap(long, default_value = "60")]
+    freeze_interval: u64,
+
+    /// Percentage of UPDATE operations that instead read frozen (immutable) objects
+    #[clap(long, default_value = "0")]
+    frozen_read_pct: Percent,
+
+    /// Every N seconds, rewrite --wave-pct of all tracked objects in one burst to trigger a compaction storm (0 = off)
+    #[clap(long, default_value = "0")]
+    wave_interval: u64,
+
+    /// Percentage of each worker's tracked objects rewritten per compaction wave
+    #[clap(long, default_value = "50")]
+    wave_pct: Percent,
+
+    /// Extra operation type and its share of transactions, e.g. delete_blob=5 (repeatable)
+    #[clap(long)]
+    workload: Vec<workload::WorkloadSpec>,
+
+    /// Initial seed objects to create per worker
+    #[clap(long, default_value = "500")]
+    seed_objects: usize,
+
+    /// Maximum tracked objects per worker (caps memory usage)
+    #[clap(long, default_value_t = DEFAULT_MAX_TRACKED_OBJECTS)]
+    max_tracked_objects: usize,
+
+    /// Maximum tracked objects across all workers (split evenly; the lower of the two caps applies)
+    #[clap(long)]
+    max_tracked_objects_total: Option<usize>,
+
+    /// Which tracked objects go when the tracker is full or memory pressure sheds objects
+    #[clap(long, value_enum, default_value = "drop-newest")]
+    eviction_policy: eviction::EvictionPolicy,
+
+    /// Memory usage threshold (0.0-1.0) above which to throttle (default: 0.75 = 75%)
+    #[clap(long, default_value = "0.75")]
+    memory_threshold: Fraction,
+
+    /// Critical memory threshold that stops all workers (default: 0.85 = 85%)
+    #[clap(long, default_value = "0.85")]
+    memory_critical: Fraction,
+
+    /// Emergency memory threshold that aborts benchmark (default: 0.92 = 92%)
+    #[clap(long, default_value = "0.92")]
+    memory_emergency: Fraction,
+
+    /// Cap on this process's own heap in MB; the memory thresholds then also apply to heap / limit
+    #[clap(long)]
+    self_memory_limit_mb: Option<u64>,
+
+    /// Gas budget per transaction
+    #[clap(long, default_value = "500000000")]
+    gas_budget: u64,
+
+    /// Gas budget per created counter in MIST, times the objects in the transaction (default: --gas-budget per tx)
+    #[clap(long)]
+    gas_budget_create: Option<u64>,
+
+    /// Gas budget per updated counter in MIST, times the objects in the transaction (default: --gas-budget per tx)
+    #[clap(long)]
+    gas_budget_update: Option<u64>,
+
+    /// Gas budget per blob created, rewritten or deleted in MIST, times the objects in the transaction
+    /// (default: --gas-budget per tx)
+    #[clap(long)]
+    gas_budget_blob: Option<u64>,
+
+    /// Stats reporting interval in seconds
+    #[clap(long, default_value = "30")]
+    stats_interval: u64,
+
+    /// Start stats intervals on wall-clock multiples of --stats-interval (e.g. every minute on the minute)
+    #[clap(long)]
+    align_intervals: bool,
+
+    /// Shift aligned interval boundaries by this many milliseconds (e.g. to match a scrape offset)
+    #[clap(long, default_value = "0")]
+    align_offset_ms: u64,
+
+    /// Use 4KB LargeBlob objects instead of MicroCounters for more I/O per TX
+    #[clap(long, default_value = "false")]
+    use_blobs: bool,
+
+    /// Blob updates rewrite only this many bytes instead of the full 4KB (requires --use-blobs)
+    #[clap(long)]
+    update_bytes: Option<u64>,
+
+    /// Blob updates append this many bytes instead of rewriting, so objects grow over time (requires --use-blobs)
+    #[clap(long, conflicts_with = "update_bytes")]
+    append_bytes: Option<u64>,
+
+    /// Growing blobs start over once they would exceed this size (Sui caps objects at 250KiB)
+    #[clap(long, default_value = "250000")]
+    max_object_bytes: u64,
+
+    /// Where the partial slice is written: prefix (offset 0) or random
+    #[clap(long, default_value = "prefix", value_parser = ["prefix", "random"])]
+    update_offset: String,
+
+    /// Blob size distribution applied at create time (requires --use-blobs):
+    /// fixed:4096, uniform:512-16384, lognormal:2048,1.0 or hist:256=50,4096=30,65536=20
+    #[clap(long)]
+    size_distribution: Option<sizes::SizeDistribution>,
+
+    /// Output file for results (JSON)
+    #[clap(long)]
+    output: Option<String>,
+
+    /// Sui keystore holding the workers' keys: resolves addresses saved without keys, and supplies fresh-mode worker identities
+    #[clap(long)]
+    keystore: Option<String>,
+
+    /// Leave worker private keys out of --save-objects and --autosave files (loading them needs --keystore)
+    #[clap(long)]
+    save_without_keys: bool,
+
+    /// Save created/tracked objects to file (for use with --load-objects in next phase)
+    #[clap(long)]
+    save_objects: Option<String>,
+
+    /// Re-read objects dropped from the tracker from the node before saving them (--save-objects)
+    #[clap(long)]
+    resolve_dropped: bool,
+
+    /// Faucet base URL
+    #[clap(long, default_value = "http://127.0.0.1:9123")]
+    faucet_url: String,
+
+    /// Max faucet requests per second (0 = unlimited)
+    #[clap(long, default_value = "4")]
+    faucet_rate: f64,
+
+    /// Use only the legacy synchronous /gas endpoint (skip the batched /v1 API)
+    #[clap(long, default_value = "false")]
+    faucet_legacy: bool,
+
+    /// Keep a reused address's coins as-is instead of merging them into one at init
+    #[clap(long, default_value = "false")]
+    no_gas_consolidation: bool,
+
+    /// Gas coins per worker; >1 splits the faucet coin so seed batches can be pipelined
+    #[clap(long, default_value = "1")]
+    gas_coins_per_worker: usize,
+
+    /// Pay each workload transaction with up to N gas coins (gas smashing); the address's
+    /// extra coins are kept as fragments instead of being merged at init
+    #[clap(long, default_value = "1")]
+    gas_smash: usize,
+
+    /// With --gas-smash, split fresh fragments off the primary coin whenever they run out
+    #[clap(long, default_value = "false")]
+    gas_smash_refill: bool,
+
+    /// Pay built-in creates and updates from two dedicated gas coins per worker (one per stream),
+    /// so gas coin writes stay out of the measured cold and hot streams
+    #[clap(long, default_value = "false")]
+    gas_streams: bool,
+
+    /// Resume an interrupted run from --autosave (workers, objects, stats, remaining duration)
+    #[clap(long, default_value = "false")]
+    resume: bool,
+
+    /// Seconds between run-state autosaves during the load phase
+    #[clap(long, default_value = "60")]
+    autosave_interval: u64,
+
+    /// Persist generated worker keypairs here and reuse them (with their gas and
+    /// objects) in later fresh-mode runs instead of new faucet-funded addresses
+    #[clap(long)]
+    identity_cache: Option<String>,
+
+    /// Cache object owners and types in this file, so later loads only fetch versions
+    /// for objects already known to belong to their worker
+    #[clap(long)]
+    meta_cache: Option<String>,
+
+    /// Checkpoint worker keypairs and seed objects to this file while seeding;
+    /// if the file already exists, fresh mode resumes seeding from it
+    #[clap(long)]
+    autosave: Option<String>,
+
+    /// Load objects from file instead of creating seed objects (use objects from previous phase)
+    #[clap(long)]
+    load_objects: Option<String>,
+
+    /// Load only these saved workers (comma-separated ids); they are renumbered from 0 in this order
+    #[clap(long, value_delimiter = ',')]
+    load_workers: Vec<usize>,
+
+    /// Load only this fraction of each saved worker's objects (the first ones saved)
+    #[clap(long)]
+    load_fraction: Option<Fraction>,
+
+    /// Transfer the loaded objects on chain to this many new workers instead of restoring the saved ones
+    #[clap(long)]
+    reshard_workers: Option<usize>,
+
+    /// Follow checkpoints during the run and record per-checkpoint transaction counts
+    #[clap(long, default_value = "false")]
+    sample_checkpoints: bool,
+
+    /// Also sum serialized effects bytes per checkpoint (one extra RPC per 50 txs)
+    #[clap(long, default_value = "false")]
+    checkpoint_effects_bytes: bool,
+
+    /// Sample /sys/block/<dev>/stat for these devices every stats interval (e.g. nvme0n1,nvme1n1)
+    #[clap(long, value_delimiter = ',')]
+    sample_block_devs: Vec<String>,
+
+    /// Capture NVMe SMART and OCP logs at run start/end for these devices (e.g. nvme0,nvme1; needs CAP_SYS_ADMIN)
+    #[clap(long, value_delimiter = ',')]
+    nvme_devices: Vec<String>,
+
+    /// Sample FDP statistics, reclaim unit handle status and FDP events every stats interval
+    /// for these FDP-enabled namespaces (e.g. nvme0n1; needs CAP_SYS_ADMIN)
+    #[clap(long, value_delimiter = ',')]
+    sample_fdp: Vec<String>,
+
+    /// Endurance group the --sample-fdp namespaces belong to
+    #[clap(long, default_value = "1")]
+    fdp_endgrp: u16,
+
+    /// Scrape the node's Prometheus endpoint for RocksDB metrics every stats interval
+    /// (e.g. http://127.0.0.1:9184/metrics)
+    #[clap(long)]
+    node_metrics_url: Option<String>,
+
+    /// Record the node's pruning config (from --node-config) and pruned-checkpoint watermark every stats interval
+    #[clap(long, default_value = "false")]
+    record_pruning: bool,
+
+    /// Metric name prefixes kept by the RocksDB metrics sampler
+    #[clap(long, value_delimiter = ',', default_value = "rocksdb_")]
+    rocksdb_metric_prefix: Vec<String>,
+
+    /// Dry-run every operation (dryRunTransactionBlock) instead of executing it: measures
+    /// client + RPC + execution simulation throughput without writing anything
+    #[clap(long, default_value = "false")]
+    simulate: bool,
+
+    /// Only measure local PTB construction + signing throughput (no network) and exit
+    #[clap(long, default_value = "false")]
+    offline_build_bench: bool,
+
+    /// Duration of --offline-build-bench in seconds
+    #[clap(long, default_value = "10")]
+    offline_build_secs: u64,
+
+    /// Poll the current epoch every N seconds and split counters and latency per epoch (0 = off)
+    #[clap(long, default_value = "10")]
+    epoch_poll_secs: u64,
+
+    /// Build workload transactions to expire N epochs after the current one (0 = valid in this epoch only)
+    #[clap(long)]
+    tx_expiration_epochs: Option<u64>,
+
+    /// Drop a transaction unsent if it waited longer than this for an in-flight permit
+    #[clap(long)]
+    stale_after_ms: Option<u64>,
+
+    /// Invariants to assert after the run; any failure fails the run (nonzero-tps, tracker, round-trip)
+    #[clap(long, value_delimiter = ',')]
+    check: Vec<checks::Check>,
+
+    /// Halve a worker's batch size after this many failures in a row of one batch-dependent
+    /// class (insufficient gas, size limit, Move abort at a command)
+    #[clap(long)]
+    batch_backoff_after: Option<u32>,
+
+    /// Double a backed-off batch again after this many successes in a row
+    #[clap(long, default_value = "20")]
+    batch_regrow_after: u32,
+
+    /// Flag a write stall when success TPS drops below this percentage of its recent baseline
+    /// while transactions are still in flight (0 = off)
+    #[clap(long, default_value = "25")]
+    stall_threshold_pct: Percent,
+
+    /// Shortest slump counted as a write stall, in seconds
+    #[clap(long, default_value = "3")]
+    stall_min_secs: u64,
+
+    /// Capture every transaction slower than this many milliseconds (digest, timings, in-flight count)
+    #[clap(long, conflicts_with = "outlier_factor")]
+    outlier_ms: Option<f64>,
+
+    /// Capture transactions slower than this multiple of the run's p99.9 so far
+    #[clap(long)]
+    outlier_factor: Option<f64>,
+
+    /// Re-fetch the effects of this share of successful transactions later in the run and check they are unchanged
+    #[clap(long, default_value = "0")]
+    verify_effects_pct: Percent,
+
+    /// How long after execution a sampled transaction is verified, in seconds
+    #[clap(long, default_value = "300")]
+    verify_delay_secs: u64,
+
+    /// Failure rate is measured over this many most recent seconds
+    #[clap(long, default_value = "60")]
+    failure_window_secs: u64,
+
+    /// Transactions the window must hold before failures throttle anything
+    #[clap(long, default_value = "100")]
+    failure_min_tx: u64,
+
+    /// Slow workers down when more than this share of recent transactions failed
+    #[clap(long, default_value = "10")]
+    failure_slow_pct: Percent,
+
+    /// Pause before each transaction while above --failure-slow-pct, in milliseconds
+    #[clap(long, default_value = "200")]
+    failure_slow_ms: u64,
+
+    /// Pause workers when more than this share of recent transactions failed
+    #[clap(long, default_value = "30")]
+    failure_pause_pct: Percent,
+
+    /// Pause length while above --failure-pause-pct, in seconds
+    #[clap(long, default_value = "5")]
+    failure_pause_secs: u64,
+
+    /// Write every tracked object's update count this run to this CSV file (object_id,worker,list,updates,size)
+    #[clap(long)]
+    update_counts_csv: Option<String>,
+
+    /// Probe RPC round-trip latency with a trivial read every N milliseconds during the run
+    #[clap(long)]
+    rpc_probe_ms: Option<u64>,
+
+    /// Write per-interval latency histograms to this file in HdrHistogram interval-log format
+    #[clap(long)]
+    hdr_log: Option<String>,
+
+    /// Append every stats interval to this file as it is sampled (CSV if the name ends in .csv, else JSON lines)
+    #[clap(long)]
+    interval_log: Option<String>,
+
+    /// Shell command to run just before the load phase starts (repeatable; stdout kept in results)
+    #[clap(long)]
+    exec_before: Vec<String>,
+
+    /// Shell command to run after the load phase ends (repeatable)
+    #[clap(long)]
+    exec_after: Vec<String>,
+
+    /// Shell command to run every stats interval during the load phase (repeatable)
+    #[clap(long)]
+    exec_interval: Vec<String>,
+
+    /// Kill hook commands that run longer than this (seconds)
+    #[clap(long, default_value = "60")]
+    exec_timeout: u64,
+
+    /// Start a node before the run and stop it afterwards (localnet: `sui start`, node: `sui-node`)
+    #[clap(long, value_enum)]
+    manage_node: Option<node::NodeMode>,
+
+    /// Binary for --manage-node (default: sui / sui-node from PATH)
+    #[clap(long)]
+    node_bin: Option<String>,
+
+    /// Node config: fullnode/validator YAML for `node`, network config dir for `localnet`
+    #[clap(long)]
+    node_config: Option<String>,
+
+    /// File receiving the managed node's stdout/stderr
+    #[clap(long, default_value = "managed-node.log")]
+    node_log: String,
+
+    /// Seconds to wait for the managed node's RPC to answer
+    #[clap(long, default_value = "180")]
+    node_ready_timeout: u64,
+
+    /// Node DB directory: its size is recorded at run start/end (and wiped with --wipe-node-db)
+    #[clap(long)]
+    node_db: Option<String>,
+
+    /// Refuse to run unless the node DB is below --fresh-db-max-mib (and --fresh-db-max-checkpoint, if set)
+    #[clap(long, default_value = "false")]
+    require_fresh_db: bool,
+
+    /// Run even if the node's protocol or API version is outside what this build's SDK supports
+    #[clap(long, default_value = "false")]
+    force: bool,
+
+    /// Largest node DB size (MiB) accepted by --require-fresh-db
+    #[clap(long, default_value = "512")]
+    fresh_db_max_mib: u64,
+
+    /// Highest checkpoint accepted by --require-fresh-db
+    #[clap(long)]
+    fresh_db_max_checkpoint: Option<u64>,
+
+    /// Delete --node-db after the managed node is stopped
+    #[clap(long, default_value = "false")]
+    wipe_node_db: bool,
+
+    /// Append every successful transaction digest to this JSONL file (for post-run audit)
+    #[clap(long)]
+    tx_digests: Option<String>,
+
+    /// Also embed recorded digests in the output JSON (capped at 100k)
+    #[clap(long, default_value = "false")]
+    tx_digests_inline: bool,
+
+    /// Close the --tx-digests / --hdr-log / --interval-log segment once it holds this many MB and start a new one
+    #[clap(long)]
+    rotate_mb: Option<u64>,
+
+    /// Close the --tx-digests / --hdr-log / --interval-log segment after this many seconds and start a new one
+    #[clap(long)]
+    rotate_secs: Option<u64>,
+
+    /// Compress closed segments with gzip or zstd
+    #[clap(long, value_enum, default_value = "none")]
+    rotate_compress: rotation::Compression,
+
+    /// Stop the run once gas charged minus storage rebates reaches this many MIST (0 = unlimited)
+    #[clap(long, default_value = "0")]
+    max_gas_spend: u64,
+
+    /// Multi-day run: refuel gas, refresh the gas price per epoch, compact trackers, rotate
+    /// the digest log daily and bound the interval history (requires --autosave)
+    #[clap(long, default_value = "false")]
+    soak: bool,
+
+    /// Don't draw the terminal progress line
+    #[clap(long, default_value = "false")]
+    quiet: bool,
+
+    /// Print the results as one JSON line on stdout at the end; logs go to stderr (implies --quiet)
+    #[clap(long, default_value = "false")]
+    summary_stdout: bool,
+
+    /// Preset workload: smoke, steady-churn, hot-cold-split, compaction-storm or blob-heavy;
+    /// fills in every knob it covers that isn't given explicitly (see presets.rs)
+    #[clap(long, value_enum)]
+    profile: Option<presets::Preset>,
+
+    /// JSON config file with per-worker profile overrides (see config.rs)
+    #[clap(long)]
+    config: Option<String>,
+
+    /// Optional subcommand (default: run a single benchmark with the flags above)
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// Set by `ab-test --simultaneous`: this side's handle on the clock both sides pace from
+    #[clap(skip)]
+    lockstep: Option<lockstep::Side>,
+}
+
+/// Subcommands that orchestrate one or more benchmark runs
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run every combination of a parameter matrix sequentially
+    Sweep(sweep::SweepArgs),
+    /// Run the same config twice (A vs B) and write a comparative report
+    AbTest(ab_test::AbTestArgs),
+    /// Re-fetch effects for recorded digests and build an authoritative cost report
+    Audit(audit::AuditArgs),
+    /// Trace an object ID back to the benchmark operation that created it
+    Lookup(lookup::LookupArgs),
+}
+
+/// Parse a byte size with optional K/M/G suffix (binary units: 1K = 1024)
+fn parse_byte_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let value: f64 = num.parse().map_err(|_| format!("invalid size '{}'", s))?;
+    let multiplier = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1u64,
+        "k" | "kb" | "kib" => 1 << 10,
+        "m" | "mb" | "mib" => 1 << 20,
+        "g" | "gb" | "gib" => 1 << 30,
+        other => return Err(format!("unknown size unit '{}' (use K, M or G)", other)),
+    };
+    Ok((value * multiplier as f64) as u64)
+}
+
+/// Tracked object for updates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedObject {
+    #[serde(with = "object_id_serde")]
+    pub id: ObjectID,
+    pub version: u64,
+    #[serde(with = "object_digest_serde")]
+    pub digest: sui_sdk::types::base_types::ObjectDigest,
+    /// Blob payload size in bytes (0 = the object type's default size)
+    #[serde(default)]
+    pub size: u64,
+    /// Which worker/op/transaction created it (None for objects found on chain)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<lookup::ObjectOrigin>,
+    /// Times this run rewrote it (not saved: a loaded population starts from zero)
+    #[serde(skip)]
+    pub updates: u32,
+}
+
+/// Custom serde for ObjectID (serialize as hex string)
+mod object_id_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sui_sdk::types::base_types::ObjectID;
+    use std::str::FromStr;
+
+    pub fn serialize<S>(id: &ObjectID, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        id.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<ObjectID, D::Error>
+    where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        ObjectID::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Custom serde for ObjectDigest (serialize as base58 string)
+mod object_digest_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sui_sdk::types::base_types::ObjectDigest;
+    use std::str::FromStr;
+
+    pub fn serialize<S>(digest: &ObjectDigest, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        digest.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<ObjectDigest, D::Error>
+    where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        ObjectDigest::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Custom serde for TransactionDigest (serialize as base58 string)
+mod tx_digest_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sui_sdk::types::base_types::TransactionDigest;
+    use std::str::FromStr;
+
+    pub fn serialize<S>(digest: &TransactionDigest, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        digest.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TransactionDigest, D::Error>
+    where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        TransactionDigest::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serializable worker objects for save/load between phases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedWorkerObjects {
+    pub worker_id: usize,
+    #[serde(with = "sui_address_serde")]
+    pub address: SuiAddress,
+    /// Base64-encoded keypair bytes for restoring worker identity (empty: resolved from --keystore)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub keypair_base64: String,
+    pub objects: Vec<TrackedObject>,
+}
+
+/// Custom serde for SuiAddress
+mod sui_address_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use sui_sdk::types::base_types::SuiAddress;
+    use std::str::FromStr;
+
+    pub fn serialize<S>(addr: &SuiAddress, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        addr.to_string().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SuiAddress, D::Error>
+    where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        SuiAddress::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Full saved state for all workers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedBenchmarkState {
+    pub total_objects: usize,
+    pub workers: Vec<SavedWorkerObjects>,
+}
+
+/// Worker state
+pub struct WorkerState {
+    id: usize,
+    address: SuiAddress,
+    keypair: SuiKeyPair,
+    gas_coin: ObjectRef,
+    /// Extra gas coins split off at init (--gas-coins-per-worker)
+    gas_pool: Vec<ObjectRef>,
+    /// Coins paid alongside the primary coin (--gas-smash) and how many the pending payment uses
+    gas_smash: usize,
+    smash_coins: Vec<ObjectRef>,
+    smashing: usize,
+    /// Fragments merged by gas payments, and fragment refills
+    smash_merged: u64,
+    smash_refills: u64,
+    /// Coins of the create and update streams (--gas-streams) and the one the pending payment uses
+    stream_gas: Option<gas_streams::StreamCoins>,
+    paying_stream: Option<gas_streams::GasStream>,
+    objects: Vec<TrackedObject>,
+    /// Objects used by the most recently submitted update batch
+    last_batch: Vec<ObjectID>,
+    /// Consecutive commands each object of last_batch got (tags add one)
+    batch_stride: usize,
+    /// Objects taken out of rotation after lock conflicts
+    quarantine: Vec<failures::QuarantinedObject>,
+    /// Objects frozen by the freeze workload (immutable; refs never change)
+    frozen: Vec<TrackedObject>,
+    /// Cold objects of a hot/cold split (--hot-fraction): owned, but never updated
+    cold: Vec<TrackedObject>,
+    cold_split: bool,
+    /// Temperature assignment of a creation-time partition (--create-hot-pct)
+    partition: Option<partition::CreationSplit>,
+    /// What to drop when the tracker is full (--eviction-policy)
+    eviction: eviction::EvictionPolicy,
+    /// Tracked (hot and cold) and frozen objects kept at most, each (--max-tracked-objects)
+    max_tracked: usize,
+    /// Where dropped objects go to be saved anyway (--save-objects)
+    spill: Option<Arc<spill::Spill>>,
+    /// Recently executed transaction digests (duplicate response detection)
+    seen_digests: dedupe::DigestLru,
+    /// Progress through the current compaction wave, if one is running
+    wave: Option<wave::WaveProgress>,
+    /// Creation index for the next object this worker creates (see lookup.rs)
+    next_origin_index: u64,
+}
+
+impl WorkerState {
+    pub fn new(id: usize, address: SuiAddress, keypair: SuiKeyPair, gas_coin: ObjectRef, objects: Vec<TrackedObject>) -> Self {
+        // Continue the numbering of a resumed worker
+        let next_origin_index = objects.iter()
+            .filter_map(|o| o.origin.as_ref())
+            .filter(|o| o.worker == id)
+            .map(|o| o.index + 1)
+            .max()
+            .unwrap_or(0);
+        Self {
+            id,
+            address,
+            keypair,
+            gas_coin,
+            gas_pool: Vec::new(),
+            gas_smash: 1,
+            smash_coins: Vec::new(),
+            smashing: 0,
+            smash_merged: 0,
+            smash_refills: 0,
+            stream_gas: None,
+            paying_stream: None,
+            objects,
+            last_batch: Vec::new(),
+            batch_stride: 1,
+            quarantine: Vec::new(),
+            frozen: Vec::new(),
+            cold: Vec::new(),
+            cold_split: false,
+            partition: None,
+            eviction: eviction::EvictionPolicy::default(),
+            max_tracked: DEFAULT_MAX_TRACKED_OBJECTS,
+            spill: None,
+            seen_digests: dedupe::DigestLru::new(dedupe::SEEN_DIGESTS_PER_WORKER),
+            wave: None,
+            next_origin_index,
+        }
+    }
+
+    /// Number a newly created object and track it if there is room
+    pub fn track_created(&mut self, mut obj: TrackedObject) {
+        if let Some(origin) = obj.origin.as_mut() {
+            origin.worker = self.id;
+            origin.index = self.next_origin_index;
+        }
+        self.next_origin_index += 1;
+        // Cap tracked objects to prevent memory bloat
+        let full = self.objects.len() + self.cold.len() >= self.max_tracked;
+        if full && self.eviction == eviction::EvictionPolicy::DropNewest {
+            self.spill(&[obj]);
+            return;
+        }
+        // Under a hot/cold split new objects are cold: written once, never updated;
+        // a partition assigns them by its hot share
+        let hot = match self.partition.as_mut() {
+            Some(split) => split.next_is_hot(),
+            None => !self.cold_split,
+        };
+        let list = if hot { &mut self.objects } else { &mut self.cold };
+        list.push(obj);
+        if full {
+            let evicted = self.eviction.evict(list, 1, &mut rand::thread_rng());
+            self.spill(&evicted);
+        }
+    }
+
+    /// Gas payment for the next transaction: the primary coin plus up to gas_smash - 1 fragments
+    fn gas_payment(&mut self) -> Vec<ObjectRef> {
+        self.paying_stream = None;
+        self.smashing = self.smash_coins.len().min(self.gas_smash.saturating_sub(1));
+        let mut payment = vec![self.gas_coin];
+        payment.extend_from_slice(&self.smash_coins[..self.smashing]);
+        payment
+    }
+
+    /// Gas payment for a workload transaction: its stream's coin under --gas-streams, gas_payment() otherwise
+    fn gas_payment_for(&mut self, workload: &str) -> Vec<ObjectRef> {
+        let stream = gas_streams::GasStream::of(workload);
+        match (stream, &self.stream_gas) {
+            (Some(stream), Some(coins)) => {
+                self.paying_stream = Some(stream);
+                self.smashing = 0;
+                vec![coins.coin(stream)]
+            }
+            _ => self.gas_payment(),
+        }
+    }
+
+    /// Effects are in: the fragments paid with were merged into the primary coin
+    fn gas_charged(&mut self, gas_coin: ObjectRef) {
+        if let (Some(stream), Some(coins)) = (self.paying_stream.take(), self.stream_gas.as_mut()) {
+            coins.charged(stream, gas_coin);
+            return;
+        }
+        self.gas_coin = gas_coin;
+        let merged = std::mem::take(&mut self.smashing);
+        self.smash_coins.drain(..merged);
+        self.smash_merged += merged as u64;
+    }
+
+    /// First object of the next update batch: sequential during a compaction wave, random otherwise
+    fn batch_start(&mut self, count: usize, rng: &mut impl Rng) -> usize {
+        let len = self.objects.len();
+        match self.wave.as_mut() {
+            Some(wave) => wave.advance(count, len),
+            None => rng.gen_range(0..len),
+        }
+    }
+}
+
+/// Outcome of one successfully executed transaction
+pub struct TxOutcome {
+    created: u64,
+    updated: u64,
+    /// Net gas charged (computation + storage - rebate), in MIST
+    gas_used: u64,
+    /// The same split into charge and storage rebate (the rebate can exceed the charge)
+    charge: GasCharge,
+    digest: TransactionDigest,
+    /// Logical bytes written (object payloads created/rewritten)
+    bytes_written: u64,
+    /// Payload size of each created/updated blob, for per-size-bucket stats
+    object_sizes: Vec<u64>,
+    /// Objects only read (immutable reads), not written
+    read: u64,
+    /// Objects deleted (--workload delete_blob)
+    deleted: u64,
+    /// Digest was already seen by this worker; nothing was (re-)applied
+    duplicate: bool,
+    /// Sequenced through consensus (touched a shared object) rather than the owned fast path
+    consensus: bool,
+}
+
+impl TxOutcome {
+    /// Nothing applied yet
+    fn empty(digest: TransactionDigest) -> Self {
+        Self {
+            created: 0,
+            updated: 0,
+            gas_used: 0,
+            charge: GasCharge::default(),
+            digest,
+            bytes_written: 0,
+            object_sizes: Vec::new(),
+            read: 0,
+            deleted: 0,
+            duplicate: false,
+            consensus: false,
+        }
+    }
+
+    fn duplicate(digest: TransactionDigest) -> Self {
+        Self { duplicate: true, ..Self::empty(digest) }
+    }
+}
+
+/// Net MIST charged by a transaction (rebates can make this zero, never negative)
+fn gas_spent(summary: &GasCostSummary) -> u64 {
+    (summary.computation_cost + summary.storage_cost).saturating_sub(summary.storage_rebate)
+}
+
+/// What a transaction charged (computation + storage) and the storage rebate it returned to the gas coin, in MIST
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GasCharge {
+    charged: u64,
+    rebate: u64,
+}
+
+impl GasCharge {
+    fn of(summary: &GasCostSummary) -> Self {
+        Self { charged: summary.computation_cost + summary.storage_cost, rebate: summary.storage_rebate }
+    }
+}
+
+/// Global benchmark statistics (hot-path counters are sharded per worker)
+pub struct BenchStats {
+    tx_submitted: ShardedCounter,
+    tx_success: ShardedCounter,
+    tx_failed: ShardedCounter,
+    objects_created: ShardedCounter,
+    objects_updated: ShardedCounter,
+    /// Cumulative net gas spent by benchmark transactions (MIST)
+    gas_spent: ShardedCounter,
+    /// Computation + storage charged, and storage rebates returned (net = charged - rebated)
+    gas_charged: ShardedCounter,
+    gas_rebated: ShardedCounter,
+    /// Lock-conflict / equivocation failures and objects quarantined because of them
+    lock_conflicts: AtomicU64,
+    objects_quarantined: AtomicU64,
+    /// Stale gas coin refs re-read from chain and repaired
+    gas_repairs: AtomicU64,
+    /// Success responses for digests already accounted for (not counted again)
+    tx_duplicates: AtomicU64,
+    /// Objects made immutable by the freeze workload and immutable objects read back
+    objects_frozen: AtomicU64,
+    objects_read: AtomicU64,
+    /// Created/updated counts per blob size bucket (--size-distribution)
+    size_buckets: sizes::SizeBucketStats,
+    /// Successful-transaction latency by op type and batch size
+    latency: latency::LatencyStats,
+    /// In-flight gauge and semaphore waits
+    inflight: inflight::InflightStats,
+    /// Compaction waves (--wave-interval)
+    waves: wave::WaveStats,
+    /// Current epoch and per-epoch latency (--epoch-poll-secs)
+    epochs: epochs::EpochStats,
+    /// Active sender count and per-stage latency (--address-stages)
+    stages: address_scaling::StageStats,
+    /// Per-worker latency, merged per tenant at the end
+    tenants: tenants::TenantStats,
+    /// Hot-reloadable knobs from --config and their change log
+    live: reload::LiveKnobs,
+    /// Transactions slower than the outlier threshold
+    outliers: outliers::OutlierStats,
+    /// Latency since the last --adaptive-inflight step (or --inflight-stages measurement)
+    adaptive: adaptive::LatencyWindow,
+    /// Built-in create/update decisions (realized mix)
+    mix: mix::MixStats,
+    /// Hot/cold write volume (--hot-fraction)
+    temperature: hot_cold::TemperatureStats,
+    partition: partition::PartitionStats,
+    expiry: expiry::ExpiryStats,
+    command_failures: command_failures::CommandFailureStats,
+    batch_backoff: batch_backoff::BackoffStats,
+    /// Fast path vs consensus counts and latency
+    paths: consensus_path::PathStats,
+    /// Transactions set aside for delayed effects verification
+    verify: verify::EffectsSampler,
+    failure_window: failure_rate::FailureWindow,
+    /// The node's PTB limits, for splitting batches reloaded from --config
+    ptb_limits: std::sync::OnceLock<limits::PtbLimits>,
+    spill: std::sync::OnceLock<Arc<spill::Spill>>,
+    /// Per-worker payload tags (--payload-tag)
+    payload_tags: std::sync::OnceLock<tagging::TagPlan>,
+    start_time: Instant,
+    /// Time source of worker pacing, throttling and the deadline
+    clock: Arc<dyn clock::Clock>,
+}
+
+impl BenchStats {
+    fn new(workers: usize, clock: Arc<dyn clock::Clock>) -> Self {
+        Self {
+            tx_submitted: ShardedCounter::new(workers),
+            tx_success: ShardedCounter::new(workers),
+            tx_failed: ShardedCounter::new(workers),
+            objects_created: ShardedCounter::new(workers),
+            objects_updated: ShardedCounter::new(workers),
+            gas_spent: ShardedCounter::new(workers),
+            gas_charged: ShardedCounter::new(workers),
+            gas_rebated: ShardedCounter::new(workers),
+            lock_conflicts: AtomicU64::new(0),
+            objects_quarantined: AtomicU64::new(0),
+            gas_repairs: AtomicU64::new(0),
+            tx_duplicates: AtomicU64::new(0),
+            objects_frozen: AtomicU64::new(0),
+            objects_read: AtomicU64::new(0),
+            size_buckets: sizes::SizeBucketStats::new(),
+            latency: latency::LatencyStats::default(),
+            inflight: inflight::InflightStats::new(workers),
+            waves: wave::WaveStats::default(),
+            epochs: epochs::EpochStats::default(),
+            stages: address_scaling::StageStats::new(workers),
+            tenants: tenants::TenantStats::new(workers),
+            live: reload::LiveKnobs::default(),
+            outliers: outliers::OutlierStats::default(),
+            adaptive: adaptive::LatencyWindow::default(),
+            mix: mix::MixStats::default(),
+            temperature: hot_cold::TemperatureStats::default(),
+            partition: partition::PartitionStats::default(),
+            expiry: expiry::ExpiryStats::default(),
+            command_failures: command_failures::CommandFailureStats::default(),
+            batch_backoff: batch_backoff::BackoffStats::default(),
+            paths: consensus_path::PathStats::default(),
+            verify: verify::EffectsSampler::default(),
+            failure_window: failure_rate::FailureWindow::default(),
+            ptb_limits: std::sync::OnceLock::new(),
+            spill: std::sync::OnceLock::new(),
+            payload_tags: std::sync::OnceLock::new(),
+            start_time: clock.now(),
+            clock,
+        }
+    }
+
+    /// Continue counting from a journal entry of an interrupted run
+    fn resumed(entry: &autosave::JournalEntry, workers: usize, clock: Arc<dyn clock::Clock>) -> Self {
+        let stats = Self::new(workers, clock);
+        stats.tx_submitted.set_base(entry.tx_submitted);
+        stats.tx_success.set_base(entry.tx_success);
+        stats.tx_failed.set_base(entry.tx_failed);
+        stats.objects_created.set_base(entry.objects_created);
+        stats.objects_updated.set_base(entry.objects_updated);
+        stats.gas_spent.set_base(entry.gas_spent);
+        // Journals written before rebate accounting only have the net figure
+        stats.gas_charged.set_base(if entry.gas_charged > 0 { entry.gas_charged } else { entry.gas_spent });
+        stats.gas_rebated.set_base(entry.gas_rebated);
+        // Backdate the start so elapsed/TPS/deadline cover the earlier segments
+        let prior = Duration::from_secs_f64(entry.elapsed_secs);
+        Self {
+            start_time: stats.start_time.checked_sub(prior).unwrap_or(stats.start_time),
+            ..stats
+        }
+    }
+
+    /// Add a transaction's gas to the running totals; returns true once net spend reaches `cap`
+    fn add_gas(&self, worker: usize, gas_used: u64, charge: GasCharge, cap: u64) -> bool {
+        self.gas_spent.add(worker, gas_used);
+        self.gas_charged.add(worker, charge.charged);
+        self.gas_rebated.add(worker, charge.rebate);
+        self.gas_cap_reached(cap)
+    }
+
+    /// Whether net spend, earlier resumed segments included, has reached `cap` (0 = no cap)
+    fn gas_cap_reached(&self, cap: u64) -> bool {
+        cap > 0 && self.net_gas() >= cap as i64
+    }
+
+    /// MIST the run actually took from its gas coins: charges minus rebates credited back
+    fn net_gas(&self) -> i64 {
+        self.gas_charged.get() as i64 - self.gas_rebated.get() as i64
+    }
+
+    /// Count a failure; lock conflicts quarantine the implicated objects, stale gas refs are repaired
+    async fn record_failure(&self, client: Option<&SuiClient>, worker: &RwLock<WorkerState>, err: &anyhow::Error, epoch: u64) {
+        {
+            let state = worker.read().await;
+            self.tx_failed.add(state.id, 1);
+            self.command_failures.record(err, &state.last_batch, state.batch_stride);
+        }
+        if expiry::is_expired(err) {
+            self.expiry.record_expired();
+        }
+        if failures::is_lock_conflict(err) {
+            self.lock_conflicts.fetch_add(1, Ordering::Relaxed);
+            let moved = failures::quarantine_for_error(&mut *worker.write().await, err, epoch);
+            self.objects_quarantined.fetch_add(moved as u64, Ordering::Relaxed);
+        } else if failures::may_have_stale_gas(err, &worker.read().await.gas_coin.0) {
+            // Only a node knows the real ref (mock transactions never half-execute)
+            let Some(client) = client else {
+                return;
+            };
+            match failures::repair_gas_coin(client, worker).await {
+                Ok(true) => {
+                    self.gas_repairs.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(false) => {}
+                Err(e) => debug!("Gas coin re-read failed: {}", e),
+            }
+            match gas_streams::repair(client, worker, err).await {
+                Ok(true) => {
+                    self.gas_repairs.fetch_add(1, Ordering::Relaxed);
+                }
+                Ok(false) => {}
+                Err(e) => debug!("Stream gas coin re-read failed: {}", e),
+            }
+        }
+    }
+
+    /// Attribute a successful blob transaction to its size buckets
+    fn record_sizes(&self, outcome: &TxOutcome) {
+        for size in &outcome.object_sizes {
+            if outcome.created > 0 {
+                self.size_buckets.record_created(*size);
+            } else {
+                self.size_buckets.record_updated(*size);
+            }
+        }
+    }
+
+    /// Per-worker totals of this run segment, from the counter shards
+    fn per_worker(&self) -> Vec<results::WorkerCounters> {
+        let elapsed = self.elapsed().as_secs_f64();
+        (0..self.tx_success.num_shards())
+            .map(|w| results::WorkerCounters {
+                worker: w,
+                tx_submitted: self.tx_submitted.shard(w),
+                tx_success: self.tx_success.shard(w),
+                tx_failed: self.tx_failed.shard(w),
+                objects_created: self.objects_created.shard(w),
+                objects_updated: self.objects_updated.shard(w),
+                gas_spent_mist: self.gas_spent.shard(w),
+                tps: if elapsed > 0.0 { self.tx_success.shard(w) as f64 / elapsed } else { 0.0 },
+            })
+            .collect()
+    }
+
+    /// Time since the benchmark started, on the run's clock
+    fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_duration_since(self.start_time)
+    }
+
+    /// Milliseconds since the benchmark started
+    fn elapsed_ms(&self) -> u64 {
+        self.elapsed().as_millis() as u64
+    }
+
+    fn report(&self) -> String {
+        let elapsed = self.elapsed().as_secs_f64();
+        let submitted = self.tx_submitted.get();
+        let success = self.tx_success.get();
+        let failed = self.tx_failed.get();
+        let created = self.objects_created.get();
+        let updated = self.objects_updated.get();
+
+        let tps = if elapsed > 0.0 { success as f64 / elapsed } else { 0.0 };
+        let ops_rate = if elapsed > 0.0 { (created + updated) as f64 / elapsed } else { 0.0 };
+
+        format!(
+            "Elapsed: {:.1}s | TX: {} submitted, {} success, {} failed | TPS: {:.1} | Objects: {} created, {} updated | Ops/s: {:.1}",
+            elapsed, submitted, success, failed, tps, created, updated, ops_rate
+        )
+    }
+}
+
+/// Parse the command line and run what it asks for (the binary's main)
+pub async fn cli() -> Result<()> {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let mut preset_overrides = Vec::new();
+    if let Some(preset) = args.profile {
+        (args, preset_overrides) = presets::expand(preset, &matches, std::env::args_os().collect())
+            .unwrap_or_else(|e| e.exit());
+    }
+    // --reshard-workers N is the run's worker count unless --workers sets it explicitly
+    if let Some(count) = args.reshard_workers {
+        if !matches!(matches.value_source("workers"), Some(ValueSource::CommandLine | ValueSource::EnvVariable)) {
+            args.workers = count;
+        }
+    }
+
+    // Initialize logging (to stderr when stdout carries the summary line)
+    let writer = if args.summary_stdout {
+        tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stderr)
+    } else {
+        tracing_subscriber::fmt::writer::BoxMakeWriter::new(std::io::stdout)
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .with_writer(writer)
+        .init();
+
+    if let Some(preset) = args.profile {
+        info!("Profile {:?}: {}{}", preset,
+            preset.knobs().iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(" "),
+            if preset_overrides.is_empty() { String::new() } else { format!(" (overridden: {})", preset_overrides.join(", ")) });
+    }
+    if let Err(e) = args.validate() {
+        Args::command().error(clap::error::ErrorKind::ValueValidation, format!("{:#}", e)).exit();
+    }
+    if args.summary_stdout && args.command.is_some() {
+        Args::command().error(clap::error::ErrorKind::ArgumentConflict,
+            "--summary-stdout applies to a plain run or --offline-build-bench, not to subcommands").exit();
+    }
+
+    match args.command.clone() {
+        Some(Command::Sweep(sweep_args)) => sweep::run_sweep(args, sweep_args).await,
+        Some(Command::AbTest(ab_args)) => ab_test::run_ab_test(args, ab_args).await,
+        Some(Command::Audit(audit_args)) => audit::run_audit(args, audit_args).await,
+        Some(Command::Lookup(lookup_args)) => lookup::run_lookup(args, lookup_args).await,
+        None if args.offline_build_bench => {
+            let result = build_bench::run_build_bench(&args);
+            if let Ok(result) = &result {
+                if let Some(output_path) = &args.output {
+                    std::fs::write(output_path, serde_json::to_string_pretty(result)?)?;
+                    info!("Results written to {}", output_path);
+                }
+            }
+            summarize(&args, result)
+        }
+        None => {
+            let result = run_benchmark(&args, clock::system()).await;
+            summarize(&args, result)
+        }
+    }
+}
+
+/// --summary-stdout: the run's one line on stdout, the result or `{"error": ...}`
+fn summarize<T: Serialize>(args: &Args, result: Result<T>) -> Result<()> {
+    if args.summary_stdout {
+        let line = match &result {
+            Ok(result) => serde_json::to_string(result)?,
+            Err(e) => serde_json::json!({ "error": format!("{:#}", e) }).to_string(),
+        };
+        println!("{}", line);
+    }
+    result.map(|_| ())
+}
+
+/// Run one complete benchmark (setup, load phase, output) and return the result;
+/// `clock` paces, throttles and ends the load phase (`clock::system()` for real time)
+pub async fn run_benchmark(args: &Args, clock: Arc<dyn clock::Clock>) -> Result<results::BenchResult> {
+    let phases = phases::Phases::new();
+    let init_start = Instant::now();
+    // The mock backend never looks at the package
+    let package_id_str = match (args.package_id.as_deref(), args.backend) {
+        (Some(id), _) => id,
+        (None, mock::Backend::Mock) => "0x0",
+        (None, mock::Backend::Node) => {
+            return Err(anyhow!("--package-id (or FDP_PACKAGE_ID) is required to run the benchmark"));
+        }
+    };
+
+    info!("╔═══════════════════════════════════════════════════════════════╗");
+    info!("║  FDP SUI Benchmark - SDK-based High-Throughput I/O           ║");
+    info!("╚═══════════════════════════════════════════════════════════════╝");
+    info!("");
+    info!("Configuration:");
+    info!("  RPC URL:       {}", args.rpc_url);
+    info!("  Package ID:    {}", package_id_str);
+    info!("  Duration:      {}s", args.duration);
+    info!("  Workers:       {}", args.workers);
+    info!("  Batch Size:    {} objects/tx", args.batch_size);
+    if args.eviction_policy != eviction::EvictionPolicy::default() {
+        info!("  Eviction:      {:?}", args.eviction_policy);
+    }
+    info!("  Max Inflight:  {}", args.max_inflight);
+    if !args.inflight_stages.is_empty() {
+        info!("  Depth Stages:  {:?} ({}s warm-up each)", args.inflight_stages, args.inflight_warmup_secs);
+    }
+    if args.adaptive_inflight {
+        info!("  Adaptive:      AIMD on p95 (target {})",
+            args.adaptive_p95_ms.map_or("2x best p95".to_string(), |ms| format!("{}ms", ms)));
+    }
+    info!("  Create %:      {}%", args.create_pct);
+    info!("  Seed Objects:  {} per worker", args.seed_objects);
+    info!("  Memory Limit:  {:.0}% throttle, {:.0}% critical, {:.0}% abort", 
+          args.memory_threshold.get() * 100.0, args.memory_critical.get() * 100.0, args.memory_emergency.get() * 100.0);
+    if let Some(bps) = args.target_write_bps {
+        info!("  Write Target:  {:.2} MiB/s logical", bps as f64 / (1024.0 * 1024.0));
+    }
+    if args.max_gas_spend > 0 {
+        info!("  Gas Cap:       {} MIST", args.max_gas_spend);
+    }
+    if args.gas_budget_create.is_some() || args.gas_budget_update.is_some() || args.gas_budget_blob.is_some() {
+        let per = |b: Option<u64>| b.map_or("-".to_string(), |mist| format!("{}", mist));
+        info!("  Gas/Object:    create {}, update {}, blob {} MIST",
+            per(args.gas_budget_create), per(args.gas_budget_update), per(args.gas_budget_blob));
+    }
+    if args.simulate {
+        info!("  Mode:          SIMULATE (dry-run only, nothing is written)");
+    }
+    if args.backend == mock::Backend::Mock {
+        info!("  Backend:       MOCK (no node; latency {:?}, errors {:?})", args.mock_latency, args.mock_errors);
+    }
+    info!("");
+
+    // Reject conflicting flags, then warn about ones that have no effect
+    args.validate()?;
+    if args.update_bytes.is_some() && !args.use_blobs {
+        warn!("--update-bytes only applies to blob updates; it has no effect without --use-blobs");
+    }
+    if args.append_bytes.is_some() && !args.use_blobs {
+        warn!("--append-bytes only applies to blob updates; it has no effect without --use-blobs");
+    }
+    if args.size_distribution.is_some() && !args.use_blobs {
+        warn!("--size-distribution only applies to blob creates; it has no effect without --use-blobs");
+    }
+    if args.use_blobs && args.batch_size > MAX_BLOB_BATCH {
+        warn!("--batch-size {} is capped at {} for blob transactions; pass --batch-size {} to make that explicit",
+            args.batch_size, MAX_BLOB_BATCH, MAX_BLOB_BATCH);
+    }
+    if args.frozen_read_pct.get() > 0 && args.freeze_pct.get() == 0 {
+        warn!("--frozen-read-pct has nothing to read without --freeze-pct");
+    }
+
+    address_scaling::validate(&args.address_stages, args.workers, args.target_tps)?;
+    depth_sweep::validate(&args.inflight_stages, args.inflight_warmup_secs, args.duration)?;
+
+    blockdev::validate(&args.sample_block_devs)?;
+    nvme::validate(&args.nvme_devices)?;
+    nvme::validate_fdp(&args.sample_fdp, args.fdp_endgrp)?;
+
+    let bench_config = match &args.config {
+        Some(path) => config::BenchConfig::load(path)?,
+        None => config::BenchConfig::default(),
+    };
+
+    let package_id = ObjectID::from_hex_literal(package_id_str)
+        .context("Invalid package ID format")?;
+
+    // Tenants may bring their own package; everyone else uses --package-id
+    let mut worker_packages = Vec::with_capacity(args.workers);
+    for worker_id in 0..args.workers {
+        worker_packages.push(match bench_config.tenant_of(worker_id)? {
+            Some(config::TenantSpec { name, package_id: Some(id), .. }) => ObjectID::from_hex_literal(id)
+                .context(format!("Invalid package ID for tenant '{}'", name))?,
+            _ => package_id,
+        });
+    }
+
+    // Start the managed node, if any (killed on early return, stopped cleanly at the end)
+    let managed_node = node::ManagedNode::start(args).await?;
+
+    // Connect to SUI node (none with --backend mock)
+    let client = match args.backend {
+        mock::Backend::Node => {
+            info!("Connecting to SUI node...");
+            let client = SuiClientBuilder::default()
+                .build(&args.rpc_url)
+                .await
+                .context("Failed to connect to SUI node")?;
+            info!("Connected to SUI node");
+            Some(client)
+        }
+        mock::Backend::Mock => None,
+    };
+
+    let compatibility = match &client {
+        Some(client) => Some(compat::check(client, args.force).await?),
+        None => None,
+    };
+
+    // Switch off requested features the published package can't serve
+    let mut probed = args.clone();
+    let mut capabilities = match (&client, args.contract_version) {
+        (_, Some(version)) => Some(capabilities::Capabilities::pinned(version, &worker_packages)),
+        (Some(client), None) => Some(capabilities::Capabilities::probe(client, &worker_packages).await?),
+        (None, None) => None,
+    };
+    if let Some(capabilities) = &mut capabilities {
+        capabilities.restrict(&mut probed)?;
+    }
+    let args = &probed;
+
+    let db_start = match &client {
+        Some(client) => Some(db_check::preflight(client, args).await?),
+        None => None,
+    };
+    if let Some(url) = &args.node_metrics_url {
+        rocksdb_metrics::validate(url, &args.rocksdb_metric_prefix).await?;
+    }
+
+    // What one PTB can carry on this network
+    let ptb_limits = match &client {
+        Some(client) => {
+            let limits = limits::PtbLimits::fetch(client).await.unwrap_or_else(|e| {
+                warn!("Could not read the protocol config ({}); assuming mainnet PTB limits", e);
+                limits::PtbLimits::default()
+            });
+            limits.validate(args)?;
+            info!("PTB limits (protocol version {}): {} commands, {} input objects, {} bytes; up to {} objects per batch",
+                limits.protocol_version, limits.max_commands, limits.max_input_objects, limits.max_tx_bytes, limits.max_batch(args));
+            Some(limits)
+        }
+        None => None,
+    };
+
+    // Cache reference gas price (fetch once, not per transaction)
+    let cached_rgp = match &client {
+        Some(client) => client.governance_api().get_reference_gas_price().await.unwrap_or(1000),
+        None => 1000,
+    };
+    info!("Cached reference gas price: {}", cached_rgp);
+    phases.record("init", init_start);
+
+    // Running flag for workers
+    let running = Arc::new(AtomicBool::new(true));
+
+    // Semaphore for concurrency control - per-worker semaphore for better parallelism
+    let aimd = args.adaptive_inflight.then(|| adaptive::Aimd::new(args.max_inflight / 4, args.max_inflight, args.adaptive_p95_ms));
+    let semaphore = Arc::new(Semaphore::new(match (&aimd, args.inflight_stages.first()) {
+        (Some(aimd), _) => aimd.limit(),
+        (None, Some(&depth)) => depth,
+        (None, None) => args.max_inflight,
+    }));
+
+    let meta_cache = args.meta_cache.as_deref().map(meta_cache::MetaCache::load).transpose()?;
+    let workers = match &client {
+        Some(client) => {
+            let workers = setup_node_workers(client, args, &worker_packages, &phases, meta_cache.as_ref()).await?;
+            if let Some(cache) = &meta_cache {
+                cache.save()?;
+            }
+            workers
+        }
+        None => {
+            let start = Instant::now();
+            let workers = mock::workers(args)?;
+            phases.record(if args.resume || args.load_objects.is_some() { "load" } else { "seeding" }, start);
+            workers
+        }
+    };
+    let setup_start = Instant::now();
+
+    // Shared hot objects (paid for by worker 0)
+    let hot = match client.as_ref().filter(|_| args.hot_objects > 0) {
+        Some(client) => {
+            let rgp = client.governance_api().get_reference_gas_price().await.unwrap_or(1000);
+            Some(hotspot::create_hot_objects(client, &workers[0], package_id, args.hot_objects, args.hot_skew, args.gas_budget, rgp).await?)
+        }
+        None => None,
+    };
+
+    let workloads = Arc::new(workload::WorkloadMix::new(&args.workload, args)?);
+
+    // Transaction digest recorder (durable record of what the run executed)
+    let recorder = if args.tx_digests.is_some() || args.tx_digests_inline {
+        Some(Arc::new(tx_log::DigestRecorder::new(args.tx_digests.as_deref(), args.tx_digests_inline, rotation::RotationPolicy::for_digests(args))?))
+    } else {
+        None
+    };
+
+    // ab-test --simultaneous: the timed runs of both sides start together
+    if let Some(side) = &args.lockstep {
+        side.start(clock.as_ref()).await;
+    }
+
+    // Initialize stats AFTER setup - this ensures DURATION measures actual benchmark time
+    let stats = match args.autosave.as_deref().filter(|_| args.resume) {
+        Some(path) => match autosave::last_journal_entry(path)? {
+            Some(entry) if entry.completed => {
+                return Err(anyhow!("Run in {} already completed; nothing to resume", path));
+            }
+            Some(entry) => {
+                let stats = BenchStats::resumed(&entry, args.workers, clock);
+                // A checkpoint taken after the cap was hit but before the run ended
+                if stats.gas_cap_reached(args.max_gas_spend) {
+                    return Err(anyhow!("Run in {} already spent {} MIST of its --max-gas-spend {}; nothing to resume",
+                        path, stats.net_gas(), args.max_gas_spend));
+                }
+                info!("Resuming run at {:.0}s of {}s ({} tx so far)",
+                    entry.elapsed_secs, args.duration, entry.tx_success);
+                Arc::new(stats)
+            }
+            None => {
+                warn!("No run journal for {} - resuming with fresh stats", path);
+                Arc::new(BenchStats::new(args.workers, clock.clone()))
+            }
+        },
+        None => Arc::new(BenchStats::new(args.workers, clock.clone())),
+    };
+    
+    phases.record("setup", setup_start);
+
+    // Start benchmark
+    let benchmark_start = stats.clock.now();
+    info!("");
+    info!("═══════════════════════════════════════════════════════════════");
+    info!("  BENCHMARK STARTED (duration: {}s)", args.duration);
+    info!("═══════════════════════════════════════════════════════════════");
+
+    let hook_timeout = Duration::from_secs(args.exec_timeout);
+    let hooks_before = hooks::run_all(&args.exec_before, "before", &stats, hook_timeout).await;
+    let run_start = timeline::Timestamp::now(&stats);
+    let nvme_start = nvme::capture_all(&args.nvme_devices, &stats);
+    let timeline = Arc::new(if args.soak {
+        timeline::Timeline::bounded(soak::SOAK_MAX_INTERVALS)
+    } else {
+        timeline::Timeline::default()
+    });
+
+    // Interval latency log (one histogram per stats interval)
+    let hdr_writer = match &args.hdr_log {
+        Some(path) => {
+            stats.latency.take_interval();
+            Some(Arc::new(std::sync::Mutex::new(hdr_log::HdrLogWriter::create(path, run_start.unix_ms, rotation::RotationPolicy::from_args(args))?)))
+        }
+        None => None,
+    };
+
+    // Interval timeline streamed as it is sampled
+    let interval_log = match &args.interval_log {
+        Some(path) => Some(Arc::new(std::sync::Mutex::new(interval_log::IntervalLog::create(path, rotation::RotationPolicy::from_args(args))?))),
+        None => None,
+    };
+
+    // Per-interval SLO checks (config `slo` section)
+    let slo_tracker = bench_config.slo.clone().map(|spec| {
+        stats.latency.take_interval();
+        Arc::new(slo::SloTracker::new(spec))
+    });
+
+    // Start stats reporter (also records the interval timeline)
+    let stats_clone = stats.clone();
+    let running_clone = running.clone();
+    let timeline_clone = timeline.clone();
+    let hdr_clone = hdr_writer.clone();
+    let interval_log_clone = interval_log.clone();
+    let slo_clone = slo_tracker.clone();
+    let ticker = timeline::Ticker::new(args);
+    let paced_by_tps = args.target_write_bps.is_none();
+    stats.live.init(&bench_config, args)?;
+    if let Some(limits) = ptb_limits {
+        let _ = stats.ptb_limits.set(limits);
+    }
+    if let Some(save_path) = &args.save_objects {
+        let _ = stats.spill.set(Arc::new(spill::Spill::create(spill::path(save_path))?));
+    }
+    if let Some(mode) = args.payload_tag {
+        let _ = stats.payload_tags.set(tagging::TagPlan::new(mode, args.workers, &bench_config)?);
+    }
+    let mut config_watcher = args.config.as_deref().map(|path| reload::ConfigWatcher::new(path, args));
+    tokio::spawn(async move {
+        while running_clone.load(Ordering::Relaxed) {
+            ticker.tick(stats_clone.clock.as_ref()).await;
+            let sample = timeline_clone.sample(&stats_clone, pacing::requested_tps(&stats_clone, paced_by_tps));
+            info!("{} | {}", stats_clone.report(), sample.at.utc);
+            if let Some(log) = &interval_log_clone {
+                if let Err(e) = log.lock().unwrap().write(&sample) {
+                    warn!("Interval log write failed: {}", e);
+                }
+            }
+            if hdr_clone.is_some() || slo_clone.is_some() {
+                let interval = stats_clone.latency.take_interval();
+                if let Some(writer) = &hdr_clone {
+                    if let Err(e) = writer.lock().unwrap().write_interval(sample.at.unix_ms, &interval) {
+                        warn!("HDR log write failed: {}", e);
+                    }
+                }
+                if let Some(tracker) = &slo_clone {
+                    tracker.record(&sample, &interval);
+                }
+            }
+            // Config changes take effect from the next interval on
+            if let Some(watcher) = &mut config_watcher {
+                watcher.poll(&stats_clone);
+            }
+        }
+    });
+
+    // Live progress line on terminals
+    let progress_bar = progress::spawn(stats.clone(), running.clone(), args.duration, args.quiet || args.summary_stdout);
+
+    // Start checkpoint sampler (node-independent logical write volume)
+    let checkpoint_handle = client.clone().filter(|_| args.sample_checkpoints).map(|client| {
+        let running = running.clone();
+        let fetch_effects = args.checkpoint_effects_bytes;
+        tokio::spawn(async move {
+            checkpoints::run_checkpoint_sampler(client, running, fetch_effects).await
+        })
+    });
+
+    // Start block device sampler (physical I/O timeline)
+    let blockdev_handle = if args.sample_block_devs.is_empty() {
+        None
+    } else {
+        let devs = args.sample_block_devs.clone();
+        let stats = stats.clone();
+        let running = running.clone();
+        Some(tokio::spawn(blockdev::run_block_sampler(devs, stats, running, ticker)))
+    };
+
+    // Start interval hooks (user-provided collectors)
+    let interval_hooks_handle = if args.exec_interval.is_empty() {
+        None
+    } else {
+        let commands = args.exec_interval.clone();
+        let stats = stats.clone();
+        let running = running.clone();
+        Some(tokio::spawn(hooks::run_interval_hooks(commands, stats, running, ticker, hook_timeout)))
+    };
+
+    // Start address-count stage controller
+    let stages_handle = (!args.address_stages.is_empty()).then(|| {
+        tokio::spawn(address_scaling::run_stages(args.address_stages.clone(), stats.clone(), running.clone(),
+            Duration::from_secs(args.duration)))
+    });
+
+    // Adaptive in-flight limit
+    let adaptive_handle = aimd.map(|aimd| {
+        tokio::spawn(adaptive::run(semaphore.clone(), stats.clone(), running.clone(), aimd,
+            args.max_inflight, args.adaptive_p95_ms))
+    });
+
+    // In-flight depth stages
+    let depth_handle = (!args.inflight_stages.is_empty()).then(|| {
+        tokio::spawn(depth_sweep::run(args.inflight_stages.clone(), semaphore.clone(), stats.clone(), running.clone(),
+            Duration::from_secs(args.duration), Duration::from_secs(args.inflight_warmup_secs)))
+    });
+
+    // Start epoch watcher (per-epoch segments)
+    let epoch_handle = client.clone().filter(|_| args.epoch_poll_secs > 0).map(|client| {
+        let interval = Duration::from_secs(args.epoch_poll_secs);
+        tokio::spawn(epochs::run_epoch_watcher(client, stats.clone(), running.clone(), interval))
+    });
+
+    // Start write-stall detector (success TPS slumps)
+    let stall_handle = (args.stall_threshold_pct.get() > 0).then(|| {
+        tokio::spawn(stalls::run_stall_detector(stats.clone(), running.clone(), args.stall_threshold_pct.get(), args.stall_min_secs))
+    });
+
+    // Latency outlier threshold: fixed, or a multiple of the running p99.9
+    if let Some(ms) = args.outlier_ms {
+        stats.outliers.set_threshold(Duration::from_secs_f64(ms / 1000.0));
+    }
+    if let Some(factor) = args.outlier_factor {
+        tokio::spawn(outliers::run_threshold_updater(stats.clone(), running.clone(), factor));
+    }
+
+    // Start RPC round-trip probe (baseline node responsiveness)
+    let probe_handle = args.rpc_probe_ms.filter(|ms| *ms > 0).zip(client.clone()).map(|(ms, client)| {
+        tokio::spawn(rpc_probe::run_rpc_probe(client, stats.clone(), running.clone(), Duration::from_millis(ms)))
+    });
+
+    // Delayed effects verification
+    let verify_handle = client.clone().filter(|_| args.verify_effects_pct.get() > 0).map(|client| {
+        stats.verify.enable(args.verify_effects_pct.get());
+        let delay = Duration::from_secs(args.verify_delay_secs);
+        tokio::spawn(verify::run_verifier(client, stats.clone(), running.clone(), args.verify_effects_pct.get(), delay))
+    });
+
+    // Start pruning sampler (pruner deletes during the run skew WAF)
+    let pruning_handle = client.clone().filter(|_| args.record_pruning).map(|client| {
+        tokio::spawn(pruning::run_pruning_sampler(client, args.node_config.clone(),
+            args.node_metrics_url.clone(), stats.clone(), running.clone(), ticker))
+    });
+
+    // Start RocksDB metrics sampler (flush vs compaction attribution)
+    let rocksdb_handle = args.node_metrics_url.clone().map(|url| {
+        let prefixes = args.rocksdb_metric_prefix.clone();
+        let stats = stats.clone();
+        let running = running.clone();
+        tokio::spawn(rocksdb_metrics::run_rocksdb_sampler(url, prefixes, stats, running, ticker))
+    });
+
+    // Start FDP telemetry sampler (placement behavior)
+    let fdp_handle = if args.sample_fdp.is_empty() {
+        None
+    } else {
+        let devs = args.sample_fdp.clone();
+        let stats = stats.clone();
+        let running = running.clone();
+        Some(tokio::spawn(nvme::run_fdp_sampler(devs, args.fdp_endgrp, stats, running, ticker)))
+    };
+
+    // Long-run maintenance (--soak)
+    let soak_handle = args.soak.then(|| {
+        let settings = soak::SoakSettings {
+            gas_budget: args.gas_budget,
+            faucet: faucet::FaucetClient::new(&args.faucet_url, args.faucet_rate, !args.faucet_legacy),
+        };
+        tokio::spawn(soak::run(client.clone(), workers.clone(), stats.clone(), running.clone(), recorder.clone(), settings))
+    });
+
+    // Memory pressure level (0-3) for graduated throttling - NEVER abort, only throttle
+    let memory_pressure = Arc::new(pressure::PressureCoordinator::new(workers.len()));
+    match memory::usage() {
+        Some(usage) => info!("Memory monitor: {} ({:.1}% in use)", memory::BACKEND, usage * 100.0),
+        None => warn!("Memory monitor: no usable backend on this platform ({}) - memory throttling is disabled", memory::BACKEND),
+    }
+    if let Some(limit_mb) = args.self_memory_limit_mb {
+        info!("Memory monitor: own heap capped at {} MB ({:.1} MB allocated)", limit_mb, heap::allocated() as f64 / (1024.0 * 1024.0));
+    }
+    
+    // Start memory monitor task
+    let memory_pressure_clone = memory_pressure.clone();
+    let running_clone = running.clone();
+    let stats_clone = stats.clone();
+    let workers_clone = workers.clone();
+    let self_memory_limit_mb = args.self_memory_limit_mb;
+    tokio::spawn(async move {
+        let mut last_level = MEM_PRESSURE_NORMAL;
+        let clock = stats_clone.clock.clone();
+        let mut last_log_time = clock.now();
+        
+        while running_clone.load(Ordering::Relaxed) {
+            let usage = memory::usage_pct();
+            // Thresholds may be reloaded from --config
+            let mem = stats_clone.live.memory();
+            
+            // Our own heap against its cap counts too; whichever is worse wins
+            let heap_usage = self_memory_limit_mb.map(heap::usage);
+            let host_level = pressure_level(usage, mem);
+            let heap_level = heap_usage.map_or(MEM_PRESSURE_NORMAL, |u| pressure_level(u, mem));
+            let new_level = host_level.max(heap_level);
+            let usage = if heap_level > host_level { heap_usage.unwrap_or(usage) } else { usage };
+            let source = if heap_level > host_level { "own heap" } else { "host" };
+            
+            // Log level changes or periodic updates during pressure
+            if new_level != last_level || (new_level > MEM_PRESSURE_NORMAL && clock.now().saturating_duration_since(last_log_time) > Duration::from_secs(30)) {
+                match new_level {
+                    MEM_PRESSURE_EMERGENCY => warn!("🔴 EMERGENCY THROTTLE: {} {:.1}% - max delay, dropping 75% objects, skipping creates", source, usage * 100.0),
+                    MEM_PRESSURE_HEAVY => warn!("🟠 HEAVY THROTTLE: {} {:.1}% - long delay, dropping 50% objects", source, usage * 100.0),
+                    MEM_PRESSURE_LIGHT => warn!("🟡 LIGHT THROTTLE: {} {:.1}% - small delay, dropping 25% objects", source, usage * 100.0),
+                    _ => if last_level > MEM_PRESSURE_NORMAL {
+                        info!("🟢 Memory recovered: {:.1}% - resuming normal operation", usage * 100.0);
+                    },
+                }
+                last_level = new_level;
+                last_log_time = clock.now();
+            }
+            
+            // Quotas and pauses are handed out once per level change
+            memory_pressure_clone.transition(new_level, &stats_clone, &workers_clone, source, usage).await;
+            
+            // Check every 500ms for faster reaction to memory spikes
+            clock.sleep(Duration::from_millis(500)).await;
+        }
+    });
+
+    // What's left of --duration after the (possibly backdated) stats start, so resumed runs only do the rest
+    let deadline = stats.clock.now() + Duration::from_secs(args.duration).saturating_sub(stats.elapsed());
+
+    // Periodic state + journal checkpoints for --resume
+    let autosave_handle = args.autosave.clone().map(|path| {
+        let workers = workers.clone();
+        let stats = stats.clone();
+        let running = running.clone();
+        let interval = Duration::from_secs(args.autosave_interval.max(1));
+        let keys = !args.save_without_keys;
+        tokio::spawn(async move {
+            loop {
+                stats.clock.sleep(interval).await;
+                if !running.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Err(e) = autosave::checkpoint_run(&path, &workers, &stats, false, keys).await {
+                    warn!("Autosave failed: {}", e);
+                }
+            }
+        })
+    });
+    let mut handles = FuturesUnordered::new();
+
+    // Effective per-worker args (config file profiles applied)
+    let mut worker_profiles = BTreeMap::new();
+    let mut worker_args = Vec::with_capacity(workers.len());
+    let mut ptb_splits = BTreeMap::new();
+    for worker_id in 0..workers.len() {
+        let (mut args, profiles) = bench_config.worker_args(args, worker_id)?;
+        if let Some(capabilities) = &capabilities {
+            capabilities.restrict_worker(&mut args);
+        }
+        args.validate().context(format!("Worker {} (profiles: {})", worker_id, profiles.join("+")))?;
+        if let Some(split) = ptb_limits.as_ref().and_then(|limits| limits.split(&mut args)) {
+            warn!("Worker {}: a batch of {} objects exceeds the PTB limits; splitting it into {} transactions of {} (rate x{})",
+                worker_id, split.requested, split.parts, split.batch_size, split.parts);
+            ptb_splits.insert(worker_id, split);
+        }
+        if !profiles.is_empty() {
+            info!("Worker {}: profile {} (create {}%, blobs {}, batch {})",
+                worker_id, profiles.join("+"), args.create_pct, args.use_blobs, args.batch_size);
+            worker_profiles.insert(worker_id, profiles);
+        }
+        worker_args.push(args);
+    }
+
+    let executor: Arc<dyn executor::TxExecutor> = match &client {
+        Some(client) => Arc::new(client.clone()),
+        None => Arc::new(mock::MockBackend::new(args)),
+    };
+
+    // Spawn worker tasks (clone worker refs so we can still access them after benchmark).
+    // Tracked objects and gas live in the shared WorkerState, so a restarted
+    // worker picks up exactly where the dead one left off.
+    let ramp_step = Duration::from_secs(args.worker_ramp_secs) / workers.len().max(1) as u32;
+    if args.worker_ramp_secs > 0 {
+        info!("Ramping up {} workers over {}s (one every {:.0}ms)", workers.len(), args.worker_ramp_secs, ramp_step.as_secs_f64() * 1000.0);
+    }
+    let spawn_worker = |worker_id: usize, restart: bool| {
+        let client = client.clone();
+        let args = worker_args[worker_id].clone();
+        let stats = stats.clone();
+        let running = running.clone();
+        let semaphore = semaphore.clone();
+        let memory_pressure = memory_pressure.clone();
+        let recorder = recorder.clone();
+        let hot = hot.clone();
+        let workloads = workloads.clone();
+        let executor = executor.clone();
+        let package_id = worker_packages[worker_id];
+        let worker = workers[worker_id].clone();  // Clone the Arc
+
+        let handle = tokio::spawn(async move {
+            if restart {
+                stats.clock.sleep(WORKER_RESTART_DELAY).await;
+            } else if !ramp_step.is_zero() {
+                // Staggered start (--worker-ramp-secs); worker 0 starts right away
+                stats.clock.sleep(ramp_step * worker_id as u32).await;
+            }
+            run_worker(
+                client,
+                worker,
+                package_id,
+                args,
+                stats,
+                running,
+                semaphore,
+                deadline,
+                cached_rgp,
+                memory_pressure,
+                recorder,
+                hot,
+                workloads,
+                executor,
+            ).await
+        });
+        async move { (worker_id, handle.await) }
+    };
+    for worker_id in 0..workers.len() {
+        handles.push(spawn_worker(worker_id, false));
+    }
+
+    // Supervise workers: respawn any that die before the run is over
+    let mut restarts = vec![0u32; workers.len()];
+    while let Some((worker_id, result)) = handles.next().await {
+        let cause = match result {
+            Ok(Ok(())) => continue,
+            Ok(Err(e)) => format!("returned error: {:#}", e),
+            Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+            Err(e) => format!("task failed: {}", e),
+        };
+        if !running.load(Ordering::Relaxed) || stats.clock.now() >= deadline {
+            warn!("Worker {} {} (run already ending)", worker_id, cause);
+            continue;
+        }
+        if restarts[worker_id] >= MAX_WORKER_RESTARTS {
+            error!("Worker {} {} - restart limit ({}) reached, continuing without it",
+                worker_id, cause, MAX_WORKER_RESTARTS);
+            continue;
+        }
+        restarts[worker_id] += 1;
+        error!("Worker {} {} - restarting ({}/{})", worker_id, cause, restarts[worker_id], MAX_WORKER_RESTARTS);
+        handles.push(spawn_worker(worker_id, true));
+    }
+
+    // Past the deadline the workers were only finishing transactions already in flight
+    let workers_done = stats.clock.now();
+    let load_end = workers_done.min(deadline);
+    phases.span("benchmark", benchmark_start, load_end);
+    phases.span("drain", load_end, workers_done);
+
+    // Stop stats reporter
+    running.store(false, Ordering::Relaxed);
+    let run_end = timeline::Timestamp::now(&stats);
+    let nvme_end = nvme::capture_all(&args.nvme_devices, &stats);
+    if let Some(progress) = progress_bar {
+        progress.finish();
+    }
+    if let Some(handle) = autosave_handle {
+        handle.abort();
+    }
+    if let Some(path) = &args.autosave {
+        let checkpoint_start = Instant::now();
+        // A run stopped by its gas cap is as finished as one that reached its deadline
+        let completed = stats.clock.now() >= deadline || stats.gas_cap_reached(args.max_gas_spend);
+        autosave::checkpoint_run(path, &workers, &stats, completed, !args.save_without_keys).await?;
+        info!("Run state checkpointed to {}{}", path, if completed { " (complete)" } else { "" });
+        phases.record("checkpoint", checkpoint_start);
+    }
+    let report_start = Instant::now();
+
+    // Final report
+    info!("");
+    info!("═══════════════════════════════════════════════════════════════");
+    info!("  BENCHMARK COMPLETE");
+    info!("═══════════════════════════════════════════════════════════════");
+    info!("{}", stats.report());
+    if let (Some(writer), Some(path)) = (&hdr_writer, &args.hdr_log) {
+        let interval = stats.latency.take_interval();
+        writer.lock().unwrap().write_interval(run_end.unix_ms, &interval)?;
+        info!("Latency interval log written to {}", path);
+    }
+    for (op, h) in stats.latency.by_op() {
+        let l = h.summary();
+        info!("Latency {:<12} n={} p50={:.1}ms p90={:.1}ms p99={:.1}ms max={:.1}ms",
+            op, l.count, l.p50_ms, l.p90_ms, l.p99_ms, l.max_ms);
+    }
+
+    let checkpoint_report = match checkpoint_handle {
+        Some(handle) => match handle.await? {
+            Ok(report) => {
+                info!("{}", report.summary_line());
+                Some(report)
+            }
+            Err(e) => {
+                warn!("Checkpoint sampler failed: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let block_devices = match blockdev_handle {
+        Some(handle) => {
+            let reports = handle.await?;
+            for (dev, r) in &reports {
+                info!("Block device {}: {:.1} MiB written ({} IOs), {:.1} MiB read ({} IOs)",
+                    dev, r.write_bytes as f64 / (1024.0 * 1024.0), r.write_ios,
+                    r.read_bytes as f64 / (1024.0 * 1024.0), r.read_ios);
+            }
+            Some(reports)
+        }
+        None => None,
+    };
+
+    let fdp = match fdp_handle {
+        Some(handle) => {
+            let reports = handle.await?;
+            for (dev, r) in &reports {
+                info!("FDP {}: {:.1} MiB host, {:.1} MiB media written, WAF {}",
+                    dev, r.host_bytes_written as f64 / (1024.0 * 1024.0),
+                    r.media_bytes_written as f64 / (1024.0 * 1024.0),
+                    r.waf.map_or("n/a".to_string(), |w| format!("{:.3}", w)));
+            }
+            Some(reports)
+        }
+        None => None,
+    };
+
+    let adaptive_report = match adaptive_handle {
+        Some(handle) => Some(handle.await?),
+        None => None,
+    };
+
+    let depth_report = match depth_handle {
+        Some(handle) => {
+            let report = handle.await?;
+            if let (Some(peak), Some(knee)) = (report.peak_tps_inflight, report.knee_inflight) {
+                info!("In-flight sweep: peak TPS at depth {}, knee at depth {}", peak, knee);
+            }
+            Some(report)
+        }
+        None => None,
+    };
+
+    let address_stages = match stages_handle {
+        Some(handle) => {
+            let stages = handle.await?;
+            for stage in &stages {
+                info!("Stage {} ({} addresses): {:.1} TPS, p50 {:.1}ms p99 {:.1}ms",
+                    stage.stage, stage.addresses, stage.tps, stage.latency.p50_ms, stage.latency.p99_ms);
+            }
+            Some(stages)
+        }
+        None => None,
+    };
+
+    let epoch_segments = match epoch_handle {
+        Some(handle) => handle.await?,
+        None => Vec::new(),
+    };
+    if epoch_segments.len() > 1 {
+        info!("Run spanned {} epochs ({}-{})", epoch_segments.len(),
+            epoch_segments[0].epoch, epoch_segments[epoch_segments.len() - 1].epoch);
+    }
+
+    let stall_report = match stall_handle {
+        Some(handle) => {
+            let report = handle.await?;
+            info!("Write stalls: {} windows, {:.0}s total ({:.1}% of the run), max depth {:.0}%",
+                report.windows.len(), report.total_stall_secs, report.stall_fraction * 100.0, report.max_depth * 100.0);
+            Some(report)
+        }
+        None => None,
+    };
+
+    let rpc_probe = match probe_handle {
+        Some(handle) => {
+            let report = handle.await?;
+            info!("RPC probe: p50={:.1}ms p99={:.1}ms max={:.1}ms ({} failures)",
+                report.latency.p50_ms, report.latency.p99_ms, report.latency.max_ms, report.failures);
+            Some(report)
+        }
+        None => None,
+    };
+
+    let effects_verification = match verify_handle {
+        Some(handle) => {
+            let report = handle.await?;
+            if report.missing + report.diverged > 0 {
+                warn!("Effects verification: {} of {} sampled transactions missing, {} diverged",
+                    report.missing, report.verified, report.diverged);
+            } else {
+                info!("Effects verification: all {} sampled transactions identical", report.verified);
+            }
+            Some(report)
+        }
+        None => None,
+    };
+
+    let pruning = match pruning_handle {
+        Some(handle) => {
+            let report = handle.await?;
+            match report.checkpoints_pruned {
+                Some(n) => info!("Pruning: lowest available checkpoint advanced by {} during the run", n),
+                None => warn!("Pruning: lowest available checkpoint could not be observed"),
+            }
+            Some(report)
+        }
+        None => None,
+    };
+
+    let rocksdb = match rocksdb_handle {
+        Some(handle) => Some(handle.await?),
+        None => None,
+    };
+
+    let soak_report = match soak_handle {
+        Some(handle) => Some(handle.await?),
+        None => None,
+    };
+
+    let hooks_interval = match interval_hooks_handle {
+        Some(handle) => handle.await?,
+        None => Vec::new(),
+    };
+    let hooks_after = hooks::run_all(&args.exec_after, "after", &stats, hook_timeout).await;
+
+    let elapsed = stats.elapsed().as_secs_f64();
+    let mut intervals = timeline.samples();
+    if let Some(report) = &stall_report {
+        stalls::annotate(&mut intervals, &report.windows);
+    }
+    let pacing = pacing::annotate(&mut intervals);
+    let slo_report = slo_tracker.map(|tracker| {
+        tracker.annotate(&mut intervals);
+        let report = tracker.report();
+        info!("SLO: {}/{} intervals met, {:.1}s of {:.1}s out of SLO ({:.2}% compliance)",
+            report.intervals_met, report.intervals, report.out_of_slo_secs, report.evaluated_secs, report.compliance * 100.0);
+        for (objective, misses) in &report.by_objective {
+            warn!("SLO: {} missed in {} intervals ({:.1}s), worst {:.2}", objective, misses.intervals, misses.secs, misses.worst);
+        }
+        report
+    });
+    if let Some(report) = &pacing {
+        info!("Pacing: {:.1} of {:.1} requested TPS, mean error {:.1}%; {}/{} intervals on target, {} generator-saturated, {} node-rejected",
+            report.achieved_tps, report.requested_tps, report.mean_abs_error * 100.0,
+            report.on_target, report.paced_intervals, report.generator_saturated, report.node_rejected);
+        if report.generator_saturated > 0 {
+            warn!("Pacing: the client fell short of --target-tps in {} intervals with few failures; the numbers reflect the generator, not the node",
+                report.generator_saturated);
+        }
+    }
+    let config = results::RunConfig {
+        workers: args.workers,
+        batch_size: args.batch_size,
+        create_pct: args.create_pct.get(),
+        max_inflight: args.max_inflight,
+        target_tps: args.target_tps,
+        target_write_bps: args.target_write_bps,
+        use_blobs: args.use_blobs,
+        update_bytes: args.update_bytes,
+        append_bytes: args.append_bytes,
+        simulate: args.simulate,
+        hot_objects: args.hot_objects,
+        hot_skew: args.hot_skew,
+        hot_pct: args.hot_pct.get(),
+        freeze_pct: args.freeze_pct.get(),
+        freeze_interval: args.freeze_interval,
+        frozen_read_pct: args.frozen_read_pct.get(),
+        wave_interval: args.wave_interval,
+        wave_pct: args.wave_pct.get(),
+        gas_smash: args.gas_smash,
+        size_distribution: args.size_distribution.as_ref().map(|d| format!("{:?}", d)),
+    };
+    let mut result = results::BenchResult {
+        schema_version: results::SCHEMA_VERSION,
+        duration_secs: elapsed,
+        run_start,
+        run_end,
+        intervals,
+        tx_submitted: stats.tx_submitted.get(),
+        tx_success: stats.tx_success.get(),
+        tx_failed: stats.tx_failed.get(),
+        objects_created: stats.objects_created.get(),
+        objects_updated: stats.objects_updated.get(),
+        tps: stats.tx_success.get() as f64 / elapsed,
+        gas_spent_mist: stats.gas_spent.get(),
+        per_worker: stats.per_worker(),
+        worker_restarts: restarts.iter().sum::<u32>(),
+        worker_restarts_by_worker: restarts,
+        lock_conflicts: stats.lock_conflicts.load(Ordering::Relaxed),
+        objects_quarantined: stats.objects_quarantined.load(Ordering::Relaxed),
+        gas_repairs: stats.gas_repairs.load(Ordering::Relaxed),
+        tx_duplicates: stats.tx_duplicates.load(Ordering::Relaxed),
+        objects_frozen: stats.objects_frozen.load(Ordering::Relaxed),
+        objects_read: stats.objects_read.load(Ordering::Relaxed),
+        gas_cap_reached: stats.gas_cap_reached(args.max_gas_spend),
+        config,
+        sections: BTreeMap::new(),
+    };
+
+    if let Some(preset) = args.profile {
+        result.section("profile", presets::PresetReport::of(preset))?;
+    }
+
+    if !bench_config.workers.is_empty() {
+        result.section("worker_profiles", results::WorkerProfiles {
+            profiles: &bench_config.workers,
+            assignment: worker_profiles,
+        })?;
+    }
+
+    if !bench_config.tenants.is_empty() {
+        let packages: Vec<String> = worker_packages.iter().map(|p| p.to_string()).collect();
+        let reports = tenants::report(&bench_config, &stats, &packages, elapsed)?;
+        for tenant in &reports {
+            info!("Tenant {}: {} workers, {:.1} TPS (target {}), p99 {:.1}ms",
+                tenant.name, tenant.workers.len(), tenant.tps,
+                tenant.target_tps.map_or("-".to_string(), |t| t.to_string()), tenant.latency.p99_ms);
+        }
+        result.section("tenants", reports)?;
+    }
+
+    result.section("latency", stats.latency.to_json())?;
+    if args.wave_interval > 0 {
+        let report = stats.waves.report(args.wave_interval, args.wave_pct.get());
+        info!("Compaction waves: {} | p99 in wave {:.1}ms vs {:.1}ms outside",
+            report.waves.len(), report.latency_in_wave.p99_ms, report.latency_outside_wave.p99_ms);
+        result.section("waves", report)?;
+    }
+    if args.gas_smash > 1 {
+        let (mut merged, mut refills, mut left) = (0u64, 0u64, 0usize);
+        for worker in &workers {
+            let state = worker.read().await;
+            merged += state.smash_merged;
+            refills += state.smash_refills;
+            left += state.smash_coins.len();
+        }
+        info!("Gas smashing: {} fragments merged by gas payments, {} refills, {} left", merged, refills, left);
+        result.section("gas_smash", results::GasSmashSummary {
+            coins_per_tx: args.gas_smash,
+            refill: args.gas_smash_refill,
+            fragments_merged: merged,
+            refills,
+            fragments_left: left,
+        })?;
+    }
+    if args.gas_streams {
+        let streams = gas_streams::report(&workers).await;
+        for stream in &streams {
+            info!("Gas stream {:?}: {} transactions on {} coins", stream.stream, stream.transactions, stream.coins.len());
+        }
+        result.section("gas_streams", streams)?;
+    }
+    if let Some(hot) = &hot {
+        let report = hot.report();
+        info!("Hotspot: {} shared updates over {} objects, top object {:.1}%, effective objects {:.1}",
+            report.updates, report.hot_objects, report.top_share * 100.0, report.effective_objects);
+        result.section("hotspot", report)?;
+    }
+    let queue = stats.inflight.summary(args.workers, stats.elapsed());
+    info!("Queue: peak {} in flight (max {}), mean permit wait {:.2}ms ({:.1}% of worker time)",
+        queue.peak_inflight, args.max_inflight, queue.mean_semaphore_wait_ms, queue.semaphore_wait_fraction * 100.0);
+    result.section("queue", queue)?;
+    let charged = stats.gas_charged.get();
+    if charged > 0 {
+        let (rebate, net) = (stats.gas_rebated.get(), stats.net_gas());
+        let accounting = results::GasAccounting {
+            charged_mist: charged,
+            storage_rebate_mist: rebate,
+            net_mist: net,
+            rebate_fraction: rebate as f64 / charged as f64,
+            net_mist_per_tx: net as f64 / stats.tx_success.get().max(1) as f64,
+            net_mist_per_sec: net as f64 / elapsed,
+        };
+        info!("Gas: {} MIST charged, {} rebated ({:.1}%), net {} MIST ({:.0} MIST/s)",
+            charged, rebate, accounting.rebate_fraction * 100.0, net, accounting.net_mist_per_sec);
+        result.section("gas_accounting", accounting)?;
+    }
+    if let Some(report) = pacing {
+        result.section("pacing", report)?;
+    }
+    if let Some(report) = slo_report {
+        result.section("slo", report)?;
+    }
+    if let Some(report) = adaptive_report {
+        result.section("adaptive_inflight", report)?;
+    }
+    if let Some(report) = depth_report {
+        result.section("inflight_sweep", report)?;
+    }
+    if let Some(plan) = stats.payload_tags.get() {
+        result.section("payload_tags", plan.report())?;
+    }
+    let mix_report = stats.mix.report(args.mix_schedule, args.mix_seed, args.create_pct);
+    info!("Mix: {:.2}% creates realized of {}% requested ({} creates, {} updates, {:?} schedule)",
+        mix_report.realized_create_pct, mix_report.requested_create_pct, mix_report.creates, mix_report.updates, args.mix_schedule);
+    result.section("mix", mix_report)?;
+    if let Some(limits) = ptb_limits {
+        result.section("ptb_limits", limits::PtbReport { limits, max_batch: limits.max_batch(args), splits: ptb_splits })?;
+    }
+
+    if let Some(fraction) = args.hot_fraction {
+        let (mut hot_objects, mut cold_objects) = (0, 0);
+        for worker in &workers {
+            let state = worker.read().await;
+            hot_objects += state.objects.len();
+            cold_objects += state.cold.len();
+        }
+        let report = stats.temperature.report(fraction.get(), hot_objects, cold_objects);
+        info!("Hot/cold: {} hot objects rewritten {:.1}x on average, {} cold; {:.1}% of bytes written went to the hot set",
+            report.hot_objects, report.rewrites_per_hot_object, report.cold_objects, report.hot_byte_share * 100.0);
+        result.section("hot_cold", report)?;
+    }
+    if let Some(hot_pct) = args.create_hot_pct {
+        let mut splits = Vec::with_capacity(workers.len());
+        for worker in &workers {
+            let state = worker.read().await;
+            if let Some(split) = &state.partition {
+                splits.push((split.clone(), state.objects.len(), state.cold.len()));
+            }
+        }
+        let report = stats.partition.report(hot_pct.get(), args.cold_leak_pct.get(), &splits);
+        info!("Partition: {} hot / {} cold objects assigned; {:.1}% of updated objects were cold",
+            report.assigned_hot, report.assigned_cold, report.leaked_share * 100.0);
+        result.section("partition", report)?;
+    }
+
+    // How often each object was actually rewritten
+    {
+        let states = futures::future::join_all(workers.iter().map(|w| w.read())).await;
+        let rows = || states.iter().enumerate().flat_map(|(worker, state)| {
+            let lists = [("tracked", &state.objects), ("cold", &state.cold), ("frozen", &state.frozen)];
+            lists.into_iter()
+                .flat_map(move |(list, objects)| objects.iter().map(move |obj| (worker, list, obj)))
+                .chain(state.quarantine.iter().map(move |q| (worker, "quarantined", &q.object)))
+        });
+        let mut report = churn::report(rows().map(|(_, _, obj)| obj.updates).collect());
+        info!("Update counts: {} objects, mean {:.1} updates, max {}, {} never updated; top 10% took {:.1}% of updates",
+            report.objects, report.mean_updates, report.max_updates, report.never_updated, report.top_decile_share * 100.0);
+        if let Some(path) = &args.update_counts_csv {
+            match churn::write_csv(path, rows()) {
+                Ok(()) => {
+                    info!("Per-object update counts written to {}", path);
+                    report.csv = Some(path.clone());
+                }
+                Err(e) => warn!("Could not write update counts: {:#}", e),
+            }
+        }
+        result.section("update_counts", report)?;
+    }
+
+    let path_report = stats.paths.report();
+    if path_report.consensus.transactions > 0 {
+        info!("Consensus: {} tx p50 {:.1}ms p99 {:.1}ms; fast path: {} tx p50 {:.1}ms p99 {:.1}ms",
+            path_report.consensus.transactions, path_report.consensus.latency.p50_ms, path_report.consensus.latency.p99_ms,
+            path_report.fast_path.transactions, path_report.fast_path.latency.p50_ms, path_report.fast_path.latency.p99_ms);
+    }
+    result.section("consensus_paths", path_report)?;
+
+    let any_size_distribution = args.size_distribution.is_some()
+        || bench_config.workers.iter().any(|p| p.size_distribution.is_some());
+    if any_size_distribution {
+        result.section("size_buckets", stats.size_buckets.to_json())?;
+    }
+
+    // Where the growing blobs ended up
+    if let Some(append_bytes) = args.append_bytes.filter(|_| args.use_blobs) {
+        let mut sizes = Vec::new();
+        for worker in &workers {
+            sizes.extend(worker.read().await.objects.iter().map(|o| if o.size > 0 { o.size } else { BLOB_OBJECT_BYTES }));
+        }
+        let mean = if sizes.is_empty() { 0.0 } else { sizes.iter().sum::<u64>() as f64 / sizes.len() as f64 };
+        info!("Object growth: {} tracked blobs, mean {:.0} bytes, max {} bytes",
+            sizes.len(), mean, sizes.iter().max().unwrap_or(&0));
+        result.section("object_growth", results::ObjectGrowth {
+            append_bytes,
+            max_object_bytes: args.max_object_bytes,
+            tracked_objects: sizes.len(),
+            mean_bytes: mean,
+            max_bytes: sizes.iter().max().copied(),
+        })?;
+    }
+
+    if let Some(report) = checkpoint_report {
+        result.section("checkpoints", report)?;
+    }
+
+    if let Some(reports) = block_devices {
+        result.section("block_devices", reports)?;
+    }
+
+    if !args.nvme_devices.is_empty() {
+        result.section("nvme", nvme::reports(nvme_start, nvme_end))?;
+    }
+
+    if let Some(reports) = fdp {
+        result.section("fdp", reports)?;
+    }
+
+    if let Some(limit_mb) = args.self_memory_limit_mb {
+        let heap = heap::HeapSummary::now(limit_mb);
+        info!("Own heap: peak {:.1} MB, {:.1} MB at end (limit {} MB)", heap.peak_mb, heap.end_mb, limit_mb);
+        result.section("self_memory", heap)?;
+    }
+
+    let throttle_report = stats.failure_window.report(&failure_rate::ThrottleSettings::from_args(args));
+    if throttle_report.slowdowns + throttle_report.pauses > 0 {
+        result.section("failure_throttle", throttle_report)?;
+    }
+
+    let pressure_report = memory_pressure.report();
+    if !pressure_report.transitions.is_empty() {
+        result.section("memory_pressure", pressure_report)?;
+    }
+
+    if args.backend == mock::Backend::Mock {
+        result.section("mock_backend", mock::MockSummary { latency: &args.mock_latency, errors: &args.mock_errors })?;
+    }
+
+    if !workloads.is_empty() {
+        let reports = workloads.report();
+        for report in &reports {
+            info!("Workload {} ({}%): {} ok, {} failed, {} objects",
+                report.name, report.pct, report.tx_success, report.tx_failed, report.objects);
+        }
+        if let Some(gas_only) = reports.iter().find(|r| r.name == "gas_only") {
+            let latency = stats.latency.by_op().get("gas_only").map(|h| h.summary()).unwrap_or_else(|| latency::Histogram::new().summary());
+            let written = stats.objects_created.get() + stats.objects_updated.get();
+            if let Some(baseline) = gas_only::baseline(gas_only, latency, stats.net_gas(), stats.tx_success.get(), written) {
+                info!("Gas baseline: {:.0} MIST net per gas-only tx (p50 {:.1}ms), {:.0} for the rest{}",
+                    baseline.baseline_net_mist, baseline.baseline_latency.p50_ms, baseline.other_net_mist,
+                    baseline.payload_net_mist_per_object.map_or(String::new(), |m| format!(", {:.0} per payload object", m)));
+                result.section("gas_baseline", baseline)?;
+            }
+        }
+        result.section("workloads", reports)?;
+    }
+
+    let config_changes = stats.live.changes();
+    if !config_changes.is_empty() {
+        result.section("config_changes", config_changes)?;
+    }
+
+    if let Some(stages) = address_stages {
+        result.section("address_stages", stages)?;
+    }
+
+    if args.epoch_poll_secs > 0 {
+        result.section("epochs", epoch_segments)?;
+    }
+
+    if args.outlier_ms.is_some() || args.outlier_factor.is_some() {
+        let report = stats.outliers.report(args.outlier_ms, args.outlier_factor);
+        info!("Outliers: {} captured, {} more not kept (threshold {})", report.captured, report.dropped,
+            report.threshold_ms.map_or("never reached".to_string(), |t| format!("{:.1}ms", t)));
+        result.section("outliers", report)?;
+    }
+
+    if let Some(report) = stall_report {
+        result.section("stalls", report)?;
+    }
+
+    if let Some(report) = rpc_probe {
+        result.section("rpc_probe", report)?;
+    }
+    if let Some(report) = effects_verification {
+        result.section("effects_verification", report)?;
+    }
+
+    if let Some(report) = pruning {
+        result.section("pruning", report)?;
+    }
+
+    if let Some(report) = rocksdb {
+        result.section("rocksdb", report)?;
+    }
+
+    if let Some(report) = soak_report {
+        info!("Soak: {} full days, {} refuels ({} failed), {} gas price refreshes, {} compactions",
+            report.days.len(), report.refuels, report.refuel_failures, report.rgp_refreshes, report.compactions);
+        result.section("soak", report)?;
+    }
+
+    if !(hooks_before.is_empty() && hooks_interval.is_empty() && hooks_after.is_empty()) {
+        result.section("hooks", results::HookRuns {
+            before: hooks_before,
+            interval: hooks_interval,
+            after: hooks_after,
+        })?;
+    }
+
+    let mut rotated = BTreeMap::new();
+    if let Some(recorder) = &recorder {
+        recorder.flush()?;
+        if let Some(path) = &args.tx_digests {
+            info!("Transaction digests appended to {}", path);
+        }
+        if let Some((records, truncated)) = recorder.inline_records() {
+            result.section("tx_digests", records)?;
+            result.section("tx_digests_truncated", truncated)?;
+        }
+        if let Some(report) = recorder.finish_segments()? {
+            rotated.insert("tx_digests", report);
+        }
+    }
+    if let Some(writer) = &hdr_writer {
+        if let Some(report) = writer.lock().unwrap().finish_segments()? {
+            rotated.insert("hdr_log", report);
+        }
+    }
+    if let Some(log) = &interval_log {
+        if let Some(report) = log.lock().unwrap().finish_segments()? {
+            rotated.insert("interval_log", report);
+        }
+    }
+    if !rotated.is_empty() {
+        result.section("log_rotation", rotated)?;
+    }
+
+    if let (Some(client), Some(start)) = (&client, db_start) {
+        let end = db_check::fingerprint(client, args.node_db.as_deref()).await
+            .map_err(|e| warn!("Node DB fingerprint at run end failed: {:#}", e))
+            .ok();
+        result.section("db_fingerprint", results::DbFingerprints { start, end })?;
+    }
+    if let Some(cache) = &meta_cache {
+        result.section("meta_cache", cache.report())?;
+    }
+    if let Some(side) = &args.lockstep {
+        result.section("lockstep", side.report())?;
+    }
+    if args.tx_expiration_epochs.is_some() || args.stale_after_ms.is_some() {
+        result.section("expiry", stats.expiry.report(args.tx_expiration_epochs, args.stale_after_ms))?;
+    }
+    if let Some(after) = args.batch_backoff_after {
+        result.section("batch_backoff", stats.batch_backoff.report(after, args.batch_regrow_after))?;
+    }
+    if let Some(report) = stats.command_failures.report() {
+        result.section("command_failures", report)?;
+    }
+    if let Some(capabilities) = &capabilities {
+        result.section("capabilities", capabilities.report())?;
+    }
+    if let Some(report) = compatibility {
+        result.section("compatibility", report)?;
+    }
+
+    if let Some(node) = managed_node {
+        result.section("node", node.stop().await?)?;
+    }
+    phases.record("report", report_start);
+
+    // Save objects to file if requested (for use in next phase)
+    if let Some(save_path) = &args.save_objects {
+        let save_start = Instant::now();
+        info!("Saving objects and keypairs to {}...", save_path);
+        let mut saved_state = autosave::snapshot_state(&workers, !args.save_without_keys).await;
+        if let Some(spill) = stats.spill.get() {
+            let mut dropped = spill.read()?;
+            let mut gone = 0;
+            if let (true, Some(client)) = (args.resolve_dropped, &client) {
+                (dropped, gone) = spill::resolve(client, dropped).await?;
+            }
+            let saved = spill::merge(&mut saved_state, dropped);
+            if saved > 0 {
+                info!("Including {} objects dropped from the tracker during the run", saved);
+            }
+            result.section("dropped_objects", spill.report(saved, args.resolve_dropped, gone))?;
+        }
+        autosave::write_state(save_path, &saved_state)?;
+        info!("Saved {} objects and {} worker keypairs to {}", saved_state.total_objects, workers.len(), save_path);
+        phases.record("save", save_start);
+    }
+
+    // End-of-run invariants (--check); a failed check fails the run once the results are written
+    let checks = if args.check.is_empty() {
+        None
+    } else {
+        let state = autosave::snapshot_state(&workers, false).await;
+        let report = checks::run(&args.check, &result, &state);
+        result.section("checks", &report)?;
+        Some(report)
+    };
+
+    // Everything but writing the results file itself
+    let phase_report = phases.report();
+    phase_report.log();
+    result.section("phases", phase_report)?;
+
+    // Write output file if requested
+    if let Some(output_path) = &args.output {
+        std::fs::write(output_path, serde_json::to_string_pretty(&result)?)?;
+        info!("Results written to {}", output_path);
+    }
+    if let Some(report) = checks {
+        report.into_result()?;
+    }
+
+    Ok(result)
+}
+
+/// Fund (or restore) the workers and create their seed objects on the node
+async fn setup_node_workers(
+    client: &SuiClient,
+    args: &Args,
+    worker_packages: &[ObjectID],
+    phases: &phases::Phases,
+    meta_cache: Option<&meta_cache::MetaCache>,
+) -> Result<Vec<Arc<RwLock<WorkerState>>>> {
+    let faucet = faucet::FaucetClient::new(&args.faucet_url, args.faucet_rate, !args.faucet_legacy);
+
+    // Initialize workers IN PARALLEL (much faster than sequential)
+    info!("Initializing {} workers in parallel...", args.workers);
+    let init_start = Instant::now();
+    
+    // Worker initialization depends on whether we're loading from previous phase
+    let mut workers = Vec::new();
+    
+    // --resume restores from the autosave file exactly like load mode
+    let load_path = if args.resume { args.autosave.as_ref() } else { args.load_objects.as_ref() };
+    if let Some(load_path) = load_path {
+        // ═══════════════════════════════════════════════════════════════════════════
+        // LOAD MODE: Restore workers from saved state (same keypairs = same ownership)
+        // ═══════════════════════════════════════════════════════════════════════════
+        info!("Loading workers and objects from {}...", load_path);
+        let load_start = Instant::now();
+        
+        let saved_state = load::read(load_path, args)?;
+        
+        info!("Found {} saved workers with {} total objects", 
+            saved_state.workers.len(), saved_state.total_objects);
+        
+        if let Some(count) = args.reshard_workers {
+            // New workers take over the objects on chain (fresh refs, no refresh needed)
+            workers = reshard::reshard(client, &faucet, saved_state, count, args.batch_size, args.gas_budget).await?;
+            info!("Re-sharded onto {} workers in {:.1}s", workers.len(), load_start.elapsed().as_secs_f64());
+            phases.record("load", load_start);
+            let faucet_start = Instant::now();
+            prepare_worker_gas(client, &workers, args).await?;
+            phases.record("faucet", faucet_start);
+            return Ok(workers);
+        }
+        
+        // Restore workers with their original keypairs, several at a time
+        workers = futures::stream::iter(saved_state.workers)
+            .map(|saved_worker| {
+                let faucet = &faucet;
+                async move {
+                    // Decode the keypair from base64
+                    let keypair = SuiKeyPair::decode_base64(&saved_worker.keypair_base64)
+                        .context(format!("Failed to decode keypair for worker {}", saved_worker.worker_id))?;
+                    
+                    // Request gas for this address (same address that owns the objects)
+                    let gas_coin = request_gas_from_faucet(client, faucet, saved_worker.address).await?;
+                    
+                    info!("Worker {}: restored with {} objects (address: {})", 
+                        saved_worker.worker_id, saved_worker.objects.len(), 
+                        &saved_worker.address.to_string()[..16]);
+                    
+                    Ok::<_, anyhow::Error>(Arc::new(RwLock::new(WorkerState::new(
+                        saved_worker.worker_id,
+                        saved_worker.address,
+                        keypair,
+                        gas_coin,
+                        saved_worker.objects,
+                    ))))
+                }
+            })
+            .buffered(load::CONCURRENCY)
+            .try_collect()
+            .await?;
+        
+        info!("Loaded {} workers in {:.1}s", workers.len(), load_start.elapsed().as_secs_f64());
+        
+        // Refresh object versions from chain (objects may have been updated since save)
+        info!("Refreshing object versions from chain...");
+        let refresh_start = Instant::now();
+        refresh_all_workers(client, &workers, meta_cache).await?;
+        info!("Object versions refreshed in {:.1}s", refresh_start.elapsed().as_secs_f64());
+        phases.record("load", load_start);
+
+        let faucet_start = Instant::now();
+        prepare_worker_gas(client, &workers, args).await?;
+        phases.record("faucet", faucet_start);
+        
+    } else {
+        // ═══════════════════════════════════════════════════════════════════════════
+        // FRESH MODE: Create new workers with random keypairs
+        // (or resume an interrupted seed phase from the autosave file)
+        // ═══════════════════════════════════════════════════════════════════════════
+        let resumed = match &args.autosave {
+            Some(path) if std::path::Path::new(path).exists() => {
+                let state = load::with_keys(autosave::read_state(path)?, args, path)?;
+                info!("Resuming seeding from {}: {} workers, {} objects already created",
+                    path, state.workers.len(), state.total_objects);
+                state.workers
+            }
+            _ => Vec::new(),
+        };
+
+        let cached_identities = match &args.identity_cache {
+            Some(path) => identity::load(path)?,
+            None => Vec::new(),
+        };
+        let keystore = args.keystore.as_deref().map(keystore::Keystore::load).transpose()?;
+
+        // (id, address, keypair, objects, cached identity)
+        let mut keypairs: Vec<(usize, SuiAddress, SuiKeyPair, Vec<TrackedObject>, bool)> = Vec::new();
+        for i in 0..args.workers {
+            if let Some(saved) = resumed.iter().find(|w| w.worker_id == i) {
+                let keypair = SuiKeyPair::decode_base64(&saved.keypair_base64)
+                    .context(format!("Failed to decode keypair for worker {}", i))?;
+                keypairs.push((i, saved.address, keypair, saved.objects.clone(), false));
+            } else if let Some(cached) = cached_identities.iter().find(|c| c.worker_id == i) {
+                let keypair = SuiKeyPair::decode_base64(&cached.keypair_base64)
+                    .context(format!("Failed to decode cached keypair for worker {}", i))?;
+                keypairs.push((i, cached.address, keypair, Vec::new(), true));
+            } else if let Some((address, keypair)) = keystore.as_ref().and_then(|k| k.identity(i)) {
+                // Known addresses keep their gas and objects like cached identities
+                keypairs.push((i, address, keypair, Vec::new(), true));
+            } else {
+                let (address, keypair): (SuiAddress, AccountKeyPair) = get_key_pair();
+                keypairs.push((i, address, SuiKeyPair::Ed25519(keypair), Vec::new(), false));
+            }
+        }
+        let reused = keypairs.iter().filter(|k| k.4).count();
+        if reused > 0 {
+            info!("Reusing {} cached or keystore worker identities", reused);
+        }
+        let min_reuse_balance = args.gas_budget * MIN_REUSE_BALANCE_BUDGETS;
+        let tracked_cap = args.tracked_objects_cap();
+        
+        // Request gas from faucet in parallel batches (to avoid overwhelming faucet)
+        let batch_size = 8; // Process 8 workers at a time
+        
+        for chunk in keypairs.chunks(batch_size) {
+            let mut faucet_futures = Vec::new();
+            for (i, address, keypair, objects, cached) in chunk {
+                let client = client.clone();
+                let faucet = &faucet;
+                let addr = *address;
+                let id = *i;
+                let kp = keypair.copy();
+                let mut objects = objects.clone();
+                let cached = *cached;
+                let package_id = worker_packages[id];
+                faucet_futures.push(async move {
+                    // Cached identities keep their gas and objects from earlier runs
+                    let existing = if cached {
+                        objects = identity::owned_bench_objects(&client, addr, package_id, tracked_cap, meta_cache).await?;
+                        identity::existing_gas(&client, addr, min_reuse_balance).await?
+                    } else {
+                        None
+                    };
+                    let gas_coin = match existing {
+                        Some(coin) => coin,
+                        None => request_gas_from_faucet(&client, faucet, addr).await?,
+                    };
+                    Ok::<_, anyhow::Error>((id, addr, kp, gas_coin, objects))
+                });
+            }
+            
+            // Execute batch in parallel
+            let results = futures::future::join_all(faucet_futures).await;
+            for result in results {
+                let (id, address, keypair, gas_coin, objects) = result?;
+                info!("Worker {}: ready", id);
+                workers.push(Arc::new(RwLock::new(WorkerState::new(id, address, keypair, gas_coin, objects))));
+            }
+        }
+        info!("Workers initialized in {:.1}s", init_start.elapsed().as_secs_f64());
+
+        if let Some(path) = &args.identity_cache {
+            let current = autosave::snapshot_state(&workers, true).await.workers.into_iter()
+                .map(|w| identity::CachedIdentity {
+                    worker_id: w.worker_id,
+                    address: w.address,
+                    keypair_base64: w.keypair_base64,
+                })
+                .collect();
+            identity::save(path, current, &cached_identities)?;
+            info!("Worker identities cached in {}", path);
+        }
+
+        let faucet_start = Instant::now();
+        prepare_worker_gas(client, &workers, args).await?;
+        phases.record("faucet", faucet_start);
+
+        if !resumed.is_empty() {
+            // Seeds from the interrupted run may have been touched since the autosave
+            refresh_all_workers(client, &workers, meta_cache).await?;
+        }
+
+        // Create seed objects for each worker IN PARALLEL (only what's still missing)
+        let mut remaining_total = 0usize;
+        let mut seed_counts = Vec::with_capacity(workers.len());
+        for worker in &workers {
+            let missing = args.seed_objects.saturating_sub(worker.read().await.objects.len());
+            remaining_total += missing;
+            seed_counts.push(missing);
+        }
+        info!("Creating seed objects ({} per worker, {} remaining) in parallel...",
+            args.seed_objects, remaining_total);
+        let seed_start = Instant::now();
+        let seeded = Arc::new(AtomicU64::new(0));
+        let seeding_done = Arc::new(AtomicBool::new(false));
+
+        // Progress + autosave reporter for the seed phase
+        let progress_handle = {
+            let seeded = seeded.clone();
+            let seeding_done = seeding_done.clone();
+            let workers = workers.clone();
+            let autosave_path = args.autosave.clone();
+            let keys = !args.save_without_keys;
+            tokio::spawn(async move {
+                while !seeding_done.load(Ordering::Relaxed) {
+                    sleep(Duration::from_secs(SEED_PROGRESS_INTERVAL_SECS)).await;
+                    if seeding_done.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let done = seeded.load(Ordering::Relaxed);
+                    let elapsed = seed_start.elapsed().as_secs_f64();
+                    let rate = if elapsed > 0.0 { done as f64 / elapsed } else { 0.0 };
+                    let eta = if rate > 0.0 { (remaining_total as u64).saturating_sub(done) as f64 / rate } else { 0.0 };
+                    info!("Seeding: {}/{} objects ({:.1} obj/s, ETA {:.0}s)", done, remaining_total, rate, eta);
+                    if let Some(path) = &autosave_path {
+                        if let Err(e) = autosave::save_workers(path, &workers, keys).await {
+                            warn!("Seed autosave failed: {}", e);
+                        }
+                    }
+                }
+            })
+        };
+
+        let mut seed_futures = Vec::new();
+        for (worker_id, (worker, count)) in workers.iter().zip(seed_counts).enumerate() {
+            let client = client.clone();
+            let package_id = worker_packages[worker_id];
+            let w = worker.clone();
+            let seeded = seeded.clone();
+            seed_futures.push(async move {
+                create_seed_objects(&client, w, package_id, count, args.gas_budget, &seeded).await
+            });
+        }
+        // Execute all seed creations in parallel
+        let seed_results = futures::future::join_all(seed_futures).await;
+        seeding_done.store(true, Ordering::Relaxed);
+        progress_handle.abort();
+
+        // Checkpoint whatever was created, even if some worker failed
+        if let Some(path) = &args.autosave {
+            autosave::save_workers(path, &workers, !args.save_without_keys).await?;
+            info!("Seed state saved to {}", path);
+        }
+        for result in seed_results {
+            result?;
+        }
+        info!("Seed objects created in {:.1}s", seed_start.elapsed().as_secs_f64());
+        phases.record("seeding", seed_start);
+    }
+
+    Ok(workers)
+}
+
+/// Merge each worker's coins into one, then split into --gas-coins-per-worker coins (in parallel)
+async fn prepare_worker_gas(client: &SuiClient, workers: &[Arc<RwLock<WorkerState>>], args: &Args) -> Result<()> {
+    let smash = args.gas_smash > 1;
+    if args.no_gas_consolidation && args.gas_coins_per_worker <= 1 && !smash && !args.gas_streams {
+        return Ok(());
+    }
+    let rgp = client.governance_api().get_reference_gas_price().await.unwrap_or(1000);
+    let prepare = workers.iter().map(|w| async move {
+        // Gas smashing consumes the extra coins as it goes instead
+        if !args.no_gas_consolidation && !smash {
+            gas_pool::consolidate_gas(client, w, args.gas_budget, rgp).await?;
+        }
+        gas_pool::split_gas_coin(client, w, args.gas_coins_per_worker, args.gas_budget, rgp).await?;
+        if args.gas_streams {
+            gas_streams::split(client, w, args.gas_budget, rgp).await?;
+        }
+        if smash {
+            w.write().await.gas_smash = args.gas_smash;
+            gas_pool::collect_fragments(client, w).await?;
+        }
+        Ok::<_, anyhow::Error>(())
+    });
+    for result in futures::future::join_all(prepare).await {
+        result?;
+    }
+    Ok(())
+}
+
+/// Request gas from the faucet and return the worker's largest coin
+async fn request_gas_from_faucet(client: &SuiClient, faucet: &faucet::FaucetClient, address: SuiAddress) -> Result<ObjectRef> {
+    if let Err(e) = faucet.request(address).await {
+        warn!("All faucet attempts failed for {} ({}), checking existing coins...", address, e);
+    }
+
+    // Retry getting coins with exponential backoff
+    for attempt in 1..=5 {
+        let coins = client
+            .coin_read_api()
+            .get_coins(address, None, None, None)
+            .await
+            .context("Failed to get coins")?;
+
+        if let Some(coin) = coins.data.into_iter().max_by_key(|c| c.balance) {
+            info!("Got gas coin for {}: {} (balance: {})", address, coin.coin_object_id, coin.balance);
+            return Ok((coin.coin_object_id, coin.version, coin.digest));
+        }
+        
+        if attempt < 5 {
+            let delay = Duration::from_millis(250 * (1 << attempt)); // exponential backoff
+            debug!("No coins found for {} (attempt {}), retrying in {:?}...", address, attempt, delay);
+            sleep(delay).await;
+        }
+    }
+
+    Err(anyhow!("No gas coins found for address {} after multiple retries", address))
+}
+
+/// Create initial seed objects for a worker
+///
+/// Batches are pipelined across the worker's gas coins (primary + gas_pool),
+/// one in-flight batch per coin; with a single coin this is sequential.
+async fn create_seed_objects(
+    client: &SuiClient,
+    worker: Arc<RwLock<WorkerState>>,
+    package_id: ObjectID,
+    count: usize,
+    gas_budget: u64,
+    progress: &AtomicU64,
+) -> Result<()> {
+    if count == 0 {
+        return Ok(());
+    }
+    let batch_size = 100; // Create in batches
+    let batches: Vec<usize> = (0..count).step_by(batch_size)
+        .map(|start| (count - start).min(batch_size))
+        .collect();
+
+    let (address, keypair, coins) = {
+        let mut state = worker.write().await;
+        let mut coins = vec![state.gas_coin];
+        coins.append(&mut state.gas_pool);
+        (state.address, state.keypair.copy(), coins)
+    };
+    let depth = coins.len();
+    let coin_pool = std::sync::Mutex::new(coins);
+
+    // Get reference gas price
+    let rgp = client
+        .governance_api()
+        .get_reference_gas_price()
+        .await
+        .unwrap_or(1000);
+
+    let result = futures::stream::iter(batches.into_iter().map(Ok))
+        .try_for_each_concurrent(depth, |batch| {
+            let worker = worker.clone();
+            let keypair = &keypair;
+            let coin_pool = &coin_pool;
+            async move {
+                let gas_coin = coin_pool.lock().unwrap().pop()
+                    .ok_or_else(|| anyhow!("No free gas coin for seed batch"))?;
+                let outcome = execute_seed_batch(client, address, keypair, gas_coin, package_id, batch, gas_budget, rgp).await;
+                let (gas_coin, created) = match outcome {
+                    Ok((gas_coin, created)) => (gas_coin, Ok(created)),
+                    Err(e) => (gas_coin, Err(e)),
+                };
+                coin_pool.lock().unwrap().push(gas_coin);
+                let created = created?;
+
+                let mut state = worker.write().await;
+                for obj in created {
+                    state.track_created(obj);
+                }
+                progress.fetch_add(batch as u64, Ordering::Relaxed);
+                debug!("Worker {}: created {} seed objects, total: {}", state.id, batch, state.objects.len());
+                Ok::<_, anyhow::Error>(())
+            }
+        })
+        .await;
+
+    // Hand the (updated) coins back to the worker
+    let mut coins = coin_pool.into_inner().unwrap();
+    let mut state = worker.write().await;
+    state.gas_coin = coins.remove(0);
+    state.gas_pool = coins;
+    result
+}
+
+/// Execute one create_batch seed transaction; returns the updated gas coin and created objects
+#[allow(clippy::too_many_arguments)]
+async fn execute_seed_batch(
+    client: &SuiClient,
+    address: SuiAddress,
+    keypair: &SuiKeyPair,
+    gas_coin: ObjectRef,
+    package_id: ObjectID,
+    batch: usize,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<(ObjectRef, Vec<TrackedObject>)> {
+    // Build create_batch transaction
+    let mut builder = ProgrammableTransactionBuilder::new();
+    // Must call pure() before programmable_move_call to avoid borrow conflict
+    let batch_arg = builder.pure(batch as u64).unwrap();
+    builder.programmable_move_call(
+        package_id,
+        Identifier::new("io_churn").unwrap(),
+        Identifier::new("create_batch").unwrap(),
+        vec![],
+        vec![batch_arg],
+    );
+
+    let pt = builder.finish();
+
+    let tx_data = TransactionData::new_programmable(
+        address,
+        vec![gas_coin],
+        pt,
+        gas_budget,
+        rgp,
+    );
+
+    // Sign and create transaction using Transaction::from_data_and_signer
+    let tx = Transaction::from_data_and_signer(
+        tx_data,
+        vec![keypair],
+    );
+
+    let response = client
+        .quorum_driver_api()
+        .execute_transaction_block(
+            tx,
+            SuiTransactionBlockResponseOptions::new()
+                .with_effects()
+                .with_object_changes(),
+            Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+        )
+        .await
+        .context("Failed to execute create_batch")?;
+
+    let mut gas_coin = gas_coin;
+    let mut created = Vec::new();
+
+    // Update gas coin
+    if let Some(effects) = &response.effects {
+        let gas_obj = effects.gas_object();
+        gas_coin = (gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest);
+
+        // Track created objects
+        if let Some(changes) = &response.object_changes {
+            for change in changes {
+                if let sui_sdk::rpc_types::ObjectChange::Created { object_id, version, digest, .. } = change {
+                    created.push(TrackedObject {
+                        id: *object_id,
+                        version: version.value(),
+                        digest: *digest,
+                        size: 0,
+                        origin: Some(lookup::ObjectOrigin::new(lookup::OriginOp::Seed, response.digest)),
+                        updates: 0,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok((gas_coin, created))
+}
+
+/// Refresh every worker's objects, several workers at a time
+async fn refresh_all_workers(client: &SuiClient, workers: &[Arc<RwLock<WorkerState>>], meta_cache: Option<&meta_cache::MetaCache>) -> Result<()> {
+    futures::stream::iter(workers)
+        .map(|worker| refresh_worker_objects(client, worker.clone(), meta_cache))
+        .buffer_unordered(load::CONCURRENCY)
+        .try_collect::<Vec<()>>()
+        .await?;
+    Ok(())
+}
+
+/// Refresh object versions from chain (needed when loading objects from previous phase)
+async fn refresh_worker_objects(
+    client: &SuiClient,
+    worker: Arc<RwLock<WorkerState>>,
+    meta_cache: Option<&meta_cache::MetaCache>,
+) -> Result<()> {
+    let mut state = worker.write().await;
+    
+    if state.objects.is_empty() {
+        return Ok(());
+    }
+    
+    // Query objects in batches to get current versions
+    let batch_size = 50;
+    let mut refreshed_objects = Vec::new();
+    
+    for chunk in state.objects.chunks(batch_size) {
+        let object_ids: Vec<ObjectID> = chunk.iter().map(|o| o.id).collect();
+        // Owner and type only for objects the cache doesn't already vouch for
+        let (known, unknown) = match meta_cache {
+            Some(cache) => cache.partition(&object_ids, state.address),
+            None => (Vec::new(), object_ids),
+        };
+        let bare = sui_sdk::rpc_types::SuiObjectDataOptions::new();
+        let full = sui_sdk::rpc_types::SuiObjectDataOptions::new().with_owner().with_type();
+        let mut fresh = std::collections::HashMap::with_capacity(chunk.len());
+        
+        for (ids, options) in [(known, bare), (unknown, full)] {
+            if ids.is_empty() {
+                continue;
+            }
+            let response = client
+                .read_api()
+                .multi_get_object_with_options(ids.clone(), options)
+                .await
+                .context("Failed to query objects")?;
+            
+            for (id, obj_response) in ids.iter().zip(response) {
+                let Some(data) = obj_response.data else {
+                    if let Some(cache) = meta_cache {
+                        cache.forget(id);
+                    }
+                    continue;
+                };
+                if let Some(cache) = meta_cache {
+                    if data.owner.is_some() && !cache.record(&data, state.address) {
+                        continue;
+                    }
+                }
+                let saved = chunk.iter().find(|o| o.id == data.object_id);
+                fresh.insert(data.object_id, TrackedObject {
+                    id: data.object_id,
+                    version: data.version.value(),
+                    digest: data.digest,
+                    // Sizes and origins aren't on chain metadata; keep what was saved
+                    size: saved.map_or(0, |o| o.size),
+                    origin: saved.and_then(|o| o.origin.clone()),
+                    updates: 0,
+                });
+            }
+        }
+        // Keep the saved order
+        refreshed_objects.extend(chunk.iter().filter_map(|o| fresh.remove(&o.id)));
+    }
+    
+    let old_count = state.objects.len();
+    let new_count = refreshed_objects.len();
+    
+    state.objects = refreshed_objects;
+    
+    if new_count < old_count {
+        debug!("Worker {}: refreshed {} objects ({} no longer exist or aren't its own)", 
+            state.id, new_count, old_count - new_count);
+    } else {
+        debug!("Worker {}: refreshed {} objects", state.id, new_count);
+    }
+    
+    Ok(())
+}
+
+/// Run a single worker
+#[allow(clippy::too_many_arguments)]
+async fn run_worker(
+    client: Option<SuiClient>,
+    worker: Arc<RwLock<WorkerState>>,
+    package_id: ObjectID,
+    mut args: Args,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    semaphore: Arc<Semaphore>,
+    deadline: Instant,
+    cached_rgp: u64,
+    memory_pressure: Arc<pressure::PressureCoordinator>,
+    recorder: Option<Arc<tx_log::DigestRecorder>>,
+    hot: Option<Arc<hotspot::HotSet>>,
+    workloads: Arc<workload::WorkloadMix>,
+    executor: Arc<dyn executor::TxExecutor>,
+) -> Result<()> {
+    let worker_id = worker.read().await.id;
+    // Use StdRng which is Send (unlike thread_rng)
+    let mut rng = match args.mix_seed {
+        Some(seed) => rand::rngs::StdRng::seed_from_u64(seed.wrapping_add(worker_id as u64)),
+        None => rand::rngs::StdRng::from_entropy(),
+    };
+    let mut create_scheduler = mix::CreateScheduler::new(args.mix_schedule);
+    let partial = args.update_bytes
+        .filter(|bytes| *bytes < BLOB_OBJECT_BYTES)
+        .map(|bytes| PartialUpdate { bytes, random_offset: args.update_offset == "random" });
+    let append = args.append_bytes.map(|bytes| AppendUpdate { bytes, max_size: args.max_object_bytes });
+    let create_blobs = ops::CreateBlobs { size_dist: args.size_distribution.clone() };
+    // Mixed-size populations must keep each blob's size on full rewrites
+    let update_blobs = ops::UpdateBlobs { partial, append, same_size: args.size_distribution.is_some() };
+    let gas_budgets = workload::GasBudgets::of(&args);
+    let tags = stats.payload_tags.get().and_then(|plan| plan.worker(worker_id));
+    let contract = args.contract_version.unwrap_or(abi::LATEST);
+    let mut pace = args.lockstep.as_ref().map(|side| side.pace(worker_id, args.workers));
+    // Validate the cached gas ref up front (restarted workers may hold a stale one)
+    if let Some(client) = &client {
+        match failures::repair_gas_coin(client, &worker).await {
+            Ok(true) => {
+                stats.gas_repairs.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(false) => {}
+            Err(e) => debug!("Worker {}: gas coin pre-validation failed: {}", worker_id, e),
+        }
+    }
+    {
+        let mut state = worker.write().await;
+        state.spill = stats.spill.get().cloned();
+        let excess = state.limit_tracked(args.eviction_policy, args.tracked_objects_cap());
+        if excess > 0 {
+            debug!("Worker {}: {} objects over the tracker cap of {} dropped", worker_id, excess, state.max_tracked);
+        }
+    }
+    if let Some(fraction) = args.hot_fraction {
+        let mut state = worker.write().await;
+        state.split_hot_cold(fraction.get());
+        debug!("Worker {}: {} hot, {} cold objects", worker_id, state.objects.len(), state.cold.len());
+    }
+    if let Some(hot_pct) = args.create_hot_pct {
+        let mut state = worker.write().await;
+        state.start_partition(hot_pct.get());
+        debug!("Worker {}: partitioned into {} hot, {} cold objects", worker_id, state.objects.len(), state.cold.len());
+    }
+    let mut epoch = current_epoch(client.as_ref()).await;
+    let mut quarantine_timer = failures::QuarantineTimer::new();
+    let mut freeze_timer = frozen::FreezeTimer::new(Duration::from_secs(args.freeze_interval.max(1)));
+    let wave_interval = Duration::from_secs(args.wave_interval);
+    let mut last_wave = wave::WaveStats::due_index(&stats, wave_interval);
+    let mut consecutive_failures = 0u32;
+    let mut batch_backoff = args.batch_backoff_after.map(|after| batch_backoff::BatchBackoff::new(after, args.batch_regrow_after));
+    let failure_throttle = failure_rate::ThrottleSettings::from_args(&args);
+    const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+    const BACKOFF_ON_FAILURE: Duration = Duration::from_millis(500);
+    const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+    let staged = !args.address_stages.is_empty();
+    let mut knobs_generation = 0;
+
+    let clock = stats.clock.clone();
+    while running.load(Ordering::Relaxed) && clock.now() < deadline {
+        // A soak run follows the reference gas price across epochs
+        let cached_rgp = stats.epochs.reference_gas_price().unwrap_or(cached_rgp);
+
+        // Knobs reloaded from --config since the last transaction
+        let generation = stats.live.generation();
+        if generation != knobs_generation {
+            knobs_generation = generation;
+            if let Some(knobs) = stats.live.worker(worker_id) {
+                knobs.apply(&mut args);
+            }
+            if let Some(split) = stats.ptb_limits.get().and_then(|limits| limits.split(&mut args)) {
+                warn!("Worker {}: reloaded batch of {} objects exceeds the PTB limits; splitting it into {} transactions of {}",
+                    worker_id, split.requested, split.parts, split.batch_size);
+            }
+        }
+
+        // Address-count stages: only the first N workers' addresses send
+        if staged && worker_id >= stats.stages.active() {
+            clock.sleep(Duration::from_millis(100)).await;
+            continue;
+        }
+
+        // Return quarantined objects once their lock can no longer block us
+        if quarantine_timer.due() && !worker.read().await.quarantine.is_empty() {
+            epoch = current_epoch(client.as_ref()).await.max(epoch);
+            let mut state = worker.write().await;
+            let released = match &client {
+                Some(client) => failures::release_quarantine(client, &mut state, epoch).await,
+                None => Ok(mock::release_quarantine(&mut state)),
+            };
+            if let Err(e) = released {
+                debug!("Quarantine re-read failed: {}", e);
+            }
+        }
+
+        // Compaction wave: all workers start rewriting their population at the same time
+        if args.wave_interval > 0 {
+            let due = wave::WaveStats::due_index(&stats, wave_interval);
+            if due > last_wave {
+                last_wave = due;
+                let mut state = worker.write().await;
+                if state.wave.is_none() && !state.objects.is_empty() {
+                    state.wave = Some(wave::WaveProgress::new(due, state.objects.len(), args.wave_pct.get()));
+                    stats.waves.begin(&stats, due);
+                    info!("Worker {}: compaction wave {} started", worker_id, due);
+                }
+            }
+        }
+
+        // Periodically retire part of the population as immutable cold data
+        // (not under --simulate: nothing would actually be frozen)
+        if let Some(client) = client.as_ref().filter(|_| args.freeze_pct.get() > 0 && !args.simulate && freeze_timer.due()) {
+            let started = Instant::now();
+            match frozen::freeze_objects(client, &worker, package_id, args.freeze_pct.get(), args.use_blobs, args.gas_budget, cached_rgp).await {
+                Ok(Some(outcome)) => {
+                    stats.tx_submitted.add(worker_id, 1);
+                    stats.tx_success.add(worker_id, 1);
+                    stats.objects_frozen.fetch_add(outcome.updated, Ordering::Relaxed);
+                    stats.latency.record("freeze", outcome.updated, started.elapsed());
+                    stats.paths.record(&outcome, started.elapsed());
+                    stats.epochs.record_latency("freeze", started.elapsed());
+                    if stats.add_gas(worker_id, outcome.gas_used, outcome.charge, args.max_gas_spend) {
+                        stop_on_gas_cap(&running, &stats, args.max_gas_spend);
+                    }
+                    if let Some(recorder) = &recorder {
+                        recorder.record(&outcome.digest, worker_id, "freeze", stats.elapsed_ms());
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    stats.tx_submitted.add(worker_id, 1);
+                    stats.record_failure(Some(client), &worker, &e, epoch).await;
+                    debug!("Worker {}: freeze failed: {:?}", worker_id, e);
+                }
+            }
+        }
+
+        // Gas smashing: split fresh fragments once the last ones were merged away
+        let refill = args.gas_smash_refill && !args.simulate && worker.read().await.smash_coins.is_empty();
+        if let Some(client) = client.as_ref().filter(|_| refill) {
+            match gas_pool::refill_fragments(client, &worker, args.gas_smash - 1, args.gas_budget, cached_rgp).await {
+                Ok(summary) => {
+                    stats.tx_submitted.add(worker_id, 1);
+                    stats.tx_success.add(worker_id, 1);
+                    if stats.add_gas(worker_id, gas_spent(&summary), GasCharge::of(&summary), args.max_gas_spend) {
+                        stop_on_gas_cap(&running, &stats, args.max_gas_spend);
+                    }
+                }
+                Err(e) => {
+                    stats.tx_submitted.add(worker_id, 1);
+                    stats.record_failure(Some(client), &worker, &e, epoch).await;
+                    debug!("Worker {}: gas fragment refill failed: {:?}", worker_id, e);
+                }
+            }
+        }
+
+        // Graduated memory pressure throttling, as assigned by the coordinator
+        let pressure_level = memory_pressure.level();
+        let (drop_quota, delay) = memory_pressure.take_directive(worker_id);
+        
+        // Drop tracked objects to free memory, once per pressure transition
+        if drop_quota > 0 {
+            let mut state = worker.write().await;
+            let dropped = state.evict(drop_quota).len();
+            memory_pressure.record_dropped(dropped);
+            debug!("Pressure L{}: dropped {} objects (keeping {})", pressure_level, dropped, state.objects.len());
+        }
+        
+        if pressure_level > MEM_PRESSURE_NORMAL && !delay.is_zero() {
+            // Delay to let memory recover
+            clock.sleep(delay).await;
+        }
+
+        // At emergency level, skip creates entirely and only do updates
+        let update_only = pressure_level > MEM_PRESSURE_NORMAL && memory_pressure.skip_creates();
+        if update_only && worker.read().await.objects.is_empty() {
+            // No objects to update - just wait
+            clock.sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+
+        // Adaptive throttling based on the recent failure rate
+        stats.failure_window.throttle(stats.tx_submitted.get(), stats.tx_failed.get(), &failure_throttle, clock.as_ref()).await;
+
+        // Acquire permit
+        let permit_wait_started = Instant::now();
+        let _permit = stats.inflight.acquire(&semaphore, worker_id).await?;
+        let permit_wait = permit_wait_started.elapsed();
+        // Work that queued through a stall is not sent late; start over with fresh work
+        if stats.expiry.drop_stale(permit_wait, args.stale_after_ms.map(Duration::from_millis)) {
+            continue;
+        }
+        let expiration_epoch = args.tx_expiration_epochs.map(|n| stats.epochs.current().unwrap_or(epoch) + n);
+
+        // Decide operation type (waves only rewrite existing objects)
+        let wave_index = worker.read().await.wave.map(|w| w.index);
+        let batch_size = batch_backoff.as_ref().map_or(args.batch_size, |b| b.batch_size(args.batch_size));
+        let mut workload_ctx = workload::BuildContext { package_id, batch_size, use_blobs: args.use_blobs, tags, contract, cold_leak: false, expiration_epoch };
+        let mut extra = workloads.pick(&mut rng).filter(|_| wave_index.is_none() && !update_only);
+        if let Some(idx) = extra {
+            if !workloads.get(idx).ready(&*worker.read().await, &workload_ctx) {
+                extra = None;
+            }
+        }
+        let builtin = wave_index.is_none() && extra.is_none();
+        let do_create = builtin && !update_only && create_scheduler.next(args.create_pct, || rng.gen_range(0..100));
+        if builtin && !update_only {
+            stats.mix.record(do_create);
+        }
+        let op = match (args.use_blobs, do_create) {
+            (true, true) => "create_blob",
+            (true, false) => "update_blob",
+            (false, true) => "create",
+            (false, false) => "update",
+        };
+        let read_frozen = builtin && !do_create && !update_only && args.frozen_read_pct.get() > 0
+            && rng.gen_range(0..100) < args.frozen_read_pct.get() as u32
+            && !worker.read().await.frozen.is_empty();
+        let hot = hot.as_deref().filter(|_| builtin && !do_create && !read_frozen && !update_only && rng.gen_range(0..100) < args.hot_pct.get() as u32);
+        // Leak-through: a built-in update that rewrites cold objects of a partition
+        workload_ctx.cold_leak = builtin && !do_create && !read_frozen && !update_only && hot.is_none() && args.create_hot_pct.is_some()
+            && args.cold_leak_pct.get() > 0 && rng.gen_range(0..100) < args.cold_leak_pct.get() as u32;
+        let op = match extra {
+            Some(idx) => workloads.get(idx).name(),
+            None if read_frozen => "read_frozen",
+            None if hot.is_some() => "update_shared",
+            None => op,
+        };
+
+        let wave_running = stats.waves.active();
+        let started = Instant::now();
+        let on_wire = stats.inflight.start();
+        let inflight_at_submit = stats.inflight.inflight();
+        let result = if let Some(idx) = extra {
+            workload::execute(executor.as_ref(), &worker, workloads.get(idx), &workload_ctx, &gas_budgets, cached_rgp, args.simulate).await
+        } else if let Some(client) = client.as_ref().filter(|_| read_frozen) {
+            // Immutable cold objects (read only)
+            frozen::execute_frozen_read(client, &worker, package_id, args.batch_size, args.use_blobs, args.gas_budget, cached_rgp, args.simulate).await
+        } else if let (Some(hot), Some(client)) = (hot, &client) {
+            // Shared counters (consensus path)
+            hotspot::execute_hot_update(client, &worker, hot.package_id(), hot, args.batch_size, args.gas_budget, cached_rgp, args.simulate).await
+        } else {
+            // 4KB LargeBlob objects (40x more I/O per object) or MicroCounter objects (~100 bytes each)
+            let op_spec: &dyn workload::Workload = match (args.use_blobs, do_create) {
+                (true, true) => &create_blobs,
+                (true, false) => &update_blobs,
+                (false, true) => &ops::CreateCounters,
+                (false, false) => &ops::UpdateCounters,
+            };
+            workload::execute(executor.as_ref(), &worker, op_spec, &workload_ctx, &gas_budgets, cached_rgp, args.simulate).await
+        };
+        let inflight_at_completion = stats.inflight.inflight();
+        drop(on_wire);
+        stats.outliers.observe(&stats, worker_id, op, &result, outliers::TxTiming {
+            latency: started.elapsed(),
+            permit_wait,
+            inflight_at_submit,
+            inflight_at_completion,
+        });
+
+        stats.tx_submitted.add(worker_id, 1);
+        if let Some(idx) = extra {
+            workloads.record(idx, &result);
+        }
+
+        match &result {
+            Ok(outcome) if outcome.duplicate => {
+                stats.tx_duplicates.fetch_add(1, Ordering::Relaxed);
+                warn!("Worker {}: duplicate response for {} ignored", worker_id, outcome.digest);
+            }
+            Ok(outcome) => {
+                stats.tx_success.add(worker_id, 1);
+                stats.objects_created.add(worker_id, outcome.created);
+                stats.objects_updated.add(worker_id, outcome.updated);
+                stats.objects_read.fetch_add(outcome.read, Ordering::Relaxed);
+                stats.record_sizes(outcome);
+                if args.hot_fraction.is_some() && extra.is_none() && hot.is_none() {
+                    stats.temperature.record(outcome);
+                }
+                if args.create_hot_pct.is_some() && builtin && hot.is_none() {
+                    stats.partition.record(outcome, workload_ctx.cold_leak);
+                }
+                stats.latency.record(op, outcome.created + outcome.updated + outcome.read, started.elapsed());
+                stats.paths.record(outcome, started.elapsed());
+                stats.verify.sample(outcome, stats.clock.now());
+                stats.adaptive.record(started.elapsed());
+                stats.epochs.record_latency(op, started.elapsed());
+                if staged {
+                    stats.stages.record_latency(started.elapsed());
+                }
+                stats.tenants.record_latency(worker_id, started.elapsed());
+                if args.wave_interval > 0 {
+                    stats.waves.record_latency(started.elapsed(), wave_running);
+                }
+                if let Some(index) = wave_index {
+                    stats.waves.record(index, outcome.updated);
+                }
+                consecutive_failures = 0;  // Reset on success
+                if !read_frozen && hot.is_none() {
+                    stats.batch_backoff.success(worker_id, batch_backoff.as_mut(), args.batch_size);
+                }
+                if stats.add_gas(worker_id, outcome.gas_used, outcome.charge, args.max_gas_spend) {
+                    stop_on_gas_cap(&running, &stats, args.max_gas_spend);
+                }
+                if let Some(recorder) = &recorder {
+                    recorder.record(&outcome.digest, worker_id, op, stats.elapsed_ms());
+                }
+            }
+            Err(e) => {
+                stats.record_failure(client.as_ref(), &worker, e, epoch).await;
+                debug!("Transaction failed: {:?}", e);
+                if !read_frozen && hot.is_none() {
+                    stats.batch_backoff.failure(worker_id, batch_backoff.as_mut(), e, args.batch_size);
+                }
+                
+                // Exponential backoff on consecutive failures
+                consecutive_failures += 1;
+                if consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                    let backoff = std::cmp::min(
+                        BACKOFF_ON_FAILURE * consecutive_failures,
+                        MAX_BACKOFF
+                    );
+                    warn!("Worker: {} consecutive failures, backing off {:?}", consecutive_failures, backoff);
+                    clock.sleep(backoff).await;
+                }
+            }
+        }
+
+        // Waves run flat out; finish this worker's share once its objects are covered
+        if let Some(index) = wave_index {
+            let mut state = worker.write().await;
+            if state.wave.is_some_and(|w| w.done()) {
+                state.wave = None;
+                drop(state);
+                stats.waves.finish(&stats, index);
+                info!("Worker {}: compaction wave {} done", worker_id, index);
+            }
+            continue;
+        }
+
+        // Rate limiting: by logical bytes if --target-write-bps is set, else by TPS
+        // (shared by the workers currently sending)
+        let senders = if staged { stats.stages.active() } else { args.workers };
+        let bytes = match &result {
+            Ok(outcome) => outcome.bytes_written,
+            // A failed transaction counts as the bytes it would have written
+            Err(_) if args.use_blobs && hot.is_none() => {
+                let object_bytes = match (append, partial) {
+                    (Some(a), _) if !do_create => a.bytes,
+                    (None, Some(p)) if !do_create => p.bytes,
+                    _ => BLOB_OBJECT_BYTES,
+                };
+                args.batch_size.min(MAX_BLOB_BATCH) as u64 * object_bytes
+            }
+            Err(_) => args.batch_size as u64 * COUNTER_OBJECT_BYTES,
+        };
+        pacing::hold(&args, senders, bytes, pace.as_mut(), clock.as_ref()).await;
+    }
+
+    Ok(())
+}
+/// Human-readable payload of a worker panic
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Current epoch (0 if the node can't be asked, or there is none)
+async fn current_epoch(client: Option<&SuiClient>) -> u64 {
+    let Some(client) = client else {
+        return 0;
+    };
+    client.governance_api()
+        .get_latest_sui_system_state()
+        .await
+        .map(|s| s.epoch)
+        .unwrap_or(0)
+}
+
+/// End the run once the gas cap is reached (first worker to notice logs it)
+fn stop_on_gas_cap(running: &AtomicBool, stats: &BenchStats, cap: u64) {
+    if running.swap(false, Ordering::Relaxed) {
+        warn!("Gas cap reached: {} MIST spent net of storage rebates (cap {}) - ending run",
+            stats.net_gas(), cap);
+    }
+}
+
+/// Dry-run a transaction instead of executing it (--simulate)
+///
+/// Nothing is written, so object and gas refs stay valid and are not touched;
+/// counts come from the simulated effects.
+async fn simulate_tx(client: &SuiClient, tx_data: TransactionData, gas_id: ObjectID, object_bytes: u64) -> Result<TxOutcome> {
+    let response = client.read_api().dry_run_transaction_block(tx_data).await?;
+    let effects = &response.effects;
+    if effects.status().is_err() {
+        return Err(anyhow!("Simulated transaction failed: {:?}", effects.status()));
+    }
+    let mut created = 0u64;
+    let mut updated = 0u64;
+    for change in &response.object_changes {
+        match change {
+            sui_sdk::rpc_types::ObjectChange::Created { .. } => created += 1,
+            sui_sdk::rpc_types::ObjectChange::Mutated { object_id, .. } if *object_id != gas_id => updated += 1,
+            _ => {}
+        }
+    }
+    Ok(TxOutcome {
+        created,
+        updated,
+        gas_used: gas_spent(effects.gas_cost_summary()),
+        charge: GasCharge::of(effects.gas_cost_summary()),
+        digest: *effects.transaction_digest(),
+        bytes_written: (created + updated) * object_bytes,
+        object_sizes: Vec::new(),
+        read: 0,
+        deleted: 0,
+        duplicate: false,
+        consensus: !effects.shared_objects().is_empty(),
+    })
+}
+
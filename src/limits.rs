@@ -0,0 +1,177 @@
+// PTB size limits from the node's protocol config
+//
+// A batch that is too large for one programmable transaction fails with an
+// error that names a protocol limit nobody set on the command line. At startup
+// the node's protocol config is read once and the per-transaction limits that
+// a batch runs into - commands, input objects, new object IDs, serialized size
+// and bytes written - are turned into the largest batch one PTB can carry.
+// Blob sizes that can't fit in an object at all are rejected. A --batch-size
+// (or a profile's) above the limit is split into equal smaller batches, and
+// that worker's --target-tps goes up by the same factor so the object rate is
+// unchanged; the split is logged and reported under `ptb_limits`.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use sui_sdk::rpc_types::SuiProtocolConfigValue;
+use sui_sdk::SuiClient;
+use tracing::warn;
+
+use crate::{sizes, Args, BLOB_OBJECT_BYTES, MAX_BLOB_BATCH};
+
+/// Serialized bytes one batched object adds to a transaction (object ref input, Move call, pure args)
+const BYTES_PER_OBJECT: u64 = 200;
+/// Sender, gas data, expiration and signature
+const TX_OVERHEAD_BYTES: u64 = 1024;
+/// Object header and length prefix on top of a blob's payload
+const OBJECT_OVERHEAD_BYTES: u64 = 128;
+
+/// The limits a batch runs into, as the node reports them
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PtbLimits {
+    pub protocol_version: u64,
+    pub max_commands: u64,
+    pub max_input_objects: u64,
+    pub max_new_objects: u64,
+    pub max_tx_bytes: u64,
+    pub max_object_bytes: u64,
+    pub max_written_bytes: u64,
+}
+
+impl Default for PtbLimits {
+    /// Mainnet values, used when the node doesn't report a limit
+    fn default() -> Self {
+        Self {
+            protocol_version: 0,
+            max_commands: 1024,
+            max_input_objects: 2048,
+            max_new_objects: 2048,
+            max_tx_bytes: 128 * 1024,
+            max_object_bytes: 250 * 1024,
+            max_written_bytes: 5_000_000,
+        }
+    }
+}
+
+impl PtbLimits {
+    pub async fn fetch(client: &SuiClient) -> Result<Self> {
+        let config = client.read_api().get_protocol_config(None).await?;
+        let mut limits = Self { protocol_version: config.protocol_version.as_u64(), ..Self::default() };
+        let attr = |name: &str| match config.attributes.get(name) {
+            Some(Some(SuiProtocolConfigValue::U64(v))) => Some(*v),
+            Some(Some(SuiProtocolConfigValue::U32(v))) => Some(*v as u64),
+            Some(Some(SuiProtocolConfigValue::U16(v))) => Some(*v as u64),
+            _ => None,
+        };
+        for (name, field) in [
+            ("max_programmable_tx_commands", &mut limits.max_commands),
+            ("max_input_objects", &mut limits.max_input_objects),
+            ("max_num_new_move_object_ids", &mut limits.max_new_objects),
+            ("max_tx_size_bytes", &mut limits.max_tx_bytes),
+            ("max_move_object_size", &mut limits.max_object_bytes),
+            ("max_size_written_objects", &mut limits.max_written_bytes),
+        ] {
+            match attr(name) {
+                Some(v) => *field = v,
+                None => warn!("Protocol config has no {}; assuming {}", name, field),
+            }
+        }
+        Ok(limits)
+    }
+
+    /// Largest batch one PTB can carry with these settings
+    pub fn max_batch(&self, args: &Args) -> usize {
+        let object_bytes = if args.use_blobs { self.largest_blob(args) } else { 0 };
+        let by_size = self.max_tx_bytes.saturating_sub(TX_OVERHEAD_BYTES) / BYTES_PER_OBJECT;
+        // The gas coin is an input object too
+        // --payload-tag adds a tagging call per updated object
+        let commands = if args.payload_tag.is_some() { self.max_commands / 2 } else { self.max_commands };
+        let mut max = [commands, self.max_input_objects.saturating_sub(1), self.max_new_objects, by_size]
+            .into_iter().min().unwrap_or(1);
+        if object_bytes > 0 {
+            max = max.min(self.max_written_bytes / object_bytes);
+        }
+        max.max(1) as usize
+    }
+
+    fn largest_blob(&self, args: &Args) -> u64 {
+        let blob = match (args.append_bytes, &args.size_distribution) {
+            (Some(_), _) => args.max_object_bytes,
+            (None, Some(_)) => sizes::MAX_BLOB_SIZE,
+            (None, None) => BLOB_OBJECT_BYTES,
+        };
+        blob + OBJECT_OVERHEAD_BYTES
+    }
+
+    /// Reject blob sizes no object can hold
+    pub fn validate(&self, args: &Args) -> Result<()> {
+        if args.use_blobs && self.largest_blob(args) > self.max_object_bytes {
+            return Err(anyhow!(
+                "Blobs of up to {} bytes don't fit the node's {}-byte object limit (protocol version {}); lower --max-object-bytes or --size-distribution",
+                self.largest_blob(args) - OBJECT_OVERHEAD_BYTES, self.max_object_bytes, self.protocol_version));
+        }
+        Ok(())
+    }
+
+    /// Split an oversized batch into equal batches that fit, raising the rate to match
+    pub fn split(&self, args: &mut Args) -> Option<PtbSplit> {
+        let requested = if args.use_blobs { args.batch_size.min(MAX_BLOB_BATCH) } else { args.batch_size };
+        let max = self.max_batch(args);
+        if requested <= max {
+            return None;
+        }
+        let parts = requested.div_ceil(max);
+        args.batch_size = requested.div_ceil(parts);
+        args.target_tps *= parts as u64;
+        Some(PtbSplit { requested, parts, batch_size: args.batch_size })
+    }
+}
+
+/// An oversized batch and what it was split into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct PtbSplit {
+    pub requested: usize,
+    pub parts: usize,
+    pub batch_size: usize,
+}
+
+/// `ptb_limits` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct PtbReport {
+    #[serde(flatten)]
+    pub limits: PtbLimits,
+    pub max_batch: usize,
+    /// Workers whose batches were split
+    pub splits: BTreeMap<usize, PtbSplit>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn args(argv: &[&str]) -> Args {
+        Args::try_parse_from([&["fdp-sui-bench"], argv].concat()).unwrap()
+    }
+
+    #[test]
+    fn oversized_batches_are_split_evenly() {
+        let limits = PtbLimits { max_commands: 100, ..PtbLimits::default() };
+        let mut args = args(&["--batch-size", "250", "--target-tps", "10"]);
+        let split = limits.split(&mut args).unwrap();
+        assert_eq!(split, PtbSplit { requested: 250, parts: 3, batch_size: 84 });
+        assert_eq!((args.batch_size, args.target_tps), (84, 30));
+        // A batch that fits is left alone
+        assert_eq!(limits.split(&mut args), None);
+    }
+
+    #[test]
+    fn blob_batches_are_bounded_by_bytes_written() {
+        let limits = PtbLimits { max_written_bytes: 50_000, ..PtbLimits::default() };
+        let blobs = args(&["--use-blobs"]);
+        assert_eq!(limits.max_batch(&blobs), 50_000 / (BLOB_OBJECT_BYTES + OBJECT_OVERHEAD_BYTES) as usize);
+        assert!(limits.validate(&blobs).is_ok());
+        let small = PtbLimits { max_object_bytes: 1024, ..limits };
+        assert!(small.validate(&blobs).is_err());
+    }
+}
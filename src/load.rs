@@ -0,0 +1,81 @@
+// Partial loads (--load-workers, --load-fraction)
+//
+// A saved state from a long fill phase can hold more workers and objects than
+// a follow-up experiment needs. --load-workers restores only the listed saved
+// workers, and --load-fraction keeps only the first share of each worker's
+// objects (in saved order). Loaded workers are numbered from 0 in the order
+// listed, so the run's worker ids stay contiguous; object origins keep the ids
+// of the workers that created them.
+
+use anyhow::{anyhow, Result};
+
+use crate::keystore::{self, Keystore};
+use crate::validate::Fraction;
+use crate::{autosave, Args, SavedBenchmarkState};
+
+/// Workers restored (faucet call + object refresh) at once
+pub const CONCURRENCY: usize = 8;
+
+/// Read a state file and apply the partial-load options
+pub fn read(path: &str, args: &Args) -> Result<SavedBenchmarkState> {
+    let state = select(autosave::read_state(path)?, &args.load_workers, args.load_fraction)?;
+    with_keys(state, args, path)
+}
+
+/// Resolve the keys of workers saved without them (--keystore)
+pub fn with_keys(mut state: SavedBenchmarkState, args: &Args, path: &str) -> Result<SavedBenchmarkState> {
+    match &args.keystore {
+        Some(keystore_path) => Keystore::load(keystore_path)?.fill(&mut state)?,
+        None => keystore::require_keys(&state, path)?,
+    }
+    Ok(state)
+}
+
+pub fn select(mut state: SavedBenchmarkState, workers: &[usize], fraction: Option<Fraction>) -> Result<SavedBenchmarkState> {
+    if !workers.is_empty() {
+        let mut saved = std::mem::take(&mut state.workers);
+        for &id in workers {
+            let pos = saved.iter().position(|w| w.worker_id == id)
+                .ok_or_else(|| anyhow!("--load-workers: worker {} is not in the saved state (or listed twice)", id))?;
+            state.workers.push(saved.swap_remove(pos));
+        }
+        for (new_id, worker) in state.workers.iter_mut().enumerate() {
+            worker.worker_id = new_id;
+        }
+    }
+    if let Some(fraction) = fraction {
+        for worker in &mut state.workers {
+            let keep = (worker.objects.len() as f64 * fraction.get()).ceil() as usize;
+            worker.objects.truncate(keep);
+        }
+    }
+    state.total_objects = state.workers.iter().map(|w| w.objects.len()).sum();
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SavedWorkerObjects, TrackedObject};
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SuiAddress};
+
+    fn state(objects: &[usize]) -> SavedBenchmarkState {
+        let workers = objects.iter().enumerate().map(|(id, &n)| SavedWorkerObjects {
+            worker_id: id,
+            address: SuiAddress::ZERO,
+            keypair_base64: String::new(),
+            objects: (0..n).map(|i| TrackedObject { id: ObjectID::from_single_byte(i as u8), version: 1, digest: ObjectDigest::random(), size: 0, origin: None, updates: 0 }).collect(),
+        }).collect();
+        SavedBenchmarkState { total_objects: objects.iter().sum(), workers }
+    }
+
+    #[test]
+    fn subsets_are_renumbered_and_trimmed() {
+        let loaded = select(state(&[10, 20, 30, 40]), &[3, 1], Some(Fraction::try_from(0.25).unwrap())).unwrap();
+        let layout: Vec<(usize, usize)> = loaded.workers.iter().map(|w| (w.worker_id, w.objects.len())).collect();
+        assert_eq!(layout, vec![(0, 10), (1, 5)]);
+        assert_eq!(loaded.total_objects, 15);
+        assert!(select(state(&[10]), &[1], None).is_err());
+        assert!(select(state(&[10, 10]), &[0, 0], None).is_err());
+    }
+}
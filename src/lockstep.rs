@@ -0,0 +1,163 @@
+// Simultaneous A/B runs on a shared clock (ab-test --simultaneous)
+//
+// Run one after the other, the two sides of an A/B test see different
+// conditions: time of day, other tenants of the host, the client machine's own
+// load. With --simultaneous both run at once against their own node, and this
+// clock keeps their load identical. Each side does its setup (faucet, seeding,
+// loading) on its own and then waits for the other, so the timed runs start
+// at the same instant. From there each worker submits on a fixed slot grid
+// counted from that instant - slot n of worker k falls at the same moment on
+// both sides - instead of sleeping the pacing interval after each
+// transaction. A worker whose transaction overran its slot skips to the next
+// free one; skipped slots are counted per side in the `lockstep` section, so a
+// side that couldn't hold the pace is visible rather than silently slower.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Barrier;
+use tracing::info;
+
+use crate::clock::Clock;
+
+/// The clock both sides share
+#[derive(Debug)]
+pub struct Lockstep {
+    labels: Vec<String>,
+    barrier: Barrier,
+    epoch: OnceLock<Instant>,
+    slots: Vec<AtomicU64>,
+    missed: Vec<AtomicU64>,
+}
+
+impl Lockstep {
+    pub fn new(labels: &[&str]) -> Arc<Self> {
+        Arc::new(Self {
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+            barrier: Barrier::new(labels.len()),
+            epoch: OnceLock::new(),
+            slots: labels.iter().map(|_| AtomicU64::new(0)).collect(),
+            missed: labels.iter().map(|_| AtomicU64::new(0)).collect(),
+        })
+    }
+
+    pub fn side(self: &Arc<Self>, index: usize) -> Side {
+        Side { clock: self.clone(), index }
+    }
+}
+
+/// One side's handle on the shared clock (carried in its Args)
+#[derive(Debug, Clone)]
+pub struct Side {
+    clock: Arc<Lockstep>,
+    index: usize,
+}
+
+impl Side {
+    /// Wait for the other side to finish setup; returns the common start
+    pub async fn start(&self, time: &dyn Clock) -> Instant {
+        info!("Lockstep: {} ready, waiting for the other side", self.clock.labels[self.index]);
+        self.clock.barrier.wait().await;
+        *self.clock.epoch.get_or_init(|| time.now())
+    }
+
+    /// Slot pacing for one worker, from the common start
+    pub fn pace(&self, worker_id: usize, workers: usize) -> Pace {
+        Pace { side: self.clone(), worker_id, workers: workers.max(1), next: None }
+    }
+
+    pub fn report(&self) -> LockstepReport {
+        LockstepReport {
+            side: self.clock.labels[self.index].clone(),
+            sides: self.clock.labels.clone(),
+            slots: self.clock.slots[self.index].load(Ordering::Relaxed),
+            missed_slots: self.clock.missed[self.index].load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A worker's position on the slot grid
+pub struct Pace {
+    side: Side,
+    worker_id: usize,
+    workers: usize,
+    next: Option<Instant>,
+}
+
+impl Pace {
+    /// Next slot at or after `now`, given the previous one; (slot, slots skipped)
+    fn advance(previous: Instant, interval: Duration, now: Instant) -> (Instant, u64) {
+        let next = previous + interval;
+        if next >= now || interval.is_zero() {
+            return (next, 0);
+        }
+        let behind = ((now - next).as_secs_f64() / interval.as_secs_f64()).ceil() as u32;
+        (next + interval * behind, behind as u64)
+    }
+
+    /// Sleep until the worker's next slot; workers are staggered evenly across one interval
+    pub async fn wait(&mut self, interval: Duration, time: &dyn Clock) {
+        let Some(&epoch) = self.side.clock.epoch.get() else {
+            time.sleep(interval).await;
+            return;
+        };
+        let previous = self.next
+            .unwrap_or_else(|| epoch + interval.mul_f64(self.worker_id as f64 / self.workers as f64));
+        let (slot, missed) = Self::advance(previous, interval, time.now());
+        let clock = &self.side.clock;
+        clock.slots[self.side.index].fetch_add(1, Ordering::Relaxed);
+        clock.missed[self.side.index].fetch_add(missed, Ordering::Relaxed);
+        self.next = Some(slot);
+        time.sleep_until(slot).await;
+    }
+}
+
+/// `lockstep` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct LockstepReport {
+    pub side: String,
+    pub sides: Vec<String>,
+    /// Slots the side's workers paced to
+    pub slots: u64,
+    /// Slots skipped because a transaction overran them
+    pub missed_slots: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn overrun_slots_are_skipped_not_bunched() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        // On time: the next slot is one interval on
+        assert_eq!(Pace::advance(start, interval, start + Duration::from_millis(40)), (start + interval, 0));
+        // Done at 350ms: the slots at 100, 200 and 300 have passed, 400 is next
+        let (slot, missed) = Pace::advance(start, interval, start + Duration::from_millis(350));
+        assert_eq!((slot, missed), (start + Duration::from_millis(400), 3));
+    }
+
+    #[test]
+    fn workers_send_on_the_slot_grid_in_simulated_time() {
+        let clock = VirtualClock::new();
+        let side = Lockstep::new(&["a"]).side(0);
+        let interval = Duration::from_millis(100);
+        let mut slots = Vec::new();
+        futures::executor::block_on(async {
+            let epoch = side.start(clock.as_ref()).await;
+            // Worker 1 of 2 is staggered half an interval; its 5th transaction takes 350ms and overruns 3 slots
+            let mut pace = side.pace(1, 2);
+            for n in 1..=10 {
+                pace.wait(interval, clock.as_ref()).await;
+                slots.push((clock.now() - epoch).as_millis());
+                clock.advance(if n == 5 { Duration::from_millis(350) } else { Duration::from_millis(30) });
+            }
+        });
+        assert_eq!(slots, vec![150, 250, 350, 450, 550, 950, 1050, 1150, 1250, 1350]);
+        let report = side.report();
+        assert_eq!((report.slots, report.missed_slots), (10, 3));
+    }
+}
@@ -0,0 +1,166 @@
+// Object provenance and the `lookup` subcommand
+//
+// Object IDs are derived from the creating transaction's digest and the
+// object's position in it, so they are deterministic for a given transaction
+// but say nothing about which part of the benchmark made them. Every object a
+// worker creates is stamped with its origin - worker, the worker's running
+// creation index, op type, and creation digest - which is saved with the
+// tracked objects in --save-objects / --autosave state files.
+//
+// `lookup <object-id> --state <file>` finds an object in state files. Objects
+// no longer tracked (dropped by the tracking cap or memory pressure) can still
+// be traced with --tx-digests: the object's last transaction is read from
+// chain and matched against the digest log, which names the worker and op
+// that last wrote it (the creating one, if it was never updated).
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args as ClapArgs;
+use serde::{Deserialize, Serialize};
+use sui_sdk::rpc_types::SuiObjectDataOptions;
+use sui_sdk::types::base_types::{ObjectID, TransactionDigest};
+use sui_sdk::SuiClientBuilder;
+
+use crate::tx_log::{read_tx_log, TxRecord};
+use crate::Args;
+
+/// Operation that created an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginOp {
+    Seed,
+    Create,
+    CreateBlob,
+}
+
+/// Where a tracked object came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectOrigin {
+    pub worker: usize,
+    /// Position among all objects this worker created (tracked or not)
+    pub index: u64,
+    pub op: OriginOp,
+    #[serde(with = "crate::tx_digest_serde")]
+    pub digest: TransactionDigest,
+}
+
+impl ObjectOrigin {
+    /// Origin of an object created by `digest`; worker and index are stamped when it is tracked
+    pub fn new(op: OriginOp, digest: TransactionDigest) -> Self {
+        Self { worker: 0, index: 0, op, digest }
+    }
+}
+
+/// Arguments for the `lookup` subcommand
+#[derive(ClapArgs, Debug, Clone)]
+pub struct LookupArgs {
+    /// Object ID to trace (0x-prefixed hex)
+    object_id: String,
+
+    /// State files to search (--save-objects / --autosave output); repeatable
+    #[clap(long, required = true)]
+    state: Vec<String>,
+
+    /// Digest log of the run (--tx-digests), to trace objects missing from the state via the chain
+    #[clap(long)]
+    tx_digests: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LookupResult {
+    object_id: String,
+    /// State file the object was found in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    state_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worker: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<u64>,
+    /// None for objects saved before origins were recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    origin: Option<ObjectOrigin>,
+    /// Recorded transaction that last wrote the object (chain fallback)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_written_by: Option<TxRecord>,
+}
+
+pub async fn run_lookup(base: Args, lookup: LookupArgs) -> Result<()> {
+    let id = ObjectID::from_hex_literal(&lookup.object_id).context("Invalid object ID")?;
+    let mut result = LookupResult {
+        object_id: id.to_string(),
+        state_file: None,
+        worker: None,
+        version: None,
+        origin: None,
+        last_written_by: None,
+    };
+
+    for path in &lookup.state {
+        let state = crate::autosave::read_state(path)?;
+        let found = state.workers.iter()
+            .find_map(|w| w.objects.iter().find(|o| o.id == id).map(|o| (w.worker_id, o)));
+        if let Some((worker, object)) = found {
+            result.state_file = Some(path.clone());
+            result.worker = Some(worker);
+            result.version = Some(object.version);
+            result.origin = object.origin.clone();
+            break;
+        }
+    }
+
+    if result.origin.is_none() {
+        if let Some(log) = &lookup.tx_digests {
+            let client = SuiClientBuilder::default()
+                .build(&base.rpc_url)
+                .await
+                .context("Failed to connect to SUI node")?;
+            let response = client.read_api()
+                .get_object_with_options(id, SuiObjectDataOptions::new().with_previous_transaction())
+                .await?;
+            if let Some(previous) = response.data.and_then(|d| d.previous_transaction) {
+                let previous = previous.to_string();
+                result.last_written_by = read_tx_log(log)?.into_iter().find(|r| r.digest == previous);
+            }
+        }
+    }
+
+    if result.state_file.is_none() && result.last_written_by.is_none() {
+        return Err(anyhow!("Object {} not found in {} state file(s){}", id, lookup.state.len(),
+            if lookup.tx_digests.is_some() { " or the digest log" } else { "" }));
+    }
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SavedBenchmarkState, SavedWorkerObjects, TrackedObject};
+    use clap::Parser;
+    use sui_sdk::types::base_types::{ObjectDigest, SuiAddress};
+
+    #[test]
+    fn objects_are_found_in_state_files_with_their_origin() {
+        let dir = std::env::temp_dir().join(format!("fdp-lookup-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json").to_string_lossy().into_owned();
+        let id = ObjectID::from_single_byte(42);
+        let origin = ObjectOrigin { worker: 3, index: 17, op: OriginOp::CreateBlob, digest: TransactionDigest::new([9; 32]) };
+        let object = TrackedObject { id, version: 5, digest: ObjectDigest::random(), size: 4096, origin: Some(origin.clone()), updates: 4 };
+        let state = SavedBenchmarkState {
+            total_objects: 1,
+            workers: vec![SavedWorkerObjects { worker_id: 3, address: SuiAddress::random_for_testing_only(), keypair_base64: String::new(), objects: vec![object] }],
+        };
+        crate::autosave::write_state(&path, &state).unwrap();
+        let base = Args::try_parse_from(["fdp-sui-bench"]).unwrap();
+        let lookup = |object_id: ObjectID| LookupArgs { object_id: object_id.to_string(), state: vec![path.clone()], tx_digests: None };
+
+        assert!(futures::executor::block_on(run_lookup(base.clone(), lookup(id))).is_ok());
+        let missing = futures::executor::block_on(run_lookup(base, lookup(ObjectID::from_single_byte(43)))).unwrap_err();
+        assert!(missing.to_string().contains("not found in 1 state file(s)"), "{}", missing);
+
+        let saved = crate::autosave::read_state(&path).unwrap();
+        let read_back = saved.workers[0].objects[0].origin.clone().unwrap();
+        assert_eq!((read_back.worker, read_back.index, read_back.op, read_back.digest), (3, 17, OriginOp::CreateBlob, origin.digest));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
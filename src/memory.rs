@@ -0,0 +1,128 @@
+// Host memory usage for the throttle (--memory-threshold and friends)
+//
+// One backend per platform: /proc/meminfo on Linux, Mach VM statistics on
+// macOS, GlobalMemoryStatusEx on Windows. Anywhere else (or if the backend
+// can't be read) usage is unknown and the throttle never engages, which the
+// run log states up front instead of running unprotected without a word.
+
+/// Which backend this build reads memory usage from
+#[cfg(target_os = "linux")]
+pub const BACKEND: &str = "/proc/meminfo";
+#[cfg(target_os = "macos")]
+pub const BACKEND: &str = "mach host_statistics64";
+#[cfg(windows)]
+pub const BACKEND: &str = "GlobalMemoryStatusEx";
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub const BACKEND: &str = "none";
+
+/// Fraction of physical memory in use (0.0 - 1.0), if it can be read
+pub fn usage() -> Option<f64> {
+    let (total, available) = read()?;
+    if total == 0 {
+        return None;
+    }
+    Some(total.saturating_sub(available) as f64 / total as f64)
+}
+
+/// Fraction of physical memory in use, 0.0 if unknown (never throttles)
+pub fn usage_pct() -> f64 {
+    usage().unwrap_or(0.0)
+}
+
+/// (total, available) bytes from MemTotal / MemAvailable
+#[cfg(target_os = "linux")]
+fn read() -> Option<(u64, u64)> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| -> Option<u64> {
+        let line = meminfo.lines().find(|l| l.starts_with(name))?;
+        line.split_whitespace().nth(1)?.parse::<u64>().ok().map(|kb| kb * 1024)
+    };
+    Some((field("MemTotal:")?, field("MemAvailable:")?))
+}
+
+/// (total, available) bytes; free, inactive, purgeable and speculative pages count as available
+#[cfg(target_os = "macos")]
+#[allow(deprecated)] // libc points mach_host_self at the mach2 crate
+fn read() -> Option<(u64, u64)> {
+    fn sysctl_u64(name: &std::ffi::CStr) -> Option<u64> {
+        let mut value = [0u8; 8];
+        let mut len = value.len();
+        // SAFETY: value is a writable buffer of len bytes; integer sysctls fit in 8
+        let ret = unsafe {
+            libc::sysctlbyname(name.as_ptr(), value.as_mut_ptr().cast(), &mut len, std::ptr::null_mut(), 0)
+        };
+        (ret == 0).then(|| u64::from_le_bytes(value))
+    }
+
+    let total = sysctl_u64(c"hw.memsize")?;
+    let page = sysctl_u64(c"hw.pagesize")?;
+    // SAFETY: vm_statistics64 is plain data; count is its size in integer_t units
+    let mut vm: libc::vm_statistics64 = unsafe { std::mem::zeroed() };
+    let mut count = libc::HOST_VM_INFO64_COUNT;
+    let ret = unsafe {
+        libc::host_statistics64(libc::mach_host_self(), libc::HOST_VM_INFO64, (&mut vm as *mut libc::vm_statistics64).cast(), &mut count)
+    };
+    if ret != libc::KERN_SUCCESS {
+        return None;
+    }
+    let pages = vm.free_count as u64 + vm.inactive_count as u64 + vm.purgeable_count as u64 + vm.speculative_count as u64;
+    Some((total, pages * page))
+}
+
+/// (total, available) physical bytes
+#[cfg(windows)]
+fn read() -> Option<(u64, u64)> {
+    #[repr(C)]
+    struct MemoryStatusEx {
+        length: u32,
+        memory_load: u32,
+        total_phys: u64,
+        avail_phys: u64,
+        total_page_file: u64,
+        avail_page_file: u64,
+        total_virtual: u64,
+        avail_virtual: u64,
+        avail_extended_virtual: u64,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GlobalMemoryStatusEx(buffer: *mut MemoryStatusEx) -> i32;
+    }
+
+    let mut status = MemoryStatusEx {
+        length: std::mem::size_of::<MemoryStatusEx>() as u32,
+        memory_load: 0,
+        total_phys: 0,
+        avail_phys: 0,
+        total_page_file: 0,
+        avail_page_file: 0,
+        total_virtual: 0,
+        avail_virtual: 0,
+        avail_extended_virtual: 0,
+    };
+    // SAFETY: status is a MEMORYSTATUSEX with dwLength set, as the call requires
+    if unsafe { GlobalMemoryStatusEx(&mut status) } == 0 {
+        return None;
+    }
+    Some((status.total_phys, status.avail_phys))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn read() -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_is_a_fraction_where_the_backend_can_be_read() {
+        match usage() {
+            Some(used) => assert!((0.0..=1.0).contains(&used), "{}", used),
+            None => assert_eq!(BACKEND, "none"),
+        }
+        assert!((0.0..=1.0).contains(&usage_pct()));
+    }
+}
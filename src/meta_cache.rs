@@ -0,0 +1,221 @@
+// Object owner/type cache (--meta-cache)
+//
+// Restoring a saved population asks the node about every tracked object. The
+// version has to come from the node, since it changes with every write, but an
+// io_churn object's owner and type practically never change. With
+// --meta-cache FILE both are kept per object: objects already in the cache as
+// owned by their worker are refreshed with a bare version lookup, and only new
+// or mismatched ones are fetched with owner and type. Those are checked too:
+// an object that now belongs to someone else, or isn't a MicroCounter or
+// LargeBlob, is dropped from the worker instead of failing its updates later.
+// Objects the node no longer returns leave the cache. Objects rediscovered
+// through --identity-cache go in with the type their listing already carried.
+// The cache is written back once the workers are set up and reported in the
+// `meta_cache` section. Versions are never cached.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use sui_sdk::rpc_types::SuiObjectData;
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::object::Owner;
+
+/// Who owns an object, as far as the benchmark cares
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Holder {
+    Address(#[serde(with = "crate::sui_address_serde")] SuiAddress),
+    Shared,
+    Immutable,
+    /// Wrapped in another object, or an ownership kind the benchmark doesn't use
+    Other,
+}
+
+impl Holder {
+    fn of(owner: &Owner) -> Self {
+        match owner {
+            Owner::AddressOwner(address) => Holder::Address(*address),
+            Owner::Shared { .. } => Holder::Shared,
+            Owner::Immutable => Holder::Immutable,
+            _ => Holder::Other,
+        }
+    }
+}
+
+/// io_churn type of an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectKind {
+    Counter,
+    Blob,
+    Other,
+}
+
+impl ObjectKind {
+    pub fn of(type_: &str) -> Self {
+        if type_.ends_with("::io_churn::MicroCounter") {
+            ObjectKind::Counter
+        } else if type_.ends_with("::io_churn::LargeBlob") {
+            ObjectKind::Blob
+        } else {
+            ObjectKind::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectMeta {
+    pub owner: Holder,
+    pub kind: ObjectKind,
+}
+
+impl ObjectMeta {
+    /// Whether `address` can keep updating the object
+    pub fn usable_by(&self, address: SuiAddress) -> bool {
+        self.owner == Holder::Address(address) && self.kind != ObjectKind::Other
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    #[serde(with = "crate::object_id_serde")]
+    id: ObjectID,
+    #[serde(flatten)]
+    meta: ObjectMeta,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheFile {
+    objects: Vec<CacheEntry>,
+}
+
+/// `meta_cache` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct MetaCacheReport {
+    pub path: String,
+    /// Entries read from the file
+    pub loaded: usize,
+    /// Entries written back
+    pub entries: usize,
+    /// Objects refreshed without fetching owner and type
+    pub hits: u64,
+    /// Objects whose owner and type were fetched
+    pub misses: u64,
+    /// Objects dropped from their worker: owned by someone else or not an io_churn object
+    pub foreign: u64,
+    /// Entries removed because the node no longer returns the object
+    pub forgotten: u64,
+}
+
+pub struct MetaCache {
+    path: String,
+    loaded: usize,
+    objects: Mutex<HashMap<ObjectID, ObjectMeta>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    foreign: AtomicU64,
+    forgotten: AtomicU64,
+}
+
+impl MetaCache {
+    /// Read the cache file (empty when it doesn't exist yet)
+    pub fn load(path: &str) -> Result<Self> {
+        let file = if Path::new(path).exists() {
+            let content = std::fs::read_to_string(path).context(format!("Failed to read metadata cache: {}", path))?;
+            serde_json::from_str(&content).context(format!("Failed to parse metadata cache: {}", path))?
+        } else {
+            CacheFile::default()
+        };
+        let objects: HashMap<_, _> = file.objects.into_iter().map(|e| (e.id, e.meta)).collect();
+        Ok(Self {
+            path: path.to_string(),
+            loaded: objects.len(),
+            objects: Mutex::new(objects),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            foreign: AtomicU64::new(0),
+            forgotten: AtomicU64::new(0),
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let objects = self.objects.lock().unwrap();
+        let mut entries: Vec<CacheEntry> = objects.iter().map(|(id, meta)| CacheEntry { id: *id, meta: *meta }).collect();
+        entries.sort_by_key(|e| e.id);
+        let json = serde_json::to_string(&CacheFile { objects: entries })?;
+        std::fs::write(&self.path, json).context(format!("Failed to write metadata cache: {}", self.path))
+    }
+
+    /// Split `ids` into those cached as usable by `address` and those to fetch in full
+    pub fn partition(&self, ids: &[ObjectID], address: SuiAddress) -> (Vec<ObjectID>, Vec<ObjectID>) {
+        let objects = self.objects.lock().unwrap();
+        let (known, unknown): (Vec<ObjectID>, Vec<ObjectID>) = ids.iter()
+            .partition(|id| objects.get(id).is_some_and(|meta| meta.usable_by(address)));
+        self.hits.fetch_add(known.len() as u64, Ordering::Relaxed);
+        self.misses.fetch_add(unknown.len() as u64, Ordering::Relaxed);
+        (known, unknown)
+    }
+
+    /// Cache what a full fetch returned; false if `address` can't use the object
+    pub fn record(&self, data: &SuiObjectData, address: SuiAddress) -> bool {
+        let meta = ObjectMeta {
+            owner: data.owner.as_ref().map_or(Holder::Other, Holder::of),
+            kind: data.type_.as_ref().map_or(ObjectKind::Other, |t| ObjectKind::of(&t.to_string())),
+        };
+        self.insert(data.object_id, meta);
+        let usable = meta.usable_by(address);
+        if !usable {
+            self.foreign.fetch_add(1, Ordering::Relaxed);
+        }
+        usable
+    }
+
+    pub fn insert(&self, id: ObjectID, meta: ObjectMeta) {
+        self.objects.lock().unwrap().insert(id, meta);
+    }
+
+    /// The node no longer returns the object
+    pub fn forget(&self, id: &ObjectID) {
+        if self.objects.lock().unwrap().remove(id).is_some() {
+            self.forgotten.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn report(&self) -> MetaCacheReport {
+        MetaCacheReport {
+            path: self.path.clone(),
+            loaded: self.loaded,
+            entries: self.objects.lock().unwrap().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            foreign: self.foreign.load(Ordering::Relaxed),
+            forgotten: self.forgotten.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_objects_cached_as_the_workers_own_skip_the_full_fetch() {
+        let cache = MetaCache::load("/nonexistent/meta-cache.json").unwrap();
+        let (me, other) = (SuiAddress::random_for_testing_only(), SuiAddress::random_for_testing_only());
+        let ids: Vec<ObjectID> = (1..=4).map(ObjectID::from_single_byte).collect();
+        cache.insert(ids[0], ObjectMeta { owner: Holder::Address(me), kind: ObjectKind::Blob });
+        cache.insert(ids[1], ObjectMeta { owner: Holder::Address(other), kind: ObjectKind::Blob });
+        cache.insert(ids[2], ObjectMeta { owner: Holder::Immutable, kind: ObjectKind::Counter });
+        let (known, unknown) = cache.partition(&ids, me);
+        assert_eq!(known, vec![ids[0]]);
+        assert_eq!(unknown, ids[1..].to_vec());
+        cache.forget(&ids[2]);
+        cache.forget(&ids[3]);
+        let report = cache.report();
+        assert_eq!((report.hits, report.misses, report.forgotten, report.entries), (1, 3, 1, 2));
+        assert_eq!(ObjectKind::of("0x2a::io_churn::LargeBlob"), ObjectKind::Blob);
+    }
+}
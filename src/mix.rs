@@ -0,0 +1,149 @@
+// Create/update mix realization (--mix-schedule, --mix-seed)
+//
+// By default every transaction rolls the dice against --create-pct, so a short
+// run (or one worker of a long one) can land several points away from the
+// requested mix. `--mix-schedule interleaved` spreads creates evenly among
+// updates instead, Bresenham-style: each worker adds --create-pct to an
+// accumulator per decision and creates whenever it crosses 100, so after any N
+// decisions the worker is within one transaction of the requested share.
+// `--mix-seed` seeds the worker RNGs instead (seed + worker id), so a random
+// schedule is at least reproducible. Either way the `mix` section reports what
+// the run actually did next to what was asked for.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::validate::Percent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MixSchedule {
+    /// Roll against --create-pct for every transaction
+    Random,
+    /// Spread creates evenly among updates (exact share over any window)
+    Interleaved,
+}
+
+/// A worker's create/update decisions
+#[derive(Debug, Clone)]
+pub struct CreateScheduler {
+    schedule: MixSchedule,
+    /// Interleaved: create percentage points owed so far, below 100
+    acc: u32,
+}
+
+impl CreateScheduler {
+    pub fn new(schedule: MixSchedule) -> Self {
+        Self { schedule, acc: 0 }
+    }
+
+    /// Whether the next built-in transaction creates; `roll` draws 0..100 for the random schedule
+    pub fn next(&mut self, create_pct: Percent, roll: impl FnOnce() -> u32) -> bool {
+        match self.schedule {
+            MixSchedule::Random => roll() < create_pct.get() as u32,
+            MixSchedule::Interleaved => {
+                self.acc += create_pct.get() as u32;
+                if self.acc >= 100 {
+                    self.acc -= 100;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// Built-in create/update decisions of all workers
+#[derive(Debug, Default)]
+pub struct MixStats {
+    creates: AtomicU64,
+    updates: AtomicU64,
+}
+
+impl MixStats {
+    pub fn record(&self, create: bool) {
+        let counter = if create { &self.creates } else { &self.updates };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn report(&self, schedule: MixSchedule, seed: Option<u64>, create_pct: Percent) -> MixReport {
+        let creates = self.creates.load(Ordering::Relaxed);
+        let updates = self.updates.load(Ordering::Relaxed);
+        let decisions = creates + updates;
+        let realized_create_pct = if decisions > 0 { creates as f64 * 100.0 / decisions as f64 } else { 0.0 };
+        MixReport {
+            schedule,
+            seed,
+            requested_create_pct: create_pct.get(),
+            creates,
+            updates,
+            realized_create_pct,
+            deviation_pct: realized_create_pct - create_pct.get() as f64,
+        }
+    }
+}
+
+/// `mix` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct MixReport {
+    pub schedule: MixSchedule,
+    pub seed: Option<u64>,
+    /// --create-pct at the start of the run (reloads and profiles may have changed it per worker)
+    pub requested_create_pct: u8,
+    /// Built-in transactions decided as creates / updates (waves, --workload and throttled updates excluded)
+    pub creates: u64,
+    pub updates: u64,
+    pub realized_create_pct: f64,
+    /// realized - requested, in percentage points
+    pub deviation_pct: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pct(v: u8) -> Percent {
+        Percent::try_from(v).unwrap()
+    }
+
+    #[test]
+    fn interleaved_schedule_hits_the_share_over_any_prefix() {
+        let mut scheduler = CreateScheduler::new(MixSchedule::Interleaved);
+        let decisions: Vec<bool> = (0..200).map(|_| scheduler.next(pct(30), || unreachable!())).collect();
+        for n in 1..=decisions.len() {
+            let creates = decisions[..n].iter().filter(|c| **c).count() as f64;
+            assert!((creates - n as f64 * 0.3).abs() < 1.0, "{} creates after {}", creates, n);
+        }
+        // Creates are spread out, never two in a row at 30%
+        assert!(!decisions.windows(2).any(|w| w[0] && w[1]));
+    }
+
+    #[test]
+    fn interleaved_schedule_handles_the_extremes() {
+        let mut never = CreateScheduler::new(MixSchedule::Interleaved);
+        assert!((0..100).all(|_| !never.next(pct(0), || 0)));
+        let mut always = CreateScheduler::new(MixSchedule::Interleaved);
+        assert!((0..100).all(|_| always.next(pct(100), || 99)));
+    }
+
+    #[test]
+    fn random_schedule_compares_the_roll() {
+        let mut scheduler = CreateScheduler::new(MixSchedule::Random);
+        assert!(scheduler.next(pct(5), || 4));
+        assert!(!scheduler.next(pct(5), || 5));
+    }
+
+    #[test]
+    fn report_gives_the_realized_share() {
+        let stats = MixStats::default();
+        for i in 0..40 {
+            stats.record(i % 8 == 0);
+        }
+        let report = stats.report(MixSchedule::Random, Some(7), pct(10));
+        assert_eq!((report.creates, report.updates), (5, 35));
+        assert_eq!(report.realized_create_pct, 12.5);
+        assert_eq!(report.deviation_pct, 2.5);
+    }
+}
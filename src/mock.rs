@@ -0,0 +1,427 @@
+// Mock backend (--backend mock)
+//
+// Transactions are still built and signed, then answered locally instead of
+// by a node: after a latency drawn from --mock-latency, either with a synthetic
+// failure (--mock-errors) or with effects derived from the transaction itself.
+// Owned inputs are mutated to the next lamport version, create_* calls create
+// as many objects as they ask for, delete_blob deletes its argument. Workers
+// get synthetic gas and seed objects, so pacing, stats, throttling, quarantine,
+// autosave and result output all run without a node and without spending gas.
+// Only features that read the chain (hot objects, freezing, gas smashing,
+// checkpoint/epoch/pruning samplers, ...) are unavailable.
+//
+// Latency specs (milliseconds):
+//   fixed:20              every transaction takes 20ms
+//   uniform:5-50          uniform in [5, 50]
+//   lognormal:20,0.5      median 20ms, sigma 0.5
+//
+// Error specs: kind=pct, e.g. lock=1,timeout=0.5
+//   lock      owned object lock conflict (quarantines the object)
+//   timeout   response lost
+//   abort     Move abort
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use futures::future::BoxFuture;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_sdk::types::crypto::{get_key_pair, AccountKeyPair, EncodeDecodeBase64, SuiKeyPair};
+use sui_sdk::types::transaction::{Argument, CallArg, Command, ObjectArg, Transaction, TransactionData, TransactionDataAPI, TransactionKind};
+use tokio::sync::RwLock;
+use tokio::time::sleep;
+use tracing::info;
+
+use crate::executor::TxExecutor;
+use crate::keystore::Keystore;
+use crate::lookup::{ObjectOrigin, OriginOp};
+use crate::workload::Effects;
+use crate::{gas_streams, load, Args, GasCharge, TrackedObject, TxOutcome, WorkerState};
+
+/// Gas units charged per transaction and per object it touches
+const GAS_UNITS_PER_TX: u64 = 1_000;
+const GAS_UNITS_PER_OBJECT: u64 = 200;
+/// A deleted object's storage deposit comes back, worth more than touching it costs
+const REBATE_UNITS_PER_DELETE: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Submit to the Sui node at --rpc-url
+    Node,
+    /// Answer transactions locally with synthetic latency, errors and effects
+    Mock,
+}
+
+/// Synthetic transaction latency
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyDist {
+    Fixed(f64),
+    Uniform { min: f64, max: f64 },
+    LogNormal { median: f64, sigma: f64 },
+}
+
+impl FromStr for LatencyDist {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (kind, params) = spec.split_once(':')
+            .ok_or_else(|| format!("invalid latency distribution '{}': expected kind:params", spec))?;
+        let parse_ms = |s: &str| -> Result<f64, String> {
+            let v: f64 = s.trim().trim_end_matches("ms").parse().map_err(|_| format!("invalid latency '{}'", s))?;
+            if !(v >= 0.0 && v.is_finite()) {
+                return Err(format!("latency {} must be a non-negative number of milliseconds", v));
+            }
+            Ok(v)
+        };
+
+        match kind.trim() {
+            "fixed" => Ok(LatencyDist::Fixed(parse_ms(params)?)),
+            "uniform" => {
+                let (min, max) = params.split_once('-')
+                    .ok_or_else(|| "uniform expects min-max, e.g. uniform:5-50".to_string())?;
+                let (min, max) = (parse_ms(min)?, parse_ms(max)?);
+                if min > max {
+                    return Err(format!("uniform range {}-{} is empty", min, max));
+                }
+                Ok(LatencyDist::Uniform { min, max })
+            }
+            "lognormal" => {
+                let (median, sigma) = params.split_once(',')
+                    .ok_or_else(|| "lognormal expects median,sigma, e.g. lognormal:20,0.5".to_string())?;
+                let median = parse_ms(median)?;
+                let sigma: f64 = sigma.trim().parse().map_err(|_| format!("invalid sigma '{}'", sigma))?;
+                if sigma < 0.0 {
+                    return Err("lognormal sigma must be >= 0".to_string());
+                }
+                Ok(LatencyDist::LogNormal { median, sigma })
+            }
+            other => Err(format!("unknown latency distribution '{}' (fixed, uniform, lognormal)", other)),
+        }
+    }
+}
+
+impl LatencyDist {
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Duration {
+        let ms = match self {
+            LatencyDist::Fixed(ms) => *ms,
+            LatencyDist::Uniform { min, max } => rng.gen_range(*min..=*max),
+            LatencyDist::LogNormal { median, sigma } => {
+                // Box-Muller standard normal, then scale to the lognormal
+                let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                median * (sigma * z).exp()
+            }
+        };
+        Duration::from_secs_f64(ms / 1000.0)
+    }
+}
+
+/// Kind of synthetic failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorKind {
+    Lock,
+    Timeout,
+    Abort,
+}
+
+/// `--mock-errors` entry: a failure kind and the share of transactions that get it
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorSpec {
+    pub kind: ErrorKind,
+    pub pct: f64,
+}
+
+impl FromStr for ErrorSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, pct) = s.split_once('=').ok_or_else(|| format!("expected <kind>=<pct>, got '{}'", s))?;
+        let kind = match kind.trim() {
+            "lock" => ErrorKind::Lock,
+            "timeout" => ErrorKind::Timeout,
+            "abort" => ErrorKind::Abort,
+            other => return Err(format!("unknown error kind '{}' (lock, timeout, abort)", other)),
+        };
+        let pct: f64 = pct.trim().trim_end_matches('%').parse().map_err(|_| format!("invalid percentage '{}'", pct))?;
+        if !(0.0..=100.0).contains(&pct) {
+            return Err(format!("{} is not a percentage (0-100)", pct));
+        }
+        Ok(Self { kind, pct })
+    }
+}
+
+/// Checks for --backend mock
+pub fn validate(args: &Args) -> Result<()> {
+    let total: f64 = args.mock_errors.iter().map(|e| e.pct).sum();
+    if total > 100.0 {
+        return Err(anyhow!("--mock-errors add up to {}%, more than 100%", total));
+    }
+    if args.backend == Backend::Node {
+        if !args.mock_errors.is_empty() {
+            return Err(anyhow!("--mock-errors needs --backend mock"));
+        }
+        return Ok(());
+    }
+    let node_only = [
+        (args.hot_objects > 0, "--hot-objects"),
+        (args.freeze_pct.get() > 0, "--freeze-pct"),
+        (args.gas_smash > 1, "--gas-smash"),
+        (args.gas_coins_per_worker > 1, "--gas-coins-per-worker"),
+        (args.identity_cache.is_some(), "--identity-cache"),
+        (args.meta_cache.is_some(), "--meta-cache"),
+        (args.sample_checkpoints, "--sample-checkpoints"),
+        (args.rpc_probe_ms.is_some(), "--rpc-probe-ms"),
+        (args.verify_effects_pct.get() > 0, "--verify-effects-pct"),
+        (args.record_pruning, "--record-pruning"),
+        (args.resolve_dropped, "--resolve-dropped"),
+        (args.reshard_workers.is_some(), "--reshard-workers"),
+        (args.manage_node.is_some(), "--manage-node"),
+        (args.require_fresh_db, "--require-fresh-db"),
+    ];
+    if let Some((_, flag)) = node_only.iter().find(|(set, _)| *set) {
+        return Err(anyhow!("{} needs a node; it can't be used with --backend mock", flag));
+    }
+    Ok(())
+}
+
+/// `mock_backend` section: the results are synthetic
+#[derive(Debug, Serialize)]
+pub struct MockSummary<'a> {
+    pub latency: &'a LatencyDist,
+    pub errors: &'a [ErrorSpec],
+}
+
+/// Answers transactions locally (see the top of this file)
+pub struct MockBackend {
+    latency: LatencyDist,
+    errors: Vec<ErrorSpec>,
+}
+
+impl MockBackend {
+    pub fn new(args: &Args) -> Self {
+        Self { latency: args.mock_latency.clone(), errors: args.mock_errors.clone() }
+    }
+
+    fn roll_error(&self, rng: &mut impl Rng) -> Option<ErrorKind> {
+        let mut roll = rng.gen_range(0.0..100.0);
+        for spec in &self.errors {
+            if roll < spec.pct {
+                return Some(spec.kind);
+            }
+            roll -= spec.pct;
+        }
+        None
+    }
+
+    /// Wait out the latency, then fail or answer with the transaction's effects
+    async fn answer(&self, tx_data: &TransactionData) -> Result<Effects> {
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        sleep(self.latency.sample(&mut rng)).await;
+        let effects = synthesize(tx_data);
+        match self.roll_error(&mut rng) {
+            None => Ok(effects),
+            Some(ErrorKind::Lock) => {
+                // Named like a node would, so the object is quarantined
+                let locked = effects.mutated.first().or(effects.gas_coin.as_ref())
+                    .map_or("?".to_string(), |(id, _, _)| id.to_string());
+                Err(anyhow!("ObjectLockConflict: object {} is already locked by a conflicting transaction (mock backend)", locked))
+            }
+            Some(ErrorKind::Timeout) => Err(anyhow!("Request timed out waiting for effects (mock backend)")),
+            Some(ErrorKind::Abort) => Err(anyhow!("MoveAbort in command 0, abort code 1 (mock backend)")),
+        }
+    }
+}
+
+impl TxExecutor for MockBackend {
+    fn execute(&self, tx: Transaction) -> BoxFuture<'_, Result<Effects>> {
+        Box::pin(async move { self.answer(tx.transaction_data()).await })
+    }
+
+    fn simulate(&self, tx_data: TransactionData, gas_id: ObjectID, object_bytes: u64) -> BoxFuture<'_, Result<TxOutcome>> {
+        Box::pin(async move {
+            let effects = self.answer(&tx_data).await?;
+            let created = effects.created.len() as u64;
+            let updated = effects.mutated.iter().filter(|(id, _, _)| *id != gas_id).count() as u64;
+            Ok(TxOutcome {
+                created,
+                updated,
+                gas_used: effects.gas_used,
+                charge: effects.charge,
+                bytes_written: (created + updated) * object_bytes,
+                ..TxOutcome::empty(effects.digest)
+            })
+        })
+    }
+}
+
+/// First pure argument of a call, read as a u64 (the count of create_* calls)
+fn pure_u64(inputs: &[CallArg], args: &[Argument]) -> Option<u64> {
+    args.iter().find_map(|arg| match arg {
+        Argument::Input(i) => match inputs.get(*i as usize) {
+            Some(CallArg::Pure(bytes)) => bytes.as_slice().try_into().ok().map(u64::from_le_bytes),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// What executing the transaction would do to its owned objects
+fn synthesize(tx_data: &TransactionData) -> Effects {
+    let mut effects = Effects::empty(TransactionDigest::random());
+    let gas = tx_data.gas_data();
+    let pt = match tx_data.kind() {
+        TransactionKind::ProgrammableTransaction(pt) => pt,
+        #[allow(unreachable_patterns)]
+        _ => return effects,
+    };
+
+    let owned: Vec<_> = pt.inputs.iter()
+        .filter_map(|input| match input {
+            CallArg::Object(ObjectArg::ImmOrOwnedObject(obj_ref)) => Some(*obj_ref),
+            _ => None,
+        })
+        .collect();
+    // Everything the transaction writes moves to one new lamport version
+    let lamport = owned.iter().chain(&gas.payment).map(|(_, v, _)| v.value()).max().unwrap_or(0) + 1;
+    let version = SequenceNumber::from_u64(lamport);
+
+    let mut deleted = Vec::new();
+    for command in &pt.commands {
+        let Command::MoveCall(call) = command else { continue };
+        let function = call.function.as_str();
+        if function.starts_with("create") {
+            let count = pure_u64(&pt.inputs, &call.arguments).unwrap_or(1);
+            for _ in 0..count {
+                effects.created.push((ObjectID::random(), version, ObjectDigest::random()));
+            }
+        } else if function == "delete_blob" {
+            for arg in &call.arguments {
+                if let Argument::Input(i) = arg {
+                    if let Some(CallArg::Object(ObjectArg::ImmOrOwnedObject((id, _, _)))) = pt.inputs.get(*i as usize) {
+                        deleted.push(*id);
+                    }
+                }
+            }
+        }
+    }
+    effects.mutated = owned.iter()
+        .filter(|(id, _, _)| !deleted.contains(id))
+        .map(|(id, _, _)| (*id, version, ObjectDigest::random()))
+        .collect();
+    effects.deleted = deleted;
+
+    // Coins paid alongside the first are merged into it
+    if let Some((gas_id, _, _)) = gas.payment.first() {
+        effects.gas_coin = Some((*gas_id, version, ObjectDigest::random()));
+    }
+    let objects = (effects.created.len() + effects.mutated.len() + effects.deleted.len()) as u64;
+    effects.charge = GasCharge {
+        charged: gas.price * (GAS_UNITS_PER_TX + GAS_UNITS_PER_OBJECT * objects),
+        rebate: gas.price * REBATE_UNITS_PER_DELETE * effects.deleted.len() as u64,
+    };
+    effects.gas_used = effects.charge.charged.saturating_sub(effects.charge.rebate);
+    effects
+}
+
+fn synthetic_object(version: u64, origin: Option<ObjectOrigin>) -> TrackedObject {
+    TrackedObject { id: ObjectID::random(), version, digest: ObjectDigest::random(), size: 0, origin, updates: 0 }
+}
+
+/// Workers with synthetic gas: restored from --load-objects/--resume, or fresh with seed objects
+pub fn workers(args: &Args) -> Result<Vec<Arc<RwLock<WorkerState>>>> {
+    let gas_coin = || (ObjectID::random(), SequenceNumber::from_u64(1), ObjectDigest::random());
+    let load_path = if args.resume { args.autosave.as_ref() } else { args.load_objects.as_ref() };
+    let mut workers = Vec::with_capacity(args.workers);
+    if let Some(path) = load_path {
+        let saved = load::read(path, args)?;
+        info!("Mock backend: restoring {} workers with {} objects from {}", saved.workers.len(), saved.total_objects, path);
+        for w in saved.workers {
+            let keypair = SuiKeyPair::decode_base64(&w.keypair_base64)
+                .context(format!("Failed to decode keypair for worker {}", w.worker_id))?;
+            workers.push(WorkerState::new(w.worker_id, w.address, keypair, gas_coin(), w.objects));
+        }
+    } else {
+        info!("Mock backend: {} workers with {} synthetic seed objects each", args.workers, args.seed_objects);
+        let keystore = args.keystore.as_deref().map(Keystore::load).transpose()?;
+        for id in 0..args.workers {
+            let (address, keypair) = keystore.as_ref().and_then(|k| k.identity(id)).unwrap_or_else(|| {
+                let (address, keypair): (SuiAddress, AccountKeyPair) = get_key_pair();
+                (address, SuiKeyPair::Ed25519(keypair))
+            });
+            let mut state = WorkerState::new(id, address, keypair, gas_coin(), Vec::new());
+            let seed_tx = TransactionDigest::random();
+            for _ in 0..args.seed_objects {
+                state.track_created(synthetic_object(1, Some(ObjectOrigin::new(OriginOp::Seed, seed_tx))));
+            }
+            workers.push(state);
+        }
+    }
+    if args.gas_streams {
+        workers.iter_mut().for_each(gas_streams::mock);
+    }
+    Ok(workers.into_iter().map(|w| Arc::new(RwLock::new(w))).collect())
+}
+
+/// Nothing is really locked: quarantined objects go straight back into rotation
+pub fn release_quarantine(state: &mut WorkerState) -> usize {
+    let released: Vec<TrackedObject> = std::mem::take(&mut state.quarantine).into_iter().map(|q| q.object).collect();
+    let count = released.len();
+    state.objects.extend(released);
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_specs_parse_and_sample_in_range() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        assert_eq!("fixed:20".parse::<LatencyDist>().unwrap().sample(&mut rng), Duration::from_millis(20));
+        let uniform: LatencyDist = "uniform:5ms-50ms".parse().unwrap();
+        for _ in 0..100 {
+            let d = uniform.sample(&mut rng);
+            assert!(d >= Duration::from_millis(5) && d <= Duration::from_millis(50));
+        }
+        assert!("lognormal:20,0.5".parse::<LatencyDist>().is_ok());
+        assert!("uniform:50-5".parse::<LatencyDist>().is_err());
+        assert!("normal:20".parse::<LatencyDist>().is_err());
+    }
+
+    #[test]
+    fn error_specs_parse() {
+        let spec: ErrorSpec = "lock=1.5%".parse().unwrap();
+        assert_eq!((spec.kind, spec.pct), (ErrorKind::Lock, 1.5));
+        assert!("timeout=101".parse::<ErrorSpec>().is_err());
+        assert!("oom=1".parse::<ErrorSpec>().is_err());
+    }
+
+    #[test]
+    fn error_rolls_follow_the_shares() {
+        let backend = MockBackend {
+            latency: LatencyDist::Fixed(0.0),
+            errors: vec![ErrorSpec { kind: ErrorKind::Lock, pct: 10.0 }, ErrorSpec { kind: ErrorKind::Abort, pct: 30.0 }],
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let rolls: Vec<_> = (0..10_000).map(|_| backend.roll_error(&mut rng)).collect();
+        let share = |kind| rolls.iter().filter(|r| **r == kind).count() as f64 / 100.0;
+        assert!((share(Some(ErrorKind::Lock)) - 10.0).abs() < 2.0);
+        assert!((share(Some(ErrorKind::Abort)) - 30.0).abs() < 2.0);
+        assert_eq!(share(Some(ErrorKind::Timeout)), 0.0);
+    }
+
+    #[test]
+    fn pure_count_is_read_from_the_call_arguments() {
+        let inputs = vec![CallArg::Object(ObjectArg::ImmOrOwnedObject((ObjectID([1; 32]), SequenceNumber(1), ObjectDigest([1; 32])))),
+            CallArg::Pure(20u64.to_le_bytes().to_vec())];
+        assert_eq!(pure_u64(&inputs, &[Argument::Input(0), Argument::Input(1)]), Some(20));
+        assert_eq!(pure_u64(&inputs, &[Argument::Input(0)]), None);
+    }
+}
@@ -0,0 +1,180 @@
+// Managed sui node lifecycle (--manage-node)
+//
+// Starts a local network (`sui start`) or a single sui-node from a config file
+// before the run, waits until its RPC answers, and stops it again afterwards -
+// optionally wiping its DB directory - so a cold-start experiment is one
+// command. The node's stdout/stderr go to --node-log.
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fs::File;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use sui_sdk::SuiClientBuilder;
+use tokio::process::{Child, Command};
+use tokio::time::{sleep, timeout};
+use tracing::{info, warn};
+
+use crate::Args;
+
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Grace period after SIGTERM before the node is killed
+const STOP_GRACE: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeMode {
+    /// `sui start --with-faucet --force-regenesis` (validators + fullnode + faucet)
+    Localnet,
+    /// `sui-node --config-path <--node-config>`
+    Node,
+}
+
+/// What happened to the managed node, embedded in the results
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeInfo {
+    pub mode: NodeMode,
+    pub command: Vec<String>,
+    pub ready_secs: f64,
+    /// Exited on SIGTERM within the grace period
+    pub stopped_cleanly: bool,
+    pub db_wiped: bool,
+}
+
+/// A node process owned by this run (killed on drop if not stopped explicitly)
+pub struct ManagedNode {
+    child: Child,
+    info: NodeInfo,
+    db_path: Option<String>,
+    wipe_db: bool,
+}
+
+fn node_command(args: &Args, mode: NodeMode) -> Result<Vec<String>> {
+    let cmd = match mode {
+        NodeMode::Localnet => {
+            let mut cmd = vec![
+                args.node_bin.clone().unwrap_or_else(|| "sui".into()),
+                "start".into(),
+                "--with-faucet".into(),
+                "--force-regenesis".into(),
+            ];
+            if let Some(config) = &args.node_config {
+                cmd.extend(["--network.config".into(), config.clone()]);
+            }
+            cmd
+        }
+        NodeMode::Node => {
+            let config = args.node_config.clone()
+                .ok_or_else(|| anyhow!("--manage-node node requires --node-config <fullnode.yaml>"))?;
+            vec![
+                args.node_bin.clone().unwrap_or_else(|| "sui-node".into()),
+                "--config-path".into(),
+                config,
+            ]
+        }
+    };
+    Ok(cmd)
+}
+
+impl ManagedNode {
+    /// Start the node per --manage-node and wait until its RPC answers (None if not managed)
+    pub async fn start(args: &Args) -> Result<Option<Self>> {
+        let Some(mode) = args.manage_node else {
+            return Ok(None);
+        };
+        if args.wipe_node_db && args.node_db.is_none() {
+            return Err(anyhow!("--wipe-node-db requires --node-db <dir>"));
+        }
+        let command = node_command(args, mode)?;
+
+        let log = File::create(&args.node_log).context(format!("Failed to create {}", args.node_log))?;
+        info!("Starting managed node: {} (log: {})", command.join(" "), args.node_log);
+        let child = Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .kill_on_drop(true)
+            .spawn()
+            .context(format!("Failed to start {}", command[0]))?;
+
+        let mut node = Self {
+            child,
+            info: NodeInfo { mode, command, ready_secs: 0.0, stopped_cleanly: false, db_wiped: false },
+            db_path: args.node_db.clone(),
+            wipe_db: args.wipe_node_db,
+        };
+        node.wait_ready(&args.rpc_url, Duration::from_secs(args.node_ready_timeout)).await?;
+        Ok(Some(node))
+    }
+
+    async fn wait_ready(&mut self, rpc_url: &str, limit: Duration) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(anyhow!("Managed node exited during startup ({})", status));
+            }
+            if let Ok(client) = SuiClientBuilder::default().build(rpc_url).await {
+                if client.read_api().get_latest_checkpoint_sequence_number().await.is_ok() {
+                    self.info.ready_secs = started.elapsed().as_secs_f64();
+                    info!("Managed node ready after {:.1}s", self.info.ready_secs);
+                    return Ok(());
+                }
+            }
+            if started.elapsed() > limit {
+                return Err(anyhow!("Managed node RPC at {} not ready after {}s", rpc_url, limit.as_secs()));
+            }
+            sleep(READY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// SIGTERM the node, kill it after a grace period, then wipe the DB if requested
+    pub async fn stop(mut self) -> Result<NodeInfo> {
+        if let Some(pid) = self.child.id() {
+            info!("Stopping managed node (pid {})...", pid);
+            // SAFETY: plain kill(2) on our own child's pid
+            #[cfg(unix)]
+            unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+            // No SIGTERM elsewhere: terminate right away
+            #[cfg(not(unix))]
+            self.child.start_kill()?;
+            match timeout(STOP_GRACE, self.child.wait()).await {
+                Ok(status) => self.info.stopped_cleanly = status.is_ok(),
+                Err(_) => {
+                    warn!("Managed node did not exit within {}s, killing", STOP_GRACE.as_secs());
+                    self.child.kill().await?;
+                }
+            }
+        }
+        if self.wipe_db {
+            if let Some(path) = &self.db_path {
+                std::fs::remove_dir_all(path).context(format!("Failed to wipe node DB {}", path))?;
+                self.info.db_wiped = true;
+                info!("Wiped node DB {}", path);
+            }
+        }
+        Ok(self.info.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn args(argv: &[&str]) -> Args {
+        Args::try_parse_from([&["fdp-sui-bench"], argv].concat()).unwrap()
+    }
+
+    #[test]
+    fn node_commands_follow_the_mode() {
+        assert_eq!(node_command(&args(&[]), NodeMode::Localnet).unwrap(),
+            vec!["sui", "start", "--with-faucet", "--force-regenesis"]);
+        assert_eq!(node_command(&args(&["--node-config", "net"]), NodeMode::Localnet).unwrap(),
+            vec!["sui", "start", "--with-faucet", "--force-regenesis", "--network.config", "net"]);
+        assert_eq!(node_command(&args(&["--node-bin", "/opt/sui-node", "--node-config", "fullnode.yaml"]), NodeMode::Node).unwrap(),
+            vec!["/opt/sui-node", "--config-path", "fullnode.yaml"]);
+        assert!(node_command(&args(&[]), NodeMode::Node).is_err());
+    }
+}
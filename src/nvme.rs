@@ -0,0 +1,515 @@
+// NVMe SMART / OCP log capture (--nvme-devices)
+//
+// Reads the SMART / Health Information log (LID 0x02) and, where supported, the
+// OCP Datacenter SMART / Health log (LID 0xC0) via NVMe admin passthrough at run
+// start and end. SMART data units written counts host writes; the OCP physical
+// media units written counts what the NAND actually absorbed, so the two deltas
+// give write amplification for the run directly. Needs CAP_SYS_ADMIN.
+//
+// The same passthrough path also samples FDP telemetry (see below).
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::timeline::{Ticker, Timestamp};
+use crate::BenchStats;
+
+const ADMIN_GET_LOG_PAGE: u8 = 0x02;
+const LID_SMART: u8 = 0x02;
+const LID_OCP_SMART: u8 = 0xC0;
+const LOG_PAGE_BYTES: usize = 512;
+/// Controller-wide log (all namespaces)
+const NSID_ALL: u32 = 0xFFFF_FFFF;
+/// SMART data units are thousands of 512-byte units
+const DATA_UNIT_BYTES: u64 = 512 * 1000;
+/// _IOWR('N', 0x41, struct nvme_admin_cmd)
+const NVME_IOCTL_ADMIN_CMD: u64 = 0xC048_4E41;
+/// _IO('N', 0x40) - namespace ID of a namespace block device
+const NVME_IOCTL_ID: u64 = 0x4E40;
+/// _IOWR('N', 0x43, struct nvme_passthru_cmd)
+const NVME_IOCTL_IO_CMD: u64 = 0xC048_4E43;
+
+/// struct nvme_passthru_cmd from <linux/nvme_ioctl.h>
+#[repr(C)]
+#[derive(Default)]
+struct NvmePassthruCmd {
+    opcode: u8,
+    flags: u8,
+    rsvd1: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    addr: u64,
+    metadata_len: u32,
+    data_len: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeout_ms: u32,
+    result: u32,
+}
+
+/// Raw counters from one capture
+#[derive(Debug, Clone, Serialize)]
+pub struct NvmeCounters {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub data_units_read: u64,
+    pub data_units_written: u64,
+    pub host_read_commands: u64,
+    pub host_write_commands: u64,
+    /// OCP physical media units written/read (bytes); None if the log page is unsupported
+    pub physical_media_bytes_written: Option<u64>,
+    pub physical_media_bytes_read: Option<u64>,
+}
+
+/// Start/end captures and deltas for one device
+#[derive(Debug, Clone, Serialize)]
+pub struct NvmeReport {
+    pub start: NvmeCounters,
+    pub end: NvmeCounters,
+    pub host_bytes_written: u64,
+    pub host_bytes_read: u64,
+    pub host_write_commands: u64,
+    pub physical_media_bytes_written: Option<u64>,
+    /// physical_media_bytes_written / host_bytes_written
+    pub waf: Option<f64>,
+}
+
+fn device_path(dev: &str) -> String {
+    if dev.starts_with('/') { dev.to_string() } else { format!("/dev/{}", dev) }
+}
+
+/// Issue a passthrough command whose data buffer is `buf`
+#[cfg(unix)]
+fn passthru(file: &File, request: u64, mut cmd: NvmePassthruCmd, buf: &mut [u8], what: &str) -> Result<()> {
+    cmd.addr = buf.as_mut_ptr() as u64;
+    cmd.data_len = buf.len() as u32;
+    // SAFETY: cmd matches the kernel's nvme_passthru_cmd layout and addr points to
+    // a live buffer of data_len bytes for the duration of the call
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), request as _, &mut cmd as *mut NvmePassthruCmd) };
+    if ret < 0 {
+        return Err(anyhow!("{} failed: {}", what, std::io::Error::last_os_error()));
+    }
+    if ret > 0 {
+        return Err(anyhow!("{} failed: NVMe status 0x{:X}", what, ret));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn passthru(_file: &File, _request: u64, _cmd: NvmePassthruCmd, _buf: &mut [u8], what: &str) -> Result<()> {
+    Err(anyhow!("{} failed: NVMe passthrough needs Linux", what))
+}
+
+/// Get Log Page with log specific field / identifier (cdw10 LSP, cdw11 LSI)
+fn get_log_page_ext(file: &File, lid: u8, nsid: u32, lsp: u8, lsi: u16, len: usize) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; len];
+    let numd = (len / 4 - 1) as u32;
+    let cmd = NvmePassthruCmd {
+        opcode: ADMIN_GET_LOG_PAGE,
+        nsid,
+        cdw10: lid as u32 | ((lsp as u32 & 0x7F) << 8) | ((numd & 0xFFFF) << 16),
+        cdw11: (numd >> 16) | ((lsi as u32) << 16),
+        ..Default::default()
+    };
+    passthru(file, NVME_IOCTL_ADMIN_CMD, cmd, &mut buf, &format!("Get Log Page 0x{:02X}", lid))?;
+    Ok(buf)
+}
+
+fn get_log_page(file: &File, lid: u8) -> Result<Vec<u8>> {
+    get_log_page_ext(file, lid, NSID_ALL, 0, 0, LOG_PAGE_BYTES)
+}
+
+/// 128-bit little-endian counter, saturated to u64 (JSON numbers are 64-bit)
+fn u64_at(buf: &[u8], offset: usize) -> u64 {
+    let v = u128::from_le_bytes(buf[offset..offset + 16].try_into().unwrap());
+    v.min(u64::MAX as u128) as u64
+}
+
+fn le_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(buf[offset..offset + 2].try_into().unwrap())
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+fn le_u64(buf: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_logs(dev: &str) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let path = device_path(dev);
+    let file = File::open(&path).context(format!("Failed to open {}", path))?;
+    let smart = get_log_page(&file, LID_SMART).context(format!("{}: SMART log", path))?;
+    Ok((smart, get_log_page(&file, LID_OCP_SMART).ok()))
+}
+
+fn capture(dev: &str, stats: &BenchStats) -> Result<NvmeCounters> {
+    let (smart, ocp) = read_logs(dev)?;
+    Ok(NvmeCounters {
+        at: Timestamp::now(stats),
+        data_units_read: u64_at(&smart, 32),
+        data_units_written: u64_at(&smart, 48),
+        host_read_commands: u64_at(&smart, 64),
+        host_write_commands: u64_at(&smart, 80),
+        physical_media_bytes_written: ocp.as_ref().map(|b| u64_at(b, 0)),
+        physical_media_bytes_read: ocp.as_ref().map(|b| u64_at(b, 16)),
+    })
+}
+
+/// Check every device answers a SMART log read before the run starts
+pub fn validate(devs: &[String]) -> Result<()> {
+    for dev in devs {
+        let (_, ocp) = read_logs(dev)?;
+        if ocp.is_none() {
+            warn!("{}: OCP SMART log (0xC0) not supported - WAF will not be computed", dev);
+        }
+    }
+    Ok(())
+}
+
+/// Capture counters for all devices (devices that fail are logged and skipped)
+pub fn capture_all(devs: &[String], stats: &BenchStats) -> BTreeMap<String, NvmeCounters> {
+    devs.iter()
+        .filter_map(|dev| match capture(dev, stats) {
+            Ok(c) => Some((dev.clone(), c)),
+            Err(e) => {
+                warn!("NVMe capture: {:#}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Pair start and end captures into per-device reports
+pub fn reports(
+    start: BTreeMap<String, NvmeCounters>,
+    mut end: BTreeMap<String, NvmeCounters>,
+) -> BTreeMap<String, NvmeReport> {
+    start.into_iter()
+        .filter_map(|(dev, start)| {
+            let end = end.remove(&dev)?;
+            let host_bytes_written = end.data_units_written.saturating_sub(start.data_units_written) * DATA_UNIT_BYTES;
+            let physical = match (start.physical_media_bytes_written, end.physical_media_bytes_written) {
+                (Some(s), Some(e)) => Some(e.saturating_sub(s)),
+                _ => None,
+            };
+            let waf = physical.filter(|_| host_bytes_written > 0)
+                .map(|p| p as f64 / host_bytes_written as f64);
+            match waf {
+                Some(waf) => info!("NVMe {}: {:.1} MiB host writes, WAF {:.3}",
+                    dev, host_bytes_written as f64 / (1024.0 * 1024.0), waf),
+                None => info!("NVMe {}: {:.1} MiB host writes",
+                    dev, host_bytes_written as f64 / (1024.0 * 1024.0)),
+            }
+            let report = NvmeReport {
+                host_bytes_written,
+                host_bytes_read: end.data_units_read.saturating_sub(start.data_units_read) * DATA_UNIT_BYTES,
+                host_write_commands: end.host_write_commands.saturating_sub(start.host_write_commands),
+                physical_media_bytes_written: physical,
+                waf,
+                start,
+                end,
+            };
+            Some((dev, report))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// FDP telemetry (--sample-fdp)
+//
+// For FDP-enabled namespaces the endurance-group scoped FDP log pages are read
+// every stats interval: FDP Statistics (0x22: host/media bytes written, media
+// erased), Reclaim Unit Handle Usage (0x21) and FDP Events (0x23, host and
+// controller events), plus the per-placement-handle Reclaim Unit Available
+// Media Writes from I/O Management Receive (RUH Status). Devices are namespace
+// block devices (nvme0n1) so the namespace ID can be resolved.
+// ---------------------------------------------------------------------------
+
+const LID_FDP_RUH_USAGE: u8 = 0x21;
+const LID_FDP_STATS: u8 = 0x22;
+const LID_FDP_EVENTS: u8 = 0x23;
+const FDP_STATS_BYTES: usize = 64;
+const FDP_EVENT_BYTES: usize = 64;
+/// Header plus 63 event entries
+const FDP_EVENTS_LOG_BYTES: usize = 64 * FDP_EVENT_BYTES;
+const FDP_RUH_USAGE_BYTES: usize = 4096;
+const IO_MGMT_RECEIVE: u8 = 0x12;
+const IO_MGMT_RUH_STATUS: u32 = 0x01;
+const RUH_STATUS_BYTES: usize = 4096;
+
+/// FDP Statistics log page (bytes)
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct FdpStats {
+    pub host_bytes_written: u64,
+    pub media_bytes_written: u64,
+    pub media_bytes_erased: u64,
+}
+
+/// Reclaim unit handle status of one placement ID
+#[derive(Debug, Clone, Serialize)]
+pub struct RuhStatus {
+    pub placement_id: u16,
+    pub ruh_id: u16,
+    /// Estimated Active Reclaim Unit Time Remaining (seconds)
+    pub time_remaining_secs: u32,
+    /// Reclaim Unit Available Media Writes (logical blocks)
+    pub available_media_writes: u64,
+}
+
+/// One FDP capture
+#[derive(Debug, Clone, Serialize)]
+pub struct FdpSample {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub stats: FdpStats,
+    /// Reclaim unit handles by attribute (unused / host_specified / controller_specified)
+    pub ruh_usage: BTreeMap<&'static str, u32>,
+    pub ruh_status: Vec<RuhStatus>,
+    /// Events currently in the host and controller event logs, by type
+    pub host_events: BTreeMap<String, u32>,
+    pub controller_events: BTreeMap<String, u32>,
+}
+
+/// Per-device FDP timeline plus run deltas
+#[derive(Debug, Clone, Serialize)]
+pub struct FdpReport {
+    pub samples: Vec<FdpSample>,
+    pub host_bytes_written: u64,
+    pub media_bytes_written: u64,
+    pub media_bytes_erased: u64,
+    /// media_bytes_written / host_bytes_written over the run
+    pub waf: Option<f64>,
+}
+
+fn fdp_event_name(kind: u8) -> String {
+    match kind {
+        0x00 => "ru_not_fully_written".into(),
+        0x01 => "ru_time_limit_exceeded".into(),
+        0x02 => "ctrl_reset_modified_ruh".into(),
+        0x03 => "invalid_placement_id".into(),
+        0x80 => "media_reallocated".into(),
+        0x81 => "implicitly_modified_ruh".into(),
+        other => format!("type_0x{:02x}", other),
+    }
+}
+
+#[cfg(unix)]
+fn namespace_id(file: &File) -> Result<u32> {
+    // SAFETY: NVME_IOCTL_ID takes no argument and returns the NSID
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), NVME_IOCTL_ID as _) };
+    if ret <= 0 {
+        return Err(anyhow!("NVME_IOCTL_ID failed (not a namespace device?): {}", std::io::Error::last_os_error()));
+    }
+    Ok(ret as u32)
+}
+
+#[cfg(not(unix))]
+fn namespace_id(_file: &File) -> Result<u32> {
+    Err(anyhow!("NVME_IOCTL_ID failed: NVMe passthrough needs Linux"))
+}
+
+fn fdp_events(file: &File, endgrp: u16, host: bool) -> Result<BTreeMap<String, u32>> {
+    let buf = get_log_page_ext(file, LID_FDP_EVENTS, NSID_ALL, host as u8, endgrp, FDP_EVENTS_LOG_BYTES)?;
+    let count = (le_u32(&buf, 0) as usize).min(FDP_EVENTS_LOG_BYTES / FDP_EVENT_BYTES - 1);
+    let mut by_type = BTreeMap::new();
+    for i in 0..count {
+        *by_type.entry(fdp_event_name(buf[FDP_EVENT_BYTES * (i + 1)])).or_insert(0) += 1;
+    }
+    Ok(by_type)
+}
+
+fn ruh_status(file: &File, nsid: u32) -> Result<Vec<RuhStatus>> {
+    let mut buf = vec![0u8; RUH_STATUS_BYTES];
+    let cmd = NvmePassthruCmd {
+        opcode: IO_MGMT_RECEIVE,
+        nsid,
+        cdw10: IO_MGMT_RUH_STATUS,
+        cdw11: (RUH_STATUS_BYTES / 4 - 1) as u32,
+        ..Default::default()
+    };
+    passthru(file, NVME_IOCTL_IO_CMD, cmd, &mut buf, "I/O Management Receive (RUH Status)")?;
+    let count = (le_u16(&buf, 14) as usize).min((RUH_STATUS_BYTES - 16) / 32);
+    Ok((0..count)
+        .map(|i| {
+            let d = 16 + 32 * i;
+            RuhStatus {
+                placement_id: le_u16(&buf, d),
+                ruh_id: le_u16(&buf, d + 2),
+                time_remaining_secs: le_u32(&buf, d + 4),
+                available_media_writes: le_u64(&buf, d + 8),
+            }
+        })
+        .collect())
+}
+
+fn fdp_capture(dev: &str, endgrp: u16, stats: &BenchStats) -> Result<FdpSample> {
+    let path = device_path(dev);
+    let file = File::open(&path).context(format!("Failed to open {}", path))?;
+    let nsid = namespace_id(&file).context(path.clone())?;
+
+    let raw = get_log_page_ext(&file, LID_FDP_STATS, NSID_ALL, 0, endgrp, FDP_STATS_BYTES)
+        .context(format!("{}: FDP statistics", path))?;
+    let fdp_stats = FdpStats {
+        host_bytes_written: u64_at(&raw, 0),
+        media_bytes_written: u64_at(&raw, 16),
+        media_bytes_erased: u64_at(&raw, 32),
+    };
+
+    let mut ruh_usage = BTreeMap::new();
+    match get_log_page_ext(&file, LID_FDP_RUH_USAGE, NSID_ALL, 0, endgrp, FDP_RUH_USAGE_BYTES) {
+        Ok(buf) => {
+            let count = (le_u16(&buf, 0) as usize).min((FDP_RUH_USAGE_BYTES - 8) / 8);
+            for i in 0..count {
+                let attr = match buf[8 + 8 * i] {
+                    0 => "unused",
+                    1 => "host_specified",
+                    2 => "controller_specified",
+                    _ => "reserved",
+                };
+                *ruh_usage.entry(attr).or_insert(0) += 1;
+            }
+        }
+        Err(e) => debug!("{}: {:#}", path, e),
+    }
+
+    let ruh_status = ruh_status(&file, nsid).unwrap_or_else(|e| {
+        debug!("{}: {:#}", path, e);
+        Vec::new()
+    });
+
+    Ok(FdpSample {
+        at: Timestamp::now(stats),
+        stats: fdp_stats,
+        ruh_usage,
+        ruh_status,
+        host_events: fdp_events(&file, endgrp, true).unwrap_or_default(),
+        controller_events: fdp_events(&file, endgrp, false).unwrap_or_default(),
+    })
+}
+
+/// Check the FDP statistics log is readable on every device before the run starts
+pub fn validate_fdp(devs: &[String], endgrp: u16) -> Result<()> {
+    for dev in devs {
+        let path = device_path(dev);
+        let file = File::open(&path).context(format!("Failed to open {}", path))?;
+        namespace_id(&file).context(path.clone())?;
+        get_log_page_ext(&file, LID_FDP_STATS, NSID_ALL, 0, endgrp, FDP_STATS_BYTES)
+            .context(format!("{}: FDP statistics (is FDP enabled on endurance group {}?)", path, endgrp))?;
+    }
+    Ok(())
+}
+
+/// Sample FDP telemetry every stats interval until `running` is cleared (one final sample at the end)
+pub async fn run_fdp_sampler(
+    devs: Vec<String>,
+    endgrp: u16,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    ticker: Ticker,
+) -> BTreeMap<String, FdpReport> {
+    let mut timelines: BTreeMap<String, Vec<FdpSample>> = devs.iter().map(|d| (d.clone(), Vec::new())).collect();
+    let sample_all = |timelines: &mut BTreeMap<String, Vec<FdpSample>>| {
+        for (dev, samples) in timelines.iter_mut() {
+            match fdp_capture(dev, endgrp, &stats) {
+                Ok(sample) => samples.push(sample),
+                Err(e) => warn!("FDP sampler: {:#}", e),
+            }
+        }
+    };
+
+    sample_all(&mut timelines);
+    while running.load(Ordering::Relaxed) {
+        ticker.tick(stats.clock.as_ref()).await;
+        sample_all(&mut timelines);
+    }
+
+    timelines.into_iter()
+        .map(|(dev, samples)| {
+            let (first, last) = match (samples.first(), samples.last()) {
+                (Some(f), Some(l)) => (f.stats, l.stats),
+                _ => (FdpStats::default(), FdpStats::default()),
+            };
+            let host = last.host_bytes_written.saturating_sub(first.host_bytes_written);
+            let media = last.media_bytes_written.saturating_sub(first.media_bytes_written);
+            let report = FdpReport {
+                host_bytes_written: host,
+                media_bytes_written: media,
+                media_bytes_erased: last.media_bytes_erased.saturating_sub(first.media_bytes_erased),
+                waf: (host > 0).then(|| media as f64 / host as f64),
+                samples,
+            };
+            (dev, report)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(data_units_written: u64, physical_media_bytes_written: Option<u64>) -> NvmeCounters {
+        NvmeCounters {
+            at: Timestamp::default(),
+            data_units_read: 0,
+            data_units_written,
+            host_read_commands: 0,
+            host_write_commands: data_units_written * 10,
+            physical_media_bytes_written,
+            physical_media_bytes_read: None,
+        }
+    }
+
+    #[test]
+    fn counters_are_read_little_endian_and_saturate() {
+        let mut buf = vec![0u8; 64];
+        buf[16..32].copy_from_slice(&1234u128.to_le_bytes());
+        buf[32..48].copy_from_slice(&u128::MAX.to_le_bytes());
+        assert_eq!(u64_at(&buf, 16), 1234);
+        assert_eq!(u64_at(&buf, 32), u64::MAX);
+        assert_eq!((le_u16(&buf, 16), le_u32(&buf, 16), le_u64(&buf, 16)), (1234, 1234, 1234));
+    }
+
+    #[test]
+    fn waf_is_media_writes_over_host_writes() {
+        let start = BTreeMap::from([
+            ("nvme0".to_string(), counters(1_000, Some(0))),
+            ("nvme1".to_string(), counters(1_000, None)),
+            ("nvme2".to_string(), counters(1_000, Some(0))),
+        ]);
+        let end = BTreeMap::from([
+            ("nvme0".to_string(), counters(3_000, Some(2_000 * DATA_UNIT_BYTES * 3 / 2))),
+            ("nvme1".to_string(), counters(2_000, None)),
+        ]);
+
+        let reports = reports(start, end);
+
+        assert_eq!(reports.keys().collect::<Vec<_>>(), vec!["nvme0", "nvme1"]);
+        let nvme0 = &reports["nvme0"];
+        assert_eq!((nvme0.host_bytes_written, nvme0.host_write_commands), (2_000 * DATA_UNIT_BYTES, 20_000));
+        assert_eq!(nvme0.waf, Some(1.5));
+        assert_eq!((reports["nvme1"].physical_media_bytes_written, reports["nvme1"].waf), (None, None));
+    }
+
+    #[test]
+    fn unknown_fdp_events_keep_their_type_code() {
+        assert_eq!(fdp_event_name(0x80), "media_reallocated");
+        assert_eq!(fdp_event_name(0x7f), "type_0x7f");
+        assert_eq!(device_path("nvme0n1"), "/dev/nvme0n1");
+        assert_eq!(device_path("/dev/nvme0n1"), "/dev/nvme0n1");
+    }
+}
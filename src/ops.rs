@@ -0,0 +1,341 @@
+// Built-in owned-object operations
+//
+// Counter and blob creates and updates, run through workload::execute. Each
+// is only its Move call(s) and how the effects change the tracked objects.
+
+use anyhow::{anyhow, Result};
+use rand::{Rng, SeedableRng};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::ObjectArg;
+use sui_sdk::types::Identifier;
+
+use crate::abi;
+use crate::lookup::{ObjectOrigin, OriginOp};
+use crate::sizes::SizeDistribution;
+use crate::workload::{BuildContext, Effects, Planned, Workload};
+use crate::{
+    AppendUpdate, PartialUpdate, TrackedObject, TxOutcome, WorkerState,
+    BLOB_OBJECT_BYTES, COUNTER_OBJECT_BYTES, MAX_BLOB_BATCH,
+};
+
+fn call(builder: &mut ProgrammableTransactionBuilder, ctx: &BuildContext, function: &'static str, args: Vec<sui_sdk::types::transaction::Argument>) {
+    builder.programmable_move_call(
+        ctx.package_id,
+        Identifier::new("io_churn").unwrap(),
+        Identifier::new(abi::resolve(ctx.contract, function)).unwrap(),
+        vec![],
+        args,
+    );
+}
+
+/// Pass `count` tracked objects, starting at the worker's next batch position, to `add`,
+/// which adds `stride` commands for each
+pub fn for_update_batch(
+    state: &mut WorkerState,
+    count: usize,
+    stride: usize,
+    builder: &mut ProgrammableTransactionBuilder,
+    mut add: impl FnMut(&mut ProgrammableTransactionBuilder, sui_sdk::types::transaction::Argument) -> Result<()>,
+) -> Result<()> {
+    if state.objects.is_empty() {
+        return Err(anyhow!("No objects to update"));
+    }
+    let count = count.min(state.objects.len());
+    // Round-robin from a random start (sequential during a compaction wave)
+    let start_idx = state.batch_start(count, &mut rand::rngs::StdRng::from_entropy());
+    let mut batch = Vec::with_capacity(count);
+    for i in 0..count {
+        let obj = &state.objects[(start_idx + i) % state.objects.len()];
+        let obj_arg = builder.obj(ObjectArg::ImmOrOwnedObject((obj.id, obj.version.into(), obj.digest)))?;
+        batch.push(obj.id);
+        add(builder, obj_arg)?;
+    }
+    state.last_batch = batch;
+    state.batch_stride = stride;
+    Ok(())
+}
+
+/// New objects from the effects, to be tracked
+fn created(effects: &Effects, op: OriginOp, size: u64) -> Vec<TrackedObject> {
+    effects.created.iter()
+        .map(|(id, version, digest)| TrackedObject {
+            id: *id,
+            version: version.value(),
+            digest: *digest,
+            size,
+            origin: Some(ObjectOrigin::new(op, effects.digest)),
+            updates: 0,
+        })
+        .collect()
+}
+
+/// Move tracked objects to their new versions and count the rewrite; calls `on_update` for each one found
+pub fn update_versions(objects: &mut [TrackedObject], effects: &Effects, mut on_update: impl FnMut(&mut TrackedObject)) -> u64 {
+    let mut updated = 0;
+    for (id, version, digest) in &effects.mutated {
+        if let Some(obj) = objects.iter_mut().find(|o| o.id == *id) {
+            obj.version = version.value();
+            obj.digest = *digest;
+            obj.updates = obj.updates.saturating_add(1);
+            on_update(obj);
+            updated += 1;
+        }
+    }
+    updated
+}
+
+/// io_churn::create_batch (MicroCounter objects, ~100 bytes each)
+pub struct CreateCounters;
+
+impl Workload for CreateCounters {
+    fn name(&self) -> &'static str {
+        "create"
+    }
+
+    fn build_ptb(&self, _state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        let count_arg = builder.pure(ctx.batch_size as u64)?;
+        match ctx.tags {
+            Some(tags) => {
+                let tag_arg = builder.pure(tags.create)?;
+                call(builder, ctx, "create_batch_tagged", vec![count_arg, tag_arg]);
+            }
+            None => call(builder, ctx, "create_batch", vec![count_arg]),
+        }
+        Ok(Planned { object_bytes: COUNTER_OBJECT_BYTES, size: 0, objects: ctx.batch_size })
+    }
+
+    fn apply_effects(&self, _objects: &mut Vec<TrackedObject>, effects: &Effects, planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        let new = created(effects, OriginOp::Create, 0);
+        outcome.created = new.len() as u64;
+        outcome.bytes_written = outcome.created * planned.object_bytes;
+        new
+    }
+}
+
+/// io_churn::increment_simple on a batch of tracked counters
+pub struct UpdateCounters;
+
+impl Workload for UpdateCounters {
+    fn name(&self) -> &'static str {
+        "update"
+    }
+
+    fn build_ptb(&self, state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        let tag_arg = ctx.tags.map(|tags| builder.pure(tags.update)).transpose()?;
+        for_update_batch(state, ctx.batch_size, 1 + tag_arg.is_some() as usize, builder, |builder, obj_arg| {
+            call(builder, ctx, "increment_simple", vec![obj_arg]);
+            if let Some(tag_arg) = tag_arg {
+                call(builder, ctx, "tag_counter", vec![obj_arg, tag_arg]);
+            }
+            Ok(())
+        })?;
+        Ok(Planned { object_bytes: COUNTER_OBJECT_BYTES, size: 0, objects: state.last_batch.len() })
+    }
+
+    fn apply_effects(&self, objects: &mut Vec<TrackedObject>, effects: &Effects, planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        outcome.updated = update_versions(objects, effects, |_| {});
+        outcome.bytes_written = outcome.updated * planned.object_bytes;
+        Vec::new()
+    }
+}
+
+/// io_churn::create_blob_batch (4KB LargeBlob objects), or create_blob_batch_sized
+/// with one size drawn from the distribution for the whole batch
+pub struct CreateBlobs {
+    pub size_dist: Option<SizeDistribution>,
+}
+
+impl Workload for CreateBlobs {
+    fn name(&self) -> &'static str {
+        "create_blob"
+    }
+
+    fn build_ptb(&self, _state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        // 20 blobs = 80KB per TX
+        let objects = ctx.batch_size.min(MAX_BLOB_BATCH);
+        let count_arg = builder.pure(objects as u64)?;
+        if let Some(tags) = ctx.tags {
+            let size = self.size_dist.as_ref().map_or(BLOB_OBJECT_BYTES, |dist| dist.sample(&mut rand::rngs::StdRng::from_entropy()));
+            let size_arg = builder.pure(size)?;
+            let tag_arg = builder.pure(tags.create)?;
+            call(builder, ctx, "create_blob_batch_tagged", vec![count_arg, size_arg, tag_arg]);
+            let recorded = if self.size_dist.is_some() { size } else { 0 };
+            return Ok(Planned { object_bytes: size, size: recorded, objects });
+        }
+        match &self.size_dist {
+            Some(dist) => {
+                let size = dist.sample(&mut rand::rngs::StdRng::from_entropy());
+                let size_arg = builder.pure(size)?;
+                call(builder, ctx, "create_blob_batch_sized", vec![count_arg, size_arg]);
+                Ok(Planned { object_bytes: size, size, objects })
+            }
+            None => {
+                call(builder, ctx, "create_blob_batch", vec![count_arg]);
+                Ok(Planned { object_bytes: BLOB_OBJECT_BYTES, size: 0, objects })
+            }
+        }
+    }
+
+    fn apply_effects(&self, _objects: &mut Vec<TrackedObject>, effects: &Effects, planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        let new = created(effects, OriginOp::CreateBlob, planned.size);
+        outcome.created = new.len() as u64;
+        outcome.bytes_written = outcome.created * planned.object_bytes;
+        outcome.object_sizes = vec![planned.object_bytes; new.len()];
+        new
+    }
+}
+
+/// Blob rewrites: full (update_blob / update_blob_same_size), a slice
+/// (update_blob_partial, --update-bytes) or an append (append_blob, --append-bytes)
+pub struct UpdateBlobs {
+    pub partial: Option<PartialUpdate>,
+    pub append: Option<AppendUpdate>,
+    /// Keep each blob's size on full rewrites (mixed-size populations)
+    pub same_size: bool,
+}
+
+impl Workload for UpdateBlobs {
+    fn name(&self) -> &'static str {
+        "update_blob"
+    }
+
+    fn build_ptb(&self, state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned> {
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        let tag_arg = ctx.tags.map(|tags| builder.pure(tags.update)).transpose()?;
+        for_update_batch(state, ctx.batch_size.min(MAX_BLOB_BATCH), 1 + tag_arg.is_some() as usize, builder, |builder, obj_arg| {
+            match (self.append, self.partial) {
+                // Grow the blob instead of rewriting it
+                (Some(a), _) => {
+                    let len_arg = builder.pure(a.bytes)?;
+                    let max_arg = builder.pure(a.max_size)?;
+                    call(builder, ctx, "append_blob", vec![obj_arg, len_arg, max_arg]);
+                }
+                // Rewrite only a slice of the blob
+                (None, Some(p)) => {
+                    let offset = if p.random_offset { rng.gen_range(0..=BLOB_OBJECT_BYTES - p.bytes) } else { 0 };
+                    let offset_arg = builder.pure(offset)?;
+                    let len_arg = builder.pure(p.bytes)?;
+                    call(builder, ctx, "update_blob_partial", vec![obj_arg, offset_arg, len_arg]);
+                }
+                (None, None) => {
+                    let function = if self.same_size { "update_blob_same_size" } else { "update_blob" };
+                    call(builder, ctx, function, vec![obj_arg]);
+                }
+            }
+            if let Some(tag_arg) = tag_arg {
+                call(builder, ctx, "tag_blob", vec![obj_arg, tag_arg]);
+            }
+            Ok(())
+        })?;
+        let object_bytes = self.append.map(|a| a.bytes).or(self.partial.map(|p| p.bytes)).unwrap_or(BLOB_OBJECT_BYTES);
+        Ok(Planned { object_bytes, size: 0, objects: state.last_batch.len() })
+    }
+
+    fn apply_effects(&self, objects: &mut Vec<TrackedObject>, effects: &Effects, _planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject> {
+        let (mut bytes_written, mut object_sizes) = (0, Vec::new());
+        outcome.updated = update_versions(objects, effects, |obj| {
+            let size = if obj.size > 0 { obj.size } else { BLOB_OBJECT_BYTES };
+            if let Some(a) = self.append {
+                obj.size = a.grown(size);
+                bytes_written += a.bytes;
+                object_sizes.push(obj.size);
+                return;
+            }
+            bytes_written += self.partial.map_or(size, |p| p.bytes.min(size));
+            object_sizes.push(size);
+        });
+        outcome.bytes_written = bytes_written;
+        outcome.object_sizes = object_sizes;
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::types::base_types::{ObjectDigest, ObjectID, SequenceNumber, TransactionDigest};
+
+    fn id(n: u8) -> ObjectID {
+        ObjectID([n; 32])
+    }
+
+    fn tracked(n: u8, version: u64, size: u64) -> TrackedObject {
+        TrackedObject { id: id(n), version, digest: ObjectDigest([n; 32]), size, origin: None, updates: 0 }
+    }
+
+    fn effects(created: &[u8], mutated: &[(u8, u64)]) -> Effects {
+        let mut effects = Effects::empty(TransactionDigest([9; 32]));
+        effects.created = created.iter().map(|n| (id(*n), SequenceNumber(1), ObjectDigest([*n; 32]))).collect();
+        effects.mutated = mutated.iter().map(|(n, v)| (id(*n), SequenceNumber(*v), ObjectDigest([n + 100; 32]))).collect();
+        effects
+    }
+
+    fn outcome() -> TxOutcome {
+        TxOutcome::empty(TransactionDigest([9; 32]))
+    }
+
+    #[test]
+    fn create_counters_tracks_every_created_object() {
+        let effects = effects(&[1, 2, 3], &[]);
+        let planned = Planned { object_bytes: COUNTER_OBJECT_BYTES, size: 0, objects: 2 };
+        let mut out = outcome();
+        let new = CreateCounters.apply_effects(&mut Vec::new(), &effects, planned, &mut out);
+        assert_eq!(new.len(), 3);
+        assert_eq!(out.created, 3);
+        assert_eq!(out.bytes_written, 3 * COUNTER_OBJECT_BYTES);
+        let origin = new[0].origin.as_ref().unwrap();
+        assert_eq!(origin.op, OriginOp::Create);
+        assert_eq!(origin.digest, effects.digest);
+    }
+
+    #[test]
+    fn update_counters_bumps_versions_of_tracked_objects_only() {
+        let mut objects = vec![tracked(1, 1, 0), tracked(2, 1, 0)];
+        // 7 stands in for the gas coin: mutated, but not tracked
+        let effects = effects(&[], &[(1, 5), (7, 5)]);
+        let planned = Planned { object_bytes: COUNTER_OBJECT_BYTES, size: 0, objects: 2 };
+        let mut out = outcome();
+        UpdateCounters.apply_effects(&mut objects, &effects, planned, &mut out);
+        assert_eq!(out.updated, 1);
+        assert_eq!(out.bytes_written, COUNTER_OBJECT_BYTES);
+        assert_eq!((objects[0].version, objects[0].digest), (5, ObjectDigest([101; 32])));
+        assert_eq!((objects[0].updates, objects[1].updates), (1, 0));
+        assert_eq!(objects[1].version, 1);
+    }
+
+    #[test]
+    fn create_blobs_records_sampled_size() {
+        let op = CreateBlobs { size_dist: None };
+        let planned = Planned { object_bytes: 2048, size: 2048, objects: 2 };
+        let mut out = outcome();
+        let new = op.apply_effects(&mut Vec::new(), &effects(&[1, 2], &[]), planned, &mut out);
+        assert!(new.iter().all(|o| o.size == 2048));
+        assert_eq!(out.object_sizes, vec![2048, 2048]);
+        assert_eq!(out.bytes_written, 4096);
+    }
+
+    #[test]
+    fn update_blobs_counts_partial_bytes() {
+        let op = UpdateBlobs { partial: Some(PartialUpdate { bytes: 256, random_offset: false }), append: None, same_size: false };
+        let mut objects = vec![tracked(1, 1, 0), tracked(2, 1, 128)];
+        let mut out = outcome();
+        op.apply_effects(&mut objects, &effects(&[], &[(1, 2), (2, 2)]), Planned::default(), &mut out);
+        assert_eq!(out.updated, 2);
+        // A blob smaller than the slice only rewrites what it has
+        assert_eq!(out.bytes_written, 256 + 128);
+        assert_eq!(out.object_sizes, vec![BLOB_OBJECT_BYTES, 128]);
+    }
+
+    #[test]
+    fn update_blobs_grows_and_wraps_appended_blobs() {
+        let op = UpdateBlobs { partial: None, append: Some(AppendUpdate { bytes: 1000, max_size: 10_000 }), same_size: false };
+        let mut objects = vec![tracked(1, 1, 0), tracked(2, 1, 9500)];
+        let mut out = outcome();
+        op.apply_effects(&mut objects, &effects(&[], &[(1, 2), (2, 2)]), Planned::default(), &mut out);
+        assert_eq!(objects[0].size, BLOB_OBJECT_BYTES + 1000);
+        // Past max_size the blob starts over
+        assert_eq!(objects[1].size, 1000);
+        assert_eq!(out.bytes_written, 2000);
+        assert_eq!(out.object_sizes, vec![BLOB_OBJECT_BYTES + 1000, 1000]);
+    }
+}
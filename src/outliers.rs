@@ -0,0 +1,223 @@
+// Outlier transaction capture (--outlier-ms, --outlier-factor)
+//
+// Percentiles say how bad the tail is, not which transactions made it. Any
+// transaction slower than the outlier threshold is captured individually: its
+// digest (to look up on chain or in node logs), op type, how long it waited
+// for an inflight permit, and how many transactions were on the wire when it
+// was submitted and when it completed. The threshold is either fixed
+// (--outlier-ms) or a multiple of the run's p99.9 so far (--outlier-factor),
+// re-evaluated every few seconds once enough samples exist.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::timeline::Timestamp;
+use crate::{BenchStats, TxOutcome};
+
+/// Outliers kept (the rest are only counted)
+const MAX_OUTLIERS: usize = 1000;
+
+/// Successful samples needed before p99.9 is meaningful
+const MIN_SAMPLES: u64 = 1000;
+
+/// How often the relative threshold is re-evaluated
+const UPDATE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// No threshold (capture off, or not enough samples yet)
+const NO_THRESHOLD: u64 = u64::MAX;
+
+/// Timing of one transaction, taken by the worker
+pub struct TxTiming {
+    pub latency: Duration,
+    pub permit_wait: Duration,
+    pub inflight_at_submit: u64,
+    pub inflight_at_completion: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Outlier {
+    /// Completion time
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub worker: usize,
+    pub op: &'static str,
+    pub digest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub objects: u64,
+    pub latency_ms: f64,
+    pub permit_wait_ms: f64,
+    pub inflight_at_submit: u64,
+    pub inflight_at_completion: u64,
+    pub threshold_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutlierReport {
+    pub fixed_ms: Option<f64>,
+    pub factor: Option<f64>,
+    /// Threshold in force at the end of the run
+    pub threshold_ms: Option<f64>,
+    pub captured: usize,
+    /// Outliers beyond the first MAX_OUTLIERS (counted, not kept)
+    pub dropped: u64,
+    pub outliers: Vec<Outlier>,
+}
+
+pub struct OutlierStats {
+    threshold_us: AtomicU64,
+    outliers: Mutex<Vec<Outlier>>,
+    dropped: AtomicU64,
+}
+
+impl Default for OutlierStats {
+    fn default() -> Self {
+        Self { threshold_us: AtomicU64::new(NO_THRESHOLD), outliers: Mutex::default(), dropped: AtomicU64::new(0) }
+    }
+}
+
+impl OutlierStats {
+    pub fn set_threshold(&self, threshold: Duration) {
+        self.threshold_us.store(threshold.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn threshold(&self) -> Option<u64> {
+        Some(self.threshold_us.load(Ordering::Relaxed)).filter(|t| *t != NO_THRESHOLD)
+    }
+
+    /// Capture the transaction if it was slower than the threshold
+    pub fn observe(&self, stats: &BenchStats, worker: usize, op: &'static str, result: &Result<TxOutcome>, timing: TxTiming) {
+        let Some(threshold_us) = self.threshold() else {
+            return;
+        };
+        if (timing.latency.as_micros() as u64) < threshold_us {
+            return;
+        }
+        let mut outliers = self.outliers.lock().unwrap();
+        if outliers.len() >= MAX_OUTLIERS {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        let (digest, error, objects) = match result {
+            Ok(outcome) => (Some(outcome.digest.to_string()), None, outcome.created + outcome.updated + outcome.read),
+            Err(e) => (None, Some(format!("{:#}", e)), 0),
+        };
+        outliers.push(Outlier {
+            at: Timestamp::now(stats),
+            worker,
+            op,
+            digest,
+            error,
+            objects,
+            latency_ms: timing.latency.as_secs_f64() * 1000.0,
+            permit_wait_ms: timing.permit_wait.as_secs_f64() * 1000.0,
+            inflight_at_submit: timing.inflight_at_submit,
+            inflight_at_completion: timing.inflight_at_completion,
+            threshold_ms: threshold_us as f64 / 1000.0,
+        });
+    }
+
+    pub fn report(&self, fixed_ms: Option<f64>, factor: Option<f64>) -> OutlierReport {
+        let outliers = self.outliers.lock().unwrap().clone();
+        OutlierReport {
+            fixed_ms,
+            factor,
+            threshold_ms: self.threshold().map(|t| t as f64 / 1000.0),
+            captured: outliers.len(),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            outliers,
+        }
+    }
+}
+
+/// Keep the threshold at `factor` x p99.9 of all successful transactions so far
+pub async fn run_threshold_updater(stats: Arc<BenchStats>, running: Arc<AtomicBool>, factor: f64) {
+    while running.load(Ordering::Relaxed) {
+        stats.clock.sleep(UPDATE_INTERVAL).await;
+        let overall = stats.latency.overall();
+        if overall.counts().iter().sum::<u64>() < MIN_SAMPLES {
+            continue;
+        }
+        let p999 = overall.quantile_us(0.999) as f64;
+        stats.outliers.set_threshold(Duration::from_micros((p999 * factor) as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+    use sui_sdk::types::base_types::TransactionDigest;
+
+    fn timing(latency_ms: u64) -> TxTiming {
+        TxTiming {
+            latency: Duration::from_millis(latency_ms),
+            permit_wait: Duration::from_millis(5),
+            inflight_at_submit: 3,
+            inflight_at_completion: 7,
+        }
+    }
+
+    fn success(updated: u64) -> Result<TxOutcome> {
+        Ok(TxOutcome { updated, ..TxOutcome::empty(TransactionDigest::new([4; 32])) })
+    }
+
+    #[test]
+    fn only_transactions_past_the_threshold_are_captured() {
+        let stats = BenchStats::new(1, VirtualClock::new());
+        let outliers = OutlierStats::default();
+        // No threshold yet: nothing is captured
+        outliers.observe(&stats, 0, "update", &success(1), timing(5_000));
+
+        outliers.set_threshold(Duration::from_millis(100));
+        outliers.observe(&stats, 0, "update", &success(1), timing(99));
+        outliers.observe(&stats, 1, "update", &success(8), timing(100));
+        outliers.observe(&stats, 2, "create", &Err(anyhow::anyhow!("request timed out")), timing(250));
+
+        let report = outliers.report(Some(100.0), None);
+        assert_eq!((report.threshold_ms, report.captured, report.dropped), (Some(100.0), 2, 0));
+        let slow = &report.outliers[0];
+        assert_eq!((slow.worker, slow.objects, slow.latency_ms, slow.permit_wait_ms), (1, 8, 100.0, 5.0));
+        assert_eq!(slow.digest, Some(TransactionDigest::new([4; 32]).to_string()));
+        assert_eq!((slow.inflight_at_submit, slow.inflight_at_completion), (3, 7));
+        let failed = &report.outliers[1];
+        assert_eq!((failed.digest.as_deref(), failed.error.as_deref()), (None, Some("request timed out")));
+    }
+
+    #[test]
+    fn outliers_beyond_the_cap_are_only_counted() {
+        let stats = BenchStats::new(1, VirtualClock::new());
+        let outliers = OutlierStats::default();
+        outliers.set_threshold(Duration::ZERO);
+        for _ in 0..MAX_OUTLIERS + 5 {
+            outliers.observe(&stats, 0, "update", &success(1), timing(1));
+        }
+        let report = outliers.report(None, Some(3.0));
+        assert_eq!((report.captured, report.dropped), (MAX_OUTLIERS, 5));
+    }
+
+    #[test]
+    fn the_relative_threshold_follows_p999_once_there_are_enough_samples() {
+        let clock = VirtualClock::new();
+        let stats = Arc::new(BenchStats::new(1, clock.clone()));
+        let running = Arc::new(AtomicBool::new(true));
+        {
+            // Half the samples needed per interval, all at 20ms; stop after the third update
+            let (stats, running) = (stats.clone(), running.clone());
+            clock.on_sleep(move |elapsed| {
+                for _ in 0..MIN_SAMPLES / 2 {
+                    stats.latency.record("update", 1, Duration::from_millis(20));
+                }
+                running.store(elapsed < UPDATE_INTERVAL * 3, Ordering::Relaxed);
+            });
+        }
+
+        futures::executor::block_on(run_threshold_updater(stats.clone(), running, 3.0));
+
+        // 20ms lands in the bucket whose midpoint is 19.968ms
+        assert_eq!(stats.outliers.threshold(), Some(3 * 19_968));
+    }
+}
@@ -0,0 +1,215 @@
+// Target-TPS accuracy (--target-tps)
+//
+// Every stats interval records the rate that was asked for at that moment
+// (reloaded knobs and address stages included) next to the rate achieved.
+// After the run each paced interval is classified: on target, short because
+// the node rejected load (many failures), or short although nearly everything
+// submitted succeeded - the generator itself could not submit fast enough
+// (signing, permits, per-transaction latency in the pacing loop). Without this
+// a "1000 TPS" run that really ran at 700 reads as a node result.
+//
+// `hold` is the pause itself: after each transaction a worker sleeps on the
+// run's clock for its share of --target-write-bps or --target-tps.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::lockstep::Pace;
+use crate::timeline::IntervalSample;
+use crate::{Args, BenchStats};
+
+/// Achieved within this fraction of the request counts as on target
+const TOLERANCE: f64 = 0.10;
+/// Failed share of an interval's submissions from which a shortfall is the node's
+const REJECT_SHARE: f64 = 0.10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PacingStatus {
+    OnTarget,
+    /// Short of the request with few failures: the client could not keep up
+    GeneratorSaturated,
+    /// Short of the request because a large share of submissions failed
+    NodeRejected,
+}
+
+/// Total TPS requested right now, None when the run isn't paced by TPS
+///
+/// Workers pace at their --target-tps divided by the active senders, so the
+/// request is the mean over active workers; any unpaced worker makes it open-ended.
+pub fn requested_tps(stats: &BenchStats, paced_by_tps: bool) -> Option<f64> {
+    if !paced_by_tps {
+        return None;
+    }
+    let active = stats.stages.active().max(1);
+    let mut total = 0u64;
+    for worker_id in 0..active {
+        match stats.live.worker(worker_id)?.target_tps {
+            0 => return None,
+            tps => total += tps,
+        }
+    }
+    Some(total as f64 / active as f64)
+}
+
+/// Sleep for a worker's share of the rate target after a transaction that wrote `bytes`
+///
+/// By logical bytes if --target-write-bps is set, else by TPS (on the lockstep
+/// slot grid when there is one); the target is split among `senders` workers.
+pub async fn hold(args: &Args, senders: usize, bytes: u64, pace: Option<&mut Pace>, clock: &dyn Clock) {
+    if let Some(target_bps) = args.target_write_bps.filter(|bps| *bps > 0) {
+        let per_worker_bps = target_bps as f64 / senders as f64;
+        clock.sleep(Duration::from_secs_f64(bytes as f64 / per_worker_bps)).await;
+    } else if args.target_tps > 0 {
+        let target_interval = Duration::from_secs_f64(1.0 / args.target_tps as f64 * senders as f64);
+        match pace {
+            Some(pace) => pace.wait(target_interval, clock).await,
+            None => clock.sleep(target_interval).await,
+        }
+    }
+}
+
+/// `pacing` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct PacingReport {
+    pub paced_intervals: usize,
+    pub on_target: usize,
+    pub generator_saturated: usize,
+    pub node_rejected: usize,
+    pub requested_tps: f64,
+    pub achieved_tps: f64,
+    /// Mean |achieved - requested| / requested over paced intervals
+    pub mean_abs_error: f64,
+    /// Largest shortfall of a single interval, as a fraction of its request
+    pub worst_shortfall: f64,
+}
+
+/// Classify every paced interval and summarize; None if nothing was paced
+pub fn annotate(samples: &mut [IntervalSample]) -> Option<PacingReport> {
+    let mut report = PacingReport {
+        paced_intervals: 0,
+        on_target: 0,
+        generator_saturated: 0,
+        node_rejected: 0,
+        requested_tps: 0.0,
+        achieved_tps: 0.0,
+        mean_abs_error: 0.0,
+        worst_shortfall: 0.0,
+    };
+    let (mut requested_txs, mut achieved_txs, mut paced_secs) = (0.0, 0.0, 0.0);
+    let (mut prev_secs, mut prev_submitted, mut prev_failed) = (0.0, 0, 0);
+    for sample in samples.iter_mut() {
+        let dt = sample.at.elapsed_secs - prev_secs;
+        let submitted = sample.tx_submitted.saturating_sub(prev_submitted);
+        let failed = sample.tx_failed.saturating_sub(prev_failed);
+        (prev_secs, prev_submitted, prev_failed) = (sample.at.elapsed_secs, sample.tx_submitted, sample.tx_failed);
+        let Some(requested) = sample.requested_tps.filter(|r| *r > 0.0 && dt > 0.0) else {
+            continue;
+        };
+
+        let error = (sample.interval_tps - requested) / requested;
+        let status = if error >= -TOLERANCE {
+            PacingStatus::OnTarget
+        } else if submitted > 0 && failed as f64 / submitted as f64 >= REJECT_SHARE {
+            PacingStatus::NodeRejected
+        } else {
+            PacingStatus::GeneratorSaturated
+        };
+        sample.pacing = Some(status);
+        match status {
+            PacingStatus::OnTarget => report.on_target += 1,
+            PacingStatus::GeneratorSaturated => report.generator_saturated += 1,
+            PacingStatus::NodeRejected => report.node_rejected += 1,
+        }
+        report.paced_intervals += 1;
+        report.mean_abs_error += error.abs();
+        report.worst_shortfall = report.worst_shortfall.max(-error);
+        requested_txs += requested * dt;
+        achieved_txs += sample.interval_tps * dt;
+        paced_secs += dt;
+    }
+    if report.paced_intervals == 0 {
+        return None;
+    }
+    report.mean_abs_error /= report.paced_intervals as f64;
+    report.requested_tps = requested_txs / paced_secs;
+    report.achieved_tps = achieved_txs / paced_secs;
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+    use clap::Parser;
+
+    fn sample(secs: f64, submitted: u64, success: u64, requested: Option<f64>) -> IntervalSample {
+        let mut s = IntervalSample::default();
+        s.at.elapsed_secs = secs;
+        s.tx_submitted = submitted;
+        s.tx_success = success;
+        s.tx_failed = submitted - success;
+        s.requested_tps = requested;
+        s
+    }
+
+    #[test]
+    fn shortfalls_are_split_between_client_and_node() {
+        let mut samples = vec![
+            // 10s at 100 TPS: on target
+            sample(10.0, 1_000, 1_000, Some(100.0)),
+            // 60 TPS, all succeeded: the generator fell behind
+            sample(20.0, 1_600, 1_600, Some(100.0)),
+            // 50 TPS of 100 submitted: the node rejected half
+            sample(30.0, 2_600, 2_100, Some(100.0)),
+            // Unpaced interval is left alone
+            sample(40.0, 3_600, 3_100, None),
+        ];
+        let mut prev = (0.0, 0);
+        for s in samples.iter_mut() {
+            s.interval_tps = (s.tx_success - prev.1) as f64 / (s.at.elapsed_secs - prev.0);
+            prev = (s.at.elapsed_secs, s.tx_success);
+        }
+
+        let report = annotate(&mut samples).unwrap();
+        let statuses: Vec<_> = samples.iter().map(|s| s.pacing).collect();
+        assert_eq!(statuses, vec![
+            Some(PacingStatus::OnTarget),
+            Some(PacingStatus::GeneratorSaturated),
+            Some(PacingStatus::NodeRejected),
+            None,
+        ]);
+        assert_eq!((report.paced_intervals, report.on_target, report.generator_saturated, report.node_rejected), (3, 1, 1, 1));
+        assert!((report.achieved_tps - 70.0).abs() < 1e-9);
+        assert!((report.mean_abs_error - 0.3).abs() < 1e-9);
+        assert!((report.worst_shortfall - 0.5).abs() < 1e-9);
+    }
+
+    /// Transactions one worker sends in `secs` of simulated time, each taking `tx`
+    fn sent_in(argv: &[&str], senders: usize, bytes: u64, tx: Duration, secs: u64) -> u64 {
+        let args = Args::try_parse_from([&["fdp-sui-bench"], argv].concat()).unwrap();
+        let clock = VirtualClock::new();
+        let mut sent = 0;
+        futures::executor::block_on(async {
+            while clock.elapsed() < Duration::from_secs(secs) {
+                clock.advance(tx);
+                sent += 1;
+                hold(&args, senders, bytes, None, clock.as_ref()).await;
+            }
+        });
+        sent
+    }
+
+    #[test]
+    fn workers_hold_their_share_of_the_rate_target() {
+        // 8 TPS over 2 senders: one transaction every 250ms
+        assert_eq!(sent_in(&["--target-tps", "8"], 2, 0, Duration::ZERO, 60), 240);
+        // The pause follows the transaction, so slow transactions stretch the interval
+        assert_eq!(sent_in(&["--target-tps", "8"], 2, 0, Duration::from_millis(250), 60), 120);
+        // 1 MiB/s over 2 senders at 64 KiB per transaction: 8 per second
+        assert_eq!(sent_in(&["--target-write-bps", "1M"], 2, 65536, Duration::ZERO, 60), 480);
+        // Unpaced: no pause at all
+        assert_eq!(sent_in(&[], 2, 0, Duration::from_millis(100), 60), 600);
+    }
+}
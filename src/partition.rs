@@ -0,0 +1,151 @@
+// Creation-time temperature partitions (--create-hot-pct, --cold-leak-pct)
+//
+// --hot-fraction splits whatever population a worker has when the load phase
+// starts, so which objects end up hot depends on how the population came
+// about. A partition instead assigns every object a temperature as it enters
+// the worker: with --create-hot-pct 20 exactly one object in five is hot,
+// interleaved in creation order (seeds and loaded objects first, in saved
+// order, then every object created during the run), and the rest are cold.
+// Built-in updates draw only from the hot partition, except that
+// --cold-leak-pct of update transactions rewrite a batch of cold objects
+// instead, so the amount of cold data disturbed is a setting too. A leaked
+// object stays cold. Saved states don't record the partition; a loaded
+// population is partitioned again by the same rule. The `partition` section
+// counts objects per partition and the writes that went to each.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{TxOutcome, WorkerState};
+
+/// Temperature of each object a worker takes on, in order
+#[derive(Debug, Clone)]
+pub struct CreationSplit {
+    hot_pct: u64,
+    assigned: u64,
+    hot: u64,
+}
+
+impl CreationSplit {
+    pub fn new(hot_pct: u8) -> Self {
+        Self { hot_pct: hot_pct as u64, assigned: 0, hot: 0 }
+    }
+
+    /// Whether the next object is hot: keeps the hot count at hot_pct% of those assigned, rounded up
+    pub fn next_is_hot(&mut self) -> bool {
+        self.assigned += 1;
+        let hot = self.hot * 100 < self.assigned * self.hot_pct;
+        if hot {
+            self.hot += 1;
+        }
+        hot
+    }
+
+    /// (hot, cold) objects assigned so far
+    pub fn assigned(&self) -> (u64, u64) {
+        (self.hot, self.assigned - self.hot)
+    }
+}
+
+impl WorkerState {
+    /// Partition the tracked objects by `hot_pct`; from now on created objects are assigned as they come
+    pub fn start_partition(&mut self, hot_pct: u8) {
+        if self.partition.is_some() {
+            return;
+        }
+        let mut split = CreationSplit::new(hot_pct);
+        let (hot, cold): (Vec<_>, Vec<_>) = std::mem::take(&mut self.objects).into_iter()
+            .partition(|_| split.next_is_hot());
+        self.objects = hot;
+        self.cold.extend(cold);
+        self.cold_split = true;
+        self.partition = Some(split);
+    }
+
+    /// Trade the hot and cold lists, so a leak-through update picks its batch from the cold one
+    pub fn swap_partitions(&mut self) {
+        std::mem::swap(&mut self.objects, &mut self.cold);
+    }
+}
+
+/// Update writes per partition
+#[derive(Debug, Default)]
+pub struct PartitionStats {
+    hot_writes: AtomicU64,
+    hot_bytes: AtomicU64,
+    leaked_writes: AtomicU64,
+    leaked_bytes: AtomicU64,
+}
+
+impl PartitionStats {
+    pub fn record(&self, outcome: &TxOutcome, leaked: bool) {
+        if outcome.updated == 0 {
+            return;
+        }
+        let (writes, bytes) = if leaked { (&self.leaked_writes, &self.leaked_bytes) } else { (&self.hot_writes, &self.hot_bytes) };
+        writes.fetch_add(outcome.updated, Ordering::Relaxed);
+        bytes.fetch_add(outcome.bytes_written, Ordering::Relaxed);
+    }
+
+    pub fn report(&self, hot_pct: u8, cold_leak_pct: u8, workers: &[(CreationSplit, usize, usize)]) -> PartitionReport {
+        let hot_writes = self.hot_writes.load(Ordering::Relaxed);
+        let leaked_writes = self.leaked_writes.load(Ordering::Relaxed);
+        let updates = hot_writes + leaked_writes;
+        let mut report = PartitionReport {
+            hot_pct,
+            cold_leak_pct,
+            assigned_hot: 0,
+            assigned_cold: 0,
+            hot_objects: 0,
+            cold_objects: 0,
+            hot_writes,
+            hot_bytes: self.hot_bytes.load(Ordering::Relaxed),
+            leaked_writes,
+            leaked_bytes: self.leaked_bytes.load(Ordering::Relaxed),
+            leaked_share: if updates > 0 { leaked_writes as f64 / updates as f64 } else { 0.0 },
+        };
+        for (split, hot, cold) in workers {
+            let (assigned_hot, assigned_cold) = split.assigned();
+            report.assigned_hot += assigned_hot;
+            report.assigned_cold += assigned_cold;
+            report.hot_objects += hot;
+            report.cold_objects += cold;
+        }
+        report
+    }
+}
+
+/// `partition` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionReport {
+    pub hot_pct: u8,
+    pub cold_leak_pct: u8,
+    /// Objects assigned to each partition (seeds, loaded and created)
+    pub assigned_hot: u64,
+    pub assigned_cold: u64,
+    /// Objects tracked in each at the end of the run
+    pub hot_objects: usize,
+    pub cold_objects: usize,
+    /// Object writes and logical bytes of updates to the hot partition, and of leak-through updates
+    pub hot_writes: u64,
+    pub hot_bytes: u64,
+    pub leaked_writes: u64,
+    pub leaked_bytes: u64,
+    /// Share of updated objects that were cold
+    pub leaked_share: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fifth_object_is_hot_at_twenty_percent() {
+        let mut split = CreationSplit::new(20);
+        let hot: Vec<bool> = (0..10).map(|_| split.next_is_hot()).collect();
+        assert_eq!(hot, [true, false, false, false, false, true, false, false, false, false]);
+        assert_eq!(split.assigned(), (2, 8));
+        let mut all_hot = CreationSplit::new(100);
+        assert!((0..5).all(|_| all_hot.next_is_hot()));
+    }
+}
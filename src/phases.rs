@@ -0,0 +1,98 @@
+// Wall-clock time of every run phase
+//
+// duration_secs only covers the load phase, but a run also spends time
+// connecting, funding workers, seeding or restoring objects, draining the last
+// in-flight transactions and saving state. Each phase is recorded here as a
+// span from one clock started with the run, so the `phases` section adds up
+// to the end-to-end time (whatever falls between phases is reported as
+// unaccounted instead of being silently dropped).
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Instant;
+use tracing::info;
+
+use crate::timeline::{unix_ms, utc_rfc3339};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Phase {
+    pub name: &'static str,
+    /// Seconds since the run started
+    pub start_secs: f64,
+    pub start_utc: String,
+    pub duration_secs: f64,
+}
+
+/// `phases` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseReport {
+    pub phases: Vec<Phase>,
+    /// Run start to the last recorded phase's end
+    pub total_secs: f64,
+    /// Time between phases (argument checks, logging, sampler start-up)
+    pub unaccounted_secs: f64,
+}
+
+pub struct Phases {
+    origin: Instant,
+    origin_unix_ms: u64,
+    phases: Mutex<Vec<Phase>>,
+}
+
+impl Phases {
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), origin_unix_ms: unix_ms(), phases: Mutex::default() }
+    }
+
+    /// Record `name` as running from `start` until now
+    pub fn record(&self, name: &'static str, start: Instant) {
+        self.span(name, start, Instant::now());
+    }
+
+    pub fn span(&self, name: &'static str, start: Instant, end: Instant) {
+        let start_secs = start.saturating_duration_since(self.origin).as_secs_f64();
+        self.phases.lock().unwrap().push(Phase {
+            name,
+            start_secs,
+            start_utc: utc_rfc3339(self.origin_unix_ms + (start_secs * 1000.0) as u64),
+            duration_secs: end.saturating_duration_since(start).as_secs_f64(),
+        });
+    }
+
+    pub fn report(&self) -> PhaseReport {
+        let mut phases = self.phases.lock().unwrap().clone();
+        phases.sort_by(|a, b| a.start_secs.total_cmp(&b.start_secs));
+        let total_secs = phases.iter().map(|p| p.start_secs + p.duration_secs).fold(0.0, f64::max);
+        let recorded: f64 = phases.iter().map(|p| p.duration_secs).sum();
+        PhaseReport { phases, total_secs, unaccounted_secs: (total_secs - recorded).max(0.0) }
+    }
+}
+
+impl PhaseReport {
+    pub fn log(&self) {
+        let list: Vec<String> = self.phases.iter().map(|p| format!("{} {:.1}s", p.name, p.duration_secs)).collect();
+        info!("Phases: {} (total {:.1}s, {:.1}s between phases)", list.join(", "), self.total_secs, self.unaccounted_secs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn phases_are_ordered_and_gaps_are_unaccounted() {
+        let phases = Phases::new();
+        let at = |secs: u64| phases.origin + Duration::from_secs(secs);
+        phases.span("load", at(10), at(70));
+        phases.span("setup", at(0), at(8));
+        phases.span("drain", at(70), at(72));
+
+        let report = phases.report();
+
+        let names: Vec<_> = report.phases.iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["setup", "load", "drain"]);
+        assert_eq!((report.total_secs, report.unaccounted_secs), (72.0, 2.0));
+        assert_eq!(report.phases[1].start_utc, utc_rfc3339(phases.origin_unix_ms + 10_000));
+    }
+}
@@ -0,0 +1,150 @@
+// Named workload presets (--profile <name>)
+//
+// A preset is a full set of knobs for one of the standard lab experiments, so
+// a new operator can run `--profile steady-churn` and get a result that is
+// comparable with everyone else's. The preset's knobs are inserted into the
+// command line ahead of what was typed, and only for flags the operator did not
+// set: anything given explicitly (or through its environment variable) wins.
+// The expanded command line goes through clap and `Args::validate` like any
+// other, so a preset can never produce settings a hand-written run couldn't.
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Parser, ValueEnum};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use crate::Args;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// One minute, two workers, low rate: checks the setup works end to end
+    Smoke,
+    /// Half an hour of paced counter updates with a trickle of creates
+    SteadyChurn,
+    /// A small hot set rewritten over and over next to cold objects written once
+    HotColdSplit,
+    /// Large population with a compaction wave every five minutes
+    CompactionStorm,
+    /// Blob creates and rewrites with mixed object sizes
+    BlobHeavy,
+}
+
+impl Preset {
+    /// Knobs set by the preset: (argument id, value); "true" marks a flag without value
+    pub fn knobs(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Preset::Smoke => &[
+                ("duration", "60"),
+                ("workers", "2"),
+                ("batch_size", "10"),
+                ("seed_objects", "50"),
+                ("target_tps", "20"),
+                ("stats_interval", "10"),
+            ],
+            Preset::SteadyChurn => &[
+                ("duration", "1800"),
+                ("workers", "8"),
+                ("batch_size", "50"),
+                ("create_pct", "5"),
+                ("seed_objects", "2000"),
+                ("target_tps", "200"),
+                ("mix_schedule", "interleaved"),
+            ],
+            Preset::HotColdSplit => &[
+                ("duration", "1800"),
+                ("workers", "8"),
+                ("batch_size", "50"),
+                ("create_pct", "20"),
+                ("seed_objects", "2000"),
+                ("target_tps", "200"),
+                ("hot_fraction", "0.1"),
+            ],
+            Preset::CompactionStorm => &[
+                ("duration", "1800"),
+                ("workers", "8"),
+                ("batch_size", "50"),
+                ("create_pct", "5"),
+                ("seed_objects", "5000"),
+                ("target_tps", "200"),
+                ("wave_interval", "300"),
+                ("wave_pct", "50"),
+            ],
+            Preset::BlobHeavy => &[
+                ("duration", "1800"),
+                ("workers", "8"),
+                ("use_blobs", "true"),
+                ("batch_size", "20"),
+                ("create_pct", "30"),
+                ("seed_objects", "500"),
+                ("size_distribution", "lognormal:4096,1.0"),
+            ],
+        }
+    }
+}
+
+/// `profile` section of the results: the preset and the knobs it stands for
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetReport {
+    pub name: Preset,
+    pub knobs: BTreeMap<&'static str, &'static str>,
+}
+
+impl PresetReport {
+    pub fn of(preset: Preset) -> Self {
+        Self { name: preset, knobs: preset.knobs().iter().copied().collect() }
+    }
+}
+
+/// Re-parse the command line with the preset's knobs filled in where no explicit
+/// flag was given; returns the arguments and the preset knobs that were overridden
+pub fn expand(preset: Preset, parsed: &ArgMatches, argv: Vec<OsString>) -> Result<(Args, Vec<&'static str>), clap::Error> {
+    let mut argv = argv.into_iter();
+    let mut expanded: Vec<OsString> = argv.next().into_iter().collect();
+    let mut overridden = Vec::new();
+    for (id, value) in preset.knobs() {
+        if matches!(parsed.value_source(id), Some(ValueSource::CommandLine | ValueSource::EnvVariable)) {
+            overridden.push(*id);
+            continue;
+        }
+        expanded.push(format!("--{}", id.replace('_', "-")).into());
+        if *value != "true" {
+            expanded.push((*value).into());
+        }
+    }
+    expanded.extend(argv);
+    Ok((Args::try_parse_from(expanded)?, overridden))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn expand_args(argv: &[&str]) -> (Args, Vec<&'static str>) {
+        let argv: Vec<OsString> = argv.iter().map(OsString::from).collect();
+        let matches = Args::command().try_get_matches_from(argv.clone()).unwrap();
+        let args = Args::try_parse_from(argv.clone()).unwrap();
+        expand(args.profile.unwrap(), &matches, argv).unwrap()
+    }
+
+    #[test]
+    fn every_preset_expands_to_valid_arguments() {
+        for preset in Preset::value_variants() {
+            let name = preset.to_possible_value().unwrap().get_name().to_string();
+            let (args, overridden) = expand_args(&["fdp-sui-bench", "--profile", &name]);
+            assert!(overridden.is_empty());
+            args.validate().unwrap_or_else(|e| panic!("preset {}: {:#}", name, e));
+        }
+    }
+
+    #[test]
+    fn explicit_flags_win_over_the_preset() {
+        let (args, overridden) = expand_args(&["fdp-sui-bench", "--profile", "blob-heavy", "--batch-size", "5"]);
+        assert_eq!(overridden, vec!["batch_size"]);
+        assert_eq!(args.batch_size, 5);
+        assert!(args.use_blobs);
+        assert_eq!(args.create_pct.get(), 30);
+    }
+}
@@ -0,0 +1,173 @@
+// Central memory-pressure response
+//
+// The memory monitor decides how the run reacts to pressure, once per level
+// change, instead of every worker re-deciding on every loop iteration. When the
+// level rises, each worker gets a drop quota - the level's share of the objects
+// it tracks at that moment (workers with 50 or fewer are left alone) - which it
+// evicts once, per --eviction-policy, on its next iteration. Every worker also
+// gets the level's pause between transactions, and at the emergency level
+// creates stop. When the level falls, quotas are cancelled and the pauses
+// shortened. So the aggregate effect of a transition is known at the moment it
+// happens: the `memory_pressure` section lists each one with the objects it
+// asked the workers to drop.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::timeline::Timestamp;
+use crate::{BenchStats, WorkerState, MEM_PRESSURE_EMERGENCY, MEM_PRESSURE_HEAVY, MEM_PRESSURE_LIGHT, MEM_PRESSURE_NORMAL};
+
+/// Workers tracking this few objects are never asked to drop any
+const MIN_OBJECTS_TO_DROP: usize = 50;
+
+/// How the run responds to a pressure level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Response {
+    pub drop_pct: usize,
+    pub delay: Duration,
+    pub skip_creates: bool,
+}
+
+impl Response {
+    pub fn of(level: u8) -> Self {
+        let (drop_pct, delay_ms, skip_creates) = match level {
+            MEM_PRESSURE_EMERGENCY => (75, 2000, true),   // Drop 75%, 2s delay, no creates
+            MEM_PRESSURE_HEAVY => (50, 1000, false),      // Drop 50%, 1s delay
+            MEM_PRESSURE_LIGHT => (25, 250, false),       // Drop 25%, 250ms delay
+            _ => (0, 0, false),
+        };
+        Self { drop_pct, delay: Duration::from_millis(delay_ms), skip_creates }
+    }
+}
+
+#[derive(Default)]
+struct Directive {
+    drop_quota: AtomicUsize,
+    delay_ms: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PressureTransition {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub from: u8,
+    pub to: u8,
+    /// "host" or "own heap"
+    pub source: &'static str,
+    pub usage: f64,
+    pub delay_ms: u64,
+    /// Objects the workers were asked to drop, in total
+    pub drop_assigned: usize,
+}
+
+/// `memory_pressure` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct PressureReport {
+    pub transitions: Vec<PressureTransition>,
+    pub objects_dropped: u64,
+}
+
+pub struct PressureCoordinator {
+    level: AtomicU8,
+    directives: Vec<Directive>,
+    transitions: Mutex<Vec<PressureTransition>>,
+    dropped: AtomicU64,
+}
+
+impl PressureCoordinator {
+    pub fn new(workers: usize) -> Self {
+        Self {
+            level: AtomicU8::new(MEM_PRESSURE_NORMAL),
+            directives: (0..workers).map(|_| Directive::default()).collect(),
+            transitions: Mutex::default(),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level.load(Ordering::Relaxed)
+    }
+
+    /// At the emergency level workers only update
+    pub fn skip_creates(&self) -> bool {
+        Response::of(self.level()).skip_creates
+    }
+
+    /// Move to `to` and hand out the new level's quotas and pauses; a no-op if the level is unchanged
+    pub async fn transition(&self, to: u8, stats: &BenchStats, workers: &[Arc<RwLock<WorkerState>>], source: &'static str, usage: f64) {
+        let from = self.level.swap(to, Ordering::Relaxed);
+        if from == to {
+            return;
+        }
+        let response = Response::of(to);
+        let mut drop_assigned = 0;
+        for (directive, worker) in self.directives.iter().zip(workers) {
+            let quota = if to > from {
+                let tracked = worker.read().await.objects.len();
+                if tracked > MIN_OBJECTS_TO_DROP { tracked * response.drop_pct / 100 } else { 0 }
+            } else {
+                0
+            };
+            drop_assigned += quota;
+            directive.drop_quota.store(quota, Ordering::Relaxed);
+            directive.delay_ms.store(response.delay.as_millis() as u64, Ordering::Relaxed);
+        }
+        self.transitions.lock().unwrap().push(PressureTransition {
+            at: Timestamp::now(stats),
+            from,
+            to,
+            source,
+            usage,
+            delay_ms: response.delay.as_millis() as u64,
+            drop_assigned,
+        });
+    }
+
+    /// The worker's outstanding drop quota (cleared) and its current pause
+    pub fn take_directive(&self, worker: usize) -> (usize, Duration) {
+        let Some(directive) = self.directives.get(worker) else {
+            return (0, Duration::ZERO);
+        };
+        let quota = directive.drop_quota.swap(0, Ordering::Relaxed);
+        (quota, Duration::from_millis(directive.delay_ms.load(Ordering::Relaxed)))
+    }
+
+    pub fn record_dropped(&self, count: usize) {
+        self.dropped.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub fn report(&self) -> PressureReport {
+        PressureReport {
+            transitions: self.transitions.lock().unwrap().clone(),
+            objects_dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotas_are_handed_out_once_per_transition() {
+        let coordinator = PressureCoordinator::new(2);
+        coordinator.directives[0].drop_quota.store(30, Ordering::Relaxed);
+        coordinator.directives[0].delay_ms.store(250, Ordering::Relaxed);
+        assert_eq!(coordinator.take_directive(0), (30, Duration::from_millis(250)));
+        // The quota is taken once; the pause stays until the next transition
+        assert_eq!(coordinator.take_directive(0), (0, Duration::from_millis(250)));
+        assert_eq!(coordinator.take_directive(1), (0, Duration::ZERO));
+        assert_eq!(coordinator.take_directive(7), (0, Duration::ZERO));
+    }
+
+    #[test]
+    fn responses_escalate_with_the_level() {
+        assert_eq!(Response::of(MEM_PRESSURE_NORMAL).drop_pct, 0);
+        assert_eq!(Response::of(MEM_PRESSURE_LIGHT).delay, Duration::from_millis(250));
+        assert!(!Response::of(MEM_PRESSURE_HEAVY).skip_creates);
+        assert!(Response::of(MEM_PRESSURE_EMERGENCY).skip_creates);
+    }
+}
@@ -0,0 +1,89 @@
+// Terminal progress display
+//
+// An indicatif bar on stderr, updated once per second while the load phase
+// runs: elapsed vs --duration, remaining time, current TPS (over the last
+// tick) and failures. Only drawn when stderr is a terminal and --quiet is not
+// set, so piped/CI runs keep clean logs.
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+use crate::BenchStats;
+
+const TEMPLATE: &str = "[{bar:30}] {pos:>5}s/{len}s  {msg}";
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The bar and the task updating it
+pub struct Progress {
+    bar: ProgressBar,
+    handle: JoinHandle<()>,
+}
+
+impl Progress {
+    /// Stop updating and erase the bar
+    pub fn finish(self) {
+        self.handle.abort();
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Spawn the progress bar (None when disabled or stderr isn't a terminal)
+pub fn spawn(stats: Arc<BenchStats>, running: Arc<AtomicBool>, duration_secs: u64, quiet: bool) -> Option<Progress> {
+    if quiet || !std::io::stderr().is_terminal() {
+        return None;
+    }
+    let bar = ProgressBar::new(duration_secs).with_style(style());
+    let handle = {
+        let bar = bar.clone();
+        tokio::spawn(async move {
+            let mut last_success = stats.tx_success.get();
+            while running.load(Ordering::Relaxed) {
+                sleep(REDRAW_INTERVAL).await;
+                let success = stats.tx_success.get();
+                let tps = success.saturating_sub(last_success) as f64 / REDRAW_INTERVAL.as_secs_f64();
+                last_success = success;
+                update(&bar, stats.elapsed(), tps, success, stats.tx_failed.get());
+            }
+        })
+    };
+    Some(Progress { bar, handle })
+}
+
+fn style() -> ProgressStyle {
+    ProgressStyle::with_template(TEMPLATE)
+        .expect("progress template")
+        .progress_chars("#-")
+}
+
+/// Move the bar to `elapsed` (capped at --duration) and show the remaining time and counters
+fn update(bar: &ProgressBar, elapsed: Duration, tps: f64, success: u64, failed: u64) {
+    let duration = bar.length().unwrap_or(0);
+    let remaining = Duration::from_secs(duration).saturating_sub(elapsed);
+    bar.set_position(elapsed.as_secs().min(duration));
+    bar.set_message(format!("ETA {:>5}s | TPS {:>8.1} | ok {} | failed {}", remaining.as_secs(), tps, success, failed));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_bar_follows_elapsed_time_up_to_the_duration() {
+        let bar = ProgressBar::hidden().with_style(style());
+        bar.set_length(60);
+
+        update(&bar, Duration::from_millis(15_700), 812.34, 12_000, 3);
+        assert_eq!(bar.position(), 15);
+        assert_eq!(bar.message(), "ETA    44s | TPS    812.3 | ok 12000 | failed 3");
+
+        // A run that overshoots its duration (draining, final checkpoint) stays at the end
+        update(&bar, Duration::from_secs(75), 0.0, 12_500, 3);
+        assert_eq!(bar.position(), 60);
+        assert!(bar.message().starts_with("ETA     0s |"), "{}", bar.message());
+    }
+}
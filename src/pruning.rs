@@ -0,0 +1,184 @@
+// Pruning context capture (--record-pruning)
+//
+// An aggressively pruning node deletes old objects, effects and checkpoints
+// while the benchmark writes, and those deletes turn into tombstones and extra
+// compaction, i.e. more WAF than the workload itself explains. Runs are only
+// comparable if the pruning setup is known, so this records it automatically:
+// the authority-store-pruning-config section of --node-config (if given), and
+// every stats interval the lowest checkpoint the node still serves plus the
+// pruner watermarks/counters from --node-metrics-url.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use sui_sdk::SuiClient;
+use tracing::warn;
+
+use crate::timeline::{Ticker, Timestamp};
+use crate::BenchStats;
+
+/// Node config section holding the pruner settings
+const PRUNING_SECTION: &str = "authority-store-pruning-config";
+
+/// Pruner metrics kept from the Prometheus endpoint
+const PRUNING_METRIC_PREFIXES: &[&str] = &["last_pruned", "num_pruned", "pruning_"];
+
+/// `key: value` lines of the pruning section in a sui-node YAML config (or
+/// the fullnode.yaml of a localnet network config directory)
+///
+/// Only the flat keys directly under the section are read (no YAML parser is
+/// available); nested maps are skipped.
+pub fn read_pruning_config(path: &str) -> Result<Option<BTreeMap<String, String>>> {
+    let path = match std::path::Path::new(path) {
+        dir if dir.is_dir() => dir.join("fullnode.yaml"),
+        file => file.to_path_buf(),
+    };
+    let text = std::fs::read_to_string(&path).context(format!("Failed to read node config {}", path.display()))?;
+    let mut lines = text.lines();
+    let Some(header) = lines.find(|l| l.trim_start().starts_with(&format!("{}:", PRUNING_SECTION))) else {
+        return Ok(None);
+    };
+    let section_indent = header.len() - header.trim_start().len();
+    let mut config = BTreeMap::new();
+    let mut key_indent = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent <= section_indent {
+            break;
+        }
+        if *key_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+        if let Some((key, value)) = trimmed.split_once(':') {
+            let value = value.split(" #").next().unwrap_or("").trim();
+            if !value.is_empty() {
+                config.insert(key.trim().to_string(), value.trim_matches('"').to_string());
+            }
+        }
+    }
+    Ok(Some(config))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PruningSample {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    /// Lowest checkpoint the node still returns (None if the query failed)
+    pub lowest_checkpoint: Option<u64>,
+    pub latest_checkpoint: Option<u64>,
+    /// Pruner metrics (only with --node-metrics-url)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metrics: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PruningReport {
+    pub node_config: Option<String>,
+    /// authority-store-pruning-config keys (None if not found or no --node-config)
+    pub config: Option<BTreeMap<String, String>>,
+    pub samples: Vec<PruningSample>,
+    /// How far the lowest available checkpoint moved during the run
+    pub checkpoints_pruned: Option<u64>,
+}
+
+async fn lowest_checkpoint(client: &SuiClient) -> Option<u64> {
+    match client.read_api().get_checkpoints(None, Some(1), false).await {
+        Ok(page) => page.data.first().map(|c| c.sequence_number),
+        Err(e) => {
+            warn!("Pruning sampler: lowest checkpoint query failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Sample every stats interval until `running` is cleared (one final sample at the end)
+pub async fn run_pruning_sampler(
+    client: SuiClient,
+    node_config: Option<String>,
+    metrics_url: Option<String>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    ticker: Ticker,
+) -> PruningReport {
+    let config = match node_config.as_deref().map(read_pruning_config).transpose() {
+        Ok(config) => config.flatten(),
+        Err(e) => {
+            warn!("Pruning config: {:#}", e);
+            None
+        }
+    };
+    let http = reqwest::Client::new();
+    let prefixes: Vec<String> = PRUNING_METRIC_PREFIXES.iter().map(|p| p.to_string()).collect();
+    let mut samples = Vec::new();
+    loop {
+        let metrics = match &metrics_url {
+            Some(url) => crate::rocksdb_metrics::scrape_totals(&http, url, &prefixes).await
+                .unwrap_or_else(|e| {
+                    warn!("Pruning sampler: {:#}", e);
+                    BTreeMap::new()
+                }),
+            None => BTreeMap::new(),
+        };
+        samples.push(PruningSample {
+            at: Timestamp::now(&stats),
+            lowest_checkpoint: lowest_checkpoint(&client).await,
+            latest_checkpoint: client.read_api().get_latest_checkpoint_sequence_number().await.ok(),
+            metrics,
+        });
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+        ticker.tick(stats.clock.as_ref()).await;
+    }
+
+    let lowest: Vec<u64> = samples.iter().filter_map(|s| s.lowest_checkpoint).collect();
+    let checkpoints_pruned = match (lowest.first(), lowest.last()) {
+        (Some(first), Some(last)) => Some(last.saturating_sub(*first)),
+        _ => None,
+    };
+    PruningReport { node_config, config, samples, checkpoints_pruned }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_keys_of_the_pruning_section_are_read() {
+        let dir = std::env::temp_dir().join(format!("fdp-pruning-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("fullnode.yaml"), "\
+db-path: /opt/sui/db
+authority-store-pruning-config:
+  # keep a day of effects
+  num-latest-epoch-dbs-to-retain: 3
+  num-epochs-to-retain: 0 # aggressive
+  periodic-compaction-threshold-days:
+  killswitch-tombstone-pruning: \"false\"
+  pruning-run-delay-seconds: 60
+  nested:
+    ignored: 1
+checkpoint-executor-config:
+  checkpoint-execution-max-concurrency: 200
+").unwrap();
+
+        // A network config directory resolves to its fullnode.yaml
+        let config = read_pruning_config(&dir.to_string_lossy()).unwrap().unwrap();
+
+        assert_eq!(config, BTreeMap::from([
+            ("killswitch-tombstone-pruning".to_string(), "false".to_string()),
+            ("num-epochs-to-retain".to_string(), "0".to_string()),
+            ("num-latest-epoch-dbs-to-retain".to_string(), "3".to_string()),
+            ("pruning-run-delay-seconds".to_string(), "60".to_string()),
+        ]));
+        std::fs::write(dir.join("fullnode.yaml"), "db-path: /opt/sui/db\n").unwrap();
+        assert!(read_pruning_config(&dir.to_string_lossy()).unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
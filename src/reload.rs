@@ -0,0 +1,292 @@
+// Runtime reconfiguration (--config file watch)
+//
+// Long soak runs shouldn't need a restart (and a cold cache) just to try a
+// different rate or mix. At every stats interval boundary the --config file's
+// modification time is checked; if it changed, the file is re-read and the
+// hot-reloadable knobs - target TPS, create_pct, batch size and the memory
+// thresholds - are swapped in as one generation. Workers pick up the new
+// generation before their next transaction, the memory monitor on its next
+// check. Every reload, applied or rejected, is recorded as a config-change
+// event so the interval timeline can be read against it.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+use crate::config::BenchConfig;
+use crate::timeline::Timestamp;
+use crate::validate::Percent;
+use crate::{Args, BenchStats};
+
+/// Reloadable per-worker knobs (target_tps in --target-tps units, as in Args)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkerKnobs {
+    pub target_tps: u64,
+    pub create_pct: Percent,
+    pub batch_size: usize,
+}
+
+impl WorkerKnobs {
+    fn of(args: &Args) -> Self {
+        Self { target_tps: args.target_tps, create_pct: args.create_pct, batch_size: args.batch_size }
+    }
+
+    pub fn apply(&self, args: &mut Args) {
+        args.target_tps = self.target_tps;
+        args.create_pct = self.create_pct;
+        args.batch_size = self.batch_size;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MemoryThresholds {
+    pub threshold: f64,
+    pub critical: f64,
+    pub emergency: f64,
+}
+
+impl MemoryThresholds {
+    fn of(args: &Args) -> Self {
+        Self { threshold: args.memory_threshold.get(), critical: args.memory_critical.get(), emergency: args.memory_emergency.get() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KnobChange {
+    pub knob: &'static str,
+    /// None = the same change on every worker (or a global knob)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker: Option<usize>,
+    pub from: f64,
+    pub to: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub generation: u64,
+    pub changes: Vec<KnobChange>,
+    /// Why the new file was not applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The knobs currently in force
+#[derive(Default)]
+pub struct LiveKnobs {
+    generation: AtomicU64,
+    workers: RwLock<Vec<WorkerKnobs>>,
+    memory: RwLock<MemoryThresholds>,
+    changes: Mutex<Vec<ConfigChange>>,
+}
+
+impl LiveKnobs {
+    /// Knobs at startup (generation 0, not recorded as a change)
+    pub fn init(&self, config: &BenchConfig, base: &Args) -> Result<()> {
+        let (workers, memory) = resolve(config, base)?;
+        *self.workers.write().unwrap() = workers;
+        *self.memory.write().unwrap() = memory;
+        Ok(())
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub fn worker(&self, worker_id: usize) -> Option<WorkerKnobs> {
+        self.workers.read().unwrap().get(worker_id).copied()
+    }
+
+    pub fn memory(&self) -> MemoryThresholds {
+        *self.memory.read().unwrap()
+    }
+
+    pub fn changes(&self) -> Vec<ConfigChange> {
+        self.changes.lock().unwrap().clone()
+    }
+
+    fn record(&self, stats: &BenchStats, changes: Vec<KnobChange>, error: Option<String>) {
+        self.changes.lock().unwrap().push(ConfigChange {
+            at: Timestamp::now(stats),
+            generation: self.generation(),
+            changes,
+            error,
+        });
+    }
+}
+
+/// Effective reloadable knobs for every worker plus the memory thresholds
+fn resolve(config: &BenchConfig, base: &Args) -> Result<(Vec<WorkerKnobs>, MemoryThresholds)> {
+    let mut workers = Vec::with_capacity(base.workers);
+    for worker_id in 0..base.workers {
+        workers.push(WorkerKnobs::of(&config.worker_args(base, worker_id)?.0));
+    }
+    let memory = MemoryThresholds::of(&config.global_args(base));
+    if !(memory.threshold <= memory.critical && memory.critical <= memory.emergency) {
+        return Err(anyhow::anyhow!("Memory thresholds must satisfy threshold <= critical <= emergency"));
+    }
+    Ok((workers, memory))
+}
+
+type KnobGetter = fn(&WorkerKnobs) -> f64;
+
+/// Differences between two generations, collapsed where every worker changed alike
+fn diff(old: &[WorkerKnobs], new: &[WorkerKnobs], old_mem: MemoryThresholds, new_mem: MemoryThresholds) -> Vec<KnobChange> {
+    let knobs: [(&'static str, KnobGetter); 3] = [
+        ("target_tps", |k| k.target_tps as f64),
+        ("create_pct", |k| k.create_pct.get() as f64),
+        ("batch_size", |k| k.batch_size as f64),
+    ];
+    let mut changes = Vec::new();
+    for (knob, get) in knobs {
+        let changed: Vec<KnobChange> = old.iter().zip(new).enumerate()
+            .filter(|(_, (a, b))| get(a) != get(b))
+            .map(|(worker, (a, b))| KnobChange { knob, worker: Some(worker), from: get(a), to: get(b) })
+            .collect();
+        let uniform = changed.len() == old.len()
+            && changed.windows(2).all(|w| w[0].from == w[1].from && w[0].to == w[1].to);
+        match changed.first() {
+            Some(first) if uniform => changes.push(KnobChange { worker: None, ..first.clone() }),
+            _ => changes.extend(changed),
+        }
+    }
+    let memory = [
+        ("memory_threshold", old_mem.threshold, new_mem.threshold),
+        ("memory_critical", old_mem.critical, new_mem.critical),
+        ("memory_emergency", old_mem.emergency, new_mem.emergency),
+    ];
+    for (knob, from, to) in memory {
+        if from != to {
+            changes.push(KnobChange { knob, worker: None, from, to });
+        }
+    }
+    changes
+}
+
+/// Watches the --config file; polled from the stats reporter at each interval boundary
+pub struct ConfigWatcher {
+    path: String,
+    base: Args,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: &str, base: &Args) -> Self {
+        Self { path: path.to_string(), base: base.clone(), modified: modified(path) }
+    }
+
+    /// Apply the file if it changed since the last check
+    pub fn poll(&mut self, stats: &BenchStats) {
+        let now = modified(&self.path);
+        if now.is_none() || now == self.modified {
+            return;
+        }
+        self.modified = now;
+        let resolved = BenchConfig::load(&self.path).and_then(|config| resolve(&config, &self.base));
+        let (workers, memory) = match resolved {
+            Ok(knobs) => knobs,
+            Err(e) => {
+                warn!("Config reload rejected: {:#}", e);
+                stats.live.record(stats, Vec::new(), Some(format!("{:#}", e)));
+                return;
+            }
+        };
+        let live = &stats.live;
+        let changes = {
+            let mut current = live.workers.write().unwrap();
+            let mut current_mem = live.memory.write().unwrap();
+            let changes = diff(&current, &workers, *current_mem, memory);
+            if !changes.is_empty() {
+                *current = workers;
+                *current_mem = memory;
+                live.generation.fetch_add(1, Ordering::AcqRel);
+            }
+            changes
+        };
+        if changes.is_empty() {
+            info!("Config file changed, no reloadable knobs differ");
+            return;
+        }
+        for change in &changes {
+            match change.worker {
+                Some(w) => info!("Config reload: worker {} {} {} -> {}", w, change.knob, change.from, change.to),
+                None => info!("Config reload: {} {} -> {}", change.knob, change.from, change.to),
+            }
+        }
+        live.record(stats, changes, None);
+    }
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock;
+    use clap::Parser;
+    use std::time::Duration;
+
+    fn knobs(target_tps: u64, create_pct: u8, batch_size: usize) -> WorkerKnobs {
+        WorkerKnobs { target_tps, create_pct: Percent::try_from(create_pct).unwrap(), batch_size }
+    }
+
+    /// Rewrite the config with a distinct modification time, as an edit seconds later would have
+    fn rewrite(path: &str, json: &str, generation: u64) {
+        std::fs::write(path, json).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(generation)).unwrap();
+    }
+
+    #[test]
+    fn changes_on_every_worker_collapse_into_one() {
+        let memory = MemoryThresholds { threshold: 0.7, critical: 0.8, emergency: 0.9 };
+        let old = [knobs(100, 5, 10), knobs(100, 5, 10)];
+        let new = [knobs(200, 5, 10), knobs(200, 50, 10)];
+
+        let changes = diff(&old, &new, memory, MemoryThresholds { threshold: 0.6, ..memory });
+
+        let summary: Vec<_> = changes.iter().map(|c| (c.knob, c.worker, c.from, c.to)).collect();
+        assert_eq!(summary, vec![
+            ("target_tps", None, 100.0, 200.0),
+            ("create_pct", Some(1), 5.0, 50.0),
+            ("memory_threshold", None, 0.7, 0.6),
+        ]);
+        assert!(diff(&old, &old, memory, memory).is_empty());
+    }
+
+    #[test]
+    fn edits_to_the_config_file_are_applied_or_recorded_as_rejected() {
+        let dir = std::env::temp_dir().join(format!("fdp-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bench.json").to_string_lossy().into_owned();
+        let base = Args::try_parse_from(["fdp-sui-bench", "--workers", "2", "--target-tps", "100"]).unwrap();
+        let stats = BenchStats::new(2, clock::system());
+        rewrite(&path, "{}", 0);
+        stats.live.init(&BenchConfig::load(&path).unwrap(), &base).unwrap();
+        let mut watcher = ConfigWatcher::new(&path, &base);
+
+        // Unchanged file: nothing happens
+        watcher.poll(&stats);
+        assert!(stats.live.changes().is_empty());
+
+        rewrite(&path, r#"{ "target_tps": 400, "workers": [{ "workers": 1, "batch_size": 20 }] }"#, 1);
+        watcher.poll(&stats);
+        assert_eq!(stats.live.generation(), 1);
+        assert_eq!(stats.live.worker(0).unwrap().target_tps, 400);
+        assert_eq!(stats.live.worker(1).unwrap().batch_size, 20);
+        assert_eq!(stats.live.changes()[0].changes.len(), 2);
+
+        rewrite(&path, r#"{ "batch_size": 0 }"#, 2);
+        watcher.poll(&stats);
+        assert_eq!(stats.live.generation(), 1);
+        let rejected = &stats.live.changes()[1];
+        assert!(rejected.error.is_some() && rejected.changes.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
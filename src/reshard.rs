@@ -0,0 +1,172 @@
+// Re-sharding a loaded population (--reshard-workers N)
+//
+// A saved state ties every object to the worker whose address owns it, so a
+// plain --load-objects run has exactly the saved worker layout. With
+// --reshard-workers N the run starts N fresh workers instead, and every saved
+// worker transfers its objects to them on chain: objects are dealt round-robin
+// in saved order, one transfer transaction per --batch-size objects, signed
+// with the saved keypair and paid from a faucet grant to the old address. The
+// new workers then track the objects at their post-transfer versions, so phase
+// two can run with a different concurrency than phase one on the same
+// population. The transfers rewrite every object once; they are part of the
+// `load` phase and not of the measured run.
+
+use anyhow::{anyhow, Context, Result};
+use futures::{StreamExt, TryStreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use sui_sdk::rpc_types::{SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions};
+use sui_sdk::types::base_types::{ObjectID, SuiAddress};
+use sui_sdk::types::crypto::{get_key_pair, AccountKeyPair, EncodeDecodeBase64, SuiKeyPair};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{ObjectArg, Transaction, TransactionData};
+use sui_sdk::types::transaction_driver_types::ExecuteTransactionRequestType;
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::faucet::FaucetClient;
+use crate::{load, refresh_worker_objects, request_gas_from_faucet, SavedBenchmarkState, SavedWorkerObjects, TrackedObject, WorkerState};
+
+/// Target worker of the `index`-th saved object
+fn target(index: usize, workers: usize) -> usize {
+    index % workers
+}
+
+/// Move every saved object to one of `workers` new workers; returns the new workers
+pub async fn reshard(
+    client: &SuiClient,
+    faucet: &FaucetClient,
+    saved: SavedBenchmarkState,
+    workers: usize,
+    batch_size: usize,
+    gas_budget: u64,
+) -> Result<Vec<Arc<RwLock<WorkerState>>>> {
+    let rgp = client.governance_api().get_reference_gas_price().await.unwrap_or(1000);
+    let targets: Vec<(SuiAddress, SuiKeyPair)> = (0..workers)
+        .map(|_| {
+            let (address, keypair): (SuiAddress, AccountKeyPair) = get_key_pair();
+            (address, SuiKeyPair::Ed25519(keypair))
+        })
+        .collect();
+    let addresses: Vec<SuiAddress> = targets.iter().map(|t| t.0).collect();
+    info!("Re-sharding {} objects from {} saved workers onto {} new workers...",
+        saved.total_objects, saved.workers.len(), workers);
+
+    // Position of each saved worker's first object in the round-robin deal
+    let mut offset = 0;
+    let sources: Vec<(usize, SavedWorkerObjects)> = saved.workers.into_iter()
+        .map(|w| {
+            let start = offset;
+            offset += w.objects.len();
+            (start, w)
+        })
+        .collect();
+
+    let moved: Vec<Vec<(usize, TrackedObject)>> = futures::stream::iter(sources)
+        .map(|(start, saved_worker)| transfer_worker(client, faucet, saved_worker, start, &addresses, batch_size, gas_budget, rgp))
+        .buffer_unordered(load::CONCURRENCY)
+        .try_collect()
+        .await?;
+
+    let mut objects: Vec<Vec<TrackedObject>> = vec![Vec::new(); workers];
+    for (to, object) in moved.into_iter().flatten() {
+        objects[to].push(object);
+    }
+    let new_workers = futures::stream::iter(targets.into_iter().zip(objects).enumerate())
+        .map(|(id, ((address, keypair), objects))| async move {
+            let gas_coin = request_gas_from_faucet(client, faucet, address).await?;
+            info!("Worker {}: re-sharded with {} objects (address: {})", id, objects.len(), &address.to_string()[..16]);
+            Ok::<_, anyhow::Error>(Arc::new(RwLock::new(WorkerState::new(id, address, keypair, gas_coin, objects))))
+        })
+        .buffered(load::CONCURRENCY)
+        .try_collect()
+        .await?;
+    Ok(new_workers)
+}
+
+/// Transfer one saved worker's objects to their new owners; returns (new worker, object at its new version)
+#[allow(clippy::too_many_arguments)]
+async fn transfer_worker(
+    client: &SuiClient,
+    faucet: &FaucetClient,
+    saved: SavedWorkerObjects,
+    start: usize,
+    targets: &[SuiAddress],
+    batch_size: usize,
+    gas_budget: u64,
+    rgp: u64,
+) -> Result<Vec<(usize, TrackedObject)>> {
+    if saved.objects.is_empty() {
+        return Ok(Vec::new());
+    }
+    let keypair = SuiKeyPair::decode_base64(&saved.keypair_base64)
+        .context(format!("Failed to decode keypair for worker {}", saved.worker_id))?;
+    let destinations: HashMap<ObjectID, usize> = saved.objects.iter().enumerate()
+        .map(|(i, o)| (o.id, target(start + i, targets.len())))
+        .collect();
+    let gas_coin = request_gas_from_faucet(client, faucet, saved.address).await?;
+    // Current refs; objects that no longer exist are left behind
+    let old = Arc::new(RwLock::new(WorkerState::new(saved.worker_id, saved.address, keypair, gas_coin, saved.objects)));
+    // Not through --meta-cache: the objects change owner right below
+    refresh_worker_objects(client, old.clone(), None).await?;
+    let mut state = old.write().await;
+
+    let mut moved = Vec::with_capacity(state.objects.len());
+    let objects = std::mem::take(&mut state.objects);
+    for chunk in objects.chunks(batch_size.max(1)) {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let mut by_target: Vec<Vec<&TrackedObject>> = vec![Vec::new(); targets.len()];
+        for obj in chunk {
+            by_target[destinations[&obj.id]].push(obj);
+        }
+        for (to, objs) in by_target.iter().enumerate().filter(|(_, objs)| !objs.is_empty()) {
+            let args = objs.iter()
+                .map(|o| builder.obj(ObjectArg::ImmOrOwnedObject((o.id, o.version.into(), o.digest))))
+                .collect::<Result<Vec<_>, _>>()?;
+            builder.transfer_args(targets[to], args);
+        }
+        let tx_data = TransactionData::new_programmable(state.address, vec![state.gas_coin], builder.finish(), gas_budget, rgp);
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&state.keypair]);
+        let response = client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await
+            .context("Failed to execute re-shard transfer")?;
+        let effects = response.effects.as_ref()
+            .ok_or_else(|| anyhow!("Worker {}: re-shard transfer returned no effects", state.id))?;
+        if effects.status().is_err() {
+            return Err(anyhow!("Worker {}: re-shard transfer failed: {:?}", state.id, effects.status()));
+        }
+        let gas_obj = effects.gas_object();
+        state.gas_coin = (gas_obj.object_id(), gas_obj.version(), gas_obj.reference.digest);
+        let refs: HashMap<ObjectID, _> = effects.mutated().iter().map(|o| (o.object_id(), o.reference.clone())).collect();
+        for obj in chunk {
+            let new_ref = refs.get(&obj.id)
+                .ok_or_else(|| anyhow!("Worker {}: object {} missing from re-shard transfer effects", state.id, obj.id))?;
+            moved.push((destinations[&obj.id], TrackedObject {
+                version: new_ref.version.value(),
+                digest: new_ref.digest,
+                updates: 0,
+                ..obj.clone()
+            }));
+        }
+    }
+    info!("Saved worker {}: transferred {} objects", state.id, moved.len());
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn objects_are_dealt_round_robin() {
+        let owners: Vec<usize> = (0..7).map(|i| target(i, 3)).collect();
+        assert_eq!(owners, vec![0, 1, 2, 0, 1, 2, 0]);
+    }
+}
@@ -0,0 +1,232 @@
+// Benchmark result schema (bench_results.json)
+//
+// The top level of a result file is a typed, versioned struct. Headline
+// counters, the interval timeline and the run config are fixed fields that
+// every reader can rely on; the optional sections (latency, nvme, stalls,
+// tenants, ...) are serialized from their own typed reports and kept by name,
+// so a reader that doesn't know a section just passes it through.
+//
+// SCHEMA_VERSION is bumped whenever a fixed field is added, renamed or changes
+// meaning, with a matching step in `upgrade`. `load` reads any older result
+// (files from before versioning are version 1) and upgrades it, so comparison
+// and report tooling keep working across releases.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::config::WorkerProfile;
+use crate::db_check::DbFingerprint;
+use crate::hooks::HookRun;
+use crate::timeline::{IntervalSample, Timestamp};
+
+/// Current result schema
+///
+/// 1: unversioned outputs (no `schema_version`)
+/// 2: `schema_version` field, typed top level
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Run settings recorded with the result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunConfig {
+    pub workers: usize,
+    pub batch_size: usize,
+    pub create_pct: u8,
+    pub max_inflight: usize,
+    pub target_tps: u64,
+    pub target_write_bps: Option<u64>,
+    pub use_blobs: bool,
+    pub update_bytes: Option<u64>,
+    pub append_bytes: Option<u64>,
+    pub simulate: bool,
+    pub hot_objects: usize,
+    pub hot_skew: f64,
+    pub hot_pct: u8,
+    pub freeze_pct: u8,
+    pub freeze_interval: u64,
+    pub frozen_read_pct: u8,
+    pub wave_interval: u64,
+    pub wave_pct: u8,
+    pub gas_smash: usize,
+    pub size_distribution: Option<String>,
+}
+
+/// One worker's share of the run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkerCounters {
+    pub worker: usize,
+    pub tx_submitted: u64,
+    pub tx_success: u64,
+    pub tx_failed: u64,
+    pub objects_created: u64,
+    pub objects_updated: u64,
+    pub gas_spent_mist: u64,
+    pub tps: f64,
+}
+
+/// A complete result file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchResult {
+    pub schema_version: u32,
+    pub duration_secs: f64,
+    pub run_start: Timestamp,
+    pub run_end: Timestamp,
+    pub intervals: Vec<IntervalSample>,
+    pub tx_submitted: u64,
+    pub tx_success: u64,
+    pub tx_failed: u64,
+    pub objects_created: u64,
+    pub objects_updated: u64,
+    pub tps: f64,
+    pub gas_spent_mist: u64,
+    pub per_worker: Vec<WorkerCounters>,
+    pub worker_restarts: u32,
+    pub worker_restarts_by_worker: Vec<u32>,
+    pub lock_conflicts: u64,
+    pub objects_quarantined: u64,
+    pub gas_repairs: u64,
+    pub tx_duplicates: u64,
+    pub objects_frozen: u64,
+    pub objects_read: u64,
+    pub gas_cap_reached: bool,
+    pub config: RunConfig,
+    /// Optional sections by name
+    #[serde(flatten)]
+    pub sections: BTreeMap<String, Value>,
+}
+
+impl BenchResult {
+    /// Add an optional section
+    pub fn section(&mut self, name: &str, report: impl Serialize) -> Result<()> {
+        self.sections.insert(name.to_string(), serde_json::to_value(report)?);
+        Ok(())
+    }
+
+    /// Headline metric by name, for sweep and A/B summaries
+    pub fn metric(&self, key: &str) -> Option<f64> {
+        Some(match key {
+            "tps" => self.tps,
+            "tx_success" => self.tx_success as f64,
+            "tx_failed" => self.tx_failed as f64,
+            "objects_created" => self.objects_created as f64,
+            "objects_updated" => self.objects_updated as f64,
+            "duration_secs" => self.duration_secs,
+            _ => return None,
+        })
+    }
+}
+
+/// `worker_profiles` section
+#[derive(Debug, Serialize)]
+pub struct WorkerProfiles<'a> {
+    pub profiles: &'a [WorkerProfile],
+    /// Profile names applied, by worker index
+    pub assignment: BTreeMap<usize, Vec<String>>,
+}
+
+/// `gas_smash` section
+#[derive(Debug, Serialize)]
+pub struct GasSmashSummary {
+    pub coins_per_tx: usize,
+    pub refill: bool,
+    pub fragments_merged: u64,
+    pub refills: u64,
+    pub fragments_left: usize,
+}
+
+/// `object_growth` section
+#[derive(Debug, Serialize)]
+pub struct ObjectGrowth {
+    pub append_bytes: u64,
+    pub max_object_bytes: u64,
+    pub tracked_objects: usize,
+    pub mean_bytes: f64,
+    pub max_bytes: Option<u64>,
+}
+
+/// `gas_accounting` section: what the gas coins paid and got back
+#[derive(Debug, Serialize)]
+pub struct GasAccounting {
+    /// Computation + storage charged
+    pub charged_mist: u64,
+    /// Storage rebates credited back (deletes, rewrites to smaller objects)
+    pub storage_rebate_mist: u64,
+    /// charged - rebate; negative when deletes returned more than the run cost
+    pub net_mist: i64,
+    pub rebate_fraction: f64,
+    pub net_mist_per_tx: f64,
+    pub net_mist_per_sec: f64,
+}
+
+/// `hooks` section
+#[derive(Debug, Serialize)]
+pub struct HookRuns {
+    pub before: Vec<HookRun>,
+    pub interval: Vec<HookRun>,
+    pub after: Vec<HookRun>,
+}
+
+/// `db_fingerprint` section
+#[derive(Debug, Serialize)]
+pub struct DbFingerprints {
+    pub start: DbFingerprint,
+    pub end: Option<DbFingerprint>,
+}
+
+/// Bring a result of any older schema up to SCHEMA_VERSION
+pub fn upgrade(mut value: Value) -> Result<Value> {
+    let object = value.as_object_mut().ok_or_else(|| anyhow!("Result is not a JSON object"))?;
+    let mut version = object.get("schema_version").and_then(Value::as_u64).unwrap_or(1) as u32;
+    if version > SCHEMA_VERSION {
+        return Err(anyhow!("Result schema {} is newer than this build understands ({})", version, SCHEMA_VERSION));
+    }
+    while version < SCHEMA_VERSION {
+        match version {
+            // Same fields, now versioned; missing newer counters read as zero
+            1 => {}
+            _ => unreachable!(),
+        }
+        version += 1;
+    }
+    object.insert("schema_version".to_string(), Value::from(version));
+    Ok(value)
+}
+
+/// Read a result file of any schema version
+pub fn load(path: &str) -> Result<BenchResult> {
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read results file: {}", path))?;
+    let value: Value = serde_json::from_str(&content)
+        .context(format!("Failed to parse results file: {}", path))?;
+    serde_json::from_value(upgrade(value)?)
+        .context(format!("Results file {} does not match schema {}", path, SCHEMA_VERSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_result_is_upgraded_on_load() {
+        // Written before schema_version, and before later counters such as objects_read existed
+        let result = load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/results_v1.json")).unwrap();
+        assert_eq!(result.schema_version, SCHEMA_VERSION);
+        assert_eq!((result.tx_success, result.tx_failed, result.tps), (12_000, 40, 199.93));
+        assert_eq!((result.objects_read, result.tx_duplicates, result.gas_cap_reached), (0, 0, false));
+        assert_eq!(result.per_worker[1].gas_spent_mist, 4_800_000_000);
+        assert_eq!(result.worker_restarts_by_worker, [0, 1]);
+        assert_eq!((result.config.batch_size, result.config.use_blobs, result.config.frozen_read_pct), (50, true, 0));
+        assert_eq!(result.intervals[0].at.unix_ms, 1_767_225_630_000);
+        assert_eq!(result.metric("objects_updated"), Some(570_000.0));
+        // Sections pass through by name
+        assert_eq!(result.sections["latency"]["update_blob"]["count"], 11_400);
+        assert!(result.sections.contains_key("queue"));
+
+        let newer = serde_json::json!({ "schema_version": SCHEMA_VERSION + 1 });
+        assert!(upgrade(newer).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+// Node RocksDB metrics sampler (--node-metrics-url)
+//
+// sui-node exports its RocksDB properties (SST and memtable sizes, pending
+// compaction bytes, running flushes/compactions, per-level stats...) on its
+// Prometheus endpoint. The sampler scrapes it every stats interval and keeps the
+// metrics matching --rocksdb-metric-prefix, summed over column families, so
+// physical writes can be attributed to flush vs compaction alongside the
+// workload. The last scrape is also kept per column family.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::timeline::{Ticker, Timestamp};
+use crate::BenchStats;
+
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One Prometheus sample line
+struct MetricLine<'a> {
+    name: &'a str,
+    labels: &'a str,
+    value: f64,
+}
+
+/// Parse a Prometheus text-format line (comments and malformed lines give None)
+fn parse_line(line: &str) -> Option<MetricLine<'_>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (name, labels, rest) = match line.find('{') {
+        Some(open) => {
+            let close = line[open..].find('}')? + open;
+            (&line[..open], &line[open + 1..close], &line[close + 1..])
+        }
+        None => {
+            let space = line.find(char::is_whitespace)?;
+            (&line[..space], "", &line[space..])
+        }
+    };
+    let value = rest.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some(MetricLine { name, labels, value })
+}
+
+/// Column family label of a sample (cf_name / cf / db)
+fn column_family(labels: &str) -> String {
+    for part in labels.split(',') {
+        if let Some((key, value)) = part.split_once('=') {
+            if matches!(key.trim(), "cf_name" | "cf" | "db_name") {
+                return value.trim().trim_matches('"').to_string();
+            }
+        }
+    }
+    "_".to_string()
+}
+
+/// One scrape: metric -> value summed over labels, plus the per-CF breakdown
+#[derive(Debug, Clone, Default)]
+struct Scrape {
+    totals: BTreeMap<String, f64>,
+    by_cf: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+fn parse_scrape(body: &str, prefixes: &[String]) -> Scrape {
+    let mut scrape = Scrape::default();
+    for metric in body.lines().filter_map(parse_line) {
+        if !prefixes.iter().any(|p| metric.name.starts_with(p.as_str())) || !metric.value.is_finite() {
+            continue;
+        }
+        *scrape.totals.entry(metric.name.to_string()).or_default() += metric.value;
+        *scrape.by_cf.entry(column_family(metric.labels)).or_default()
+            .entry(metric.name.to_string()).or_default() += metric.value;
+    }
+    scrape
+}
+
+async fn scrape(http: &reqwest::Client, url: &str, prefixes: &[String]) -> Result<Scrape> {
+    let body = http.get(url).timeout(SCRAPE_TIMEOUT).send().await
+        .context(format!("Failed to scrape {}", url))?
+        .error_for_status()?
+        .text().await?;
+    Ok(parse_scrape(&body, prefixes))
+}
+
+/// Metrics matching `prefixes`, summed over labels (also used by the pruning sampler)
+pub async fn scrape_totals(http: &reqwest::Client, url: &str, prefixes: &[String]) -> Result<BTreeMap<String, f64>> {
+    Ok(scrape(http, url, prefixes).await?.totals)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSample {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// Timeline plus last-minus-first change of every metric
+#[derive(Debug, Clone, Serialize)]
+pub struct RocksDbReport {
+    pub url: String,
+    pub samples: Vec<MetricsSample>,
+    pub deltas: BTreeMap<String, f64>,
+    /// Last scrape per column family
+    pub final_by_cf: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+/// Check the endpoint answers and exposes at least one matching metric
+pub async fn validate(url: &str, prefixes: &[String]) -> Result<()> {
+    let found = scrape(&reqwest::Client::new(), url, prefixes).await?;
+    if found.totals.is_empty() {
+        return Err(anyhow!("{} exposes no metrics starting with {:?}", url, prefixes));
+    }
+    Ok(())
+}
+
+/// Scrape every stats interval until `running` is cleared (one final scrape at the end)
+pub async fn run_rocksdb_sampler(
+    url: String,
+    prefixes: Vec<String>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    ticker: Ticker,
+) -> RocksDbReport {
+    let http = reqwest::Client::new();
+    let mut samples = Vec::new();
+    let mut last = Scrape::default();
+    loop {
+        match scrape(&http, &url, &prefixes).await {
+            Ok(s) => {
+                samples.push(MetricsSample { at: Timestamp::now(&stats), metrics: s.totals.clone() });
+                last = s;
+            }
+            Err(e) => warn!("RocksDB metrics sampler: {:#}", e),
+        }
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+        ticker.tick(stats.clock.as_ref()).await;
+    }
+
+    let deltas = match (samples.first(), samples.last()) {
+        (Some(first), Some(last)) => last.metrics.iter()
+            .map(|(name, v)| (name.clone(), v - first.metrics.get(name).copied().unwrap_or(0.0)))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+    RocksDbReport { url, samples, deltas, final_by_cf: last.by_cf }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prometheus_lines_parse_with_and_without_labels() {
+        let line = parse_line(r#"rocksdb_total_sst_files_size{cf_name="objects",db="store"} 1.5e9 1700000000"#).unwrap();
+        assert_eq!((line.name, line.value), ("rocksdb_total_sst_files_size", 1.5e9));
+        assert_eq!(column_family(line.labels), "objects");
+        let line = parse_line("rocksdb_num_running_flushes 2").unwrap();
+        assert_eq!((line.name, line.labels, line.value), ("rocksdb_num_running_flushes", "", 2.0));
+        assert!(parse_line("# TYPE rocksdb_num_running_flushes gauge").is_none());
+        assert!(parse_line("rocksdb_broken{cf=\"a\" 1").is_none());
+        assert_eq!(column_family("host=\"a\""), "_");
+    }
+
+    #[test]
+    fn scrapes_sum_matching_metrics_over_column_families() {
+        let body = "\
+# HELP rocksdb_estimate_pending_compaction_bytes pending
+rocksdb_estimate_pending_compaction_bytes{cf_name=\"objects\"} 100
+rocksdb_estimate_pending_compaction_bytes{cf_name=\"transactions\"} 50
+rocksdb_num_running_compactions{cf_name=\"objects\"} 1
+other_metric 7
+rocksdb_bad{cf_name=\"objects\"} NaN
+";
+        let scrape = parse_scrape(body, &["rocksdb_estimate".to_string(), "rocksdb_num".to_string(), "rocksdb_bad".to_string()]);
+
+        assert_eq!(scrape.totals, BTreeMap::from([
+            ("rocksdb_estimate_pending_compaction_bytes".to_string(), 150.0),
+            ("rocksdb_num_running_compactions".to_string(), 1.0),
+        ]));
+        assert_eq!(scrape.by_cf["objects"]["rocksdb_estimate_pending_compaction_bytes"], 100.0);
+        assert_eq!(scrape.by_cf["transactions"].len(), 1);
+    }
+}
@@ -0,0 +1,414 @@
+// Size/time-based rotation of streamed output files (--rotate-mb, --rotate-secs, --rotate-compress)
+//
+// The digest log, the HDR interval log and the interval timeline log grow for
+// as long as the run does. On a multi-day run they reach gigabytes, and the
+// whole file is at risk until the run ends. With a rotation limit, the file at
+// the configured path is only the open segment. Once it holds --rotate-mb or
+// has been open for --rotate-secs, it is closed and renamed to
+// `<path>.<NNNNN>`, and a fresh file takes its place. --soak also closes the
+// digest log's segment once a day. A new HDR or CSV segment gets its header
+// again (HDR with the original StartTime), so every segment parses on its own.
+// With --rotate-compress, closed segments are compressed in-process (gzip or
+// zstd), on a background thread so that workers don't wait. A segment whose
+// compression fails stays uncompressed and that is logged. `<path>.index.json`
+// lists every segment in order: file
+// name, compression, time span and sizes. It is rewritten after each rotation
+// and at the end of the run, and the `log_rotation` section of the results
+// points to it. Nothing a rerun or the run a --resume continues left at the
+// path is overwritten: the open file is appended to (open_append), earlier
+// segments keep their index entries, and numbering continues after the
+// highest segment in the index or on disk.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::timeline::unix_ms;
+use crate::Args;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Suffix a compressed segment's file name gets
+    fn suffix(self) -> &'static str {
+        match self {
+            Compression::None => "",
+            Compression::Gzip => ".gz",
+            Compression::Zstd => ".zst",
+        }
+    }
+
+    /// Write `src` compressed to `dst`
+    fn compress(self, src: &str, dst: &str) -> Result<()> {
+        let mut input = File::open(src).context(format!("Failed to open {}", src))?;
+        let output = BufWriter::new(File::create(dst).context(format!("Failed to create {}", dst))?);
+        match self {
+            Compression::None => {}
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+                std::io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+            Compression::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(output, 0)?;
+                std::io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// When to close a segment and what to do with it
+#[derive(Debug, Clone, Copy)]
+pub struct RotationPolicy {
+    pub max_bytes: Option<u64>,
+    pub max_age: Option<Duration>,
+    pub compress: Compression,
+}
+
+impl RotationPolicy {
+    /// None unless a size or age limit is set
+    pub fn from_args(args: &Args) -> Option<Self> {
+        if args.rotate_mb.is_none() && args.rotate_secs.is_none() {
+            return None;
+        }
+        Some(Self {
+            max_bytes: args.rotate_mb.map(|mb| mb * 1024 * 1024),
+            max_age: args.rotate_secs.map(Duration::from_secs),
+            compress: args.rotate_compress,
+        })
+    }
+
+    /// The digest log's policy: under --soak it is always segmented, for the daily cut
+    pub fn for_digests(args: &Args) -> Option<Self> {
+        Self::from_args(args).or_else(|| {
+            args.soak.then_some(Self { max_bytes: None, max_age: None, compress: args.rotate_compress })
+        })
+    }
+}
+
+/// One segment in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentEntry {
+    pub file: String,
+    pub compression: Compression,
+    pub first_unix_ms: u64,
+    pub last_unix_ms: u64,
+    /// Bytes written to the segment
+    pub bytes: u64,
+    /// Size on disk once compressed
+    pub compressed_bytes: Option<u64>,
+}
+
+/// The segments of one output file
+pub struct Segments {
+    path: String,
+    policy: RotationPolicy,
+    opened: Instant,
+    opened_unix_ms: u64,
+    bytes: u64,
+    /// Number of the next closed segment
+    next: usize,
+    entries: Arc<Mutex<Vec<SegmentEntry>>>,
+    compressing: Vec<JoinHandle<()>>,
+}
+
+impl Segments {
+    /// Segments at `path`, continuing after any an earlier run left there
+    pub fn new(path: &str, policy: RotationPolicy) -> Self {
+        // The earlier run's open segment is appended to, so it is listed again at the end, from its first write
+        let (reopened, entries): (Vec<SegmentEntry>, Vec<SegmentEntry>) = read_index(&index_path(path)).into_iter()
+            .partition(|e| e.file == path);
+        let bytes = std::fs::metadata(path).map_or(0, |m| m.len());
+        let opened_unix_ms = match reopened.last() {
+            Some(entry) if bytes > 0 => entry.first_unix_ms,
+            _ => unix_ms(),
+        };
+        let next = entries.iter()
+            .filter_map(|e| Path::new(&e.file).file_name()?.to_str().map(str::to_string))
+            .chain(sibling_names(path))
+            .filter_map(|name| segment_number(path, &name))
+            .map(|n| n + 1)
+            .max()
+            .unwrap_or(0);
+        if next > 0 {
+            debug!("{}: {} earlier segments, numbering from {:05}", path, entries.len(), next);
+        }
+        Self {
+            path: path.to_string(),
+            policy,
+            opened: Instant::now(),
+            opened_unix_ms,
+            bytes,
+            next,
+            entries: Arc::new(Mutex::new(entries)),
+            compressing: Vec::new(),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn wrote(&mut self, bytes: usize) {
+        self.bytes += bytes as u64;
+    }
+
+    /// Whether the open segment has reached a limit
+    pub fn due(&self) -> bool {
+        self.policy.max_bytes.is_some_and(|max| self.bytes >= max)
+            || self.policy.max_age.is_some_and(|max| self.opened.elapsed() >= max)
+    }
+
+    /// Move the open segment (already flushed by the caller) aside and queue its
+    /// compression; the caller then opens a fresh file at `path`. Returns the closed file
+    pub fn close(&mut self) -> Result<String> {
+        // Skip numbers taken by files the index doesn't know about
+        let closed = loop {
+            let closed = format!("{}.{:05}", self.path, self.next);
+            self.next += 1;
+            if !segment_exists(&closed) {
+                break closed;
+            }
+        };
+        std::fs::rename(&self.path, &closed)
+            .context(format!("Failed to rotate {} to {}", self.path, closed))?;
+        let index = self.entries.lock().unwrap().len();
+        debug!("Rotated {} to {} ({} bytes)", self.path, closed, self.bytes);
+        self.entries.lock().unwrap().push(SegmentEntry {
+            file: closed.clone(),
+            compression: Compression::None,
+            first_unix_ms: self.opened_unix_ms,
+            last_unix_ms: unix_ms(),
+            bytes: self.bytes,
+            compressed_bytes: None,
+        });
+        self.opened = Instant::now();
+        self.opened_unix_ms = unix_ms();
+        self.bytes = 0;
+
+        let compress = self.policy.compress;
+        let entries = self.entries.clone();
+        let index_path = index_path(&self.path);
+        self.compressing.push(std::thread::spawn(move || {
+            compress_segment(&entries, index, compress);
+            if let Err(e) = write_index(&index_path, &entries.lock().unwrap()) {
+                warn!("Failed to write segment index {}: {:#}", index_path, e);
+            }
+        }));
+        Ok(closed)
+    }
+
+    /// Wait for pending compression and write the final index, the open segment last
+    pub fn finish(&mut self) -> Result<RotationReport> {
+        for handle in self.compressing.drain(..) {
+            let _ = handle.join();
+        }
+        let mut entries = self.entries.lock().unwrap().clone();
+        entries.push(SegmentEntry {
+            file: self.path.clone(),
+            compression: Compression::None,
+            first_unix_ms: self.opened_unix_ms,
+            last_unix_ms: unix_ms(),
+            bytes: self.bytes,
+            compressed_bytes: None,
+        });
+        let index = index_path(&self.path);
+        write_index(&index, &entries)?;
+        Ok(RotationReport {
+            index,
+            segments: entries.len(),
+            bytes: entries.iter().map(|e| e.bytes).sum(),
+            on_disk_bytes: entries.iter().map(|e| e.compressed_bytes.unwrap_or(e.bytes)).sum(),
+        })
+    }
+}
+
+/// Open `path` for appending, keeping what an earlier run left there; true if
+/// the file is empty and still needs its header
+pub fn open_append(path: &str) -> Result<(BufWriter<File>, bool)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!("Failed to open {}", path))?;
+    let empty = file.metadata()?.len() == 0;
+    Ok((BufWriter::new(file), empty))
+}
+
+fn index_path(path: &str) -> String {
+    format!("{}.index.json", path)
+}
+
+/// Entries of an existing index (none if there is no readable one)
+fn read_index(path: &str) -> Vec<SegmentEntry> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!("Ignoring unreadable segment index {}: {}", path, e);
+        Vec::new()
+    })
+}
+
+/// Names of the files next to `path`
+fn sibling_names(path: &str) -> Vec<String> {
+    let dir = Path::new(path).parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    std::fs::read_dir(dir).into_iter().flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect()
+}
+
+/// Segment number of `name` if it is a segment of `path` (`<file>.NNNNN`, possibly compressed)
+fn segment_number(path: &str, name: &str) -> Option<usize> {
+    let file = Path::new(path).file_name()?.to_str()?;
+    let rest = name.strip_prefix(file)?.strip_prefix('.')?;
+    let digits = [".gz", ".zst"].iter().find_map(|s| rest.strip_suffix(s)).unwrap_or(rest);
+    if digits.len() < 5 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Whether a closed segment, compressed or not, is already at `closed`
+fn segment_exists(closed: &str) -> bool {
+    ["", ".gz", ".zst"].iter().any(|suffix| Path::new(&format!("{}{}", closed, suffix)).exists())
+}
+
+fn write_index(path: &str, entries: &[SegmentEntry]) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(entries)?)
+        .context(format!("Failed to write {}", path))
+}
+
+/// Replace segment `index` by its compressed form and update its entry (left as is if that fails)
+fn compress_segment(entries: &Mutex<Vec<SegmentEntry>>, index: usize, compress: Compression) {
+    if compress == Compression::None {
+        return;
+    }
+    let file = entries.lock().unwrap()[index].file.clone();
+    let compressed = format!("{}{}", file, compress.suffix());
+    match compress.compress(&file, &compressed) {
+        Ok(()) => {
+            if let Err(e) = std::fs::remove_file(&file) {
+                warn!("Failed to remove {} after compressing it: {}", file, e);
+            }
+            let size = std::fs::metadata(&compressed).map(|m| m.len()).ok();
+            let entry = &mut entries.lock().unwrap()[index];
+            entry.file = compressed;
+            entry.compression = compress;
+            entry.compressed_bytes = size;
+        }
+        Err(e) => {
+            warn!("Compressing {} failed: {:#}; left uncompressed", file, e);
+            let _ = std::fs::remove_file(&compressed);
+        }
+    }
+}
+
+/// One rotated file in the `log_rotation` section
+#[derive(Debug, Clone, Serialize)]
+pub struct RotationReport {
+    /// Segment index file
+    pub index: String,
+    pub segments: usize,
+    pub bytes: u64,
+    /// Total size of the segments on disk, compressed ones counted compressed
+    pub on_disk_bytes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_segments_are_moved_aside_and_indexed() {
+        let dir = std::env::temp_dir().join(format!("fdp-rotation-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("digests.jsonl").to_string_lossy().into_owned();
+        let policy = RotationPolicy { max_bytes: Some(10), max_age: None, compress: Compression::None };
+        let mut segments = Segments::new(&path, policy);
+
+        std::fs::write(&path, "0123456789ab").unwrap();
+        segments.wrote(12);
+        assert!(segments.due());
+        segments.close().unwrap();
+        assert!(!segments.due());
+        std::fs::write(&path, "cd").unwrap();
+        segments.wrote(2);
+
+        let report = segments.finish().unwrap();
+        assert_eq!((report.segments, report.bytes), (2, 14));
+        assert_eq!(std::fs::read_to_string(format!("{}.00000", path)).unwrap(), "0123456789ab");
+        let index: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report.index).unwrap()).unwrap();
+        assert_eq!(index[1]["file"], path.as_str());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn a_second_run_numbers_past_earlier_segments() {
+        let dir = std::env::temp_dir().join(format!("fdp-rotation-rerun-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("digests.jsonl").to_string_lossy().into_owned();
+        let policy = RotationPolicy { max_bytes: Some(1), max_age: None, compress: Compression::None };
+
+        let mut first = Segments::new(&path, policy);
+        std::fs::write(&path, "first").unwrap();
+        first.wrote(5);
+        first.close().unwrap();
+        first.finish().unwrap();
+        // A segment the index doesn't list (e.g. from a run that died before writing it)
+        std::fs::write(format!("{}.00001.gz", path), "stray").unwrap();
+
+        let mut second = Segments::new(&path, policy);
+        std::fs::write(&path, "second").unwrap();
+        second.wrote(6);
+        second.close().unwrap();
+        let report = second.finish().unwrap();
+
+        assert_eq!(std::fs::read_to_string(format!("{}.00000", path)).unwrap(), "first");
+        assert_eq!(std::fs::read_to_string(format!("{}.00002", path)).unwrap(), "second");
+        let index: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report.index).unwrap()).unwrap();
+        let files: Vec<_> = index.as_array().unwrap().iter().map(|e| e["file"].as_str().unwrap().to_string()).collect();
+        assert_eq!(files, vec![format!("{}.00000", path), format!("{}.00002", path), path.clone()]);
+        assert_eq!(segment_number(&path, "digests.jsonl.index.json"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn closed_segments_are_compressed_in_place() {
+        use std::io::Read;
+        let dir = std::env::temp_dir().join(format!("fdp-rotation-gzip-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("interval.csv").to_string_lossy().into_owned();
+        let policy = RotationPolicy { max_bytes: Some(1), max_age: None, compress: Compression::Gzip };
+        let mut segments = Segments::new(&path, policy);
+
+        std::fs::write(&path, "a,b\n1,2\n").unwrap();
+        segments.wrote(8);
+        let closed = segments.close().unwrap();
+        let report = segments.finish().unwrap();
+
+        assert!(!Path::new(&closed).exists());
+        let mut content = String::new();
+        flate2::read::GzDecoder::new(File::open(format!("{}.gz", closed)).unwrap()).read_to_string(&mut content).unwrap();
+        assert_eq!(content, "a,b\n1,2\n");
+        let index: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&report.index).unwrap()).unwrap();
+        assert_eq!(index[0]["file"], format!("{}.gz", closed).as_str());
+        assert_eq!(index[0]["compression"], "gzip");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+// Background RPC round-trip probe (--rpc-probe-ms)
+//
+// Issues a trivial read (latest checkpoint sequence number) at a low fixed rate
+// during the load phase and records its round-trip latency over time. The probe
+// doesn't touch execution or storage, so when transaction latency climbs while
+// probe latency stays flat the slowdown is in the execution path; when both
+// climb together the RPC server or network is degrading.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_sdk::SuiClient;
+use tokio::time::sleep;
+
+use crate::latency::{Histogram, LatencySummary};
+use crate::timeline::Timestamp;
+use crate::BenchStats;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeSample {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    /// None if the probe failed
+    pub latency_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeReport {
+    pub interval_ms: u64,
+    pub failures: u64,
+    pub latency: LatencySummary,
+    pub samples: Vec<ProbeSample>,
+}
+
+/// Probe every `interval` until `running` is cleared
+pub async fn run_rpc_probe(
+    client: SuiClient,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    interval: Duration,
+) -> ProbeReport {
+    let mut samples = Vec::new();
+    let mut histogram = Histogram::new();
+    let mut failures = 0;
+    while running.load(Ordering::Relaxed) {
+        let at = Timestamp::now(&stats);
+        let started = Instant::now();
+        let result = client.read_api().get_latest_checkpoint_sequence_number().await;
+        let elapsed = started.elapsed();
+        samples.push(match result {
+            Ok(_) => {
+                histogram.record(elapsed);
+                ProbeSample { at, latency_ms: Some(elapsed.as_secs_f64() * 1000.0), error: None }
+            }
+            Err(e) => {
+                failures += 1;
+                ProbeSample { at, latency_ms: None, error: Some(e.to_string()) }
+            }
+        });
+        sleep(interval.saturating_sub(elapsed)).await;
+    }
+    ProbeReport {
+        interval_ms: interval.as_millis() as u64,
+        failures,
+        latency: histogram.summary(),
+        samples,
+    }
+}
@@ -0,0 +1,221 @@
+// Object size distributions
+//
+// --size-distribution controls the payload size of LargeBlob objects at create
+// time so the tracked population has mixed sizes instead of a uniform 4KB.
+// One size is sampled per create transaction (each batch is homogeneous), so
+// created objects can be attributed to a size without per-object reads.
+//
+// Spec formats:
+//   fixed:4096                     every blob has 4096 bytes
+//   uniform:512-16384              uniform in [512, 16384]
+//   lognormal:2048,1.0             median 2048 bytes, sigma 1.0
+//   hist:256=50,4096=30,65536=20   size=weight pairs
+
+use rand::Rng;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Smallest and largest blob payload accepted (Sui caps objects at ~250KB)
+pub const MIN_BLOB_SIZE: u64 = 1;
+pub const MAX_BLOB_SIZE: u64 = 200 * 1024;
+
+/// Power-of-two buckets: bucket i holds sizes in (2^(i-1), 2^i]
+const NUM_SIZE_BUCKETS: usize = 19; // up to 256KiB
+
+/// Distribution of blob payload sizes
+#[derive(Debug, Clone)]
+pub enum SizeDistribution {
+    Fixed(u64),
+    Uniform { min: u64, max: u64 },
+    LogNormal { median: f64, sigma: f64 },
+    Histogram(Vec<(u64, f64)>),
+}
+
+impl FromStr for SizeDistribution {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (kind, params) = spec.split_once(':')
+            .ok_or_else(|| format!("invalid size distribution '{}': expected kind:params", spec))?;
+        let parse_size = |s: &str| -> Result<u64, String> {
+            let v: u64 = s.trim().parse().map_err(|_| format!("invalid size '{}'", s))?;
+            if !(MIN_BLOB_SIZE..=MAX_BLOB_SIZE).contains(&v) {
+                return Err(format!("size {} out of range {}..={}", v, MIN_BLOB_SIZE, MAX_BLOB_SIZE));
+            }
+            Ok(v)
+        };
+
+        match kind.trim() {
+            "fixed" => Ok(SizeDistribution::Fixed(parse_size(params)?)),
+            "uniform" => {
+                let (min, max) = params.split_once('-')
+                    .ok_or_else(|| "uniform expects min-max, e.g. uniform:512-16384".to_string())?;
+                let (min, max) = (parse_size(min)?, parse_size(max)?);
+                if min > max {
+                    return Err(format!("uniform range {}-{} is empty", min, max));
+                }
+                Ok(SizeDistribution::Uniform { min, max })
+            }
+            "lognormal" => {
+                let (median, sigma) = params.split_once(',')
+                    .ok_or_else(|| "lognormal expects median,sigma, e.g. lognormal:2048,1.0".to_string())?;
+                let median = parse_size(median)? as f64;
+                let sigma: f64 = sigma.trim().parse().map_err(|_| format!("invalid sigma '{}'", sigma))?;
+                if sigma < 0.0 {
+                    return Err("lognormal sigma must be >= 0".to_string());
+                }
+                Ok(SizeDistribution::LogNormal { median, sigma })
+            }
+            "hist" => {
+                let mut entries = Vec::new();
+                for entry in params.split(',') {
+                    let (size, weight) = entry.split_once('=')
+                        .ok_or_else(|| format!("histogram entry '{}' must be size=weight", entry))?;
+                    let weight: f64 = weight.trim().parse().map_err(|_| format!("invalid weight '{}'", weight))?;
+                    if weight < 0.0 {
+                        return Err(format!("negative weight in '{}'", entry));
+                    }
+                    entries.push((parse_size(size)?, weight));
+                }
+                if entries.iter().map(|(_, w)| w).sum::<f64>() <= 0.0 {
+                    return Err("histogram weights must sum to > 0".to_string());
+                }
+                Ok(SizeDistribution::Histogram(entries))
+            }
+            other => Err(format!("unknown size distribution '{}' (fixed, uniform, lognormal, hist)", other)),
+        }
+    }
+}
+
+impl SizeDistribution {
+    /// Draw one payload size in bytes
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        let size = match self {
+            SizeDistribution::Fixed(size) => *size,
+            SizeDistribution::Uniform { min, max } => rng.gen_range(*min..=*max),
+            SizeDistribution::LogNormal { median, sigma } => {
+                // Box-Muller standard normal, then scale to the lognormal
+                let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (median * (sigma * z).exp()).round() as u64
+            }
+            SizeDistribution::Histogram(entries) => {
+                let total: f64 = entries.iter().map(|(_, w)| w).sum();
+                let mut pick = rng.gen_range(0.0..total);
+                let mut chosen = entries[entries.len() - 1].0;
+                for (size, weight) in entries {
+                    if pick < *weight {
+                        chosen = *size;
+                        break;
+                    }
+                    pick -= weight;
+                }
+                chosen
+            }
+        };
+        size.clamp(MIN_BLOB_SIZE, MAX_BLOB_SIZE)
+    }
+}
+
+/// Per-size-bucket created/updated counters (lock-free)
+pub struct SizeBucketStats {
+    created: [AtomicU64; NUM_SIZE_BUCKETS],
+    updated: [AtomicU64; NUM_SIZE_BUCKETS],
+}
+
+impl SizeBucketStats {
+    pub fn new() -> Self {
+        Self {
+            created: std::array::from_fn(|_| AtomicU64::new(0)),
+            updated: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+
+    fn bucket(size: u64) -> usize {
+        let bits = 64 - size.saturating_sub(1).leading_zeros() as usize;
+        bits.min(NUM_SIZE_BUCKETS - 1)
+    }
+
+    pub fn record_created(&self, size: u64) {
+        self.created[Self::bucket(size)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_updated(&self, size: u64) {
+        self.updated[Self::bucket(size)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Non-empty buckets as JSON rows
+    pub fn to_json(&self) -> serde_json::Value {
+        let rows: Vec<serde_json::Value> = (0..NUM_SIZE_BUCKETS)
+            .filter_map(|i| {
+                let created = self.created[i].load(Ordering::Relaxed);
+                let updated = self.updated[i].load(Ordering::Relaxed);
+                if created == 0 && updated == 0 {
+                    return None;
+                }
+                let max = 1u64 << i;
+                let min = if i == 0 { 1 } else { (1u64 << (i - 1)) + 1 };
+                Some(serde_json::json!({
+                    "min_bytes": min,
+                    "max_bytes": max,
+                    "created": created,
+                    "updated": updated,
+                }))
+            })
+            .collect();
+        serde_json::Value::Array(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn size_specs_parse_and_sample_in_range() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        assert_eq!("fixed:4096".parse::<SizeDistribution>().unwrap().sample(&mut rng), 4096);
+        let uniform: SizeDistribution = "uniform:512-16384".parse().unwrap();
+        for _ in 0..100 {
+            assert!((512..=16384).contains(&uniform.sample(&mut rng)));
+        }
+        let lognormal: SizeDistribution = "lognormal:2048,3.0".parse().unwrap();
+        for _ in 0..100 {
+            assert!((MIN_BLOB_SIZE..=MAX_BLOB_SIZE).contains(&lognormal.sample(&mut rng)));
+        }
+        assert!("uniform:16384-512".parse::<SizeDistribution>().is_err());
+        assert!("fixed:0".parse::<SizeDistribution>().is_err());
+        assert!("fixed:300000".parse::<SizeDistribution>().is_err());
+        assert!("hist:256=0".parse::<SizeDistribution>().is_err());
+        assert!("zipf:1.1".parse::<SizeDistribution>().is_err());
+    }
+
+    #[test]
+    fn histogram_samples_follow_the_weights() {
+        let hist: SizeDistribution = "hist:256=75,4096=25,65536=0".parse().unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let sizes: Vec<u64> = (0..10_000).map(|_| hist.sample(&mut rng)).collect();
+        let share = |size| sizes.iter().filter(|s| **s == size).count() as f64 / 100.0;
+        assert!((share(256) - 75.0).abs() < 2.0);
+        assert!((share(4096) - 25.0).abs() < 2.0);
+        assert_eq!(share(65536), 0.0);
+    }
+
+    #[test]
+    fn sizes_land_in_power_of_two_buckets() {
+        assert_eq!(SizeBucketStats::bucket(1), 0);
+        assert_eq!(SizeBucketStats::bucket(2), 1);
+        assert_eq!(SizeBucketStats::bucket(4096), 12);
+        assert_eq!(SizeBucketStats::bucket(4097), 13);
+        assert_eq!(SizeBucketStats::bucket(u64::MAX), NUM_SIZE_BUCKETS - 1);
+
+        let stats = SizeBucketStats::new();
+        stats.record_created(4096);
+        stats.record_updated(3000);
+        assert_eq!(stats.to_json(), serde_json::json!([
+            { "min_bytes": 2049, "max_bytes": 4096, "created": 1, "updated": 1 },
+        ]));
+    }
+}
@@ -0,0 +1,255 @@
+// Latency and error-rate SLOs (`slo` section of --config)
+//
+// A config file can declare service-level objectives for the run:
+//
+//   { "slo": { "p99_ms": 800, "max_error_pct": 1.0, "min_tps": 500 } }
+//
+// Every stats interval is checked against each objective: latency quantiles
+// over the transactions that completed in the interval (all operations
+// merged), the share of the interval's submissions that failed, and its
+// success TPS. An interval is out of SLO when any objective is missed; latency
+// and error objectives are skipped for intervals without transactions. The
+// `slo` results section reports compliance, the total time spent out of SLO,
+// per-objective misses and the out-of-SLO intervals themselves, and each
+// timeline sample gets `slo_met`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::latency::Histogram;
+use crate::timeline::{IntervalSample, Timestamp};
+
+/// Violating intervals listed in the report (the counts cover all of them)
+const MAX_LISTED: usize = 100;
+
+/// Objectives; any left out are not checked
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SloSpec {
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub p999_ms: Option<f64>,
+    /// Failed share of submitted transactions, in percent
+    pub max_error_pct: Option<f64>,
+    pub min_tps: Option<f64>,
+}
+
+impl SloSpec {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let latencies = [self.p50_ms, self.p90_ms, self.p99_ms, self.p999_ms];
+        if latencies.iter().flatten().any(|ms| *ms <= 0.0) {
+            return Err(anyhow::anyhow!("slo: latency objectives must be positive"));
+        }
+        if self.max_error_pct.is_some_and(|pct| !(0.0..=100.0).contains(&pct)) {
+            return Err(anyhow::anyhow!("slo: max_error_pct must be between 0 and 100"));
+        }
+        if latencies.iter().all(Option::is_none) && self.max_error_pct.is_none() && self.min_tps.is_none() {
+            return Err(anyhow::anyhow!("slo: declare at least one objective"));
+        }
+        Ok(())
+    }
+
+    /// Objectives missed by an interval, with the measured value
+    fn violations(&self, latency: &Histogram, submitted: u64, failed: u64, tps: f64) -> BTreeMap<&'static str, f64> {
+        let mut missed = BTreeMap::new();
+        if !latency.is_empty() {
+            for (name, q, limit) in [("p50_ms", 0.50, self.p50_ms), ("p90_ms", 0.90, self.p90_ms), ("p99_ms", 0.99, self.p99_ms), ("p999_ms", 0.999, self.p999_ms)] {
+                let ms = latency.quantile_us(q) as f64 / 1000.0;
+                if limit.is_some_and(|limit| ms > limit) {
+                    missed.insert(name, ms);
+                }
+            }
+        }
+        if submitted > 0 {
+            let pct = failed as f64 * 100.0 / submitted as f64;
+            if self.max_error_pct.is_some_and(|limit| pct > limit) {
+                missed.insert("max_error_pct", pct);
+            }
+        }
+        if self.min_tps.is_some_and(|limit| tps < limit) {
+            missed.insert("min_tps", tps);
+        }
+        missed
+    }
+}
+
+/// One interval out of SLO
+#[derive(Debug, Clone, Serialize)]
+pub struct SloInterval {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub secs: f64,
+    /// Missed objective -> measured value
+    pub violations: BTreeMap<&'static str, f64>,
+}
+
+/// Misses of one objective
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ObjectiveMisses {
+    pub intervals: u64,
+    pub secs: f64,
+    /// Worst measured value
+    pub worst: f64,
+}
+
+/// `slo` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct SloReport {
+    pub objectives: SloSpec,
+    pub intervals: u64,
+    pub intervals_met: u64,
+    /// Share of evaluated time within SLO
+    pub compliance: f64,
+    pub evaluated_secs: f64,
+    pub out_of_slo_secs: f64,
+    pub by_objective: BTreeMap<&'static str, ObjectiveMisses>,
+    /// First out-of-SLO intervals, in order
+    pub violations: Vec<SloInterval>,
+}
+
+struct State {
+    /// (elapsed secs, submitted, failed) at the previous interval
+    prev: (f64, u64, u64),
+    intervals: u64,
+    intervals_met: u64,
+    evaluated_secs: f64,
+    out_of_slo_secs: f64,
+    by_objective: BTreeMap<&'static str, ObjectiveMisses>,
+    violations: Vec<SloInterval>,
+    /// End of every out-of-SLO interval, for the timeline
+    missed_at: Vec<f64>,
+}
+
+pub struct SloTracker {
+    spec: SloSpec,
+    state: Mutex<State>,
+}
+
+impl SloTracker {
+    pub fn new(spec: SloSpec) -> Self {
+        Self {
+            spec,
+            state: Mutex::new(State {
+                prev: (0.0, 0, 0),
+                intervals: 0,
+                intervals_met: 0,
+                evaluated_secs: 0.0,
+                out_of_slo_secs: 0.0,
+                by_objective: BTreeMap::new(),
+                violations: Vec::new(),
+                missed_at: Vec::new(),
+            }),
+        }
+    }
+
+    /// Check one stats interval; `latency` holds its per-op histograms
+    pub fn record(&self, sample: &IntervalSample, latency: &BTreeMap<&'static str, Histogram>) -> bool {
+        let mut merged = Histogram::new();
+        for h in latency.values() {
+            merged.merge(h);
+        }
+        let mut state = self.state.lock().unwrap();
+        let (prev_secs, prev_submitted, prev_failed) = state.prev;
+        state.prev = (sample.at.elapsed_secs, sample.tx_submitted, sample.tx_failed);
+        let secs = sample.at.elapsed_secs - prev_secs;
+        let violations = self.spec.violations(
+            &merged,
+            sample.tx_submitted.saturating_sub(prev_submitted),
+            sample.tx_failed.saturating_sub(prev_failed),
+            sample.interval_tps,
+        );
+        state.intervals += 1;
+        state.evaluated_secs += secs;
+        if violations.is_empty() {
+            state.intervals_met += 1;
+            return true;
+        }
+        state.out_of_slo_secs += secs;
+        state.missed_at.push(sample.at.elapsed_secs);
+        for (&name, &value) in &violations {
+            let misses = state.by_objective.entry(name).or_default();
+            // Higher is worse except for throughput
+            let worse = if name == "min_tps" { value < misses.worst || misses.intervals == 0 } else { value > misses.worst };
+            if worse {
+                misses.worst = value;
+            }
+            misses.intervals += 1;
+            misses.secs += secs;
+        }
+        if state.violations.len() < MAX_LISTED {
+            state.violations.push(SloInterval { at: sample.at.clone(), secs, violations });
+        }
+        false
+    }
+
+    /// Mark each timeline sample as in or out of SLO
+    pub fn annotate(&self, samples: &mut [IntervalSample]) {
+        let state = self.state.lock().unwrap();
+        for sample in samples {
+            sample.slo_met = Some(!state.missed_at.contains(&sample.at.elapsed_secs));
+        }
+    }
+
+    pub fn report(&self) -> SloReport {
+        let state = self.state.lock().unwrap();
+        SloReport {
+            objectives: self.spec.clone(),
+            intervals: state.intervals,
+            intervals_met: state.intervals_met,
+            compliance: if state.evaluated_secs > 0.0 { 1.0 - state.out_of_slo_secs / state.evaluated_secs } else { 1.0 },
+            evaluated_secs: state.evaluated_secs,
+            out_of_slo_secs: state.out_of_slo_secs,
+            by_objective: state.by_objective.clone(),
+            violations: state.violations.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn sample(elapsed_secs: f64, submitted: u64, failed: u64, interval_tps: f64) -> IntervalSample {
+        IntervalSample {
+            at: Timestamp { elapsed_secs, ..Timestamp::default() },
+            tx_submitted: submitted,
+            tx_failed: failed,
+            interval_tps,
+            ..IntervalSample::default()
+        }
+    }
+
+    fn latency(ms: u64) -> BTreeMap<&'static str, Histogram> {
+        let mut h = Histogram::new();
+        for _ in 0..100 {
+            h.record(Duration::from_millis(ms));
+        }
+        BTreeMap::from([("update", h)])
+    }
+
+    #[test]
+    fn out_of_slo_time_adds_up_per_objective() {
+        let tracker = SloTracker::new(SloSpec { p99_ms: Some(800.0), max_error_pct: Some(1.0), ..SloSpec::default() });
+        assert!(tracker.record(&sample(10.0, 1000, 0, 100.0), &latency(200)));
+        // Slow, then slow and failing
+        assert!(!tracker.record(&sample(20.0, 2000, 5, 100.0), &latency(1500)));
+        assert!(!tracker.record(&sample(25.0, 2500, 105, 100.0), &latency(1500)));
+        // Idle: nothing to judge
+        assert!(tracker.record(&sample(35.0, 2500, 105, 0.0), &BTreeMap::new()));
+
+        let report = tracker.report();
+        assert_eq!((report.intervals, report.intervals_met), (4, 2));
+        assert_eq!(report.out_of_slo_secs, 15.0);
+        assert!((report.compliance - 20.0 / 35.0).abs() < 1e-9);
+        assert_eq!(report.by_objective["p99_ms"].intervals, 2);
+        assert_eq!(report.by_objective["max_error_pct"].secs, 5.0);
+        assert_eq!(report.by_objective["max_error_pct"].worst, 20.0);
+
+        let mut samples = vec![sample(10.0, 0, 0, 0.0), sample(20.0, 0, 0, 0.0)];
+        tracker.annotate(&mut samples);
+        assert_eq!(samples.iter().map(|s| s.slo_met).collect::<Vec<_>>(), vec![Some(true), Some(false)]);
+    }
+}
@@ -0,0 +1,236 @@
+// Multi-day soak mode (--soak)
+//
+// A run of a few hours gets away with things a multi-day run does not: worker
+// gas coins drain, the reference gas price moves at epoch changes, the digest
+// log and the interval timeline grow without bound and the tracker vectors
+// keep the capacity of their largest moment. With --soak a maintenance task
+// runs next to the workers and, once a minute:
+//
+// - picks up a new reference gas price after every epoch change seen by the
+//   epoch watcher (--epoch-poll-secs; workers read it before each transaction),
+// - every ten minutes tops up workers whose gas balance runs low from the
+//   faucet and merges the grant into their primary coin,
+// - every hour compacts the tracked-object vectors,
+// - every 24 hours logs a one-line summary of the day and closes the day's
+//   segment of the --tx-digests log (see rotation.rs), so that the day's
+//   digests end up in their own numbered, indexed and optionally compressed
+//   file, alongside any segments --rotate-mb/--rotate-secs cut.
+//
+// The interval timeline keeps only the most recent SOAK_MAX_INTERVALS samples
+// (they are all logged as they happen, and written to --interval-log), and --soak requires --autosave so a
+// crash loses at most one autosave interval. The days and the maintenance done
+// are reported under `soak`.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use sui_sdk::SuiClient;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::counters::CounterSnapshot;
+use crate::faucet::FaucetClient;
+use crate::timeline::Timestamp;
+use crate::tx_log::DigestRecorder;
+use crate::{gas_pool, heap, BenchStats, WorkerState, MIN_REUSE_BALANCE_BUDGETS};
+
+/// Interval samples kept in memory in soak mode (about 3.5 days at 30s)
+pub const SOAK_MAX_INTERVALS: usize = 10_000;
+
+const TICK: Duration = Duration::from_secs(60);
+const REFUEL_EVERY_TICKS: u64 = 10;
+const COMPACT_EVERY_TICKS: u64 = 60;
+const DAY_TICKS: u64 = 24 * 60;
+
+/// Settings the maintenance task needs from the command line
+pub struct SoakSettings {
+    pub gas_budget: u64,
+    pub faucet: FaucetClient,
+}
+
+/// One day of a soak run
+#[derive(Debug, Clone, Serialize)]
+pub struct SoakDay {
+    pub day: u64,
+    pub start: Timestamp,
+    pub end: Timestamp,
+    #[serde(flatten)]
+    pub counters: CounterSnapshot,
+    pub tps: f64,
+    pub tracked_objects: usize,
+    pub heap_mb: f64,
+}
+
+/// `soak` section of the results
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SoakReport {
+    pub days: Vec<SoakDay>,
+    pub refuels: u64,
+    pub refuel_failures: u64,
+    pub rgp_refreshes: u64,
+    pub compactions: u64,
+    pub rotated_logs: Vec<String>,
+}
+
+/// Run maintenance until `running` is cleared
+pub async fn run(
+    client: Option<SuiClient>,
+    workers: Vec<Arc<RwLock<WorkerState>>>,
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    recorder: Option<Arc<DigestRecorder>>,
+    settings: SoakSettings,
+) -> SoakReport {
+    let mut report = SoakReport::default();
+    let mut day_start = (Timestamp::now(&stats), CounterSnapshot::read(&stats));
+    let mut rgp_epoch = stats.epochs.current();
+    let mut tick = 0u64;
+    while running.load(Ordering::Relaxed) {
+        // Short naps, so the end of the run isn't held up by a full tick
+        let tick_end = stats.clock.now() + TICK;
+        while running.load(Ordering::Relaxed) && stats.clock.now() < tick_end {
+            stats.clock.sleep(Duration::from_secs(1)).await;
+        }
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+        tick += 1;
+
+        // New epoch, possibly a new reference gas price
+        if let (Some(client), Some(epoch)) = (&client, stats.epochs.current()) {
+            if rgp_epoch != Some(epoch) {
+                match client.governance_api().get_reference_gas_price().await {
+                    Ok(rgp) => {
+                        if stats.epochs.set_reference_gas_price(rgp) {
+                            info!("Soak: epoch {} reference gas price {}", epoch, rgp);
+                        }
+                        rgp_epoch = Some(epoch);
+                        report.rgp_refreshes += 1;
+                    }
+                    Err(e) => warn!("Soak: reference gas price refresh failed: {}", e),
+                }
+            }
+        }
+
+        if let Some(client) = client.as_ref().filter(|_| tick % REFUEL_EVERY_TICKS == 0) {
+            for worker in &workers {
+                match refuel(client, worker, &settings).await {
+                    Ok(true) => report.refuels += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        report.refuel_failures += 1;
+                        warn!("Soak: refuel failed: {:#}", e);
+                    }
+                }
+            }
+        }
+
+        if tick % COMPACT_EVERY_TICKS == 0 {
+            for worker in &workers {
+                worker.write().await.compact();
+            }
+            report.compactions += 1;
+        }
+
+        if tick % DAY_TICKS == 0 {
+            let day = close_day(&stats, &workers, report.days.len() as u64 + 1, &day_start).await;
+            info!("Soak day {}: {} tx ({:.1} TPS), {} failed, {} objects created, {} updated, {} MIST gas, {} tracked objects, heap {:.0} MB",
+                day.day, day.counters.tx_success, day.tps, day.counters.tx_failed, day.counters.objects_created,
+                day.counters.objects_updated, day.counters.gas_spent, day.tracked_objects, day.heap_mb);
+            day_start = (day.end.clone(), CounterSnapshot::read(&stats));
+            report.days.push(day);
+            if let Some(recorder) = &recorder {
+                match recorder.close_segment() {
+                    Ok(Some(closed)) => report.rotated_logs.push(closed),
+                    Ok(None) => {}
+                    Err(e) => warn!("Soak: digest log rotation failed: {:#}", e),
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Counter deltas and tracker size since the start of the day
+async fn close_day(stats: &BenchStats, workers: &[Arc<RwLock<WorkerState>>], day: u64, start: &(Timestamp, CounterSnapshot)) -> SoakDay {
+    let end = Timestamp::now(stats);
+    let counters = CounterSnapshot::read(stats).since(&start.1);
+    let secs = end.elapsed_secs - start.0.elapsed_secs;
+    let mut tracked_objects = 0;
+    for worker in workers {
+        let state = worker.read().await;
+        tracked_objects += state.objects.len() + state.cold.len();
+    }
+    SoakDay {
+        day,
+        start: start.0.clone(),
+        end,
+        counters,
+        tps: if secs > 0.0 { counters.tx_success as f64 / secs } else { 0.0 },
+        tracked_objects,
+        heap_mb: heap::allocated() as f64 / (1024.0 * 1024.0),
+    }
+}
+
+/// Top up a worker whose balance fell below MIN_REUSE_BALANCE_BUDGETS gas budgets; true if it was refueled
+async fn refuel(client: &SuiClient, worker: &Arc<RwLock<WorkerState>>, settings: &SoakSettings) -> anyhow::Result<bool> {
+    let (id, address) = {
+        let state = worker.read().await;
+        (state.id, state.address)
+    };
+    let balance = client.coin_read_api().get_balance(address, None).await?.total_balance;
+    let floor = settings.gas_budget as u128 * MIN_REUSE_BALANCE_BUDGETS as u128;
+    if balance >= floor {
+        debug!("Soak: worker {} balance {} MIST", id, balance);
+        return Ok(false);
+    }
+    info!("Soak: worker {} down to {} MIST, requesting gas", id, balance);
+    settings.faucet.request(address).await?;
+    // The grant is a separate coin; fold it into the one the worker pays with
+    let rgp = client.governance_api().get_reference_gas_price().await.unwrap_or(1000);
+    gas_pool::consolidate_gas(client, worker, settings.gas_budget, rgp).await?;
+    worker.write().await.gas_pool.clear();
+    Ok(true)
+}
+
+impl WorkerState {
+    /// Give back the capacity the tracker vectors grew to at their largest
+    pub fn compact(&mut self) {
+        self.objects.shrink_to_fit();
+        self.cold.shrink_to_fit();
+        self.frozen.shrink_to_fit();
+        self.quarantine.shrink_to_fit();
+        self.last_batch.shrink_to_fit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn a_day_of_maintenance_compacts_hourly_and_closes_the_day() {
+        let clock = VirtualClock::new();
+        let stats = Arc::new(BenchStats::new(1, clock.clone()));
+        let running = Arc::new(AtomicBool::new(true));
+        {
+            // One transaction a second, stopping half a minute into the 26th hour
+            let (stats, running) = (stats.clone(), running.clone());
+            clock.on_sleep(move |elapsed| {
+                stats.tx_success.add(0, 1);
+                running.store(elapsed < Duration::from_secs(25 * 3600 + 30), Ordering::Relaxed);
+            });
+        }
+        let settings = SoakSettings { gas_budget: 1, faucet: FaucetClient::new("http://127.0.0.1:9123", 0.0, true) };
+
+        let report = futures::executor::block_on(run(None, Vec::new(), stats, running, None, settings));
+
+        assert_eq!((report.compactions, report.refuels, report.rgp_refreshes), (25, 0, 0));
+        assert_eq!(report.days.len(), 1);
+        let day = &report.days[0];
+        assert_eq!((day.day, day.start.elapsed_secs, day.end.elapsed_secs), (1, 0.0, 86_400.0));
+        assert_eq!((day.counters.tx_success, day.tps, day.tracked_objects), (86_400, 1.0, 0));
+    }
+}
@@ -0,0 +1,175 @@
+// Objects dropped from the tracker (--save-objects)
+//
+// A worker stops tracking objects when its tracker is full and when memory
+// pressure makes it shed part of its population, but the objects still exist
+// and still belong to the worker. With --save-objects every dropped object is
+// appended to <save file>.dropped.jsonl as it goes, and the end-of-run save
+// adds them back to their worker's list, so a phase-two run loads the full
+// population rather than only what survived in memory. A dropped object's ref
+// is as current as when it was dropped (nothing else writes to it);
+// --resolve-dropped re-reads them from the node before saving anyway and leaves
+// out the ones that no longer exist.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use sui_sdk::types::base_types::ObjectID;
+use sui_sdk::SuiClient;
+use tracing::warn;
+
+use crate::{SavedBenchmarkState, TrackedObject, WorkerState};
+
+/// Objects per multi-get when resolving
+const RESOLVE_CHUNK: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SpilledObject {
+    worker: usize,
+    object: TrackedObject,
+}
+
+/// `dropped_objects` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct SpillReport {
+    pub file: String,
+    pub spilled: u64,
+    /// Added back into the saved state
+    pub saved: usize,
+    /// Re-read from the node (--resolve-dropped)
+    pub resolved: bool,
+    /// No longer on chain when resolved
+    pub gone: usize,
+}
+
+pub fn path(save_path: &str) -> String {
+    format!("{}.dropped.jsonl", save_path)
+}
+
+pub struct Spill {
+    path: String,
+    file: Mutex<std::io::BufWriter<std::fs::File>>,
+    spilled: AtomicU64,
+}
+
+impl Spill {
+    /// Start an empty spill file (one from an earlier run is replaced)
+    pub fn create(path: String) -> Result<Self> {
+        let file = std::fs::File::create(&path).context(format!("Failed to create {}", path))?;
+        Ok(Self { path, file: Mutex::new(std::io::BufWriter::new(file)), spilled: AtomicU64::new(0) })
+    }
+
+    pub fn write(&self, worker: usize, objects: &[TrackedObject]) {
+        if objects.is_empty() {
+            return;
+        }
+        let mut file = self.file.lock().unwrap();
+        for object in objects {
+            let line = serde_json::to_string(&SpilledObject { worker, object: object.clone() }).expect("tracked objects serialize");
+            if let Err(e) = writeln!(file, "{}", line) {
+                warn!("Failed to spill dropped object {} to {}: {}", object.id, self.path, e);
+                return;
+            }
+        }
+        self.spilled.fetch_add(objects.len() as u64, Ordering::Relaxed);
+    }
+
+    /// Everything spilled so far, by worker
+    pub fn read(&self) -> Result<BTreeMap<usize, Vec<TrackedObject>>> {
+        self.file.lock().unwrap().flush()?;
+        let file = std::fs::File::open(&self.path).context(format!("Failed to read {}", self.path))?;
+        let mut by_worker: BTreeMap<usize, Vec<TrackedObject>> = BTreeMap::new();
+        for line in std::io::BufReader::new(file).lines() {
+            let line: SpilledObject = serde_json::from_str(&line?).context(format!("Corrupt line in {}", self.path))?;
+            by_worker.entry(line.worker).or_default().push(line.object);
+        }
+        Ok(by_worker)
+    }
+
+    pub fn report(&self, saved: usize, resolved: bool, gone: usize) -> SpillReport {
+        SpillReport { file: self.path.clone(), spilled: self.spilled.load(Ordering::Relaxed), saved, resolved, gone }
+    }
+}
+
+/// Add the spilled objects back into a snapshot; returns how many were added
+pub fn merge(state: &mut SavedBenchmarkState, mut spilled: BTreeMap<usize, Vec<TrackedObject>>) -> usize {
+    let mut added = 0;
+    for worker in &mut state.workers {
+        let Some(objects) = spilled.remove(&worker.worker_id) else { continue };
+        // Whatever is still tracked has the newer ref
+        let mut seen: HashSet<ObjectID> = worker.objects.iter().map(|o| o.id).collect();
+        for object in objects {
+            if seen.insert(object.id) {
+                worker.objects.push(object);
+                added += 1;
+            }
+        }
+    }
+    state.total_objects += added;
+    added
+}
+
+/// Current refs of spilled objects; the ones no longer on chain are left out
+pub async fn resolve(client: &SuiClient, spilled: BTreeMap<usize, Vec<TrackedObject>>) -> Result<(BTreeMap<usize, Vec<TrackedObject>>, usize)> {
+    let mut gone = 0;
+    let mut resolved = BTreeMap::new();
+    for (worker, objects) in spilled {
+        let mut current = Vec::with_capacity(objects.len());
+        for chunk in objects.chunks(RESOLVE_CHUNK) {
+            let ids: Vec<ObjectID> = chunk.iter().map(|o| o.id).collect();
+            let response = client
+                .read_api()
+                .multi_get_object_with_options(ids, sui_sdk::rpc_types::SuiObjectDataOptions::new())
+                .await
+                .context("Failed to resolve dropped objects")?;
+            for (object, response) in chunk.iter().zip(response) {
+                match response.data {
+                    Some(data) => current.push(TrackedObject { version: data.version.value(), digest: data.digest, ..object.clone() }),
+                    None => gone += 1,
+                }
+            }
+        }
+        resolved.insert(worker, current);
+    }
+    Ok((resolved, gone))
+}
+
+impl WorkerState {
+    /// Record objects the worker stopped tracking
+    pub fn spill(&self, objects: &[TrackedObject]) {
+        if let Some(spill) = &self.spill {
+            spill.write(self.id, objects);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SavedWorkerObjects;
+    use sui_sdk::types::base_types::{ObjectDigest, SuiAddress};
+
+    fn object(n: u8, version: u64) -> TrackedObject {
+        TrackedObject { id: ObjectID::from_single_byte(n), version, digest: ObjectDigest::random(), size: 0, origin: None, updates: 0 }
+    }
+
+    #[test]
+    fn spilled_objects_are_saved_once() {
+        let path = std::env::temp_dir().join(format!("spill-test-{}", std::process::id()));
+        let spill = Spill::create(path.to_string_lossy().into_owned()).unwrap();
+        spill.write(0, &[object(1, 3), object(2, 5)]);
+        spill.write(1, &[object(3, 7)]);
+        let mut state = SavedBenchmarkState {
+            total_objects: 1,
+            workers: vec![SavedWorkerObjects { worker_id: 0, address: SuiAddress::ZERO, keypair_base64: String::new(), objects: vec![object(2, 6)] }],
+        };
+        let added = merge(&mut state, spill.read().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        // Worker 1 isn't in the snapshot; object 2 is still tracked at a newer version
+        assert_eq!((added, state.total_objects), (1, 2));
+        let versions: Vec<u64> = state.workers[0].objects.iter().map(|o| o.version).collect();
+        assert_eq!(versions, vec![6, 3]);
+    }
+}
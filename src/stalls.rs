@@ -0,0 +1,223 @@
+// Write stall detection (--stall-threshold-pct, --stall-min-secs)
+//
+// When RocksDB falls behind on compaction it throttles or stops writes, and
+// the node stops finishing transactions while workers keep theirs on the
+// wire. A detector samples the counters once a second and keeps a moving
+// baseline of success TPS over normal seconds. A second counts as stalled
+// when transactions are still in flight but success TPS drops below
+// --stall-threshold-pct percent of the baseline; consecutive stalled seconds
+// lasting at least --stall-min-secs form a stall window. Total stall time is
+// the headline number for comparing FDP and non-FDP runs: less device-side
+// garbage collection should mean fewer and shallower stalls.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::timeline::{IntervalSample, Timestamp};
+use crate::BenchStats;
+
+/// Detector resolution
+const TICK: Duration = Duration::from_secs(1);
+
+/// Normal seconds needed before the baseline is trusted
+const WARMUP_TICKS: u32 = 5;
+
+/// Weight of each new normal second in the baseline
+const BASELINE_ALPHA: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StallWindow {
+    pub start: Timestamp,
+    pub end: Timestamp,
+    pub duration_secs: f64,
+    /// Success TPS before the stall
+    pub baseline_tps: f64,
+    pub min_tps: f64,
+    pub mean_tps: f64,
+    /// 1 - mean_tps / baseline_tps (1.0 = nothing completed)
+    pub depth: f64,
+    /// Mean transactions in flight during the stall
+    pub mean_inflight: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StallReport {
+    pub threshold_pct: u8,
+    pub min_secs: u64,
+    pub windows: Vec<StallWindow>,
+    pub total_stall_secs: f64,
+    /// Share of the detection time spent stalled
+    pub stall_fraction: f64,
+    pub max_depth: f64,
+}
+
+struct OpenStall {
+    start: Timestamp,
+    baseline_tps: f64,
+    tps: Vec<f64>,
+    inflight: Vec<f64>,
+}
+
+impl OpenStall {
+    fn close(self, stats: &BenchStats, min_secs: u64) -> Option<StallWindow> {
+        let end = Timestamp::now(stats);
+        let duration_secs = end.elapsed_secs - self.start.elapsed_secs;
+        if duration_secs < min_secs as f64 {
+            return None;
+        }
+        let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len().max(1) as f64;
+        let mean_tps = mean(&self.tps);
+        Some(StallWindow {
+            start: self.start,
+            end,
+            duration_secs,
+            baseline_tps: self.baseline_tps,
+            min_tps: self.tps.iter().copied().fold(f64::INFINITY, f64::min),
+            mean_tps,
+            depth: 1.0 - mean_tps / self.baseline_tps,
+            mean_inflight: mean(&self.inflight),
+        })
+    }
+}
+
+/// Sample once a second until `running` is cleared
+pub async fn run_stall_detector(
+    stats: Arc<BenchStats>,
+    running: Arc<AtomicBool>,
+    threshold_pct: u8,
+    min_secs: u64,
+) -> StallReport {
+    let started = stats.clock.now();
+    let mut windows = Vec::new();
+    let mut open: Option<OpenStall> = None;
+    let mut baseline = 0.0;
+    let mut normal_ticks = 0u32;
+    let mut last_success = stats.tx_success.get();
+    let mut last_tick = started;
+    while running.load(Ordering::Relaxed) {
+        stats.clock.sleep(TICK).await;
+        let success = stats.tx_success.get();
+        let now = stats.clock.now();
+        let dt = (now - last_tick).as_secs_f64();
+        last_tick = now;
+        let tps = success.saturating_sub(last_success) as f64 / dt;
+        last_success = success;
+        let inflight = stats.inflight.inflight();
+
+        let stalled = normal_ticks >= WARMUP_TICKS
+            && inflight > 0
+            && tps < baseline * threshold_pct as f64 / 100.0;
+        if stalled {
+            let stall = open.get_or_insert_with(|| OpenStall {
+                start: Timestamp::now(&stats),
+                baseline_tps: baseline,
+                tps: Vec::new(),
+                inflight: Vec::new(),
+            });
+            stall.tps.push(tps);
+            stall.inflight.push(inflight as f64);
+            continue;
+        }
+        if let Some(window) = open.take().and_then(|s| s.close(&stats, min_secs)) {
+            warn!("Write stall: {:.0}s, {:.0} -> {:.0} TPS (depth {:.0}%)",
+                window.duration_secs, window.baseline_tps, window.mean_tps, window.depth * 100.0);
+            windows.push(window);
+        }
+        // Idle seconds (nothing in flight, e.g. memory throttling) don't move the baseline
+        if inflight > 0 || tps > 0.0 {
+            baseline = if normal_ticks == 0 { tps } else { baseline + BASELINE_ALPHA * (tps - baseline) };
+            normal_ticks += 1;
+        }
+    }
+    // A stall still going at the end of the run counts up to the end
+    if let Some(window) = open.and_then(|s| s.close(&stats, min_secs)) {
+        windows.push(window);
+    }
+
+    let total_stall_secs: f64 = windows.iter().map(|w| w.duration_secs).sum();
+    let detected_secs = (stats.clock.now() - started).as_secs_f64();
+    StallReport {
+        threshold_pct,
+        min_secs,
+        total_stall_secs,
+        stall_fraction: if detected_secs > 0.0 { total_stall_secs / detected_secs } else { 0.0 },
+        max_depth: windows.iter().map(|w| w.depth).fold(0.0, f64::max),
+        windows,
+    }
+}
+
+/// Mark the stalled seconds of each stats interval
+pub fn annotate(samples: &mut [IntervalSample], windows: &[StallWindow]) {
+    let mut prev = 0.0;
+    for sample in samples {
+        let end = sample.at.elapsed_secs;
+        let stalled: f64 = windows.iter()
+            .map(|w| (w.end.elapsed_secs.min(end) - w.start.elapsed_secs.max(prev)).max(0.0))
+            .sum();
+        if stalled > 0.0 {
+            sample.stall_secs = Some(stalled);
+        }
+        prev = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn a_slump_in_success_tps_is_reported_as_one_stall() {
+        let clock = VirtualClock::new();
+        let stats = Arc::new(BenchStats::new(1, clock.clone()));
+        let running = Arc::new(AtomicBool::new(true));
+        let _on_the_wire = stats.inflight.start();
+        // 100 TPS, down to 10 TPS in seconds 11-15, and the run ends after second 20
+        {
+            let (stats, running) = (stats.clone(), running.clone());
+            clock.on_sleep(move |elapsed| {
+                let second = elapsed.as_secs();
+                stats.tx_success.add(0, if (11..=15).contains(&second) { 10 } else { 100 });
+                if second == 20 {
+                    running.store(false, Ordering::Relaxed);
+                }
+            });
+        }
+
+        let report = futures::executor::block_on(run_stall_detector(stats.clone(), running, 50, 3));
+
+        assert_eq!(clock.elapsed(), Duration::from_secs(20));
+        assert_eq!(report.windows.len(), 1);
+        let stall = &report.windows[0];
+        assert_eq!((stall.start.elapsed_secs, stall.end.elapsed_secs), (11.0, 16.0));
+        assert_eq!((stall.baseline_tps, stall.min_tps, stall.mean_tps), (100.0, 10.0, 10.0));
+        assert!((stall.depth - 0.9).abs() < 1e-9);
+        assert_eq!(stall.mean_inflight, 1.0);
+        assert_eq!(report.total_stall_secs, 5.0);
+        assert_eq!(report.stall_fraction, 0.25);
+    }
+
+    #[test]
+    fn a_slump_shorter_than_the_minimum_is_not_a_stall() {
+        let clock = VirtualClock::new();
+        let stats = Arc::new(BenchStats::new(1, clock.clone()));
+        let running = Arc::new(AtomicBool::new(true));
+        let _on_the_wire = stats.inflight.start();
+        {
+            let (stats, running) = (stats.clone(), running.clone());
+            clock.on_sleep(move |elapsed| {
+                let second = elapsed.as_secs();
+                stats.tx_success.add(0, if (11..=12).contains(&second) { 0 } else { 100 });
+                running.store(second < 20, Ordering::Relaxed);
+            });
+        }
+
+        let report = futures::executor::block_on(run_stall_detector(stats.clone(), running, 50, 3));
+
+        assert!(report.windows.is_empty());
+        assert_eq!(report.total_stall_secs, 0.0);
+    }
+}
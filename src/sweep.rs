@@ -0,0 +1,395 @@
+// Parameter sweep orchestration
+//
+// Runs the benchmark once per combination of a parameter matrix, e.g.
+// batch_size × workers × use_blobs, with a cooldown between runs so the
+// node can finish flushing/compacting before the next combination starts.
+//
+// Every combination is built and validated before the first run starts, and
+// the files a run writes (autosave, identity cache, logs) get the combination's
+// label appended, so the runs don't share state. Each combination writes its
+// own result JSON into the sweep directory, and a combined summary (JSON +
+// text table) is rewritten after every combination.
+// A combination that fails is logged and recorded as failed in the summary,
+// and the sweep goes on with the next one; it exits with an error at the end.
+// With --resume, combinations whose result file already exists are skipped,
+// so an interrupted sweep can be continued without repeating finished runs,
+// and only the failed ones are retried.
+//
+// Matrix spec (JSON), each key maps to the list of values to sweep:
+//   { "batch_size": [10, 50, 100], "workers": [4, 8], "use_blobs": [false, true] }
+
+use anyhow::{Context, Result, anyhow};
+use clap::Args as ClapArgs;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use crate::ab_test::own_files;
+use crate::results::{self, BenchResult};
+use crate::validate::Percent;
+use crate::{clock, run_benchmark, Args};
+
+/// Knobs that can be varied by a sweep (matrix keys)
+const SWEEPABLE_PARAMS: &[&str] = &[
+    "batch_size",
+    "workers",
+    "max_inflight",
+    "create_pct",
+    "target_tps",
+    "duration",
+    "seed_objects",
+    "use_blobs",
+    "update_bytes",
+    "append_bytes",
+    "hot_objects",
+    "hot_skew",
+    "hot_pct",
+    "wave_interval",
+    "wave_pct",
+];
+
+/// Arguments for the `sweep` subcommand
+#[derive(ClapArgs, Debug, Clone)]
+pub struct SweepArgs {
+    /// JSON matrix spec: object mapping parameter name to a list of values
+    #[clap(long)]
+    matrix: Option<String>,
+
+    /// Inline matrix axis, e.g. --param batch_size=10,50,100 (repeatable)
+    #[clap(long = "param")]
+    params: Vec<String>,
+
+    /// Directory for per-combination results and the combined summary
+    #[clap(long, default_value = "sweep_results")]
+    output_dir: String,
+
+    /// Cooldown between combinations in seconds (lets the node settle)
+    #[clap(long, default_value = "60")]
+    cooldown_secs: u64,
+
+    /// Skip combinations whose result file already exists in output_dir
+    #[clap(long, default_value = "false")]
+    resume: bool,
+}
+
+/// One point of the matrix: ordered (parameter, value) pairs
+type Combination = Vec<(String, Value)>;
+
+/// A combination's result, or why its run failed
+type Outcome = std::result::Result<BenchResult, String>;
+
+/// Run all combinations of the matrix sequentially
+pub async fn run_sweep(base: Args, sweep: SweepArgs) -> Result<()> {
+    let axes = load_axes(&sweep)?;
+    if axes.is_empty() {
+        return Err(anyhow!("Sweep matrix is empty: pass --matrix <file> or --param name=v1,v2"));
+    }
+
+    // Every combination is checked before the first run, so a bad value can't end the sweep hours in
+    let combinations = expand_matrix(&axes);
+    let output_dir = PathBuf::from(&sweep.output_dir);
+    let runs = combinations.iter().enumerate()
+        .map(|(idx, combination)| {
+            let label = combination_label(idx, combination);
+            let result_path = output_dir.join(format!("{}.json", label));
+            let args = combination_args(&base, combination, &label, &result_path)
+                .context(format!("Sweep combination {}", label))?;
+            Ok((label, result_path, args))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    std::fs::create_dir_all(&output_dir)
+        .context(format!("Failed to create sweep directory: {}", sweep.output_dir))?;
+
+    info!("Sweep: {} combinations over {} parameters -> {}",
+        combinations.len(), axes.len(), output_dir.display());
+
+    let mut rows: Vec<(Combination, Outcome)> = Vec::new();
+
+    for (idx, (combination, (label, result_path, args))) in combinations.iter().zip(runs).enumerate() {
+        // Resume: reuse results from a previous (partial) sweep
+        if sweep.resume && result_path.exists() {
+            match results::load(&result_path.to_string_lossy()) {
+                Ok(result) => {
+                    info!("Sweep [{}/{}] {}: already complete, skipping", idx + 1, combinations.len(), label);
+                    rows.push((combination.clone(), Ok(result)));
+                    continue;
+                }
+                Err(e) => warn!("Sweep: ignoring unreadable result {}: {}", result_path.display(), e),
+            }
+        }
+
+        info!("Sweep [{}/{}] {}", idx + 1, combinations.len(), label);
+        let outcome = run_benchmark(&args, clock::system()).await.map_err(|e| {
+            error!("Sweep [{}/{}] {} failed: {:#}", idx + 1, combinations.len(), label, e);
+            // A run that failed after writing its result (e.g. --check) must not count as done on --resume
+            if result_path.exists() {
+                let failed = output_dir.join(format!("{}.failed.json", label));
+                if let Err(e) = std::fs::rename(&result_path, &failed) {
+                    warn!("Sweep: failed to move {} aside: {}", result_path.display(), e);
+                }
+            }
+            format!("{:#}", e)
+        });
+        rows.push((combination.clone(), outcome));
+
+        if let Err(e) = write_summary(&output_dir, &axes, &rows) {
+            warn!("Sweep: failed to write the summary: {:#}", e);
+        }
+
+        if idx + 1 < combinations.len() && sweep.cooldown_secs > 0 {
+            info!("Sweep: cooling down for {}s...", sweep.cooldown_secs);
+            sleep(Duration::from_secs(sweep.cooldown_secs)).await;
+        }
+    }
+
+    write_summary(&output_dir, &axes, &rows)?;
+    info!("Sweep complete: summary written to {}", output_dir.join("sweep_summary.txt").display());
+    let failed = rows.iter().filter(|(_, outcome)| outcome.is_err()).count();
+    if failed > 0 {
+        return Err(anyhow!("Sweep: {} of {} combinations failed (see {}); re-run with --resume to retry them",
+            failed, rows.len(), output_dir.join("sweep_summary.txt").display()));
+    }
+    Ok(())
+}
+
+/// Collect matrix axes from the spec file and inline --param flags
+fn load_axes(sweep: &SweepArgs) -> Result<BTreeMap<String, Vec<Value>>> {
+    let mut axes = BTreeMap::new();
+
+    if let Some(path) = &sweep.matrix {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read matrix spec: {}", path))?;
+        let spec: BTreeMap<String, Vec<Value>> = serde_json::from_str(&content)
+            .context("Matrix spec must be a JSON object of parameter -> [values]")?;
+        axes.extend(spec);
+    }
+
+    for param in &sweep.params {
+        let (name, values) = param.split_once('=')
+            .ok_or_else(|| anyhow!("Invalid --param '{}': expected name=v1,v2,...", param))?;
+        let values = values.split(',')
+            .map(|v| serde_json::from_str(v.trim()).unwrap_or_else(|_| Value::String(v.trim().to_string())))
+            .collect();
+        axes.insert(name.trim().replace('-', "_"), values);
+    }
+
+    for (name, values) in &axes {
+        if !SWEEPABLE_PARAMS.contains(&name.as_str()) {
+            return Err(anyhow!("Unknown sweep parameter '{}' (supported: {})", name, SWEEPABLE_PARAMS.join(", ")));
+        }
+        if values.is_empty() {
+            return Err(anyhow!("Sweep parameter '{}' has no values", name));
+        }
+    }
+
+    Ok(axes)
+}
+
+/// Cartesian product of all axes (deterministic order for resume)
+fn expand_matrix(axes: &BTreeMap<String, Vec<Value>>) -> Vec<Combination> {
+    let mut combinations: Vec<Combination> = vec![Vec::new()];
+    for (name, values) in axes {
+        let mut next = Vec::with_capacity(combinations.len() * values.len());
+        for combination in &combinations {
+            for value in values {
+                let mut c = combination.clone();
+                c.push((name.clone(), value.clone()));
+                next.push(c);
+            }
+        }
+        combinations = next;
+    }
+    combinations
+}
+
+/// The arguments one combination runs with, checked like the command line
+///
+/// Files the run writes get the combination's label appended (as A/B sides do),
+/// so no combination resumes from or appends to another one's state and logs.
+fn combination_args(base: &Args, combination: &Combination, label: &str, result_path: &Path) -> Result<Args> {
+    let mut args = base.clone();
+    args.command = None;
+    for (param, value) in combination {
+        apply_param(&mut args, param, value)?;
+    }
+    own_files(&mut args, label);
+    args.output = Some(result_path.to_string_lossy().into_owned());
+    args.validate()?;
+    Ok(args)
+}
+
+/// Override one Args field from a matrix value
+fn apply_param(args: &mut Args, param: &str, value: &Value) -> Result<()> {
+    let as_u64 = || value.as_u64().ok_or_else(|| anyhow!("Sweep parameter '{}' expects an integer, got {}", param, value));
+    let as_percent = || -> Result<Percent> {
+        let v = u8::try_from(as_u64()?).map_err(|_| anyhow!("Sweep parameter '{}' expects a percentage, got {}", param, value))?;
+        Percent::try_from(v).map_err(|e| anyhow!("Sweep parameter '{}': {}", param, e))
+    };
+    match param {
+        "batch_size" => args.batch_size = as_u64()? as usize,
+        "workers" => args.workers = as_u64()? as usize,
+        "max_inflight" => args.max_inflight = as_u64()? as usize,
+        "create_pct" => args.create_pct = as_percent()?,
+        "target_tps" => args.target_tps = as_u64()?,
+        "duration" => args.duration = as_u64()?,
+        "seed_objects" => args.seed_objects = as_u64()? as usize,
+        "update_bytes" => args.update_bytes = Some(as_u64()?),
+        "append_bytes" => args.append_bytes = Some(as_u64()?),
+        "hot_objects" => args.hot_objects = as_u64()? as usize,
+        "hot_pct" => args.hot_pct = as_percent()?,
+        "wave_interval" => args.wave_interval = as_u64()?,
+        "wave_pct" => args.wave_pct = as_percent()?,
+        "hot_skew" => {
+            args.hot_skew = value.as_f64()
+                .ok_or_else(|| anyhow!("Sweep parameter '{}' expects a number, got {}", param, value))?;
+        }
+        "use_blobs" => {
+            args.use_blobs = value.as_bool()
+                .ok_or_else(|| anyhow!("Sweep parameter '{}' expects true/false, got {}", param, value))?;
+        }
+        _ => return Err(anyhow!("Unknown sweep parameter '{}'", param)),
+    }
+    Ok(())
+}
+
+/// Stable file-name-safe label, e.g. "c003_batch_size-50_workers-8"
+fn combination_label(idx: usize, combination: &Combination) -> String {
+    let mut label = format!("c{:03}", idx);
+    for (param, value) in combination {
+        let v = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        label.push_str(&format!("_{}-{}", param, v.replace(['/', ' '], "_")));
+    }
+    label
+}
+
+/// Write sweep_summary.json and a human-readable sweep_summary.txt table
+fn write_summary(
+    output_dir: &Path,
+    axes: &BTreeMap<String, Vec<Value>>,
+    rows: &[(Combination, Outcome)],
+) -> Result<()> {
+    let json_rows: Vec<Value> = rows.iter()
+        .map(|(combination, outcome)| {
+            let params: serde_json::Map<String, Value> = combination.iter().cloned().collect();
+            match outcome {
+                Ok(result) => serde_json::json!({
+                    "params": params,
+                    "status": "ok",
+                    "tps": result.tps,
+                    "tx_success": result.tx_success,
+                    "tx_failed": result.tx_failed,
+                    "objects_created": result.objects_created,
+                    "objects_updated": result.objects_updated,
+                    "duration_secs": result.duration_secs,
+                }),
+                Err(e) => serde_json::json!({
+                    "params": params,
+                    "status": "failed",
+                    "error": e,
+                }),
+            }
+        })
+        .collect();
+    let summary = serde_json::json!({
+        "parameters": axes.keys().collect::<Vec<_>>(),
+        "combinations": json_rows,
+    });
+    std::fs::write(output_dir.join("sweep_summary.json"), serde_json::to_string_pretty(&summary)?)?;
+
+    // Fixed-width text table: one column per parameter, then key metrics
+    let mut table = String::new();
+    for name in axes.keys() {
+        table.push_str(&format!("{:>14} ", name));
+    }
+    table.push_str(&format!("{:>10} {:>12} {:>10} {:>14}\n", "tps", "tx_success", "tx_failed", "objects_upd"));
+    for (combination, outcome) in rows {
+        for (_, value) in combination {
+            table.push_str(&format!("{:>14} ", value.to_string()));
+        }
+        match outcome {
+            Ok(result) => table.push_str(&format!(
+                "{:>10.1} {:>12} {:>10} {:>14}\n",
+                result.tps,
+                result.tx_success,
+                result.tx_failed,
+                result.objects_updated,
+            )),
+            Err(e) => table.push_str(&format!("FAILED: {}\n", e.lines().next().unwrap_or_default())),
+        }
+    }
+    std::fs::write(output_dir.join("sweep_summary.txt"), table)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn sweep_args(params: &[&str]) -> SweepArgs {
+        SweepArgs {
+            matrix: None,
+            params: params.iter().map(|p| p.to_string()).collect(),
+            output_dir: "sweep_results".to_string(),
+            cooldown_secs: 0,
+            resume: false,
+        }
+    }
+
+    #[test]
+    fn matrix_expands_to_every_combination_in_a_stable_order() {
+        let axes = load_axes(&sweep_args(&["batch-size=10,50", "use_blobs=false,true", "workers=4"])).unwrap();
+        let combinations = expand_matrix(&axes);
+
+        assert_eq!(combinations.len(), 4);
+        let labels: Vec<_> = combinations.iter().enumerate().map(|(i, c)| combination_label(i, c)).collect();
+        assert_eq!(labels, [
+            "c000_batch_size-10_use_blobs-false_workers-4",
+            "c001_batch_size-10_use_blobs-true_workers-4",
+            "c002_batch_size-50_use_blobs-false_workers-4",
+            "c003_batch_size-50_use_blobs-true_workers-4",
+        ]);
+        assert!(load_axes(&sweep_args(&["gas_budget=1,2"])).is_err());
+        assert!(load_axes(&sweep_args(&["workers"])).is_err());
+    }
+
+    #[test]
+    fn bad_values_are_rejected_before_anything_runs() {
+        let base = Args::try_parse_from(["fdp-sui-bench"]).unwrap();
+        let path = Path::new("sweep_results/c000.json");
+        let check = |param: &str, value: Value| combination_args(&base, &vec![(param.to_string(), value)], "c000", path);
+
+        check("create_pct", Value::from(40)).unwrap();
+        assert!(format!("{:#}", check("create_pct", Value::from(150)).unwrap_err()).contains("percentage"));
+        assert!(check("workers", Value::from(2.5)).is_err());
+        assert!(check("workers", Value::from("eight")).is_err());
+        // apply_param accepts it, Args::validate does not
+        assert!(check("workers", Value::from(0)).is_err());
+        assert!(check("use_blobs", Value::from(1)).is_err());
+    }
+
+    #[test]
+    fn each_combination_writes_its_own_files() {
+        let base = Args::try_parse_from(["fdp-sui-bench", "--autosave", "state.json", "--interval-log", "intervals.csv"]).unwrap();
+        let axes = load_axes(&sweep_args(&["workers=4,8"])).unwrap();
+        let runs: Vec<_> = expand_matrix(&axes).iter().enumerate()
+            .map(|(i, c)| {
+                let label = combination_label(i, c);
+                combination_args(&base, c, &label, Path::new(&format!("out/{}.json", label))).unwrap()
+            })
+            .collect();
+
+        assert_eq!(runs[0].workers, 4);
+        assert_eq!(runs[1].workers, 8);
+        assert_eq!(runs[0].autosave.as_deref(), Some("state.json.c000_workers-4"));
+        assert_eq!(runs[1].interval_log.as_deref(), Some("intervals.csv.c001_workers-8"));
+        assert_eq!(runs[1].output.as_deref(), Some("out/c001_workers-8.json"));
+        assert!(runs.iter().all(|args| args.command.is_none()));
+    }
+}
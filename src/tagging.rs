@@ -0,0 +1,125 @@
+// Stream tags in object payloads (--payload-tag)
+//
+// Validating an FDP placement policy means attributing the physical blocks a
+// device wrote back to the logical stream that produced them. With
+// --payload-tag every object the built-in creates and updates write carries a
+// 64-bit tag: blobs in the first 8 bytes of their data, counters in place of
+// their `last_update` field. The high 32 bits are the magic "FDPT"
+// (0x46445054), so on the device a tag shows up as the little-endian stream id
+// followed by "TPDF"; the low 32 bits name the stream, which is the worker, the
+// tenant (from --config), or the temperature (creates are cold, updates hot, as
+// with --hot-fraction). Creates use the io_churn `*_tagged` entry functions;
+// updates add a tag_blob / tag_counter call per object to the same PTB, which
+// halves the batch one PTB can carry. The `payload_tags` section maps every tag
+// to its stream for the analysis side.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use crate::config::BenchConfig;
+
+/// "FDPT"
+pub const TAG_MAGIC: u32 = 0x4644_5054;
+
+/// What the low 32 bits of a tag identify
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadTag {
+    /// Worker id + 1
+    Worker,
+    /// Tenant index in --config + 1 (0 = no tenant)
+    Tenant,
+    /// 1 = hot (updates), 2 = cold (creates)
+    Temperature,
+}
+
+pub fn tag(stream: u32) -> u64 {
+    (TAG_MAGIC as u64) << 32 | stream as u64
+}
+
+/// Tags one worker writes with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTags {
+    pub create: u64,
+    pub update: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaggedStream {
+    /// Hex, as it reads as a u64
+    pub tag: String,
+    pub name: String,
+}
+
+/// `payload_tags` section of the results
+#[derive(Debug, Clone, Serialize)]
+pub struct TagReport {
+    pub mode: PayloadTag,
+    pub magic: String,
+    pub streams: Vec<TaggedStream>,
+}
+
+/// Tags of every worker for one run
+pub struct TagPlan {
+    mode: PayloadTag,
+    workers: Vec<StreamTags>,
+    streams: Vec<(u32, String)>,
+}
+
+impl TagPlan {
+    pub fn new(mode: PayloadTag, workers: usize, config: &BenchConfig) -> Result<Self> {
+        let both = |stream: u32| StreamTags { create: tag(stream), update: tag(stream) };
+        let (tags, streams) = match mode {
+            PayloadTag::Worker => (
+                (0..workers).map(|id| both(id as u32 + 1)).collect(),
+                (0..workers).map(|id| (id as u32 + 1, format!("worker {}", id))).collect(),
+            ),
+            PayloadTag::Tenant => {
+                if config.tenants.is_empty() {
+                    return Err(anyhow!("--payload-tag tenant needs a --config with a tenants section"));
+                }
+                let mut streams = vec![(0, "no tenant".to_string())];
+                let mut tenant_of = vec![0u32; workers];
+                for (idx, tenant) in config.tenants.iter().enumerate() {
+                    for worker_id in BenchConfig::tenant_workers(tenant, workers)? {
+                        tenant_of[worker_id] = idx as u32 + 1;
+                    }
+                    streams.push((idx as u32 + 1, tenant.name.clone()));
+                }
+                (tenant_of.into_iter().map(both).collect(), streams)
+            }
+            PayloadTag::Temperature => (
+                vec![StreamTags { create: tag(2), update: tag(1) }; workers],
+                vec![(1, "hot".to_string()), (2, "cold".to_string())],
+            ),
+        };
+        Ok(Self { mode, workers: tags, streams })
+    }
+
+    pub fn worker(&self, worker_id: usize) -> Option<StreamTags> {
+        self.workers.get(worker_id).copied()
+    }
+
+    pub fn report(&self) -> TagReport {
+        TagReport {
+            mode: self.mode,
+            magic: format!("{:#010x}", TAG_MAGIC),
+            streams: self.streams.iter()
+                .map(|(stream, name)| TaggedStream { tag: format!("{:#018x}", tag(*stream)), name: name.clone() })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_read_as_stream_id_then_magic_on_disk() {
+        assert_eq!(tag(3).to_le_bytes(), [3, 0, 0, 0, b'T', b'P', b'D', b'F']);
+        let plan = TagPlan::new(PayloadTag::Temperature, 2, &BenchConfig::default()).unwrap();
+        assert_eq!(plan.worker(1), Some(StreamTags { create: tag(2), update: tag(1) }));
+        assert_eq!(plan.report().streams[0].tag, "0x4644505400000001");
+    }
+}
@@ -0,0 +1,101 @@
+// Multi-tenant QoS reporting (`tenants` section of --config)
+//
+// Tenants are groups of workers with their own package, object pool and rate
+// target (see config.rs). Isolation is judged per tenant: a quiet tenant's
+// latency should not move when a noisy neighbor churns through its objects,
+// and with FDP placement it ideally doesn't. Counters come from the per-worker
+// shards; latency is kept per worker and merged per tenant at the end.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::BenchConfig;
+use crate::counters::CounterSnapshot;
+use crate::latency::{Histogram, LatencySummary};
+use crate::BenchStats;
+
+/// Successful-transaction latency per worker
+pub struct TenantStats {
+    latency: Box<[Mutex<Histogram>]>,
+}
+
+impl TenantStats {
+    pub fn new(workers: usize) -> Self {
+        Self { latency: (0..workers.max(1)).map(|_| Mutex::default()).collect() }
+    }
+
+    pub fn record_latency(&self, worker: usize, latency: Duration) {
+        self.latency[worker % self.latency.len()].lock().unwrap().record(latency);
+    }
+
+    fn merged(&self, workers: &[usize]) -> Histogram {
+        let mut all = Histogram::new();
+        for &w in workers {
+            if let Some(h) = self.latency.get(w) {
+                all.merge(&h.lock().unwrap());
+            }
+        }
+        all
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantReport {
+    pub name: String,
+    pub workers: Vec<usize>,
+    pub package_id: String,
+    pub target_tps: Option<u64>,
+    #[serde(flatten)]
+    pub counters: CounterSnapshot,
+    pub tps: f64,
+    pub latency: LatencySummary,
+}
+
+/// One report per configured tenant (`packages` is the resolved package of each worker)
+pub fn report(config: &BenchConfig, stats: &BenchStats, packages: &[String], elapsed_secs: f64) -> Result<Vec<TenantReport>> {
+    let mut reports = Vec::with_capacity(config.tenants.len());
+    for tenant in &config.tenants {
+        let workers = BenchConfig::tenant_workers(tenant, packages.len())?;
+        let counters = CounterSnapshot::for_workers(stats, &workers);
+        reports.push(TenantReport {
+            name: tenant.name.clone(),
+            package_id: workers.first().map(|&w| packages[w].clone()).unwrap_or_default(),
+            target_tps: tenant.target_tps,
+            tps: if elapsed_secs > 0.0 { counters.tx_success as f64 / elapsed_secs } else { 0.0 },
+            latency: stats.tenants.merged(&workers).summary(),
+            counters,
+            workers,
+        });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock;
+
+    #[test]
+    fn tenants_report_their_own_workers_only() {
+        let config: BenchConfig = serde_json::from_str(r#"{ "tenants": [
+            { "name": "noisy", "workers": "0-2", "target_tps": 300 },
+            { "name": "victim", "workers": 3 }
+        ] }"#).unwrap();
+        let stats = BenchStats::new(4, clock::system());
+        for worker in 0..4 {
+            stats.tx_success.add(worker, 100);
+            stats.tenants.record_latency(worker, Duration::from_millis(10 + 90 * (worker == 3) as u64));
+        }
+        let packages = vec!["0xa".to_string(), "0xa".to_string(), "0xa".to_string(), "0xb".to_string()];
+
+        let reports = report(&config, &stats, &packages, 10.0).unwrap();
+
+        let noisy = &reports[0];
+        assert_eq!((noisy.workers.clone(), noisy.package_id.as_str(), noisy.target_tps), (vec![0, 1, 2], "0xa", Some(300)));
+        assert_eq!((noisy.counters.tx_success, noisy.tps, noisy.latency.count), (300, 30.0, 3));
+        let victim = &reports[1];
+        assert_eq!((victim.package_id.as_str(), victim.tps, victim.latency.max_ms), ("0xb", 10.0, 100.0));
+    }
+}
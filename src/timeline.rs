@@ -0,0 +1,210 @@
+// Run timeline and timestamps
+//
+// Every time-related output carries both a monotonic offset (seconds since the
+// load phase started, immune to clock steps) and a wall-clock UTC timestamp, so
+// client results can be aligned with node logs, iostat captures and NVMe
+// telemetry recorded by other tools on other clocks.
+//
+// With --align-intervals the stats reporter and the interval samplers wake on
+// wall-clock multiples of --stats-interval (every minute on the minute for 60),
+// shifted by --align-offset-ms, so each client interval covers exactly the
+// window of a Prometheus scrape or an external NVMe sampler on the same
+// schedule. The first interval is then shorter than the rest.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::clock::Clock;
+use crate::pacing::PacingStatus;
+use crate::{Args, BenchStats};
+
+/// Milliseconds since the Unix epoch (wall clock)
+pub fn unix_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// RFC 3339 UTC timestamp with millisecond precision, e.g. 2024-05-01T12:00:00.123Z
+pub fn utc_rfc3339(unix_ms: u64) -> String {
+    let secs = unix_ms / 1000;
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, rem / 3600, (rem % 3600) / 60, rem % 60, unix_ms % 1000)
+}
+
+/// Days since 1970-01-01 to (year, month, day) - Howard Hinnant's algorithm
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Wait until the next wall-clock boundary of `interval`, shifted by `offset`.
+/// A boundary less than a twentieth of an interval away is skipped, so a timer
+/// that fires a little early never produces a sliver interval.
+fn until_boundary(now_ms: u64, interval: Duration, offset: Duration) -> Duration {
+    let period = (interval.as_millis() as u64).max(1);
+    let since_boundary = (now_ms + period - offset.as_millis() as u64 % period) % period;
+    let mut wait = period - since_boundary;
+    if wait * 20 < period {
+        wait += period;
+    }
+    Duration::from_millis(wait)
+}
+
+/// Paces the stats reporter and interval samplers (--stats-interval, --align-intervals)
+#[derive(Debug, Clone, Copy)]
+pub struct Ticker {
+    interval: Duration,
+    /// Boundary offset when aligned to the wall clock
+    align: Option<Duration>,
+}
+
+impl Ticker {
+    pub fn new(args: &Args) -> Self {
+        Self {
+            interval: Duration::from_secs(args.stats_interval.max(1)),
+            align: args.align_intervals.then(|| Duration::from_millis(args.align_offset_ms)),
+        }
+    }
+
+    pub async fn tick(&self, clock: &dyn Clock) {
+        let wait = match self.align {
+            Some(offset) => until_boundary(unix_ms(), self.interval, offset),
+            None => self.interval,
+        };
+        clock.sleep(wait).await;
+    }
+}
+
+/// A point in time in both clocks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Timestamp {
+    pub elapsed_secs: f64,
+    pub unix_ms: u64,
+    pub utc: String,
+}
+
+impl Timestamp {
+    pub fn now(stats: &BenchStats) -> Self {
+        let ms = unix_ms();
+        Self {
+            elapsed_secs: stats.elapsed().as_secs_f64(),
+            unix_ms: ms,
+            utc: utc_rfc3339(ms),
+        }
+    }
+}
+
+/// Cumulative counters at one stats interval
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntervalSample {
+    #[serde(flatten)]
+    pub at: Timestamp,
+    pub tx_submitted: u64,
+    pub tx_success: u64,
+    pub tx_failed: u64,
+    pub objects_created: u64,
+    pub objects_updated: u64,
+    /// Success TPS since the previous sample
+    pub interval_tps: f64,
+    /// Total TPS the workers were asked for at sample time (paced runs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_tps: Option<f64>,
+    /// How the interval met its request (filled in after the run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pacing: Option<PacingStatus>,
+    /// Transactions on the wire at sample time
+    pub inflight: u64,
+    /// Mean wait for an inflight permit since the previous sample
+    pub semaphore_wait_ms: f64,
+    /// Seconds of this interval inside a write stall window (filled in after the run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_secs: Option<f64>,
+    /// Whether the interval met the config's SLOs (filled in after the run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo_met: Option<bool>,
+    #[serde(skip)]
+    wait_totals: (u64, u64),
+}
+
+/// Interval samples collected by the stats reporter
+#[derive(Default)]
+pub struct Timeline {
+    samples: Mutex<Vec<IntervalSample>>,
+    /// Keep at most this many samples, oldest dropped first (0 = all)
+    max_samples: usize,
+}
+
+impl Timeline {
+    /// A timeline that only keeps the most recent `max_samples` samples (--soak)
+    pub fn bounded(max_samples: usize) -> Self {
+        Self { samples: Mutex::default(), max_samples }
+    }
+
+    /// Record the current counters and the rate requested right now
+    pub fn sample(&self, stats: &BenchStats, requested_tps: Option<f64>) -> IntervalSample {
+        let at = Timestamp::now(stats);
+        let tx_success = stats.tx_success.get();
+        let mut samples = self.samples.lock().unwrap();
+        let (prev_elapsed, prev_success, (prev_wait, prev_acquires)) = samples.last()
+            .map_or((0.0, 0, (0, 0)), |s| (s.at.elapsed_secs, s.tx_success, s.wait_totals));
+        let dt = at.elapsed_secs - prev_elapsed;
+        let wait_totals = stats.inflight.wait_totals();
+        let acquires = wait_totals.1.saturating_sub(prev_acquires);
+        let sample = IntervalSample {
+            interval_tps: if dt > 0.0 { tx_success.saturating_sub(prev_success) as f64 / dt } else { 0.0 },
+            inflight: stats.inflight.inflight(),
+            semaphore_wait_ms: if acquires > 0 {
+                wait_totals.0.saturating_sub(prev_wait) as f64 / 1000.0 / acquires as f64
+            } else {
+                0.0
+            },
+            requested_tps,
+            pacing: None,
+            stall_secs: None,
+            slo_met: None,
+            wait_totals,
+            at,
+            tx_submitted: stats.tx_submitted.get(),
+            tx_success,
+            tx_failed: stats.tx_failed.get(),
+            objects_created: stats.objects_created.get(),
+            objects_updated: stats.objects_updated.get(),
+        };
+        samples.push(sample.clone());
+        if self.max_samples > 0 && samples.len() > self.max_samples {
+            let excess = samples.len() - self.max_samples;
+            samples.drain(..excess);
+        }
+        sample
+    }
+
+    pub fn samples(&self) -> Vec<IntervalSample> {
+        self.samples.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_ticks_land_on_wall_clock_boundaries() {
+        let minute = Duration::from_secs(60);
+        // 12:00:42.5 -> 12:01:00
+        assert_eq!(until_boundary(43_242_500, minute, Duration::ZERO), Duration::from_millis(17_500));
+        // Offset 15s: boundaries at :15
+        assert_eq!(until_boundary(43_242_500, minute, Duration::from_secs(15)), Duration::from_millis(32_500));
+        // Woken 1ms early: skip to the boundary after
+        assert_eq!(until_boundary(43_259_999, minute, Duration::ZERO), Duration::from_millis(60_001));
+    }
+}
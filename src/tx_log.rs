@@ -0,0 +1,211 @@
+// Transaction digest recorder
+//
+// Keeps a durable record of every successful transaction so post-run tooling
+// (the `audit` subcommand, checkpoint inclusion checks, gas recomputation)
+// can re-query exactly what the run executed. Digests are appended to a JSONL
+// file as they succeed and/or kept in memory for embedding in the output JSON.
+// With a rotation limit the file is split into segments (see rotation.rs);
+// --soak also closes a segment every day.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::Mutex;
+use sui_sdk::types::base_types::TransactionDigest;
+use tracing::warn;
+
+use crate::rotation::{RotationPolicy, RotationReport, Segments};
+
+/// Inline digests kept for the output JSON before truncating
+const MAX_INLINE_DIGESTS: usize = 100_000;
+
+/// One recorded transaction (one JSONL line)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxRecord {
+    pub digest: String,
+    pub worker: usize,
+    pub op: String,
+    /// Milliseconds since benchmark start
+    pub elapsed_ms: u64,
+    /// Wall-clock milliseconds since the Unix epoch
+    #[serde(default)]
+    pub unix_ms: u64,
+}
+
+/// Shared recorder used by all workers
+pub struct DigestRecorder {
+    file: Option<Mutex<BufWriter<File>>>,
+    /// Segments of the file (--rotate-mb / --rotate-secs); locked after `file`
+    segments: Option<Mutex<Segments>>,
+    inline: Option<Mutex<Vec<TxRecord>>>,
+    inline_truncated: Mutex<bool>,
+}
+
+impl DigestRecorder {
+    /// Create a recorder writing to `path` (append-only, rotated by `rotation`) and/or keeping digests inline
+    pub fn new(path: Option<&str>, inline: bool, rotation: Option<RotationPolicy>) -> Result<Self> {
+        let file = match path {
+            Some(p) => Some(Mutex::new(open_log(p)?)),
+            None => None,
+        };
+        Ok(Self {
+            file,
+            segments: path.zip(rotation).map(|(p, policy)| Mutex::new(Segments::new(p, policy))),
+            inline: if inline { Some(Mutex::new(Vec::new())) } else { None },
+            inline_truncated: Mutex::new(false),
+        })
+    }
+
+    /// Record a successful transaction
+    pub fn record(&self, digest: &TransactionDigest, worker: usize, op: &str, elapsed_ms: u64) {
+        let record = TxRecord {
+            digest: digest.to_string(),
+            worker,
+            op: op.to_string(),
+            elapsed_ms,
+            unix_ms: crate::timeline::unix_ms(),
+        };
+
+        if let Some(file) = &self.file {
+            let mut writer = file.lock().unwrap();
+            if let Ok(line) = serde_json::to_string(&record) {
+                if let Err(e) = writeln!(writer, "{}", line) {
+                    warn!("Failed to append tx digest: {}", e);
+                } else if let Some(segments) = &self.segments {
+                    let mut segments = segments.lock().unwrap();
+                    segments.wrote(line.len() + 1);
+                    if segments.due() {
+                        if let Err(e) = next_segment(&mut writer, &mut segments) {
+                            warn!("Tx digest log rotation failed: {:#}", e);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(inline) = &self.inline {
+            let mut records = inline.lock().unwrap();
+            if records.len() < MAX_INLINE_DIGESTS {
+                records.push(record);
+            } else {
+                let mut truncated = self.inline_truncated.lock().unwrap();
+                if !*truncated {
+                    warn!("More than {} tx digests - inline list truncated (use --tx-digests for a full log)", MAX_INLINE_DIGESTS);
+                    *truncated = true;
+                }
+            }
+        }
+    }
+
+    /// Flush the append-only log
+    pub fn flush(&self) -> Result<()> {
+        if let Some(file) = &self.file {
+            file.lock().unwrap().flush()?;
+        }
+        Ok(())
+    }
+
+    /// Close the open segment now, limits or not (the daily cut of --soak); the closed file, if segmented
+    pub fn close_segment(&self) -> Result<Option<String>> {
+        let (Some(file), Some(segments)) = (&self.file, &self.segments) else {
+            return Ok(None);
+        };
+        let mut writer = file.lock().unwrap();
+        let closed = next_segment(&mut writer, &mut segments.lock().unwrap())?;
+        Ok(Some(closed))
+    }
+
+    /// Flush and write the segment index (None without rotation)
+    pub fn finish_segments(&self) -> Result<Option<RotationReport>> {
+        let (Some(file), Some(segments)) = (&self.file, &self.segments) else {
+            return Ok(None);
+        };
+        file.lock().unwrap().flush()?;
+        let report = segments.lock().unwrap().finish()?;
+        Ok(Some(report))
+    }
+
+    /// Inline records for the output JSON (None when inline recording is off)
+    pub fn inline_records(&self) -> Option<(Vec<TxRecord>, bool)> {
+        let inline = self.inline.as_ref()?;
+        let records = inline.lock().unwrap().clone();
+        Some((records, *self.inline_truncated.lock().unwrap()))
+    }
+}
+
+fn open_log(path: &str) -> Result<BufWriter<File>> {
+    let f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!("Failed to open tx digest log: {}", path))?;
+    Ok(BufWriter::new(f))
+}
+
+/// Close the open segment and continue in a fresh file; returns the closed one
+fn next_segment(writer: &mut BufWriter<File>, segments: &mut Segments) -> Result<String> {
+    writer.flush()?;
+    let closed = segments.close()?;
+    *writer = open_log(segments.path())?;
+    Ok(closed)
+}
+
+/// Read a digest log: DigestRecorder JSONL lines, or one bare digest per line
+pub fn read_tx_log(path: &str) -> Result<Vec<TxRecord>> {
+    let content = std::fs::read_to_string(path)
+        .context(format!("Failed to read tx digest log: {}", path))?;
+    Ok(content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|_| TxRecord {
+            digest: line.to_string(),
+            worker: 0,
+            op: "unknown".to_string(),
+            elapsed_ms: 0,
+            unix_ms: 0,
+        }))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_digests_read_back_from_the_log() {
+        let dir = std::env::temp_dir().join(format!("fdp-tx-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("digests.jsonl").to_string_lossy().into_owned();
+        let digests = [TransactionDigest::new([1; 32]), TransactionDigest::new([2; 32])];
+        let recorder = DigestRecorder::new(Some(&path), true, None).unwrap();
+        recorder.record(&digests[0], 0, "create", 10);
+        recorder.record(&digests[1], 3, "update", 25);
+        recorder.flush().unwrap();
+
+        let records = read_tx_log(&path).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].digest, digests[0].to_string());
+        assert_eq!((records[1].worker, records[1].op.as_str(), records[1].elapsed_ms), (3, "update", 25));
+        assert!(records[1].unix_ms > 0);
+        let (inline, truncated) = recorder.inline_records().unwrap();
+        assert_eq!((inline.len(), truncated), (2, false));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bare_digest_lines_are_read_as_records() {
+        let dir = std::env::temp_dir().join(format!("fdp-tx-log-bare-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("digests.txt");
+        let digest = TransactionDigest::new([7; 32]).to_string();
+        std::fs::write(&path, format!("{}\n\n  {}  \n", digest, digest)).unwrap();
+
+        let records = read_tx_log(&path.to_string_lossy()).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.digest == digest && r.op == "unknown" && r.elapsed_ms == 0));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
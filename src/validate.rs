@@ -0,0 +1,334 @@
+// Argument validation
+//
+// Percentages and memory fractions are newtypes that reject out-of-range
+// values while the command line (or a config file) is parsed, so a typo like
+// `--create-pct 150` or `--memory-threshold 85` is an error instead of a
+// workload that silently does something else. Checks that span several flags
+// live in `Args::validate`, which runs right after parsing and again for every
+// combination a sweep or A/B test derives from the base arguments.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{gas_pool, mock, rotation, Args, BLOB_OBJECT_BYTES, MAX_BLOB_BATCH};
+
+/// Largest gas budget the network accepts for one transaction (50 SUI)
+pub const MAX_TX_GAS_BUDGET: u64 = 50_000_000_000;
+
+/// Every object in a batch costs at least this much gas (computation floor at the reference gas price)
+const MIN_GAS_PER_OBJECT: u64 = 1_000_000;
+
+/// A percentage, 0..=100
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Percent(u8);
+
+impl Percent {
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for Percent {
+    type Error = String;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        if v > 100 {
+            return Err(format!("{} is not a percentage (0-100)", v));
+        }
+        Ok(Self(v))
+    }
+}
+
+impl From<Percent> for u8 {
+    fn from(p: Percent) -> u8 {
+        p.0
+    }
+}
+
+impl FromStr for Percent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().trim_end_matches('%');
+        let v: u8 = s.parse().map_err(|_| format!("invalid percentage '{}' (0-100)", s))?;
+        Self::try_from(v)
+    }
+}
+
+impl fmt::Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A fraction of a whole, 0 < x <= 1 (memory thresholds)
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Fraction(f64);
+
+impl Fraction {
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Fraction {
+    type Error = String;
+
+    fn try_from(v: f64) -> Result<Self, Self::Error> {
+        if !(v > 0.0 && v <= 1.0) {
+            let hint = if v > 1.0 && v <= 100.0 { format!(" (did you mean {}?)", v / 100.0) } else { String::new() };
+            return Err(format!("{} is not a fraction between 0 and 1{}", v, hint));
+        }
+        Ok(Self(v))
+    }
+}
+
+impl From<Fraction> for f64 {
+    fn from(f: Fraction) -> f64 {
+        f.0
+    }
+}
+
+impl FromStr for Fraction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let v: f64 = s.trim().parse().map_err(|_| format!("invalid fraction '{}' (0-1)", s))?;
+        Self::try_from(v)
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Args {
+    /// Cross-field checks; the message names the flags to change
+    pub fn validate(&self) -> Result<()> {
+        if self.workers == 0 || self.batch_size == 0 || self.max_inflight == 0 {
+            return Err(anyhow!("--workers, --batch-size and --max-inflight must be at least 1"));
+        }
+        if !(self.memory_threshold <= self.memory_critical && self.memory_critical <= self.memory_emergency) {
+            return Err(anyhow!(
+                "Memory thresholds must satisfy --memory-threshold ({}) <= --memory-critical ({}) <= --memory-emergency ({})",
+                self.memory_threshold, self.memory_critical, self.memory_emergency));
+        }
+        if self.adaptive_p95_ms.is_some_and(|ms| ms <= 0.0 || !ms.is_finite()) {
+            return Err(anyhow!("--adaptive-p95-ms must be a positive number"));
+        }
+        if self.adaptive_p95_ms.is_some() && !self.adaptive_inflight {
+            return Err(anyhow!("--adaptive-p95-ms needs --adaptive-inflight"));
+        }
+        if self.worker_ramp_secs > 0 && self.worker_ramp_secs >= self.duration {
+            return Err(anyhow!("--worker-ramp-secs ({}) must be shorter than --duration ({})", self.worker_ramp_secs, self.duration));
+        }
+        if self.save_without_keys && self.keystore.is_none() {
+            return Err(anyhow!("--save-without-keys needs --keystore, which the saved addresses' keys are resolved from"));
+        }
+        if self.save_without_keys && self.identity_cache.is_some() {
+            return Err(anyhow!("--identity-cache stores worker keys; it can't be used with --save-without-keys"));
+        }
+        if self.resolve_dropped && self.save_objects.is_none() {
+            return Err(anyhow!("--resolve-dropped needs --save-objects"));
+        }
+        if self.align_offset_ms > 0 && !self.align_intervals {
+            return Err(anyhow!("--align-offset-ms needs --align-intervals"));
+        }
+        if self.failure_window_secs == 0 {
+            return Err(anyhow!("--failure-window-secs must be at least 1"));
+        }
+        if self.failure_slow_pct.get() > self.failure_pause_pct.get() {
+            return Err(anyhow!(
+                "--failure-slow-pct ({}) must not exceed --failure-pause-pct ({})",
+                self.failure_slow_pct.get(), self.failure_pause_pct.get()));
+        }
+        if self.self_memory_limit_mb == Some(0) {
+            return Err(anyhow!("--self-memory-limit-mb must be at least 1"));
+        }
+
+        if self.gas_budget > MAX_TX_GAS_BUDGET {
+            return Err(anyhow!("--gas-budget {} exceeds the network maximum of {} MIST", self.gas_budget, MAX_TX_GAS_BUDGET));
+        }
+        let min_budget = self.batch_size as u64 * MIN_GAS_PER_OBJECT;
+        if self.gas_budget < min_budget {
+            return Err(anyhow!(
+                "--gas-budget {} cannot cover a batch of {} objects (needs at least {} MIST); raise --gas-budget or lower --batch-size",
+                self.gas_budget, self.batch_size, min_budget));
+        }
+        let per_object = [
+            ("--gas-budget-create", self.gas_budget_create, self.batch_size),
+            ("--gas-budget-update", self.gas_budget_update, self.batch_size),
+            ("--gas-budget-blob", self.gas_budget_blob, self.batch_size.min(MAX_BLOB_BATCH)),
+        ];
+        for (flag, budget, objects) in per_object {
+            let Some(budget) = budget else { continue };
+            if budget < MIN_GAS_PER_OBJECT {
+                return Err(anyhow!("{} {} is below the {} MIST every object costs at least", flag, budget, MIN_GAS_PER_OBJECT));
+            }
+            if budget.saturating_mul(objects as u64) > MAX_TX_GAS_BUDGET {
+                return Err(anyhow!(
+                    "{} {} times a batch of {} objects exceeds the network maximum of {} MIST; lower it or --batch-size",
+                    flag, budget, objects, MAX_TX_GAS_BUDGET));
+            }
+        }
+
+        if let Some(bytes) = self.update_bytes {
+            if bytes == 0 || bytes > BLOB_OBJECT_BYTES {
+                return Err(anyhow!("--update-bytes must be between 1 and {} (blob size)", BLOB_OBJECT_BYTES));
+            }
+        }
+        if let Some(bytes) = self.append_bytes {
+            if bytes == 0 || bytes > self.max_object_bytes {
+                return Err(anyhow!("--append-bytes must be between 1 and --max-object-bytes ({})", self.max_object_bytes));
+            }
+        }
+
+        if self.create_hot_pct.is_some() && self.hot_fraction.is_some() {
+            return Err(anyhow!("--create-hot-pct and --hot-fraction both split objects into hot and cold; use one"));
+        }
+        if self.create_hot_pct.is_some_and(|pct| pct.get() == 0) {
+            return Err(anyhow!("--create-hot-pct must be at least 1, or updates have nothing to rewrite"));
+        }
+        if self.cold_leak_pct.get() > 0 && self.create_hot_pct.is_none() {
+            return Err(anyhow!("--cold-leak-pct needs --create-hot-pct"));
+        }
+        if self.hot_skew < 0.0 || !self.hot_skew.is_finite() {
+            return Err(anyhow!("--hot-skew must be a non-negative number"));
+        }
+        if self.outlier_ms.is_some_and(|ms| ms <= 0.0) || self.outlier_factor.is_some_and(|k| k <= 0.0) {
+            return Err(anyhow!("--outlier-ms and --outlier-factor must be positive"));
+        }
+        if self.gas_smash == 0 || self.gas_smash > gas_pool::MAX_MERGE_COINS {
+            return Err(anyhow!("--gas-smash must be between 1 and {}", gas_pool::MAX_MERGE_COINS));
+        }
+        if self.gas_smash_refill && self.gas_smash <= 1 {
+            return Err(anyhow!("--gas-smash-refill needs --gas-smash > 1"));
+        }
+        if self.gas_streams && (self.gas_smash > 1 || self.soak) {
+            return Err(anyhow!("--gas-streams keeps each stream on one coin; it can't be combined with --gas-smash > 1 or --soak"));
+        }
+
+        if self.tx_expiration_epochs.is_some() && self.epoch_poll_secs == 0 {
+            return Err(anyhow!("--tx-expiration-epochs counts from the epoch the watcher sees; it needs --epoch-poll-secs > 0"));
+        }
+        if self.stale_after_ms == Some(0) {
+            return Err(anyhow!("--stale-after-ms must be at least 1"));
+        }
+        if self.rotate_mb == Some(0) || self.rotate_secs == Some(0) {
+            return Err(anyhow!("--rotate-mb and --rotate-secs must be at least 1"));
+        }
+        let rotates = self.rotate_mb.is_some() || self.rotate_secs.is_some();
+        if rotates && self.tx_digests.is_none() && self.hdr_log.is_none() && self.interval_log.is_none() {
+            return Err(anyhow!("--rotate-mb / --rotate-secs rotate --tx-digests, --hdr-log and --interval-log; give at least one of them"));
+        }
+        // --soak cuts a digest segment every day
+        let soak_segments = self.soak && self.tx_digests.is_some();
+        if self.rotate_compress != rotation::Compression::None && !rotates && !soak_segments {
+            return Err(anyhow!("--rotate-compress compresses closed segments; it needs --rotate-mb, --rotate-secs or --soak with --tx-digests"));
+        }
+        if self.batch_backoff_after == Some(0) || self.batch_regrow_after == 0 {
+            return Err(anyhow!("--batch-backoff-after and --batch-regrow-after must be at least 1"));
+        }
+
+        if self.verify_effects_pct.get() > 0 && self.simulate {
+            return Err(anyhow!("--verify-effects-pct needs executed transactions; --simulate only dry-runs them"));
+        }
+        if self.soak && self.autosave.is_none() {
+            return Err(anyhow!("--soak requires --autosave <state file>"));
+        }
+        if self.soak && self.gas_smash > 1 {
+            return Err(anyhow!("--soak refuels by merging coins into the primary gas coin, which --gas-smash > 1 manages itself"));
+        }
+        if self.max_tracked_objects == 0 {
+            return Err(anyhow!("--max-tracked-objects must be at least 1"));
+        }
+        if self.max_tracked_objects_total.is_some_and(|total| total < self.workers) {
+            return Err(anyhow!("--max-tracked-objects-total must leave each of the {} workers at least one object", self.workers));
+        }
+        if self.resume && self.autosave.is_none() {
+            return Err(anyhow!("--resume requires --autosave <state file>"));
+        }
+        if self.resume && self.load_objects.is_some() {
+            return Err(anyhow!("--resume and --load-objects are mutually exclusive"));
+        }
+        if (!self.load_workers.is_empty() || self.load_fraction.is_some()) && self.load_objects.is_none() {
+            return Err(anyhow!("--load-workers and --load-fraction need --load-objects"));
+        }
+        if let Some(count) = self.reshard_workers {
+            if self.load_objects.is_none() {
+                return Err(anyhow!("--reshard-workers needs --load-objects"));
+            }
+            if count != self.workers {
+                return Err(anyhow!("--reshard-workers {} conflicts with --workers {}", count, self.workers));
+            }
+        }
+        mock::validate(self)
+    }
+
+    /// Every file a run writes besides --output, so a sweep or A/B test can give each run its own
+    pub fn per_run_files(&mut self) -> [&mut Option<String>; 8] {
+        [
+            &mut self.save_objects,
+            &mut self.autosave,
+            &mut self.identity_cache,
+            &mut self.meta_cache,
+            &mut self.update_counts_csv,
+            &mut self.hdr_log,
+            &mut self.interval_log,
+            &mut self.tx_digests,
+        ]
+    }
+
+    /// Per-worker tracker cap: --max-tracked-objects, or less if --max-tracked-objects-total says so
+    pub fn tracked_objects_cap(&self) -> usize {
+        match self.max_tracked_objects_total {
+            Some(total) => self.max_tracked_objects.min(total / self.workers.max(1)),
+            None => self.max_tracked_objects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn args(argv: &[&str]) -> Result<Args, clap::Error> {
+        Args::try_parse_from([&["fdp-sui-bench"], argv].concat())
+    }
+
+    #[test]
+    fn percentages_and_fractions_are_range_checked_at_parse_time() {
+        assert_eq!("0".parse::<Percent>().map(Percent::get), Ok(0));
+        assert_eq!("100%".parse::<Percent>().map(Percent::get), Ok(100));
+        for bad in ["101", "-1", "abc", "", "5.5"] {
+            assert!(bad.parse::<Percent>().is_err(), "{:?}", bad);
+        }
+        assert_eq!("1".parse::<Fraction>().map(Fraction::get), Ok(1.0));
+        for bad in ["0", "-0.5", "1.01", "NaN", "abc"] {
+            assert!(bad.parse::<Fraction>().is_err(), "{:?}", bad);
+        }
+        // A percentage given as a memory fraction says what was probably meant
+        assert!("85".parse::<Fraction>().unwrap_err().contains("did you mean 0.85?"));
+
+        assert!(args(&["--create-pct", "101"]).is_err());
+        assert!(args(&["--memory-threshold", "75"]).is_err());
+        assert!(args(&["--create-pct", "100", "--memory-threshold", "0.5"]).is_ok());
+    }
+
+    #[test]
+    fn cross_field_conflicts_are_rejected() {
+        args(&[]).unwrap().validate().unwrap();
+        let inverted = args(&["--memory-threshold", "0.9", "--memory-critical", "0.8"]).unwrap();
+        assert!(format!("{:#}", inverted.validate().unwrap_err()).contains("--memory-threshold (0.9) <= --memory-critical (0.8)"));
+        let slow_above_pause = args(&["--failure-slow-pct", "60", "--failure-pause-pct", "50"]).unwrap();
+        assert!(slow_above_pause.validate().is_err());
+        assert!(args(&["--resume"]).unwrap().validate().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+// Delayed effects verification (--verify-effects-pct)
+//
+// For storage testing where the question is whether what the node acknowledged
+// is still there later - after a device power cycle, a crash-restart of the
+// node, or just hours of compaction - a sample of successful transactions is
+// set aside with what their effects said at execution time (status, gas charged
+// and rebated, objects created and mutated). After --verify-delay-secs the
+// verifier re-fetches their effects and compares. A transaction the node can no
+// longer return is `missing`; one it returns with different effects has
+// `diverged`. Whatever is still waiting when the run ends is checked then.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sui_sdk::rpc_types::{SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions};
+use sui_sdk::types::base_types::TransactionDigest;
+use sui_sdk::SuiClient;
+use tracing::warn;
+
+use crate::{BenchStats, GasCharge, TxOutcome};
+
+/// Samples waiting for their check; beyond this new samples are skipped
+const MAX_PENDING: usize = 100_000;
+/// Digests per multi-get request
+const VERIFY_BATCH: usize = 50;
+/// Divergent transactions listed in the results
+const MAX_LISTED: usize = 100;
+
+/// What a transaction's effects said when it executed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expectation {
+    pub digest: TransactionDigest,
+    pub charge: GasCharge,
+    pub created: u64,
+    pub consensus: bool,
+}
+
+impl Expectation {
+    fn of(outcome: &TxOutcome) -> Self {
+        Self { digest: outcome.digest, charge: outcome.charge, created: outcome.created, consensus: outcome.consensus }
+    }
+
+    /// Why re-fetched effects don't match, if they don't
+    fn mismatch(&self, effects: &SuiTransactionBlockEffects) -> Option<String> {
+        if effects.status().is_err() {
+            return Some(format!("status now {:?}", effects.status()));
+        }
+        let charge = GasCharge::of(effects.gas_cost_summary());
+        if charge != self.charge {
+            return Some(format!("gas {}/{} rebate, was {}/{}", charge.charged, charge.rebate, self.charge.charged, self.charge.rebate));
+        }
+        self.compare(effects.created().len() as u64, !effects.shared_objects().is_empty())
+    }
+
+    fn compare(&self, created: u64, consensus: bool) -> Option<String> {
+        if created != self.created {
+            return Some(format!("{} objects created, was {}", created, self.created));
+        }
+        if consensus != self.consensus {
+            return Some(format!("shared inputs {}, was {}", consensus, self.consensus));
+        }
+        None
+    }
+}
+
+/// Samples successful transactions for later verification
+#[derive(Default)]
+pub struct EffectsSampler {
+    pct: AtomicU8,
+    pending: Mutex<VecDeque<(Instant, Expectation)>>,
+    skipped: AtomicU64,
+}
+
+impl EffectsSampler {
+    pub fn enable(&self, pct: u8) {
+        self.pct.store(pct, Ordering::Relaxed);
+    }
+
+    /// Set the transaction aside with probability --verify-effects-pct; `now` is when it finished
+    pub fn sample(&self, outcome: &TxOutcome, now: Instant) {
+        let pct = self.pct.load(Ordering::Relaxed);
+        if pct == 0 || rand::random::<u8>() % 100 >= pct {
+            return;
+        }
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() >= MAX_PENDING {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        pending.push_back((now, Expectation::of(outcome)));
+    }
+
+    /// Samples at least `delay` old at `now` (all of them with `delay` zero), up to `max`
+    fn take_due(&self, delay: Duration, max: usize, now: Instant) -> Vec<Expectation> {
+        let mut pending = self.pending.lock().unwrap();
+        let mut due = Vec::new();
+        while due.len() < max && pending.front().is_some_and(|(at, _)| now.saturating_duration_since(*at) >= delay) {
+            due.push(pending.pop_front().unwrap().1);
+        }
+        due
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Divergence {
+    pub digest: String,
+    pub reason: String,
+}
+
+/// `effects_verification` section of the results
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct VerifyReport {
+    pub sample_pct: u8,
+    pub delay_secs: u64,
+    pub verified: u64,
+    pub identical: u64,
+    /// The node no longer returns the transaction or its effects
+    pub missing: u64,
+    /// Returned with effects different from those seen at execution time
+    pub diverged: u64,
+    /// Lookups that failed outright (not counted as verified)
+    pub errors: u64,
+    /// Samples dropped because too many were waiting
+    pub skipped: u64,
+    pub divergences: Vec<Divergence>,
+}
+
+impl VerifyReport {
+    fn record(&mut self, expected: &Expectation, result: Result<(), String>, missing: bool) {
+        self.verified += 1;
+        match result {
+            Ok(()) => self.identical += 1,
+            Err(reason) => {
+                if missing {
+                    self.missing += 1;
+                } else {
+                    self.diverged += 1;
+                }
+                warn!("Effects verification: {} {}", expected.digest, reason);
+                if self.divergences.len() < MAX_LISTED {
+                    self.divergences.push(Divergence { digest: expected.digest.to_string(), reason });
+                }
+            }
+        }
+    }
+}
+
+/// Verify samples as they come due until `running` is cleared, then everything still pending
+pub async fn run_verifier(client: SuiClient, stats: Arc<BenchStats>, running: Arc<AtomicBool>, pct: u8, delay: Duration) -> VerifyReport {
+    let mut report = VerifyReport { sample_pct: pct, delay_secs: delay.as_secs(), ..VerifyReport::default() };
+    loop {
+        let finishing = !running.load(Ordering::Relaxed);
+        let due = stats.verify.take_due(if finishing { Duration::ZERO } else { delay }, VERIFY_BATCH, stats.clock.now());
+        if due.is_empty() {
+            if finishing {
+                break;
+            }
+            stats.clock.sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+        verify_batch(&client, &due, &mut report).await;
+    }
+    report.skipped = stats.verify.skipped.load(Ordering::Relaxed);
+    report
+}
+
+async fn verify_batch(client: &SuiClient, due: &[Expectation], report: &mut VerifyReport) {
+    let options = SuiTransactionBlockResponseOptions::new().with_effects();
+    let digests = due.iter().map(|e| e.digest).collect();
+    match client.read_api().multi_get_transactions_with_options(digests, options.clone()).await {
+        Ok(responses) if responses.len() == due.len() => {
+            for (expected, response) in due.iter().zip(responses) {
+                check(report, expected, response.effects.as_ref());
+            }
+        }
+        // A failed batch may just mean one digest is gone; ask one at a time
+        _ => {
+            for expected in due {
+                match client.read_api().get_transaction_with_options(expected.digest, options.clone()).await {
+                    Ok(response) => check(report, expected, response.effects.as_ref()),
+                    Err(e) if is_not_found(&e.to_string()) => check(report, expected, None),
+                    Err(e) => {
+                        report.errors += 1;
+                        warn!("Effects verification: lookup of {} failed: {}", expected.digest, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn check(report: &mut VerifyReport, expected: &Expectation, effects: Option<&SuiTransactionBlockEffects>) {
+    match effects {
+        Some(effects) => report.record(expected, expected.mismatch(effects).map_or(Ok(()), Err), false),
+        None => report.record(expected, Err("not returned by the node".to_string()), true),
+    }
+}
+
+fn is_not_found(error: &str) -> bool {
+    let error = error.to_ascii_lowercase();
+    error.contains("not found") || error.contains("could not find")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(tx: u8, created: u64) -> TxOutcome {
+        TxOutcome { created, charge: GasCharge { charged: 3_000, rebate: 1_000 }, ..TxOutcome::empty(TransactionDigest([tx; 32])) }
+    }
+
+    #[test]
+    fn samples_come_due_after_the_delay() {
+        let sampler = EffectsSampler::default();
+        sampler.sample(&outcome(1, 2));
+        assert!(sampler.take_due(Duration::ZERO, 10).is_empty(), "sampling is off by default");
+        sampler.enable(100);
+        for tx in 1..=3 {
+            sampler.sample(&outcome(tx, 2));
+        }
+        assert!(sampler.take_due(Duration::from_secs(3600), 10).is_empty());
+        let due = sampler.take_due(Duration::ZERO, 2);
+        assert_eq!(due.iter().map(|e| e.digest).collect::<Vec<_>>(), vec![TransactionDigest([1; 32]), TransactionDigest([2; 32])]);
+        assert_eq!(sampler.take_due(Duration::ZERO, 10).len(), 1);
+    }
+
+    #[test]
+    fn mismatches_are_counted_and_listed() {
+        let expected = Expectation::of(&outcome(7, 2));
+        let mut report = VerifyReport::default();
+        report.record(&expected, expected.compare(2, false).map_or(Ok(()), Err), false);
+        report.record(&expected, expected.compare(1, false).map_or(Ok(()), Err), false);
+        report.record(&expected, Err("not returned by the node".to_string()), true);
+        assert_eq!((report.verified, report.identical, report.diverged, report.missing), (3, 1, 1, 1));
+        assert_eq!(report.divergences[0].reason, "1 objects created, was 2");
+    }
+}
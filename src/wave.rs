@@ -0,0 +1,190 @@
+// Compaction-wave trigger mode (--wave-interval, --wave-pct)
+//
+// Steady churn lets RocksDB compaction keep up in the background. A wave
+// instead rewrites a large share of the whole population in one short burst:
+// every --wave-interval seconds each worker walks --wave-pct percent of its
+// tracked objects sequentially, back to back, ignoring rate limits and the
+// create/update mix. Most of those versions live in cold SST files, so the
+// burst invalidates a lot of old data at once and forces a compaction storm.
+// Latency is recorded separately for transactions submitted while a wave is
+// running, and the interval timeline shows the storm's aftermath, which is
+// where FDP's effect on tail latency shows up.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::latency::{Histogram, LatencySummary};
+use crate::timeline::Timestamp;
+use crate::BenchStats;
+
+/// A worker's position in the current wave
+#[derive(Debug, Clone, Copy)]
+pub struct WaveProgress {
+    pub index: u64,
+    next: usize,
+    remaining: usize,
+}
+
+impl WaveProgress {
+    pub fn new(index: u64, objects: usize, pct: u8) -> Self {
+        Self { index, next: 0, remaining: objects * pct as usize / 100 }
+    }
+
+    /// Start index of the next batch of `count` objects (out of `len`)
+    pub fn advance(&mut self, count: usize, len: usize) -> usize {
+        let start = self.next % len.max(1);
+        self.next = start + count;
+        self.remaining = self.remaining.saturating_sub(count);
+        start
+    }
+
+    pub fn done(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaveRecord {
+    pub index: u64,
+    pub start: Timestamp,
+    /// When the last worker finished its share
+    pub end: Option<Timestamp>,
+    pub objects_rewritten: u64,
+    pub transactions: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaveReport {
+    pub interval_secs: u64,
+    pub pct: u8,
+    pub waves: Vec<WaveRecord>,
+    /// Latency of transactions submitted while a wave was running vs. outside waves
+    pub latency_in_wave: LatencySummary,
+    pub latency_outside_wave: LatencySummary,
+}
+
+#[derive(Default)]
+struct WaveLog {
+    waves: BTreeMap<u64, WaveRecord>,
+    in_wave: Histogram,
+    outside: Histogram,
+}
+
+/// Wave bookkeeping shared by all workers
+#[derive(Default)]
+pub struct WaveStats {
+    /// Workers currently working through a wave
+    active: AtomicUsize,
+    log: Mutex<WaveLog>,
+}
+
+impl WaveStats {
+    /// Wave number due at this point of the run (0 = before the first wave)
+    pub fn due_index(stats: &BenchStats, interval: Duration) -> u64 {
+        stats.elapsed().as_secs() / interval.as_secs().max(1)
+    }
+
+    pub fn active(&self) -> bool {
+        self.active.load(Ordering::Relaxed) > 0
+    }
+
+    /// A worker starts its share of wave `index`
+    pub fn begin(&self, stats: &BenchStats, index: u64) {
+        self.active.fetch_add(1, Ordering::Relaxed);
+        self.log.lock().unwrap().waves.entry(index).or_insert_with(|| WaveRecord {
+            index,
+            start: Timestamp::now(stats),
+            end: None,
+            objects_rewritten: 0,
+            transactions: 0,
+        });
+    }
+
+    /// Count one successful wave transaction
+    pub fn record(&self, index: u64, objects: u64) {
+        if let Some(wave) = self.log.lock().unwrap().waves.get_mut(&index) {
+            wave.objects_rewritten += objects;
+            wave.transactions += 1;
+        }
+    }
+
+    /// A worker is done with its share of wave `index`
+    pub fn finish(&self, stats: &BenchStats, index: u64) {
+        self.active.fetch_sub(1, Ordering::Relaxed);
+        if let Some(wave) = self.log.lock().unwrap().waves.get_mut(&index) {
+            wave.end = Some(Timestamp::now(stats));
+        }
+    }
+
+    /// Successful-transaction latency, split by whether a wave was running
+    pub fn record_latency(&self, latency: Duration, in_wave: bool) {
+        let mut log = self.log.lock().unwrap();
+        if in_wave {
+            log.in_wave.record(latency);
+        } else {
+            log.outside.record(latency);
+        }
+    }
+
+    pub fn report(&self, interval_secs: u64, pct: u8) -> WaveReport {
+        let log = self.log.lock().unwrap();
+        WaveReport {
+            interval_secs,
+            pct,
+            waves: log.waves.values().cloned().collect(),
+            latency_in_wave: log.in_wave.summary(),
+            latency_outside_wave: log.outside.summary(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+
+    #[test]
+    fn a_wave_walks_its_share_of_objects_and_wraps() {
+        let mut wave = WaveProgress::new(1, 10, 50);
+        assert_eq!(wave.advance(3, 10), 0);
+        assert!(!wave.done());
+        assert_eq!(wave.advance(3, 4), 3);
+        // The population shrank to 4 objects; the walk wraps around it
+        assert_eq!(wave.advance(3, 4), 2);
+        assert!(wave.done());
+        assert!(WaveProgress::new(1, 1, 50).done());
+    }
+
+    #[test]
+    fn waves_are_timed_from_the_first_start_to_the_last_finish() {
+        let clock = VirtualClock::new();
+        let stats = BenchStats::new(2, clock.clone());
+        let waves = WaveStats::default();
+        clock.advance(Duration::from_secs(125));
+        let index = WaveStats::due_index(&stats, Duration::from_secs(60));
+        assert_eq!(index, 2);
+
+        waves.begin(&stats, index);
+        clock.advance(Duration::from_secs(5));
+        waves.begin(&stats, index);
+        waves.record(index, 8);
+        waves.record(index, 4);
+        waves.record_latency(Duration::from_millis(900), waves.active());
+        waves.finish(&stats, index);
+        assert!(waves.active());
+        clock.advance(Duration::from_secs(5));
+        waves.finish(&stats, index);
+        assert!(!waves.active());
+        waves.record_latency(Duration::from_millis(100), waves.active());
+
+        let report = waves.report(60, 20);
+        assert_eq!(report.waves.len(), 1);
+        let wave = &report.waves[0];
+        assert_eq!((wave.start.elapsed_secs, wave.end.as_ref().unwrap().elapsed_secs), (125.0, 135.0));
+        assert_eq!((wave.objects_rewritten, wave.transactions), (12, 2));
+        assert_eq!((report.latency_in_wave.count, report.latency_outside_wave.count), (1, 1));
+    }
+}
@@ -0,0 +1,380 @@
+// Operation types and the batch executor (--workload <name>=<pct>)
+//
+// Every owned-object operation implements `Workload`: build the transaction's
+// commands, then apply its effects to the worker's tracked objects. Signing,
+// submission, duplicate detection and gas accounting are done once, by
+// `execute`, for all of them, through the run's `TxExecutor`. Effects are first
+// reduced to plain object refs (`Effects`), so applying them is ordinary code
+// that unit tests can drive (executor.rs).
+// The built-in create/update operations live in ops.rs and are picked by the
+// worker loop. Further operation types are one self-contained module plus a
+// line in REGISTRY; `--workload name=pct` gives such an operation that share of
+// each worker's transactions, ahead of the built-in mix.
+
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use sui_sdk::rpc_types::{ObjectChange, SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse};
+use sui_sdk::types::base_types::{ObjectID, ObjectRef, TransactionDigest};
+use sui_sdk::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_sdk::types::transaction::{Transaction, TransactionData};
+use tokio::sync::RwLock;
+
+use crate::abi::ContractVersion;
+use crate::executor::{self, TxExecutor};
+use crate::expiry;
+use crate::tagging::StreamTags;
+use crate::validate::{Percent, MAX_TX_GAS_BUDGET};
+use crate::{blob_delete, clock_update, gas_only, gas_spent, Args, GasCharge, TrackedObject, TxOutcome, WorkerState};
+
+/// What a workload gets to build one transaction
+pub struct BuildContext {
+    pub package_id: ObjectID,
+    pub batch_size: usize,
+    /// This worker's objects are blobs (a config profile may differ from --use-blobs)
+    pub use_blobs: bool,
+    /// Stream tags to stamp into payloads (--payload-tag)
+    pub tags: Option<StreamTags>,
+    /// Contract version the package was published from (--contract-version)
+    pub contract: ContractVersion,
+    /// Pick update batches from the cold partition (--cold-leak-pct)
+    pub cold_leak: bool,
+    /// Last epoch the transaction is valid in (--tx-expiration-epochs)
+    pub expiration_epoch: Option<u64>,
+}
+
+/// What build_ptb decided about the batch, handed back to apply_effects
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Planned {
+    /// Logical bytes written per object (pacing and simulated runs)
+    pub object_bytes: u64,
+    /// Payload size recorded for created objects (0 = the type's default)
+    pub size: u64,
+    /// Objects the transaction creates or touches (per-object gas budgets)
+    pub objects: usize,
+}
+
+/// Gas budget of one transaction: --gas-budget, or a per-object --gas-budget-*
+/// override times the objects in the batch, so large batches get enough and
+/// small ones don't reserve a full budget
+#[derive(Debug, Clone, Copy)]
+pub struct GasBudgets {
+    pub default: u64,
+    pub create: Option<u64>,
+    pub update: Option<u64>,
+    pub blob: Option<u64>,
+}
+
+impl GasBudgets {
+    pub fn of(args: &Args) -> Self {
+        Self { default: args.gas_budget, create: args.gas_budget_create, update: args.gas_budget_update, blob: args.gas_budget_blob }
+    }
+
+    /// Per-object override that applies to a workload, by name
+    fn per_object(&self, workload: &str) -> Option<u64> {
+        match workload {
+            "create" => self.create,
+            "update" => self.update,
+            "create_blob" | "update_blob" | "delete_blob" => self.blob,
+            _ => None,
+        }
+    }
+
+    pub fn for_batch(&self, workload: &str, objects: usize) -> u64 {
+        self.per_object(workload)
+            .map_or(self.default, |mist| mist.saturating_mul(objects.max(1) as u64).min(MAX_TX_GAS_BUDGET))
+    }
+}
+
+/// A transaction's effects on owned objects
+#[derive(Debug, Clone)]
+pub struct Effects {
+    pub digest: TransactionDigest,
+    pub gas_used: u64,
+    pub charge: GasCharge,
+    /// Gas coin after the transaction
+    pub gas_coin: Option<ObjectRef>,
+    pub created: Vec<ObjectRef>,
+    pub mutated: Vec<ObjectRef>,
+    pub deleted: Vec<ObjectID>,
+    /// Touched a shared object, so it was sequenced through consensus
+    pub shared: bool,
+    /// Execution failure status of a transaction that ran and aborted
+    pub failure: Option<String>,
+}
+
+impl Effects {
+    pub fn empty(digest: TransactionDigest) -> Self {
+        Self { digest, gas_used: 0, charge: GasCharge::default(), gas_coin: None, created: Vec::new(), mutated: Vec::new(), deleted: Vec::new(), shared: false, failure: None }
+    }
+
+    /// Effects and object changes of an executed transaction (nothing if effects are missing)
+    pub fn from_response(response: &SuiTransactionBlockResponse) -> Self {
+        let mut effects = Self::empty(response.digest);
+        let Some(tx_effects) = &response.effects else {
+            return effects;
+        };
+        effects.gas_used = gas_spent(tx_effects.gas_cost_summary());
+        effects.charge = GasCharge::of(tx_effects.gas_cost_summary());
+        let gas = tx_effects.gas_object();
+        effects.gas_coin = Some((gas.object_id(), gas.version(), gas.reference.digest));
+        effects.shared = !tx_effects.shared_objects().is_empty();
+        if let SuiExecutionStatus::Failure { error } = tx_effects.status() {
+            effects.failure = Some(error.clone());
+        }
+        for change in response.object_changes.iter().flatten() {
+            match change {
+                ObjectChange::Created { object_id, version, digest, .. } => effects.created.push((*object_id, *version, *digest)),
+                ObjectChange::Mutated { object_id, version, digest, .. } => effects.mutated.push((*object_id, *version, *digest)),
+                ObjectChange::Deleted { object_id, .. } => effects.deleted.push(*object_id),
+                _ => {}
+            }
+        }
+        effects
+    }
+}
+
+/// One operation type
+pub trait Workload: Send + Sync {
+    /// Label for stats, latency and the digest log
+    fn name(&self) -> &'static str;
+
+    /// Share of each worker's transactions when used as a --workload, in percent
+    fn weight(&self) -> Percent {
+        Percent::default()
+    }
+
+    /// Whether the worker can run this operation now (e.g. has objects to spare)
+    fn ready(&self, _state: &WorkerState, _ctx: &BuildContext) -> bool {
+        true
+    }
+
+    /// Add the transaction's commands; gas payment is added by the executor
+    fn build_ptb(&self, state: &mut WorkerState, ctx: &BuildContext, builder: &mut ProgrammableTransactionBuilder) -> Result<Planned>;
+
+    /// Update tracked objects from the effects and fill in the object counts
+    /// (gas is already accounted for); returns newly created objects to track
+    fn apply_effects(&self, objects: &mut Vec<TrackedObject>, effects: &Effects, planned: Planned, outcome: &mut TxOutcome) -> Vec<TrackedObject>;
+}
+
+type Constructor = fn(&Args, Percent) -> Result<Arc<dyn Workload>>;
+
+/// Available operation types
+const REGISTRY: &[(&str, Constructor)] = &[
+    ("clock_update", clock_update::ClockUpdate::create),
+    ("delete_blob", blob_delete::DeleteBlobs::create),
+    ("gas_only", gas_only::GasOnly::create),
+];
+
+/// `--workload` value: a registered name and its share
+#[derive(Debug, Clone)]
+pub struct WorkloadSpec {
+    pub name: String,
+    pub pct: Percent,
+}
+
+impl FromStr for WorkloadSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, pct) = s.split_once('=').ok_or_else(|| format!("expected <name>=<pct>, got '{}'", s))?;
+        let name = name.trim();
+        if !REGISTRY.iter().any(|(n, _)| *n == name) {
+            let known: Vec<&str> = REGISTRY.iter().map(|(n, _)| *n).collect();
+            return Err(format!("unknown workload '{}' (available: {})", name, known.join(", ")));
+        }
+        Ok(Self { name: name.to_string(), pct: pct.parse()? })
+    }
+}
+
+#[derive(Default)]
+struct WorkloadCounters {
+    tx_success: AtomicU64,
+    tx_failed: AtomicU64,
+    objects: AtomicU64,
+    gas_charged: AtomicU64,
+    gas_rebated: AtomicU64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkloadReport {
+    pub name: &'static str,
+    pub pct: u8,
+    pub tx_success: u64,
+    pub tx_failed: u64,
+    /// Objects created, written, read or deleted
+    pub objects: u64,
+    /// Charged minus storage rebates over the successful transactions (MIST)
+    pub net_mist: i64,
+}
+
+/// The --workload operations of a run, shared by all workers
+pub struct WorkloadMix {
+    workloads: Vec<(Arc<dyn Workload>, WorkloadCounters)>,
+}
+
+impl WorkloadMix {
+    pub fn new(specs: &[WorkloadSpec], args: &Args) -> Result<Self> {
+        let total: u32 = specs.iter().map(|s| s.pct.get() as u32).sum();
+        if total > 100 {
+            return Err(anyhow!("--workload shares add up to {}%, more than 100%", total));
+        }
+        let mut workloads = Vec::with_capacity(specs.len());
+        for spec in specs {
+            if workloads.iter().any(|(w, _): &(Arc<dyn Workload>, _)| w.name() == spec.name) {
+                return Err(anyhow!("--workload {} given twice", spec.name));
+            }
+            let (_, create) = REGISTRY.iter().find(|(n, _)| *n == spec.name)
+                .ok_or_else(|| anyhow!("Unknown workload '{}'", spec.name))?;
+            workloads.push((create(args, spec.pct)?, WorkloadCounters::default()));
+        }
+        Ok(Self { workloads })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.workloads.is_empty()
+    }
+
+    /// Roll for the next transaction: an index into the mix, or None for the built-in mix
+    pub fn pick(&self, rng: &mut impl Rng) -> Option<usize> {
+        if self.workloads.is_empty() {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..100u32);
+        for (idx, (workload, _)) in self.workloads.iter().enumerate() {
+            let weight = workload.weight().get() as u32;
+            if roll < weight {
+                return Some(idx);
+            }
+            roll -= weight;
+        }
+        None
+    }
+
+    pub fn get(&self, idx: usize) -> &dyn Workload {
+        self.workloads[idx].0.as_ref()
+    }
+
+    pub fn record(&self, idx: usize, result: &Result<TxOutcome>) {
+        let counters = &self.workloads[idx].1;
+        match result {
+            Ok(outcome) if outcome.duplicate => {}
+            Ok(outcome) => {
+                counters.tx_success.fetch_add(1, Ordering::Relaxed);
+                let objects = outcome.created + outcome.updated + outcome.read + outcome.deleted;
+                counters.objects.fetch_add(objects, Ordering::Relaxed);
+                counters.gas_charged.fetch_add(outcome.charge.charged, Ordering::Relaxed);
+                counters.gas_rebated.fetch_add(outcome.charge.rebate, Ordering::Relaxed);
+            }
+            Err(_) => {
+                counters.tx_failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn report(&self) -> Vec<WorkloadReport> {
+        self.workloads.iter()
+            .map(|(workload, counters)| WorkloadReport {
+                name: workload.name(),
+                pct: workload.weight().get(),
+                tx_success: counters.tx_success.load(Ordering::Relaxed),
+                tx_failed: counters.tx_failed.load(Ordering::Relaxed),
+                objects: counters.objects.load(Ordering::Relaxed),
+                net_mist: counters.gas_charged.load(Ordering::Relaxed) as i64 - counters.gas_rebated.load(Ordering::Relaxed) as i64,
+            })
+            .collect()
+    }
+}
+
+/// Build, sign and submit one transaction of `workload`, then apply its effects
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    executor: &dyn TxExecutor,
+    worker: &Arc<RwLock<WorkerState>>,
+    workload: &dyn Workload,
+    ctx: &BuildContext,
+    gas: &GasBudgets,
+    rgp: u64,
+    simulate: bool,
+) -> Result<TxOutcome> {
+    let mut state = worker.write().await;
+    // A leak-through update sees the cold partition as the worker's objects until it is applied
+    let leak = ctx.cold_leak && !state.cold.is_empty();
+    if leak {
+        state.swap_partitions();
+    }
+    let result = execute_locked(executor, &mut state, workload, ctx, gas, rgp, simulate).await;
+    if leak {
+        state.swap_partitions();
+    }
+    result
+}
+
+/// One transaction of `workload`: its commands, the worker's gas payment and the batch's budget
+pub fn build_transaction(
+    state: &mut WorkerState,
+    workload: &dyn Workload,
+    ctx: &BuildContext,
+    gas: &GasBudgets,
+    rgp: u64,
+) -> Result<(TransactionData, Planned)> {
+    let mut builder = ProgrammableTransactionBuilder::new();
+    let planned = workload.build_ptb(state, ctx, &mut builder)?;
+    let tx_data = expiry::expire_at(TransactionData::new_programmable(
+        state.address,
+        state.gas_payment_for(workload.name()),
+        builder.finish(),
+        gas.for_batch(workload.name(), planned.objects),
+        rgp,
+    ), ctx.expiration_epoch);
+    Ok((tx_data, planned))
+}
+
+/// Sign a transaction with the worker's key
+pub fn sign(state: &WorkerState, tx_data: TransactionData) -> Transaction {
+    Transaction::from_data_and_signer(tx_data, vec![&state.keypair])
+}
+
+async fn execute_locked(
+    executor: &dyn TxExecutor,
+    state: &mut WorkerState,
+    workload: &dyn Workload,
+    ctx: &BuildContext,
+    gas: &GasBudgets,
+    rgp: u64,
+    simulate: bool,
+) -> Result<TxOutcome> {
+    let (tx_data, planned) = build_transaction(state, workload, ctx, gas, rgp)?;
+    if simulate {
+        return executor.simulate(tx_data, state.gas_coin.0, planned.object_bytes).await;
+    }
+
+    let tx = sign(state, tx_data);
+    let effects = executor.execute(tx).await?;
+    // An aborted transaction still paid gas and bumped its owned inputs
+    let outcome = executor::apply(state, workload, &effects, planned);
+    match &effects.failure {
+        Some(status) => Err(anyhow!("Transaction {} failed: {}", effects.digest, status)),
+        None => Ok(outcome),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn per_object_budgets_scale_with_the_batch() {
+        let gas = GasBudgets { default: 500_000_000, create: None, update: Some(2_000_000), blob: Some(30_000_000) };
+        assert_eq!(gas.for_batch("create", 50), 500_000_000);
+        assert_eq!(gas.for_batch("update", 50), 100_000_000);
+        assert_eq!(gas.for_batch("update", 0), 2_000_000);
+        assert_eq!(gas.for_batch("create_blob", 20), 600_000_000);
+        assert_eq!(gas.for_batch("delete_blob", 3), 90_000_000);
+        let huge = GasBudgets { blob: Some(u64::MAX / 2), ..gas };
+        assert_eq!(huge.for_batch("update_blob", 20), MAX_TX_GAS_BUDGET);
+    }
+}
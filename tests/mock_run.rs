@@ -0,0 +1,88 @@
+// End-to-end runs against the mock backend
+//
+// Each test drives a complete benchmark in-process (setup, load phase, output)
+// with --backend mock, so no node is needed, and asserts on the returned
+// BenchResult and the saved state with the same functions --check uses. The
+// mock backend stands in for an in-process localnet, which would need the Sui
+// node crates to start a network and publish io_churn from the test.
+
+use clap::Parser;
+use fdp_sui_bench::{autosave, checks, clock, run_benchmark, Args};
+use std::path::PathBuf;
+
+/// A scratch directory for one test's files
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fdp-mock-run-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Parsed and validated arguments for a short two-worker mock run, plus `extra`
+fn mock_args(extra: &[&str]) -> Args {
+    let mut argv = vec![
+        "fdp-sui-bench", "--backend", "mock", "--mock-latency", "fixed:2",
+        "--duration", "2", "--workers", "2", "--batch-size", "5", "--seed-objects", "20",
+    ];
+    argv.extend_from_slice(extra);
+    let args = Args::try_parse_from(argv).unwrap();
+    args.validate().unwrap();
+    args
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mock_run_completes_transactions() {
+    let dir = scratch("plain");
+    let output = dir.join("result.json").to_string_lossy().into_owned();
+
+    let result = run_benchmark(&mock_args(&["--output", &output]), clock::system()).await.unwrap();
+
+    checks::nonzero_tps(&result).unwrap();
+    assert_eq!(result.tx_submitted, result.tx_success + result.tx_failed);
+    assert!(result.objects_updated > 0);
+    assert_eq!(result.per_worker.len(), 2);
+    assert_eq!(result.per_worker.iter().map(|w| w.tx_success).sum::<u64>(), result.tx_success);
+    assert_eq!((result.config.workers, result.config.batch_size), (2, 5));
+    let written = fdp_sui_bench::results::load(&output).unwrap();
+    assert_eq!(written.tx_success, result.tx_success);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn saved_state_passes_checks_and_loads_into_the_next_run() {
+    let dir = scratch("save-load");
+    let saved = dir.join("objects.json").to_string_lossy().into_owned();
+
+    let first = run_benchmark(&mock_args(&["--save-objects", &saved]), clock::system()).await.unwrap();
+    let state = autosave::read_state(&saved).unwrap();
+    checks::tracker(&state).unwrap();
+    checks::round_trip(&state).unwrap();
+    assert_eq!(state.workers.len(), 2);
+    assert!(state.total_objects >= 40);
+    assert!(state.total_objects as u64 <= 40 + first.objects_created);
+
+    // The next phase starts from the saved objects instead of seeding new ones
+    let second = run_benchmark(&mock_args(&["--load-objects", &saved, "--check", "nonzero-tps,tracker"]), clock::system()).await.unwrap();
+    checks::nonzero_tps(&second).unwrap();
+    assert_eq!(second.sections["checks"]["passed"], true);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_run_stopped_by_its_gas_cap_is_not_resumed() {
+    let dir = scratch("gas-cap");
+    let state = dir.join("state.json").to_string_lossy().into_owned();
+
+    let capped = run_benchmark(&mock_args(&["--autosave", &state, "--max-gas-spend", "1"]), clock::system()).await.unwrap();
+    assert!(capped.gas_cap_reached);
+    let last = autosave::last_journal_entry(&state).unwrap().unwrap();
+    assert!(last.completed);
+    let resume = mock_args(&["--autosave", &state, "--max-gas-spend", "1", "--resume"]);
+    let err = run_benchmark(&resume, clock::system()).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("already completed"), "{:#}", err);
+
+    // A checkpoint taken after the cap was hit, as a crash before the final one leaves it
+    autosave::append_journal(&state, &autosave::JournalEntry { completed: false, ..last }).unwrap();
+    let err = run_benchmark(&resume, clock::system()).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("--max-gas-spend 1"), "{:#}", err);
+    std::fs::remove_dir_all(&dir).unwrap();
+}